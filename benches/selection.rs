@@ -0,0 +1,156 @@
+//! Benchmarks for playlist selection, dial mapping and file loading
+//!
+//! Sized for large libraries (10k+ tracks) so regressions show up before
+//! they turn into audible hiccups on Pi-class hardware. Dial mapping runs
+//! the real `Radio::tune` path against the null audio backend, fed by a
+//! mock tuner device, so no I2C hardware is needed.
+//!
+//! Run with `cargo bench`.
+
+use std::collections::BTreeSet;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+
+use mokradio::config::STATIONS_ROOT_VARIABLE;
+use mokradio::config::tuning::TuningProfile;
+use mokradio::constants;
+use mokradio::file_loader::decoder::load_and_decode;
+use mokradio::input::device::InputDevice;
+use mokradio::messages::InputEvent;
+use mokradio::radio::Radio;
+use mokradio::radio::station::content::Band;
+use mokradio::radio::station::content::tags::TrackTags;
+use mokradio::radio::station::content::track::Track;
+use mokradio::radio::station::utilities::whats_next::{next_chronologic, next_random, next_reverse, next_shuffle};
+use mokradio::radio::utilities::generate_station_volume_profile;
+
+#[path = "../tests/common/fixtures.rs"]
+mod fixtures;
+use fixtures::write_wav;
+
+/// Number of tracks in the large playlist benchmarks
+const PLAYLIST_SIZE: usize = 10_000;
+
+/// Builds a playlist of fake tracks with distinct modification times
+fn generate_playlist(size: usize) -> Vec<Track> {
+    (0..size).map(|i| {
+        Track::from_metadata(
+            PathBuf::from(format!("/stations/AM/00/playlist/track{:05}.mp3", i)),
+            chrono::Duration::seconds(180),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(i as u64 * 60)
+        )
+    }).collect()
+}
+
+fn playlist_selection(c: &mut Criterion) {
+    let playlist = generate_playlist(PLAYLIST_SIZE);
+    let sorted_playlist: BTreeSet<Track> = playlist.iter().cloned().collect();
+
+    c.bench_function("next_random 10k", |b| {
+        b.iter(|| black_box(next_random(&playlist)))
+    });
+
+    c.bench_function("next_shuffle 10k", |b| {
+        b.iter_batched(
+            || playlist.clone(),
+            |mut shuffle_playlist| black_box(next_shuffle(&mut shuffle_playlist)),
+            BatchSize::LargeInput
+        )
+    });
+
+    c.bench_function("next_chronologic 10k", |b| {
+        b.iter_batched(
+            || sorted_playlist.clone(),
+            |mut chronologic_playlist| black_box(next_chronologic(&mut chronologic_playlist)),
+            BatchSize::LargeInput
+        )
+    });
+
+    c.bench_function("next_reverse 10k", |b| {
+        b.iter_batched(
+            || sorted_playlist.clone(),
+            |mut reverse_playlist| black_box(next_reverse(&mut reverse_playlist)),
+            BatchSize::LargeInput
+        )
+    });
+
+    c.bench_function("drain chronologic 10k", |b| {
+        b.iter_batched(
+            || sorted_playlist.clone(),
+            |mut chronologic_playlist| {
+                while let Some(track) = next_chronologic(&mut chronologic_playlist) {
+                    black_box(track);
+                }
+            },
+            BatchSize::LargeInput
+        )
+    });
+}
+
+/// Stand-in for the tuner that moves the dial a tick per poll, like a fast spin
+struct SweepingTuner {
+    dial_position: usize,
+}
+
+impl InputDevice for SweepingTuner {
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.dial_position = (self.dial_position + 1) % constants::ENCODER_HALF;
+        Some(InputEvent::DialMoved { new_dial_position: self.dial_position })
+    }
+}
+
+/// Builds an empty stations tree with the null audio backend
+///
+/// Must be called before anything reads radio.info.
+fn null_radio() -> Radio {
+    let root = std::env::temp_dir().join(format!("mokradio-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("radio.info"), r#"{ "audio_backend": "Null", "storage": "Memory" }"#).unwrap();
+    unsafe { std::env::set_var(STATIONS_ROOT_VARIABLE, &root) };
+    Radio::new(constants::ENCODER_HALF / 2, Band::AM)
+}
+
+fn dial_mapping(c: &mut Criterion) {
+    c.bench_function("generate_station_volume_profile", |b| {
+        b.iter(|| black_box(generate_station_volume_profile(&TuningProfile::default())))
+    });
+
+    // Every dial reading goes through Radio::tune on the Station Manager thread
+    let mut radio = null_radio();
+    let mut tuner = SweepingTuner { dial_position: 0 };
+    c.bench_function("tune one tick", |b| {
+        b.iter(|| {
+            if let Some(InputEvent::DialMoved { new_dial_position }) = tuner.poll() {
+                radio.tune(black_box(new_dial_position));
+            }
+        })
+    });
+
+    c.bench_function("tune full dial sweep", |b| {
+        b.iter(|| {
+            for _ in 0..constants::ENCODER_HALF {
+                if let Some(InputEvent::DialMoved { new_dial_position }) = tuner.poll() {
+                    radio.tune(black_box(new_dial_position));
+                }
+            }
+        })
+    });
+}
+
+fn loader_throughput(c: &mut Criterion) {
+    let fixture_path = std::env::temp_dir().join("mokradio_bench_fixture.wav");
+    write_wav(&fixture_path, Duration::from_secs(1), &TrackTags::default()).unwrap();
+
+    c.bench_function("load_and_decode wav", |b| {
+        b.iter(|| black_box(load_and_decode(&fixture_path).unwrap()))
+    });
+
+    std::fs::remove_file(&fixture_path).ok();
+}
+
+// dial_mapping goes first, so it points radio.info at its own tree before anything reads it
+criterion_group!(benches, dial_mapping, playlist_selection, loader_throughput);
+criterion_main!(benches);
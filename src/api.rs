@@ -0,0 +1,254 @@
+//! API Module - Programmatic control of a running radio
+//!
+//! The web dashboard and other front ends talk to the Station Manager
+//! through a cloneable `RadioApi` handle. Each call becomes a
+//! `RadioCommand` on the manager's command channel, which the manager
+//! drains once per loop alongside input events. Queries carry a reply
+//! channel that the manager answers on its next loop.
+//!
+//! Going the other way, the manager publishes live events that front ends
+//! can follow over a WebSocket; see `events`. The `server` puts both on
+//! the network, with `companion` as the remote-control protocol, `rest`
+//! offering the same controls as plain HTTP resources, `audit` keeping
+//! track of who used them and `rate_limit` stopping any one client from
+//! flooding them, and `metrics` for Prometheus to scrape.
+
+pub mod audit;
+pub mod companion;
+pub mod events;
+pub mod metrics;
+pub mod rate_limit;
+pub mod rest;
+pub mod server;
+
+use std::fmt;
+use std::path::Path;
+use std::sync::mpsc::{RecvTimeoutError, Sender, channel};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::messages::{ChronologicPosition, QueueEdit, RadioCommand, RadioSnapshot, RadioStatus, SeekTo, TrackPreview};
+use crate::radio::station::content::StationID;
+
+/// Errors returned by API calls
+#[derive(Debug)]
+pub enum ApiError {
+    /// The Station Manager has shut down and dropped its command channel
+    RadioStopped,
+
+    /// The Station Manager didn't answer a query in time
+    Timeout,
+
+    /// The Station Manager couldn't carry out the command, and says why
+    Refused(String),
+}
+
+/// How long a query waits for the Station Manager to answer
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::RadioStopped => write!(f, "radio is not running"),
+            ApiError::Timeout => write!(f, "radio did not answer in time"),
+            ApiError::Refused(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Body of a volume change, from either the companion protocol or REST
+#[derive(Deserialize)]
+pub struct VolumeRequest {
+    pub level: f32,
+}
+
+/// Cloneable handle for sending commands to the Station Manager
+#[derive(Clone)]
+pub struct RadioApi {
+    commands: Sender<RadioCommand>,
+}
+
+impl RadioApi {
+    /// Creates a handle from the sending side of the manager's command channel
+    pub fn new(commands: Sender<RadioCommand>) -> Self {
+        RadioApi { commands }
+    }
+
+    /// Sends a command without waiting for the manager to act on it
+    fn send(&self, command: RadioCommand) -> Result<(), ApiError> {
+        self.commands.send(command).map_err(|_| ApiError::RadioStopped)
+    }
+
+    /// Sends a query and waits for the manager's answer
+    fn query<T>(&self, command: impl FnOnce(Sender<T>) -> RadioCommand) -> Result<T, ApiError> {
+        let (reply, answer) = channel();
+        self.send(command(reply))?;
+        answer.recv_timeout(REPLY_TIMEOUT).map_err(|e| match e {
+            RecvTimeoutError::Timeout => ApiError::Timeout,
+            RecvTimeoutError::Disconnected => ApiError::RadioStopped,
+        })
+    }
+
+    /// Returns the dial position and what's playing
+    pub fn status(&self) -> Result<RadioStatus, ApiError> {
+        self.query(|reply| RadioCommand::Status { reply })
+    }
+
+    /// Returns the Station Manager's full state, for troubleshooting
+    ///
+    /// Every station's playlist position, queue and sink, without
+    /// attaching a debugger. Nothing about playback changes.
+    pub fn snapshot(&self) -> Result<RadioSnapshot, ApiError> {
+        self.query(|reply| RadioCommand::Snapshot { reply })
+    }
+
+    /// Silences the radio and stops the Station Manager loop
+    pub fn shutdown(&self) -> Result<(), ApiError> {
+        self.send(RadioCommand::Shutdown)
+    }
+
+    /// Puts a station on the monitor output, or silences the monitor
+    ///
+    /// # Arguments
+    /// * `station_id` - Station to listen to, or `None` to stop monitoring
+    pub fn monitor_station(&self, station_id: Option<StationID>) -> Result<(), ApiError> {
+        self.send(RadioCommand::MonitorStation { station_id })
+    }
+
+    /// Splits the speaker between the dial and another station
+    ///
+    /// The dial's station plays on the left channel and this one on the
+    /// right. Only available with `dual_mono` on in radio.info.
+    ///
+    /// # Arguments
+    /// * `station_id` - Station for the right channel, or `None` to play the dial on both again
+    pub fn split_station(&self, station_id: Option<StationID>) -> Result<(), ApiError> {
+        self.send(RadioCommand::SplitStation { station_id })
+    }
+
+    /// Pins, injects or removes a file in a station's upcoming queue
+    ///
+    /// The Station Manager reconciles the edit with audio already handed
+    /// to the station's sink; see `Station::pin_next()`. Only files in the
+    /// station's own playlist can be queued (see `Station::can_queue()`).
+    pub fn edit_queue(&self, station_id: StationID, edit: QueueEdit) -> Result<(), ApiError> {
+        self.query(|reply| RadioCommand::EditQueue { station_id, edit, reply })?.map_err(ApiError::Refused)
+    }
+
+    /// Picks up files added to or removed from station playlist folders,
+    /// and Dead stations given a playable station.info
+    /// 
+    /// Returns once the rescan has started; playlists change as it finds them.
+    pub fn reload_playlists(&self) -> Result<(), ApiError> {
+        self.send(RadioCommand::ReloadPlaylists)
+    }
+
+    /// Moves the dial to a station, switching bands if needed
+    ///
+    /// The dial lands in the middle of the station's slot. A physical dial
+    /// takes over again as soon as it's turned.
+    pub fn tune(&self, station_id: StationID) -> Result<(), ApiError> {
+        self.send(RadioCommand::Tune { station_id })
+    }
+
+    /// Skips to the next track on the current station
+    ///
+    /// Announcements can't be skipped and play on.
+    pub fn skip_track(&self) -> Result<(), ApiError> {
+        self.send(RadioCommand::SkipTrack)
+    }
+
+    /// Seeks within the track playing on the current station
+    pub fn seek(&self, to: SeekTo) -> Result<(), ApiError> {
+        self.send(RadioCommand::Seek { to })
+    }
+
+    /// Sets the master volume
+    ///
+    /// # Arguments
+    /// * `level` - 0.0 (silent) to 1.0 (full volume)
+    pub fn set_volume(&self, level: f32) -> Result<(), ApiError> {
+        self.send(RadioCommand::SetVolume { level })
+    }
+
+    /// Pauses every station, or resumes them
+    ///
+    /// Unlike tuning away, a pause stops radio time: stations pick up
+    /// where they were rather than where they would have got to.
+    pub fn pause_all(&self, paused: bool) -> Result<(), ApiError> {
+        self.send(RadioCommand::PauseAll { paused })
+    }
+
+    /// Mutes the output, or unmutes it
+    ///
+    /// Stations keep playing while muted, like a real set turned right down.
+    pub fn mute(&self, muted: bool) -> Result<(), ApiError> {
+        self.send(RadioCommand::Mute { muted })
+    }
+
+    /// Switches the dial to another profile's stations
+    ///
+    /// # Arguments
+    /// * `name` - Profile from radio.info, or `None` for the standard layout
+    pub fn select_profile(&self, name: Option<&str>) -> Result<(), ApiError> {
+        self.send(RadioCommand::SelectProfile { name: name.map(str::to_string) })
+    }
+
+    /// Restarts a Chronologic station from a date or episode
+    ///
+    /// # Arguments
+    /// * `station_id` - A Chronologic station
+    /// * `start_from` - `YYYY-MM-DD` date, or an episode's file name
+    pub fn start_chronologic_from(&self, station_id: StationID, start_from: &str) -> Result<(), ApiError> {
+        self.send(RadioCommand::StartFrom { station_id, start_from: start_from.to_string() })
+    }
+
+    /// Reports where a Chronologic station has got to (None for other stations)
+    pub fn chronologic_position(&self, station_id: StationID) -> Result<Option<ChronologicPosition>, ApiError> {
+        self.query(|reply| RadioCommand::ChronologicPosition { station_id, reply })
+    }
+
+    /// Lists the next tracks a station will play, without affecting playback
+    ///
+    /// # Arguments
+    /// * `station_id` - Station to look at (unknown stations return nothing)
+    /// * `count` - Maximum number of tracks, starting with the one playing
+    pub fn preview_station(&self, station_id: StationID, count: usize) -> Result<Vec<TrackPreview>, ApiError> {
+        self.query(|reply| RadioCommand::PreviewStation { station_id, count, reply })
+    }
+
+    /// Sets up an empty dial slot as a copy of another station
+    ///
+    /// The copy shares the original's settings and folders but keeps its
+    /// own play history; see `config::cloning`. It goes on the dial
+    /// straight away, or with a profile selected, the next time the
+    /// standard layout is.
+    ///
+    /// # Arguments
+    /// * `from` - Station to copy
+    /// * `to` - Empty slot for the copy
+    /// * `name` - Name for the copy, or None to keep the original's
+    /// * `playlist` - Playlist folder for the copy, or None to share the original's
+    pub fn clone_station(&self, from: StationID, to: StationID, name: Option<&str>, playlist: Option<&Path>) -> Result<(), ApiError> {
+        self.query(|reply| RadioCommand::CloneStation {
+            from,
+            to,
+            name: name.map(str::to_string),
+            playlist: playlist.map(Path::to_path_buf),
+            reply,
+        })?.map_err(ApiError::Refused)
+    }
+
+    /// Changes fields of a station's station.info and reloads the station
+    ///
+    /// # Arguments
+    /// * `station_id` - Station to edit
+    /// * `settings` - Top-level station.info fields to set (see `StationConfig::edit()`)
+    pub fn edit_station(&self, station_id: StationID, settings: Map<String, Value>) -> Result<(), ApiError> {
+        self.query(|reply| RadioCommand::EditStation { station_id, settings, reply })?.map_err(ApiError::Refused)
+    }
+}
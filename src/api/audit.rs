@@ -0,0 +1,123 @@
+//! Audit Log - Who did what to the radio remotely
+//!
+//! Every remote action that changes the radio (tuning, volume, skips,
+//! queue edits), and every request turned away for a bad token, is
+//! appended to `audit.log` in the stations root, one line per action:
+//! seconds since the Unix epoch, the client's IP address, the action, the
+//! HTTP status it got and the request body, separated by tabs. Like play
+//! history it's plain text, so it can be read, trimmed or deleted by hand;
+//! the companion protocol serves the latest entries at `/companion/audit`.
+//!
+//! Anyone who can reach the API can add lines, if only refused ones, so
+//! the log is capped: once it passes `MAX_AUDIT_BYTES` it's renamed to
+//! `audit.log.1`, replacing the one before, and a new log is started.
+
+use std::fs::{self, OpenOptions, read_to_string};
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::config::stations_root;
+
+/// File in the stations root holding the audit log
+const AUDIT_FILE: &str = "audit.log";
+
+/// The previous log, once the current one fills up
+const ROTATED_AUDIT_FILE: &str = "audit.log.1";
+
+/// Size at which the log is rotated
+const MAX_AUDIT_BYTES: u64 = 1024 * 1024;
+
+/// Longest request body kept in an entry
+const MAX_DETAIL: usize = 200;
+
+/// One remote action
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditRecord {
+    /// When the action was taken, in seconds since the Unix epoch
+    pub time: u64,
+
+    /// IP address the request came from
+    pub source: String,
+
+    /// Companion command, e.g. "tune"
+    pub action: String,
+
+    /// HTTP status of the answer; 200 means it was carried out
+    pub status: u16,
+
+    /// Request body, shortened and on one line
+    pub detail: String,
+}
+
+/// Appends an action to the audit log
+///
+/// Failures are logged; the action itself has already been carried out.
+pub fn record(source: Option<IpAddr>, action: &str, status: u16, detail: &[u8]) {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let source = source.map_or_else(|| "unknown".to_string(), |source| source.to_string());
+    let detail: String = String::from_utf8_lossy(detail)
+        .chars()
+        .map(|character| if character.is_control() {' '} else {character})
+        .take(MAX_DETAIL)
+        .collect();
+    let line = format!("{}\t{}\t{}\t{}\t{}\n", since_epoch.as_secs(), source, action, status, detail.trim());
+
+    let audit_path = stations_root().join(AUDIT_FILE);
+    rotate_if_full(&audit_path);
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&audit_path)
+        .and_then(|mut audit| audit.write_all(line.as_bytes()));
+    if let Err(e) = written {
+        eprintln!("Failed to record {} from {} in {}: {}", action, source, audit_path.display(), e);
+    }
+}
+
+/// Moves a full log aside, so the next entry starts a new one
+fn rotate_if_full(audit_path: &Path) {
+    let full = fs::metadata(audit_path).is_ok_and(|metadata| metadata.len() >= MAX_AUDIT_BYTES);
+    if !full {
+        return;
+    }
+    if let Err(e) = fs::rename(audit_path, stations_root().join(ROTATED_AUDIT_FILE)) {
+        eprintln!("Failed to rotate {}: {}", audit_path.display(), e);
+    }
+}
+
+/// Reads the most recent actions, oldest first
+///
+/// Reaches back into the rotated log when the current one is short.
+///
+/// # Arguments
+/// * `count` - Most entries to return
+pub fn recent(count: usize) -> Vec<AuditRecord> {
+    let mut records = read_records(&stations_root().join(AUDIT_FILE));
+    if records.len() < count {
+        let mut older = read_records(&stations_root().join(ROTATED_AUDIT_FILE));
+        older.append(&mut records);
+        records = older;
+    }
+    records.split_off(records.len().saturating_sub(count))
+}
+
+/// Reads every entry in a log file; a missing file has none
+fn read_records(audit_path: &Path) -> Vec<AuditRecord> {
+    let Ok(audit) = read_to_string(audit_path) else {return Vec::new();};
+    audit.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            Some(AuditRecord {
+                time: fields.next()?.parse().ok()?,
+                source: fields.next()?.to_string(),
+                action: fields.next()?.to_string(),
+                status: fields.next()?.parse().ok()?,
+                detail: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,331 @@
+//! Companion Protocol - Remote control for a phone app
+//!
+//! A small JSON-over-HTTP protocol on the API server for a companion app
+//! (or curl). Every request must carry the `companion_token` from
+//! radio.info as a bearer token; with no token configured the protocol is
+//! switched off. Stations are named by band and dial slot, as in the rest
+//! of the API.
+//!
+//! | Request                 | Body                                      | Answer                     |
+//! |-------------------------|-------------------------------------------|----------------------------|
+//! | `POST /companion/pair`  |                                           | protocol version and bands |
+//! | `GET /companion/status` |                                           | `RadioStatus`              |
+//! | `POST /companion/tune`  | `{"band": "FM", "index": 3}`              |                            |
+//! | `POST /companion/volume`| `{"level": 0.5}`                          |                            |
+//! | `POST /companion/skip`  |                                           |                            |
+//! | `POST /companion/pause` | `{"paused": true}`                        |                            |
+//! | `POST /companion/mute`  | `{"muted": true}`                         |                            |
+//! | `POST /companion/preview`| `{"band": "FM", "index": 3, "count": 5}` | list of `TrackPreview`     |
+//! | `POST /companion/queue` | `{"band": "FM", "index": 3, "edit": "Pin", "file": "/path/to/track.mp3"}` | |
+//! | `POST /companion/position`| `{"band": "FM", "index": 3}`            | `ChronologicPosition`      |
+//! | `POST /companion/start_from`| `{"band": "FM", "index": 3, "start_from": "2021-09-01"}` | |
+//! | `POST /companion/split` | `{"band": "FM", "index": 3}`, or none to end it |     |
+//! | `GET /companion/audit`  |                                           | latest `AuditRecord`s      |
+//! | `GET /companion/snapshot`|                                          | `RadioSnapshot`, for troubleshooting |
+//! | `POST /companion/clone` | `{"band": "FM", "index": 3, "to_band": "FM", "to_index": 7}`, optionally with `name` and `playlist` | |
+//! | `POST /companion/edit`  | `{"band": "FM", "index": 3, "settings": {"name": "Jazz", "play_type": "Shuffle"}}` | |
+//!
+//! `edit` is one of `Pin`, `Inject`, `Announce` or `Remove`, and `file`
+//! must be in the station's playlist. `/companion/edit` sets top-level
+//! station.info fields and reloads the station; an edit that leaves
+//! station.info unparseable is refused. Requests
+//! without an answer reply `{"ok": true}`; failures reply with an HTTP
+//! error status and `{"error": "..."}`. Cloning onto a slot that's
+//! already set up is refused with 409. Requests that change the radio,
+//! and requests with a wrong token, go in the audit log.
+//!
+//! ```sh
+//! curl -X POST -H "Authorization: Bearer $TOKEN" -d '{"band":"FM","index":3}' http://radio:8081/companion/tune
+//! ```
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::api::{ApiError, RadioApi, VolumeRequest, audit};
+use crate::api::server::Request;
+use crate::config::radio_config;
+use crate::constants;
+use crate::messages::QueueEdit;
+use crate::radio::station::content::{Band, StationID};
+
+/// Bumped when requests or answers change incompatibly
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Tracks previewed when a request doesn't say
+const DEFAULT_PREVIEW_COUNT: usize = 5;
+
+/// Most tracks a preview will list, however many are asked for
+const MAX_PREVIEW_COUNT: usize = 50;
+
+/// Audit log entries returned by `/companion/audit`
+const AUDIT_ENTRIES: usize = 100;
+
+/// Commands that only read, and so aren't audited
+const READ_ONLY_COMMANDS: [&str; 6] = ["pair", "status", "preview", "position", "audit", "snapshot"];
+
+/// A station on the dial
+#[derive(Deserialize)]
+struct StationRequest {
+    band: String,
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct PauseRequest {
+    paused: bool,
+}
+
+#[derive(Deserialize)]
+struct MuteRequest {
+    muted: bool,
+}
+
+#[derive(Deserialize)]
+struct PreviewRequest {
+    #[serde(flatten)]
+    station: StationRequest,
+    count: Option<usize>,
+}
+
+/// Kind of queue edit, named as in `QueueEdit`
+#[derive(Deserialize)]
+enum EditKind {
+    Pin,
+    Inject,
+    Announce,
+    Remove,
+}
+
+#[derive(Deserialize)]
+struct StartFromRequest {
+    #[serde(flatten)]
+    station: StationRequest,
+    start_from: String,
+}
+
+/// Copy of a station for an empty slot (see `config::cloning`)
+#[derive(Deserialize)]
+struct CloneRequest {
+    #[serde(flatten)]
+    station: StationRequest,
+    to_band: String,
+    to_index: usize,
+    name: Option<String>,
+    playlist: Option<PathBuf>,
+}
+
+/// Fields to change in a station's station.info
+#[derive(Deserialize)]
+struct EditRequest {
+    #[serde(flatten)]
+    station: StationRequest,
+    settings: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct QueueRequest {
+    #[serde(flatten)]
+    station: StationRequest,
+    edit: EditKind,
+    file: PathBuf,
+}
+
+/// Answers a companion request
+///
+/// # Arguments
+/// * `command` - Request path after `/companion/`
+/// * `request` - The full request, for its method, token and body
+/// * `api` - Handle to the Station Manager
+///
+/// # Returns
+/// HTTP status and JSON body
+pub fn handle(command: &str, request: &Request, api: &RadioApi) -> (u16, String) {
+    if let Some((status, message)) = check_token(request) {
+        if status == 401 {
+            audit::record(request.peer, command, status, &request.body);
+        }
+        return error(status, message);
+    }
+
+    let (status, body) = dispatch(command, request, api);
+    if !READ_ONLY_COMMANDS.contains(&command) {
+        audit::record(request.peer, command, status, &request.body);
+    }
+    (status, body)
+}
+
+/// Checks a request's bearer token against the `companion_token` in radio.info
+///
+/// # Returns
+/// None if the request may go ahead, otherwise the HTTP status and
+/// message to turn it away with
+pub fn check_token(request: &Request) -> Option<(u16, &'static str)> {
+    let Some(token) = radio_config().companion_token.as_deref() else {
+        return Some((403, "companion control is not enabled"));
+    };
+    let presented = request.header("authorization").and_then(|value| value.strip_prefix("Bearer "));
+    if !presented.is_some_and(|presented| tokens_match(presented.trim(), token)) {
+        return Some((401, "missing or wrong token"));
+    }
+    None
+}
+
+/// Carries out an authenticated companion request
+fn dispatch(command: &str, request: &Request, api: &RadioApi) -> (u16, String) {
+    let expected_method = if matches!(command, "status" | "audit" | "snapshot") {"GET"} else {"POST"};
+    if request.method != expected_method {
+        return error(405, &format!("{} expects {}", command, expected_method));
+    }
+
+    let result = match command {
+        "pair" => Ok(answer(json!({
+            "protocol": PROTOCOL_VERSION,
+            "bands": Band::all().map(|band| band.name()).collect::<Vec<_>>(),
+            "stations_per_band": constants::NUMBER_OF_STATIONS,
+        }))),
+        "status" => api.status().map(answer),
+        "snapshot" => api.snapshot().map(answer),
+        "tune" => {
+            let Some(station) = parse::<StationRequest>(&request.body).and_then(into_station) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            api.tune(station).map(|_| done())
+        },
+        "volume" => {
+            let Some(volume) = parse::<VolumeRequest>(&request.body) else {
+                return error(400, "expected a level from 0.0 to 1.0");
+            };
+            api.set_volume(volume.level.clamp(0.0, 1.0)).map(|_| done())
+        },
+        "skip" => api.skip_track().map(|_| done()),
+        "pause" => {
+            let Some(pause) = parse::<PauseRequest>(&request.body) else {
+                return error(400, "expected paused true or false");
+            };
+            api.pause_all(pause.paused).map(|_| done())
+        },
+        "mute" => {
+            let Some(mute) = parse::<MuteRequest>(&request.body) else {
+                return error(400, "expected muted true or false");
+            };
+            api.mute(mute.muted).map(|_| done())
+        },
+        "split" => {
+            if !radio_config().dual_mono {
+                return error(403, "dual-mono output is not enabled");
+            }
+            let station = if request.body.trim_ascii().is_empty() {
+                None
+            } else {
+                let Some(station) = parse::<StationRequest>(&request.body).and_then(into_station) else {
+                    return error(400, "expected a band and index on the dial, or no body");
+                };
+                Some(station)
+            };
+            api.split_station(station).map(|_| done())
+        },
+        "audit" => Ok(answer(audit::recent(AUDIT_ENTRIES))),
+        "position" => {
+            let Some(station) = parse::<StationRequest>(&request.body).and_then(into_station) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            api.chronologic_position(station).map(answer)
+        },
+        "start_from" => {
+            let Some(start) = parse::<StartFromRequest>(&request.body) else {
+                return error(400, "expected a band, index and start_from date or episode");
+            };
+            let Some(station) = into_station(start.station) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            api.start_chronologic_from(station, &start.start_from).map(|_| done())
+        },
+        "preview" => {
+            let Some(preview) = parse::<PreviewRequest>(&request.body) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            let count = preview.count.unwrap_or(DEFAULT_PREVIEW_COUNT).min(MAX_PREVIEW_COUNT);
+            let Some(station) = into_station(preview.station) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            api.preview_station(station, count).map(answer)
+        },
+        "queue" => {
+            let Some(queue) = parse::<QueueRequest>(&request.body) else {
+                return error(400, "expected a band, index, edit and file");
+            };
+            let edit = match queue.edit {
+                EditKind::Pin => QueueEdit::Pin(queue.file),
+                EditKind::Inject => QueueEdit::Inject(queue.file),
+                EditKind::Announce => QueueEdit::Announce(queue.file),
+                EditKind::Remove => QueueEdit::Remove(queue.file),
+            };
+            let Some(station) = into_station(queue.station) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            api.edit_queue(station, edit).map(|_| done())
+        },
+        "clone" => {
+            let Some(clone) = parse::<CloneRequest>(&request.body) else {
+                return error(400, "expected a band, index, to_band and to_index");
+            };
+            let to = StationRequest { band: clone.to_band, index: clone.to_index };
+            let (Some(from), Some(to)) = (into_station(clone.station), into_station(to)) else {
+                return error(400, "expected a band and index on the dial for both stations");
+            };
+            api.clone_station(from, to, clone.name.as_deref(), clone.playlist.as_deref()).map(|_| done())
+        },
+        "edit" => {
+            let Some(edit) = parse::<EditRequest>(&request.body) else {
+                return error(400, "expected a band, index and settings object");
+            };
+            let Some(station) = into_station(edit.station) else {
+                return error(400, "expected a band and index on the dial");
+            };
+            api.edit_station(station, edit.settings).map(|_| done())
+        },
+        _ => return error(404, &format!("no companion command {}", command))
+    };
+
+    result.unwrap_or_else(|e: ApiError| match e {
+        ApiError::Refused(reason) => error(409, &reason),
+        e => error(503, &e.to_string())
+    })
+}
+
+/// Parses a JSON request body
+fn parse<T: for<'de> Deserialize<'de>>(body: &[u8]) -> Option<T> {
+    serde_json::from_slice(body).ok()
+}
+
+/// Resolves a band name and slot to a station on the dial
+fn into_station(request: StationRequest) -> Option<StationID> {
+    let band = Band::all().find(|band| band.name().eq_ignore_ascii_case(&request.band))?;
+    (request.index < constants::NUMBER_OF_STATIONS).then_some(StationID { band, index: request.index })
+}
+
+/// Compares tokens without stopping at the first difference
+fn tokens_match(presented: &str, token: &str) -> bool {
+    presented.len() == token.len()
+        && presented.bytes().zip(token.bytes()).fold(0u8, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+/// Answers with a value as JSON
+pub fn answer(value: impl Serialize) -> (u16, String) {
+    match serde_json::to_string(&value) {
+        Ok(body) => (200, body),
+        Err(e) => error(500, &e.to_string())
+    }
+}
+
+/// Answers a request that has nothing to report
+pub fn done() -> (u16, String) {
+    (200, json!({ "ok": true }).to_string())
+}
+
+/// Answers with an error status and message
+pub fn error(status: u16, message: &str) -> (u16, String) {
+    (status, json!({ "error": message }).to_string())
+}
@@ -0,0 +1,208 @@
+//! Event Stream - Live radio events over WebSocket
+//!
+//! The Station Manager publishes a `RadioEvent` whenever the dial lands on
+//! another station, a track starts or ends, the volume changes or a track
+//! fails to load. With `api_server` set in radio.info, clients can connect
+//! to `ws://<address>/events` and get each event as a JSON text message
+//! the moment it happens, so the dashboard and visualizers don't need to
+//! poll `RadioApi::status()`. Clients need the companion token, sent as a
+//! bearer token like companion requests, and at most `MAX_STREAMS` can be
+//! connected at once, since each holds a thread.
+//!
+//! The stream only goes one way. Anything a client sends is ignored, and
+//! a client is dropped as soon as a write to it fails; pings every
+//! `PING_INTERVAL` find clients that went away without closing.
+
+use std::io::{self, Write};
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::api::server::{Request, respond};
+use crate::messages::RadioEvent;
+
+/// Event streams that can be open at once
+const MAX_STREAMS: usize = 8;
+
+/// Quiet time after which a client is pinged to check it's still there
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Appended to the client's key to prove the server speaks WebSocket (RFC 6455)
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_PING: u8 = 0x9;
+
+/// Fans events out to every connected client
+#[derive(Clone, Default)]
+pub struct EventHub {
+    /// One channel per client, carrying events already encoded as JSON
+    subscribers: Arc<Mutex<Vec<Sender<String>>>>,
+
+    /// Event streams open now
+    streams: Arc<AtomicUsize>,
+}
+
+/// An open event stream's place in the limit, given back when dropped
+struct StreamSlot {
+    streams: Arc<AtomicUsize>,
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        self.streams.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        EventHub::default()
+    }
+
+    /// Sends an event to every client, forgetting clients that have gone
+    pub fn publish(&self, event: &RadioEvent) {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if subscribers.is_empty() {
+            return;
+        }
+        let Ok(json) = serde_json::to_string(event) else {return;};
+        subscribers.retain(|subscriber| subscriber.send(json.clone()).is_ok());
+    }
+
+    /// Takes a place for a new event stream
+    ///
+    /// # Returns
+    /// The place, or None if `MAX_STREAMS` are already open
+    fn open_stream(&self) -> Option<StreamSlot> {
+        self.streams.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |open| (open < MAX_STREAMS).then_some(open + 1)).ok()?;
+        Some(StreamSlot { streams: self.streams.clone() })
+    }
+
+    /// Returns a channel that receives every event published from now on
+    pub fn subscribe(&self) -> Receiver<String> {
+        let (subscriber, events) = channel();
+        self.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(subscriber);
+        events
+    }
+}
+
+/// Upgrades a connection to WebSocket and writes events to it until it fails
+///
+/// The request's token has already been checked by the server.
+pub fn stream_events(mut stream: TcpStream, request: &Request, hub: &EventHub) -> io::Result<()> {
+    let Some(_slot) = hub.open_stream() else {
+        return respond(&mut stream, 503, r#"{"error":"too many event streams open"}"#);
+    };
+    let Some(key) = request.header("sec-websocket-key") else {
+        respond(&mut stream, 400, r#"{"error":"not a WebSocket upgrade"}"#)?;
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "event stream request without a WebSocket key"));
+    };
+
+    let accept = accept_key(key);
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    )?;
+
+    let events = hub.subscribe();
+    loop {
+        match events.recv_timeout(PING_INTERVAL) {
+            Ok(event) => write_frame(&mut stream, OPCODE_TEXT, event.as_bytes())?,
+            Err(RecvTimeoutError::Timeout) => write_frame(&mut stream, OPCODE_PING, &[])?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Returns the `Sec-WebSocket-Accept` value answering a client's key
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+/// Writes one unmasked, unfragmented frame
+fn write_frame(stream: &mut TcpStream, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        length @ 0..=125 => frame.push(length as u8),
+        length @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        },
+        length => {
+            frame.push(127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame)
+}
+
+/// SHA-1 digest, needed only for the handshake's accept key
+fn sha1(message: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0];
+
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+
+    for block in padded.chunks(64) {
+        let mut words = [0u32; 80];
+        for (word, bytes) in words.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, added) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(added);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard padded base64
+fn base64(bytes: &[u8]) -> String {
+    bytes.chunks(3)
+        .flat_map(|chunk| {
+            let group = chunk.iter().enumerate()
+                .fold(0u32, |group, (i, byte)| group | ((*byte as u32) << (16 - 8 * i)));
+            (0..4).map(move |i| {
+                if i <= chunk.len() {
+                    BASE64_ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char
+                } else {
+                    '='
+                }
+            })
+        })
+        .collect()
+}
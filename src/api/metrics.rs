@@ -0,0 +1,126 @@
+//! Metrics - Playback and hardware stats for Prometheus
+//!
+//! `GET /metrics` answers in the Prometheus text format, so a scraper can
+//! line audio glitches up with what the Pi was doing at the time. Playback
+//! gauges come from the Station Manager's status; hardware stats are read
+//! straight from the kernel and firmware on every scrape:
+//!
+//! - CPU temperature, from the first thermal zone
+//! - Throttling flags from the firmware (`vcgencmd get_throttled`, or the
+//!   firmware's sysfs node where the kernel has one): under-voltage,
+//!   frequency capping, throttling and the soft temperature limit, each
+//!   both now and since boot
+//! - Time the CPUs sat waiting on IO, and time the SD card spent busy,
+//!   as counters to take a `rate()` of
+//!
+//! Stats that can't be read (on a desktop, say) are left out.
+//!
+//! Scrapes are rate limited like other requests, and need the companion
+//! token as a bearer token (Prometheus' `authorization` setting) unless
+//! `public_metrics` is set in radio.info.
+
+use std::fmt::Write;
+use std::fs::read_to_string;
+use std::process::Command;
+
+use crate::api::RadioApi;
+
+/// Firmware's throttling flags, where the kernel exposes them
+const THROTTLED_NODE: &str = "/sys/devices/platform/soc/soc:firmware/get_throttled";
+
+/// CPU temperature in millidegrees
+const THERMAL_ZONE: &str = "/sys/class/thermal/thermal_zone0/temp";
+
+/// Block device of the SD card
+const SD_DEVICE: &str = "mmcblk0";
+
+/// Kernel clock ticks per second in /proc/stat
+const USER_HZ: f64 = 100.0;
+
+/// Throttling flag bits: (bit, metric label) for the current state;
+/// the same flag since boot is 16 bits higher
+const THROTTLE_FLAGS: [(u32, &str); 4] = [
+    (0, "undervoltage"),
+    (1, "frequency_capped"),
+    (2, "throttled"),
+    (3, "soft_temperature_limit"),
+];
+
+/// Renders every metric in the Prometheus text format
+pub fn render(api: &RadioApi) -> String {
+    let mut metrics = String::new();
+
+    if let Ok(status) = api.status() {
+        gauge(&mut metrics, "mokradio_on_air", "Whether the tuned station is broadcasting", status.on_air as u8 as f64);
+        gauge(&mut metrics, "mokradio_volume", "Master volume, 0 to 1", status.volume as f64);
+        gauge(&mut metrics, "mokradio_paused", "Whether every station is paused", status.paused as u8 as f64);
+        gauge(&mut metrics, "mokradio_muted", "Whether the output is muted", status.muted as u8 as f64);
+        gauge(&mut metrics, "mokradio_faulted_stations", "Stations off air after repeated load errors", status.faulted.len() as f64);
+    }
+
+    if let Some(temperature) = cpu_temperature() {
+        gauge(&mut metrics, "mokradio_cpu_temperature_celsius", "SoC temperature", temperature);
+    }
+    if let Some(flags) = throttled_flags() {
+        let _ = writeln!(metrics, "# HELP mokradio_throttle_state Firmware throttling flags, now and since boot");
+        let _ = writeln!(metrics, "# TYPE mokradio_throttle_state gauge");
+        for (bit, flag) in THROTTLE_FLAGS {
+            let now = (flags >> bit) & 1;
+            let since_boot = (flags >> (bit + 16)) & 1;
+            let _ = writeln!(metrics, "mokradio_throttle_state{{flag=\"{}\",when=\"now\"}} {}", flag, now);
+            let _ = writeln!(metrics, "mokradio_throttle_state{{flag=\"{}\",when=\"since_boot\"}} {}", flag, since_boot);
+        }
+    }
+    if let Some(iowait) = cpu_iowait_seconds() {
+        counter(&mut metrics, "mokradio_cpu_iowait_seconds_total", "Time the CPUs sat idle waiting on IO", iowait);
+    }
+    if let Some(busy) = sd_busy_seconds() {
+        counter(&mut metrics, "mokradio_sd_io_seconds_total", "Time the SD card spent doing IO", busy);
+    }
+    metrics
+}
+
+fn gauge(metrics: &mut String, name: &str, help: &str, value: f64) {
+    let _ = write!(metrics, "# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n");
+}
+
+fn counter(metrics: &mut String, name: &str, help: &str, value: f64) {
+    let _ = write!(metrics, "# HELP {name} {help}\n# TYPE {name} counter\n{name} {value}\n");
+}
+
+/// Reads the SoC temperature in degrees Celsius
+fn cpu_temperature() -> Option<f64> {
+    let millidegrees: f64 = read_to_string(THERMAL_ZONE).ok()?.trim().parse().ok()?;
+    Some(millidegrees / 1000.0)
+}
+
+/// Reads the firmware's throttling flags
+fn throttled_flags() -> Option<u32> {
+    if let Some(flags) = read_to_string(THROTTLED_NODE).ok().and_then(|flags| u32::from_str_radix(flags.trim(), 16).ok()) {
+        return Some(flags);
+    }
+    // "throttled=0x50005"
+    let output = Command::new("vcgencmd").arg("get_throttled").output().ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let flags = output.trim().strip_prefix("throttled=")?;
+    u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok()
+}
+
+/// Reads the total time the CPUs have spent waiting on IO since boot
+fn cpu_iowait_seconds() -> Option<f64> {
+    let stat = read_to_string("/proc/stat").ok()?;
+    // cpu  user nice system idle iowait ...
+    let iowait: f64 = stat.lines().next()?.split_whitespace().nth(5)?.parse().ok()?;
+    Some(iowait / USER_HZ)
+}
+
+/// Reads the total time the SD card has been busy since boot
+fn sd_busy_seconds() -> Option<f64> {
+    let diskstats = read_to_string("/proc/diskstats").ok()?;
+    // major minor name reads ... (the tenth stat is milliseconds spent doing IO)
+    let fields: Vec<&str> = diskstats.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .find(|fields| fields.get(2) == Some(&SD_DEVICE))?;
+    let busy_ms: f64 = fields.get(12)?.parse().ok()?;
+    Some(busy_ms / 1000.0)
+}
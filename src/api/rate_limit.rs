@@ -0,0 +1,85 @@
+//! Rate Limiting - Keeps one client from hammering the radio
+//!
+//! Each client IP address gets a bucket of `burst` requests that refills
+//! at `requests_per_minute`. Every companion request takes one from the
+//! bucket, and a request with a wrong token takes `failed_auth_cost` (even
+//! into debt), so guessing the token gets slow quickly. A client with an
+//! empty bucket is answered 429 until it has refilled.
+//!
+//! # JSON Format
+//! ```json
+//! "rate_limit": { "requests_per_minute": 30, "burst": 10, "failed_auth_cost": 5 }
+//! ```
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::config::radio_config;
+
+/// Buckets untouched for this long are full again and can be forgotten
+const FORGET_AFTER_SECS: f64 = 3600.0;
+
+/// A client's remaining allowance
+struct Bucket {
+    requests: f64,
+    refilled: Instant,
+}
+
+/// Request allowances of every client seen recently
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<Option<IpAddr>, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        RateLimiter::default()
+    }
+
+    /// Takes one request from a client's allowance
+    ///
+    /// # Arguments
+    /// * `client` - Client address (clients without one share a bucket)
+    ///
+    /// # Returns
+    /// `true` if the client had a request left; nothing is taken otherwise
+    pub fn take(&self, client: Option<IpAddr>) -> bool {
+        self.with_bucket(client, |bucket| {
+            if bucket.requests < 1.0 {
+                return false;
+            }
+            bucket.requests -= 1.0;
+            true
+        })
+    }
+
+    /// Charges a client for a request with a wrong token
+    ///
+    /// The allowance can go below zero, down to minus `burst`, so repeated
+    /// guesses keep a client waiting longer.
+    pub fn charge_failed_auth(&self, client: Option<IpAddr>) {
+        let config = &radio_config().rate_limit;
+        self.with_bucket(client, |bucket| {
+            bucket.requests = (bucket.requests - config.failed_auth_cost).max(-config.burst);
+        });
+    }
+
+    /// Refills a client's bucket for the time since it was last used, then
+    /// runs `action` on it
+    fn with_bucket<T>(&self, client: Option<IpAddr>, action: impl FnOnce(&mut Bucket) -> T) -> T {
+        let config = &radio_config().rate_limit;
+        let refill_per_second = config.requests_per_minute / 60.0;
+        let now = Instant::now();
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        buckets.retain(|_, bucket| now.duration_since(bucket.refilled).as_secs_f64() < FORGET_AFTER_SECS);
+
+        let bucket = buckets.entry(client).or_insert(Bucket { requests: config.burst, refilled: now });
+        let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+        bucket.requests = (bucket.requests + elapsed * refill_per_second).min(config.burst);
+        bucket.refilled = now;
+        action(bucket)
+    }
+}
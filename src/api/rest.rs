@@ -0,0 +1,127 @@
+//! REST API - Plain HTTP control for scripts and home automation
+//!
+//! The everyday controls as resources, for clients that would rather not
+//! speak the companion protocol: a home automation rule can `POST
+//! /skip`, and a script can `GET /status`. Requests need the same bearer
+//! token as the companion protocol, count against the same rate limit,
+//! and changes go in the same audit log.
+//!
+//! | Request                        | Body               | Answer                    |
+//! |--------------------------------|--------------------|---------------------------|
+//! | `GET /status`                  |                    | `RadioStatus`             |
+//! | `GET /stations`                |                    | list of `StationListing`  |
+//! | `POST /tune/{band}/{index}`    |                    |                           |
+//! | `POST /skip`                   |                    |                           |
+//! | `GET /volume`                  |                    | `{"level": 0.5}`          |
+//! | `PUT /volume`                  | `{"level": 0.5}`   |                           |
+//!
+//! Answers and errors are JSON as in the companion protocol.
+//!
+//! ```sh
+//! curl -X POST -H "Authorization: Bearer $TOKEN" http://radio:8081/tune/FM/3
+//! ```
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::api::companion::{answer, check_token, done, error};
+use crate::api::{ApiError, RadioApi, VolumeRequest, audit};
+use crate::api::server::Request;
+use crate::constants;
+use crate::radio::station::content::{Band, StationID};
+
+/// One station on the dial, as listed by `/stations`
+#[derive(Debug, Clone, Serialize)]
+pub struct StationListing {
+    /// Band and dial slot, e.g. "FM 03"
+    pub station: String,
+    pub band: String,
+    pub index: usize,
+    pub name: String,
+
+    /// `play_type` the station runs as
+    pub play_type: String,
+
+    /// Whether the dial hears the station (rather than static)
+    pub on_air: bool,
+}
+
+/// Answers a REST request
+///
+/// # Arguments
+/// * `request` - The full request, for its method, path, token and body
+/// * `api` - Handle to the Station Manager
+///
+/// # Returns
+/// HTTP status and JSON body, or None if no resource has the request's path
+pub fn handle(request: &Request, api: &RadioApi) -> Option<(u16, String)> {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let resource = *segments.first()?;
+    if !matches!(resource, "status" | "stations" | "tune" | "skip" | "volume") {
+        return None;
+    }
+
+    if let Some((status, message)) = check_token(request) {
+        if status == 401 {
+            audit::record(request.peer, resource, status, request.path.as_bytes());
+        }
+        return Some(error(status, message));
+    }
+
+    let (status, body) = dispatch(&segments, request, api);
+    if request.method != "GET" {
+        audit::record(request.peer, resource, status, request.path.as_bytes());
+    }
+    Some((status, body))
+}
+
+/// Carries out an authenticated REST request
+fn dispatch(segments: &[&str], request: &Request, api: &RadioApi) -> (u16, String) {
+    let result = match (request.method.as_str(), segments) {
+        ("GET", ["status"]) => api.status().map(answer),
+        ("GET", ["stations"]) => api.snapshot().map(|snapshot| {
+            // Snapshots name stations as "FM 03"
+            let listings: Vec<StationListing> = snapshot.stations.iter()
+                .filter_map(|station| {
+                    let (band, index) = station.station.rsplit_once(' ')?;
+                    Some(StationListing {
+                        station: station.station.clone(),
+                        band: band.to_string(),
+                        index: index.parse().ok()?,
+                        name: station.name.clone(),
+                        play_type: station.play_type.clone(),
+                        on_air: station.on_air && !station.restricted,
+                    })
+                })
+                .collect();
+            answer(listings)
+        }),
+        ("POST", ["tune", band, index]) => {
+            let Some(station) = station_at(band, index) else {
+                return error(404, &format!("no station {} {} on the dial", band, index));
+            };
+            api.tune(station).map(|_| done())
+        },
+        ("POST", ["skip"]) => api.skip_track().map(|_| done()),
+        ("GET", ["volume"]) => api.status().map(|status| answer(json!({ "level": status.volume }))),
+        ("PUT", ["volume"]) => {
+            let Ok(volume) = serde_json::from_slice::<VolumeRequest>(&request.body) else {
+                return error(400, "expected a level from 0.0 to 1.0");
+            };
+            api.set_volume(volume.level.clamp(0.0, 1.0)).map(|_| done())
+        },
+        (method, _) if matches!(segments, ["status"] | ["stations"] | ["skip"] | ["volume"] | ["tune", _, _]) => {
+            return error(405, &format!("{} is not supported on {}", method, request.path));
+        },
+        _ => return error(404, &format!("no resource {}", request.path))
+    };
+
+    result.unwrap_or_else(|e: ApiError| error(503, &e.to_string()))
+}
+
+/// Resolves a band name and slot from a path to a station on the dial
+fn station_at(band: &str, index: &str) -> Option<StationID> {
+    let band = Band::all().find(|candidate| candidate.name().eq_ignore_ascii_case(band))?;
+    let index: usize = index.parse().ok()?;
+    (index < constants::NUMBER_OF_STATIONS).then_some(StationID { band, index })
+}
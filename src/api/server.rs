@@ -0,0 +1,205 @@
+//! API Server - HTTP and WebSocket front door for the radio
+//!
+//! One small listener serves the live event stream (`/events`, see
+//! `events`), the companion control protocol (`/companion/...`, see
+//! `companion`), Prometheus metrics (`/metrics`, see `metrics`) and the
+//! REST controls (`/status`, `/tune/...` and so on, see `rest`).
+//! Every connection gets its own thread and carries a single request; the
+//! server answers and closes, except for event streams, which stay open.
+//! Companion, event stream, metrics and REST requests are rate limited
+//! per client (see `rate_limit`). Event streams need the companion token
+//! too, and so do metrics unless `public_metrics` is set in radio.info.
+//!
+//! Only as much HTTP/1.1 as curl and a WebSocket client need is
+//! understood: a request line, headers and a `Content-Length` body.
+//!
+//! # JSON Format
+//! ```json
+//! "api_server": "0.0.0.0:8081"
+//! ```
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::api::{RadioApi, audit, companion, metrics, rest};
+use crate::api::events::{EventHub, stream_events};
+use crate::api::rate_limit::RateLimiter;
+use crate::config::radio_config;
+
+/// Longest a write may block on a client that stopped reading
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest request body accepted
+const MAX_BODY: usize = 64 * 1024;
+
+/// An HTTP request as read off a connection
+pub struct Request {
+    pub method: String,
+
+    /// Path without any query string
+    pub path: String,
+
+    /// Header names lowercased, values trimmed
+    headers: Vec<(String, String)>,
+
+    pub body: Vec<u8>,
+
+    /// Address the request came from
+    pub peer: Option<IpAddr>,
+}
+
+impl Request {
+    /// Returns a header's value, by case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Starts accepting API clients in the background
+///
+/// # Arguments
+/// * `address` - Address and port to listen on, e.g. "0.0.0.0:8081"
+/// * `hub` - Hub the Station Manager publishes live events to
+/// * `api` - Handle for companion commands to the Station Manager
+///
+/// # Errors
+/// Returns the error if the address can't be bound
+pub fn serve(address: &str, hub: EventHub, api: RadioApi) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    let limiter = Arc::new(RateLimiter::new());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {continue;};
+            let (hub, api, limiter) = (hub.clone(), api.clone(), limiter.clone());
+            thread::spawn(move || {
+                let peer = stream.peer_addr().map(|peer| peer.to_string()).unwrap_or_default();
+                if let Err(e) = handle_connection(stream, &hub, &api, &limiter) {
+                    eprintln!("API client {} dropped: {}", peer, e);
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Reads one request and routes it
+fn handle_connection(mut stream: TcpStream, hub: &EventHub, api: &RadioApi, limiter: &RateLimiter) -> io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let request = read_request(&stream)?;
+
+    if request.path == "/events" {
+        if !limiter.take(request.peer) {
+            return respond(&mut stream, 429, r#"{"error":"too many requests, slow down"}"#);
+        }
+        if let Some((status, message)) = companion::check_token(&request) {
+            if status == 401 {
+                limiter.charge_failed_auth(request.peer);
+            }
+            return respond(&mut stream, status, &companion::error(status, message).1);
+        }
+        return stream_events(stream, &request, hub);
+    }
+    if request.path == "/metrics" {
+        if !limiter.take(request.peer) {
+            return respond(&mut stream, 429, r#"{"error":"too many requests, slow down"}"#);
+        }
+        if !radio_config().public_metrics && let Some((status, message)) = companion::check_token(&request) {
+            if status == 401 {
+                limiter.charge_failed_auth(request.peer);
+            }
+            return respond(&mut stream, status, &companion::error(status, message).1);
+        }
+        return respond_with(&mut stream, 200, "text/plain; version=0.0.4", &metrics::render(api));
+    }
+    if let Some(command) = request.path.strip_prefix("/companion/") {
+        if !limiter.take(request.peer) {
+            audit::record(request.peer, command, 429, &request.body);
+            return respond(&mut stream, 429, r#"{"error":"too many requests, slow down"}"#);
+        }
+        let (status, body) = companion::handle(command, &request, api);
+        if status == 401 {
+            limiter.charge_failed_auth(request.peer);
+        }
+        return respond(&mut stream, status, &body);
+    }
+    if !limiter.take(request.peer) {
+        return respond(&mut stream, 429, r#"{"error":"too many requests, slow down"}"#);
+    }
+    if let Some((status, body)) = rest::handle(&request, api) {
+        if status == 401 {
+            limiter.charge_failed_auth(request.peer);
+        }
+        return respond(&mut stream, status, &body);
+    }
+    respond(&mut stream, 404, r#"{"error":"not found"}"#)
+}
+
+/// Reads a request line, headers and body
+fn read_request(stream: &TcpStream) -> io::Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("bad request line: {}", request_line.trim())));
+    };
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let method = method.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let peer = stream.peer_addr().ok().map(|address| address.ip());
+    let mut request = Request { method, path, headers, body: Vec::new(), peer };
+    let length = request.header("content-length").and_then(|length| length.parse::<usize>().ok()).unwrap_or(0);
+    if length > MAX_BODY {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("request body of {} bytes is too large", length)));
+    }
+    request.body.resize(length, 0);
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+/// Writes a JSON response and ends the exchange
+pub fn respond(stream: &mut TcpStream, status: u16, body: &str) -> io::Result<()> {
+    respond_with(stream, status, "application/json", body)
+}
+
+/// Writes a response of any content type and ends the exchange
+fn respond_with(stream: &mut TcpStream, status: u16, content_type: &str, body: &str) -> io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Error"
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, content_type, body.len(), body
+    )
+}
@@ -0,0 +1,31 @@
+// Audio module - rodio sources and wrappers shared by stations
+use rodio::Source;
+
+/// Decoded or generated audio ready to append to a station's sink
+pub type AudioSource = Box<dyn Source + Send>;
+
+pub mod ambience;
+pub mod broadcast_delay;
+pub mod droppable;
+pub mod equalizer;
+pub mod finished;
+pub mod headphone_jack;
+pub mod http_stream;
+pub mod level_meter;
+pub mod loudness;
+pub mod monitor;
+pub mod mute_relay;
+pub mod noise_gate;
+pub mod output;
+pub mod seekable;
+pub mod slew;
+pub mod split;
+pub mod static_gen;
+pub mod stereo;
+pub mod talk_over;
+pub mod test_pattern;
+pub mod test_signal;
+pub mod time_signal;
+pub mod volume;
+pub mod vu_meter;
+pub mod warm_start;
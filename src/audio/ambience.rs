@@ -0,0 +1,248 @@
+//! Ambience Generators - Synthesized filler for empty dial positions
+//!
+//! Stations that need no audio files at all, just a `play_type` in
+//! station.info:
+//! - "Numbers": an interval melody, then groups of random digits sent as
+//!   DTMF tones, repeated after a pause
+//! - "Morse": a beacon keying its message in Morse code
+//! - "Warble": a drifting shortwave heterodyne whistle
+//!
+//! Each runs forever, a transmission at a time, over a faint noise floor
+//! that fades in and out like a distant signal.
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rodio::{ChannelCount, SampleRate, Source};
+
+use crate::radio::station::config::GeneratorConfig;
+
+/// Format of the generated audio
+const SAMPLE_RATE: SampleRate = 22_050;
+
+/// Level of the tones relative to full scale
+const TONE_LEVEL: f32 = 0.25;
+
+/// Level of the noise floor under every generator
+const NOISE_LEVEL: f32 = 0.03;
+
+/// Slow fading of the whole signal (cycles per second, depth)
+const FADE_RATE: f32 = 0.07;
+const FADE_DEPTH: f32 = 0.4;
+
+/// Length of the attack and release on keyed tones, to avoid clicks
+const KEY_RAMP: Duration = Duration::from_millis(5);
+
+/// Numbers station timing
+const DIGITS_PER_GROUP: usize = 5;
+const DIGIT_LENGTH: Duration = Duration::from_millis(220);
+const DIGIT_GAP: Duration = Duration::from_millis(120);
+const GROUP_GAP: Duration = Duration::from_millis(700);
+
+/// Interval signal played before each numbers transmission (Hz, ms)
+const INTERVAL_SIGNAL: [(f32, u64); 6] = [(523.3, 300), (659.3, 300), (784.0, 300), (659.3, 300), (523.3, 600), (0.0, 600)];
+
+/// Length of each chunk of the continuous warble
+const WARBLE_CHUNK: Duration = Duration::from_secs(1);
+
+/// Kinds of generated station
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorKind {
+    /// Interval melody and DTMF digit groups
+    Numbers,
+
+    /// Morse code beacon
+    Morse,
+
+    /// Shortwave heterodyne whistle
+    Warble,
+}
+
+impl GeneratorKind {
+    /// Returns the generator for a station.info `play_type`, if it names one
+    pub fn from_play_type(play_type: &str) -> Option<GeneratorKind> {
+        match play_type {
+            "Numbers" => Some(GeneratorKind::Numbers),
+            "Morse" => Some(GeneratorKind::Morse),
+            "Warble" => Some(GeneratorKind::Warble),
+            _ => None
+        }
+    }
+}
+
+/// Endless synthesized station audio
+pub struct Ambience {
+    kind: GeneratorKind,
+    config: GeneratorConfig,
+    rng: StdRng,
+
+    /// Current transmission (or warble chunk) and position in it
+    buffer: Vec<f32>,
+    position: usize,
+
+    /// Samples generated so far, for the fade and warble oscillators
+    elapsed: u64,
+
+    /// Phase of the warble carrier, carried across chunks
+    carrier_phase: f32,
+}
+
+/// Creates the source for a generated station
+///
+/// # Arguments
+/// * `kind` - Which generator the station's `play_type` names
+/// * `config` - The station's `generator` settings
+pub fn ambience(kind: GeneratorKind, config: &GeneratorConfig) -> Ambience {
+    let rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng())
+    };
+    Ambience { kind, config: config.clone(), rng, buffer: Vec::new(), position: 0, elapsed: 0, carrier_phase: 0.0 }
+}
+
+impl Ambience {
+    /// Generates the next transmission into the buffer
+    fn generate(&mut self) {
+        let mut buffer = Vec::new();
+        match self.kind {
+            GeneratorKind::Numbers => {
+                for (frequency, millis) in INTERVAL_SIGNAL {
+                    push_tone(&mut buffer, &[frequency], Duration::from_millis(millis));
+                }
+                for _ in 0..self.config.groups.max(1) {
+                    for _ in 0..DIGITS_PER_GROUP {
+                        let digit = self.rng.random_range(0..10);
+                        push_tone(&mut buffer, &dtmf(digit), DIGIT_LENGTH);
+                        push_silence(&mut buffer, DIGIT_GAP);
+                    }
+                    push_silence(&mut buffer, GROUP_GAP);
+                }
+                push_silence(&mut buffer, Duration::from_secs_f32(self.config.pause_secs.max(0.0)));
+            },
+            GeneratorKind::Morse => {
+                let unit = Duration::from_secs_f32(1.2 / self.config.wpm.max(1) as f32);
+                for word in self.config.message.split_whitespace() {
+                    for character in word.chars() {
+                        let Some(code) = morse(character) else {continue;};
+                        for symbol in code.chars() {
+                            let length = if symbol == '-' {unit * 3} else {unit};
+                            push_tone(&mut buffer, &[self.config.tone_hz], length);
+                            push_silence(&mut buffer, unit);
+                        }
+                        // Two more units make the three between letters
+                        push_silence(&mut buffer, unit * 2);
+                    }
+                    // Four more make the seven between words
+                    push_silence(&mut buffer, unit * 4);
+                }
+                push_silence(&mut buffer, Duration::from_secs_f32(self.config.pause_secs.max(0.0)));
+            },
+            GeneratorKind::Warble => {
+                let chunk = (WARBLE_CHUNK.as_secs_f32() * SAMPLE_RATE as f32) as u64;
+                for n in 0..chunk {
+                    let t = (self.elapsed + n) as f32 / SAMPLE_RATE as f32;
+                    let frequency = self.config.tone_hz + self.config.depth_hz * (2.0 * PI * self.config.rate_hz * t).sin();
+                    self.carrier_phase = (self.carrier_phase + 2.0 * PI * frequency / SAMPLE_RATE as f32) % (2.0 * PI);
+                    buffer.push(self.carrier_phase.sin() * TONE_LEVEL);
+                }
+            }
+        }
+        self.buffer = buffer;
+        self.position = 0;
+    }
+}
+
+impl Iterator for Ambience {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.buffer.len() {
+            self.generate();
+        }
+        let signal = self.buffer.get(self.position).copied().unwrap_or(0.0);
+        self.position += 1;
+
+        let t = self.elapsed as f32 / SAMPLE_RATE as f32;
+        self.elapsed += 1;
+        let fade = 1.0 - FADE_DEPTH * (0.5 + 0.5 * (2.0 * PI * FADE_RATE * t).sin());
+        let noise = self.rng.random_range(-NOISE_LEVEL..NOISE_LEVEL);
+
+        Some(signal * fade + noise)
+    }
+}
+
+impl Source for Ambience {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        1
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Appends a keyed tone made of one or more frequencies (0 Hz is a rest)
+fn push_tone(buffer: &mut Vec<f32>, frequencies: &[f32], length: Duration) {
+    let samples = (length.as_secs_f32() * SAMPLE_RATE as f32) as usize;
+    let ramp = ((KEY_RAMP.as_secs_f32() * SAMPLE_RATE as f32) as usize).max(1);
+    let voices = frequencies.iter().filter(|frequency| **frequency > 0.0).count().max(1) as f32;
+
+    buffer.extend((0..samples).map(|n| {
+        let t = n as f32 / SAMPLE_RATE as f32;
+        let envelope = (n.min(samples - n) as f32 / ramp as f32).min(1.0);
+        let tone: f32 = frequencies.iter()
+            .filter(|frequency| **frequency > 0.0)
+            .map(|frequency| (2.0 * PI * frequency * t).sin())
+            .sum();
+        tone / voices * envelope * TONE_LEVEL
+    }));
+}
+
+/// Appends silence
+fn push_silence(buffer: &mut Vec<f32>, length: Duration) {
+    let samples = (length.as_secs_f32() * SAMPLE_RATE as f32) as usize;
+    buffer.extend(std::iter::repeat_n(0.0, samples));
+}
+
+/// DTMF row and column frequencies for a digit
+fn dtmf(digit: u32) -> [f32; 2] {
+    match digit {
+        1 => [697.0, 1209.0],
+        2 => [697.0, 1336.0],
+        3 => [697.0, 1477.0],
+        4 => [770.0, 1209.0],
+        5 => [770.0, 1336.0],
+        6 => [770.0, 1477.0],
+        7 => [852.0, 1209.0],
+        8 => [852.0, 1336.0],
+        9 => [852.0, 1477.0],
+        _ => [941.0, 1336.0]
+    }
+}
+
+/// International Morse code for a character (letters, digits, a little punctuation)
+fn morse(character: char) -> Option<&'static str> {
+    let code = match character.to_ascii_uppercase() {
+        'A' => ".-", 'B' => "-...", 'C' => "-.-.", 'D' => "-..", 'E' => ".",
+        'F' => "..-.", 'G' => "--.", 'H' => "....", 'I' => "..", 'J' => ".---",
+        'K' => "-.-", 'L' => ".-..", 'M' => "--", 'N' => "-.", 'O' => "---",
+        'P' => ".--.", 'Q' => "--.-", 'R' => ".-.", 'S' => "...", 'T' => "-",
+        'U' => "..-", 'V' => "...-", 'W' => ".--", 'X' => "-..-", 'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----", '1' => ".----", '2' => "..---", '3' => "...--", '4' => "....-",
+        '5' => ".....", '6' => "-....", '7' => "--...", '8' => "---..", '9' => "----.",
+        '/' => "-..-.", '?' => "..--..", '.' => ".-.-.-", ',' => "--..--", '=' => "-...-",
+        _ => return None
+    };
+    Some(code)
+}
@@ -0,0 +1,96 @@
+//! Broadcast Delay - A fixed lag behind a live source
+//!
+//! Live stations can run a few seconds behind their source, like the
+//! "profanity delay" on a talk station, which also keeps a stream from
+//! feeding back when its source is playing in the same room. Every sample
+//! read from the source goes into a buffer and comes out `delay` later;
+//! until the buffer has filled, the station plays silence.
+//!
+//! The source is converted to one fixed format first, so a stream that
+//! changes sample rate part way through (or starts out as silence in
+//! another format) comes out of the buffer as it went in.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rodio::source::UniformSourceIterator;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Format audio is held in the buffer in
+const CHANNELS: ChannelCount = 2;
+const SAMPLE_RATE: SampleRate = 44_100;
+
+/// Longest delay a station may ask for; a minute of audio is about 20MB
+pub const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Live source played back a fixed time late
+pub struct Delayed<S: Source> {
+    input: UniformSourceIterator<S>,
+
+    /// Samples waiting to play, oldest first
+    buffer: VecDeque<f32>,
+
+    /// Samples held back (the delay)
+    length: usize,
+
+    /// Set once the source has run out; the buffer then plays out
+    input_ended: bool,
+}
+
+/// Turns a station's `broadcast_delay_secs` into a delay
+///
+/// # Returns
+/// The delay, capped at `MAX_DELAY`; none for negative or non-finite values
+pub fn delay_from_secs(secs: f32) -> Duration {
+    if !secs.is_finite() || secs <= 0.0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f32(secs.min(MAX_DELAY.as_secs_f32()))
+}
+
+/// Delays a live source by a fixed time, at most `MAX_DELAY`
+pub fn delayed<S: Source>(source: S, delay: Duration) -> Delayed<S> {
+    let delay = delay.min(MAX_DELAY);
+    let length = (delay.as_secs_f64() * SAMPLE_RATE as f64) as usize * CHANNELS as usize;
+    Delayed {
+        input: UniformSourceIterator::new(source, CHANNELS, SAMPLE_RATE),
+        buffer: VecDeque::with_capacity(length + 1),
+        length,
+        input_ended: false,
+    }
+}
+
+impl<S: Source> Iterator for Delayed<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if !self.input_ended {
+            match self.input.next() {
+                Some(sample) => self.buffer.push_back(sample),
+                None => self.input_ended = true,
+            }
+        }
+        if self.buffer.len() > self.length || self.input_ended {
+            return self.buffer.pop_front();
+        }
+        Some(0.0)
+    }
+}
+
+impl<S: Source> Source for Delayed<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        CHANNELS
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
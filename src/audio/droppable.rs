@@ -0,0 +1,88 @@
+//! Droppable Source Wrapper
+//!
+//! rodio's `Sink::skip_one()` can only skip whatever is playing right now.
+//! Turnover needs to throw away a track that is queued but hasn't started,
+//! so every source a station appends is wrapped here and the station keeps
+//! a `DropHandle` for it. Dropping a source makes it end the moment playback
+//! reaches it, without touching the source that is currently playing.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Source that ends immediately once its handle is dropped
+pub struct Droppable<S> {
+    input: S,
+    dropped: Arc<AtomicBool>,
+}
+
+/// Handle for dropping a queued source from outside the audio thread
+#[derive(Clone)]
+pub struct DropHandle {
+    dropped: Arc<AtomicBool>,
+    duration: Option<Duration>,
+}
+
+impl DropHandle {
+    /// Marks the source as dropped; it will end as soon as it is read
+    pub fn drop_source(&self) {
+        self.dropped.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether the source has been dropped
+    pub fn is_dropped(&self) -> bool {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the source's total length, if it knew it when wrapped
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+}
+
+/// Wraps a source so it can be dropped while still queued
+///
+/// # Returns
+/// The wrapped source to append to a sink, and the handle that drops it
+pub fn droppable<S: Source>(source: S) -> (Droppable<S>, DropHandle) {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let handle = DropHandle { dropped: dropped.clone(), duration: source.total_duration() };
+    (Droppable { input: source, dropped }, handle)
+}
+
+impl<S: Source> Iterator for Droppable<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dropped.load(Ordering::Relaxed) {
+            None
+        } else {
+            self.input.next()
+        }
+    }
+}
+
+impl<S: Source> Source for Droppable<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        if self.dropped.load(Ordering::Relaxed) {Some(0)} else {self.input.current_span_len()}
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)
+    }
+}
@@ -0,0 +1,175 @@
+//! Master Equalizer
+//!
+//! Filters the mixed output of every station through the EQ bands chosen
+//! in radio.info (see `config::equalizer`). Each band is a biquad from the
+//! Audio EQ Cookbook, run separately on each channel. The master mix has
+//! a fixed format, so coefficients are worked out once when the output
+//! opens.
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use rodio::{ChannelCount, SampleRate, Source};
+
+use crate::config::equalizer::{EqBand, FilterKind};
+
+/// Normalized biquad coefficients (a0 divided out)
+#[derive(Debug, Clone, Copy)]
+struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefficients {
+    /// Works out the coefficients for a band at a sample rate
+    fn new(band: &EqBand, sample_rate: SampleRate) -> Coefficients {
+        // Keep the corner below Nyquist or the filter blows up
+        let frequency = band.frequency_hz.clamp(10.0, sample_rate as f32 * 0.45);
+        let w0 = 2.0 * PI * frequency / sample_rate as f32;
+        let cos_w0 = w0.cos();
+        let alpha = w0.sin() / (2.0 * band.q.max(0.1));
+        let a = 10f32.powf(band.gain_db / 40.0);
+        let shelf = 2.0 * a.sqrt() * alpha;
+
+        let (b0, b1, b2, a0, a1, a2) = match band.filter {
+            FilterKind::HighPass => (
+                (1.0 + cos_w0) / 2.0, -(1.0 + cos_w0), (1.0 + cos_w0) / 2.0,
+                1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha
+            ),
+            FilterKind::LowPass => (
+                (1.0 - cos_w0) / 2.0, 1.0 - cos_w0, (1.0 - cos_w0) / 2.0,
+                1.0 + alpha, -2.0 * cos_w0, 1.0 - alpha
+            ),
+            FilterKind::Peak => (
+                1.0 + alpha * a, -2.0 * cos_w0, 1.0 - alpha * a,
+                1.0 + alpha / a, -2.0 * cos_w0, 1.0 - alpha / a
+            ),
+            FilterKind::LowShelf => (
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 + shelf),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                a * ((a + 1.0) - (a - 1.0) * cos_w0 - shelf),
+                (a + 1.0) + (a - 1.0) * cos_w0 + shelf,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                (a + 1.0) + (a - 1.0) * cos_w0 - shelf
+            ),
+            FilterKind::HighShelf => (
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 + shelf),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                a * ((a + 1.0) + (a - 1.0) * cos_w0 - shelf),
+                (a + 1.0) - (a - 1.0) * cos_w0 + shelf,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                (a + 1.0) - (a - 1.0) * cos_w0 - shelf
+            ),
+        };
+
+        Coefficients { b0: b0 / a0, b1: b1 / a0, b2: b2 / a0, a1: a1 / a0, a2: a2 / a0 }
+    }
+}
+
+/// Filter memory for one band on one channel (direct form I)
+#[derive(Debug, Clone, Copy, Default)]
+struct History {
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl History {
+    fn filter(&mut self, coefficients: &Coefficients, x: f32) -> f32 {
+        let c = coefficients;
+        let y = c.b0 * x + c.b1 * self.x1 + c.b2 * self.x2 - c.a1 * self.y1 - c.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// Master mix with the EQ bands applied
+pub struct Equalized<S> {
+    input: S,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    bands: Vec<Coefficients>,
+
+    /// Filter memory, one entry per band for each channel in turn
+    history: Vec<History>,
+
+    /// Level applied before filtering to leave room for boosts
+    headroom: f32,
+
+    /// Channel of the next sample
+    channel: usize,
+}
+
+/// Wraps the master mix in the EQ
+///
+/// # Arguments
+/// * `source` - Audio in a fixed format, such as the mixer output
+/// * `bands` - Filter bands of the selected preset, applied in order
+pub fn equalized<S: Source>(source: S, bands: &[EqBand]) -> Equalized<S> {
+    let channels = source.channels();
+    let sample_rate = source.sample_rate();
+    let coefficients: Vec<Coefficients> = bands.iter()
+        .map(|band| Coefficients::new(band, sample_rate))
+        .collect();
+
+    // Only shelves and peaks boost; pass filters ignore gain_db
+    let largest_boost = bands.iter()
+        .filter(|band| !matches!(band.filter, FilterKind::HighPass | FilterKind::LowPass))
+        .map(|band| band.gain_db)
+        .fold(0.0, f32::max);
+
+    Equalized {
+        input: source,
+        channels,
+        sample_rate,
+        history: vec![History::default(); coefficients.len() * channels.max(1) as usize],
+        bands: coefficients,
+        headroom: 10f32.powf(-largest_boost / 20.0),
+        channel: 0,
+    }
+}
+
+impl<S: Source> Iterator for Equalized<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        if self.bands.is_empty() {
+            return Some(sample);
+        }
+
+        let first = self.channel * self.bands.len();
+        let history = &mut self.history[first..first + self.bands.len()];
+        let output = self.bands.iter()
+            .zip(history.iter_mut())
+            .fold(sample * self.headroom, |x, (coefficients, history)| history.filter(coefficients, x));
+
+        self.channel = (self.channel + 1) % self.channels.max(1) as usize;
+        Some(output)
+    }
+}
+
+impl<S: Source> Source for Equalized<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
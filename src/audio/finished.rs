@@ -0,0 +1,118 @@
+//! Completion Notification Wrapper
+//!
+//! Sinks don't say when a source ends; polling `Sink::len()` only notices
+//! on the manager's next loop. Wrapping a source here runs a callback on
+//! the audio thread the moment the source plays out, so the Station
+//! Manager gets an explicit event at the true end of a track. The start of
+//! a track can be reported the same way, on its first sample.
+//!
+//! A source dropped while queued (see `droppable`) never reaches its end
+//! and so never fires its callbacks.
+
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Source that calls back once when it plays to the end
+pub struct NotifyWhenFinished<S> {
+    input: S,
+    on_finished: Option<Box<dyn FnOnce() + Send>>,
+}
+
+/// Wraps a source to run `on_finished` when it runs out of samples
+///
+/// The callback runs on the audio thread, so it should only do something
+/// quick like sending a message.
+pub fn notify_when_finished<S, F>(source: S, on_finished: F) -> NotifyWhenFinished<S>
+where
+    S: Source,
+    F: FnOnce() + Send + 'static
+{
+    NotifyWhenFinished { input: source, on_finished: Some(Box::new(on_finished)) }
+}
+
+impl<S: Source> Iterator for NotifyWhenFinished<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.input.next();
+        if sample.is_none() && let Some(on_finished) = self.on_finished.take() {
+            on_finished();
+        }
+        sample
+    }
+}
+
+impl<S: Source> Source for NotifyWhenFinished<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)
+    }
+}
+
+/// Source that calls back once when its first sample is played
+pub struct NotifyWhenStarted<S> {
+    input: S,
+    on_started: Option<Box<dyn FnOnce() + Send>>,
+}
+
+/// Wraps a source to run `on_started` when the sink first pulls from it
+///
+/// Like `notify_when_finished()`, the callback runs on the audio thread.
+/// A source queued behind a paused track doesn't start until it plays.
+pub fn notify_when_started<S, F>(source: S, on_started: F) -> NotifyWhenStarted<S>
+where
+    S: Source,
+    F: FnOnce() + Send + 'static
+{
+    NotifyWhenStarted { input: source, on_started: Some(Box::new(on_started)) }
+}
+
+impl<S: Source> Iterator for NotifyWhenStarted<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(on_started) = self.on_started.take() {
+            on_started();
+        }
+        self.input.next()
+    }
+}
+
+impl<S: Source> Source for NotifyWhenStarted<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)
+    }
+}
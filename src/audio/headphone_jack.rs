@@ -0,0 +1,96 @@
+//! Headphone Jack Detection
+//!
+//! Most headphone jacks have a switch contact that opens or closes when a
+//! plug goes in. Wired to a GPIO pin, it lets the radio silence the
+//! cabinet speaker while headphones are in and bring it back when they
+//! come out, as a real set does. The speaker is silenced with the mute
+//! relay if one is fitted (the headphone output sits in front of the amp),
+//! and with `speaker_element`, an ALSA mixer switch on the sound card, if
+//! one is named; the headphones keep playing either way.
+//!
+//! The pin is read with the internal pull-up enabled, so a switch that
+//! closes to ground on insertion reads low with headphones in. Set
+//! `plugged_high` for jacks that work the other way round.
+//!
+//! # JSON Format
+//! ```json
+//! "hardware": {
+//!     "headphone_jack": { "pin": 16, "plugged_high": false, "speaker_element": "Speaker" }
+//! }
+//! ```
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rppal::gpio::Gpio;
+
+use crate::audio::mute_relay::MuteRelay;
+use crate::config::hardware::HeadphoneJackConfig;
+use crate::config::radio_config;
+
+/// How often the jack is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Reads in a row that must agree before a plug counts as in or out
+const DEBOUNCE_READS: u32 = 3;
+
+/// Starts watching the jack on a background thread until `running` is cleared
+///
+/// A pin that can't be claimed is logged and the speaker stays on.
+///
+/// # Arguments
+/// * `config` - The `headphone_jack` section of the hardware config
+/// * `mute_relay` - The amp's mute relay, if fitted, to silence the speaker with
+/// * `running` - Cleared when the output closes
+pub fn start(config: &HeadphoneJackConfig, mute_relay: Option<Arc<Mutex<MuteRelay>>>, running: Arc<AtomicBool>) {
+    let pin = match Gpio::new().and_then(|gpio| gpio.get(config.pin)) {
+        Ok(pin) => pin.into_input_pullup(),
+        Err(e) => {
+            eprintln!("Failed to claim headphone jack on BCM {}, speaker stays on: {}", config.pin, e);
+            return;
+        }
+    };
+    if mute_relay.is_none() && config.speaker_element.is_none() {
+        eprintln!("Headphone jack configured, but there is no mute relay or speaker_element to silence the speaker with");
+        return;
+    }
+    let config = config.clone();
+
+    thread::spawn(move || {
+        let mut headphones_in = false;
+        let mut agreeing_reads = 0;
+        while running.load(Ordering::Relaxed) {
+            let plugged = pin.is_high() == config.plugged_high;
+            agreeing_reads = if plugged != headphones_in {agreeing_reads + 1} else {0};
+            if agreeing_reads >= DEBOUNCE_READS {
+                headphones_in = plugged;
+                agreeing_reads = 0;
+                println!("headphones {}, speaker {}", if plugged {"in"} else {"out"}, if plugged {"off"} else {"on"});
+                silence_speaker(&config, mute_relay.as_deref(), plugged);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+/// Turns the cabinet speaker off or back on
+fn silence_speaker(config: &HeadphoneJackConfig, mute_relay: Option<&Mutex<MuteRelay>>, silenced: bool) {
+    if let Some(mute_relay) = mute_relay {
+        let mut mute_relay = mute_relay.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if silenced {mute_relay.mute()} else {mute_relay.unmute()}
+    }
+    if let Some(element) = config.speaker_element.as_deref() {
+        let card = &radio_config().volume.card;
+        let switched = Command::new("amixer")
+            .args(["-q", "-c", card, "sset", element, if silenced {"mute"} else {"unmute"}])
+            .status();
+        match switched {
+            Ok(status) if status.success() => {},
+            Ok(status) => eprintln!("Failed to switch {} on card {}: amixer exited with {}", element, card, status),
+            Err(e) => eprintln!("Failed to switch {} on card {}: {}", element, card, e),
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! HTTP Stream Source - Internet radio for Live stations
+//!
+//! Plays an Icecast/Shoutcast (or any plain HTTP) audio stream as a rodio
+//! source. A background thread connects, follows redirects, and decodes
+//! the stream with Symphonia (MP3, AAC, OGG Vorbis or FLAC, going by the
+//! `Content-Type`) into short chunks of samples that the source plays as
+//! they arrive. Opening the stream never blocks the caller: the source
+//! plays silence until the first audio comes in, and again whenever the
+//! network falls behind.
+//!
+//! The stream is live, so it isn't held up when nobody is listening:
+//! up to `MAX_BUFFERED` of audio is kept, and while the station's sink is
+//! paused the oldest is thrown away to make room, so the stream picks up
+//! close to where the broadcast is when the dial comes back. When the
+//! stream ends or the connection fails, the source plays out what it has
+//! buffered and ends with it.
+//!
+//! Only `http://` URLs are supported; there is no TLS.
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use rodio::{ChannelCount, SampleRate, Source};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSourceStream, ReadOnlySource};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Most decoded audio buffered between the network and the sink; enough
+/// for the burst Icecast servers send on connecting
+const MAX_BUFFERED: Duration = Duration::from_secs(10);
+
+/// Longest wait to connect, and for each read once connected
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Redirects followed before giving up (Icecast load balancers redirect once)
+const MAX_REDIRECTS: usize = 3;
+
+/// Silence played while waiting on the network, per span
+const UNDERRUN_SPAN: Duration = Duration::from_millis(20);
+
+/// Format of the silence before the first chunk arrives
+const DEFAULT_CHANNELS: ChannelCount = 2;
+const DEFAULT_SAMPLE_RATE: SampleRate = 44_100;
+
+/// Decoded samples in one format
+struct Chunk {
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    samples: Vec<f32>,
+}
+
+impl Chunk {
+    fn silence(channels: ChannelCount, sample_rate: SampleRate) -> Chunk {
+        let length = (UNDERRUN_SPAN.as_secs_f64() * sample_rate as f64) as usize * channels as usize;
+        Chunk { channels, sample_rate, samples: vec![0.0; length.max(channels as usize)] }
+    }
+
+    /// How long the chunk plays for
+    fn length(&self) -> Duration {
+        let samples_per_second = self.sample_rate as f64 * self.channels.max(1) as f64;
+        Duration::from_secs_f64(self.samples.len() as f64 / samples_per_second.max(1.0))
+    }
+}
+
+/// Chunks waiting to play, and how long they play for together
+#[derive(Default)]
+struct Buffer {
+    chunks: VecDeque<Chunk>,
+    length: Duration,
+}
+
+/// State shared between the network thread, the source and its status handle
+#[derive(Default)]
+struct Shared {
+    buffer: Mutex<Buffer>,
+
+    /// The network thread is done; what's buffered is the last of the stream
+    closed: AtomicBool,
+
+    /// Hung up or dropped; stop receiving and playing now
+    stopped: AtomicBool,
+
+    /// Everything received has played, or the stream was stopped
+    ended: AtomicBool,
+}
+
+impl Shared {
+    /// Buffers a chunk, throwing away the oldest audio past `MAX_BUFFERED`
+    fn push(&self, chunk: Chunk) {
+        let mut buffer = self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        buffer.length += chunk.length();
+        buffer.chunks.push_back(chunk);
+        while buffer.length > MAX_BUFFERED {
+            let Some(oldest) = buffer.chunks.pop_front() else {break;};
+            buffer.length = buffer.length.saturating_sub(oldest.length());
+        }
+    }
+
+    /// Takes the next chunk to play, if one has arrived
+    fn pop(&self) -> Option<Chunk> {
+        let mut buffer = self.buffer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let chunk = buffer.chunks.pop_front()?;
+        buffer.length = buffer.length.saturating_sub(chunk.length());
+        Some(chunk)
+    }
+}
+
+/// Playing internet stream
+pub struct HttpStream {
+    shared: Arc<Shared>,
+    current: Chunk,
+    position: usize,
+}
+
+/// Handle for checking on a stream from outside the audio thread
+#[derive(Clone)]
+pub struct StreamStatus {
+    shared: Arc<Shared>,
+}
+
+impl StreamStatus {
+    /// Returns whether the stream has played out after ending or losing its
+    /// connection, or has been stopped
+    pub fn has_ended(&self) -> bool {
+        self.shared.ended.load(Ordering::Relaxed) || self.shared.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Hangs up, and ends the source the next time it's read
+    pub fn stop(&self) {
+        self.shared.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Starts playing a stream
+///
+/// # Arguments
+/// * `url` - `http://` URL of the stream
+///
+/// # Returns
+/// The source to append to a sink, and a handle to see when it has ended
+pub fn open(url: &str) -> (HttpStream, StreamStatus) {
+    let shared = Arc::new(Shared::default());
+    let url = url.to_string();
+    let receiving = shared.clone();
+    thread::spawn(move || {
+        if let Err(e) = receive(&url, &receiving) {
+            eprintln!("Live stream {} stopped: {}", url, e);
+        }
+        receiving.closed.store(true, Ordering::Release);
+    });
+    let source = HttpStream {
+        shared: shared.clone(),
+        current: Chunk::silence(DEFAULT_CHANNELS, DEFAULT_SAMPLE_RATE),
+        position: 0,
+    };
+    (source, StreamStatus { shared })
+}
+
+/// Connects to the stream and decodes it into chunks until it ends, or
+/// the source is stopped or dropped
+fn receive(url: &str, shared: &Shared) -> Result<(), Box<dyn std::error::Error>> {
+    let (response, content_type) = connect(url)?;
+    let mut hint = Hint::new();
+    if let Some(extension) = extension_for(&content_type) {
+        hint.with_extension(extension);
+    }
+    let stream = MediaSourceStream::new(Box::new(ReadOnlySource::new(response)), Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())?;
+    let mut format = probed.format;
+    let track = format.tracks().iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("no audio in the stream")?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+    println!("live stream {} connected", url);
+
+    while !shared.stopped.load(Ordering::Relaxed) {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A damaged frame is skipped, as on a bad signal
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let spec = *decoded.spec();
+        // Nothing to play, and an empty chunk would end the source's span at zero
+        if decoded.frames() == 0 || spec.channels.count() == 0 {
+            continue;
+        }
+        let mut samples = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        samples.copy_interleaved_ref(decoded);
+        // Nobody listening may mean a full buffer; the broadcast goes on without them
+        shared.push(Chunk {
+            channels: spec.channels.count() as ChannelCount,
+            sample_rate: spec.rate,
+            samples: samples.samples().to_vec(),
+        });
+    }
+    Ok(())
+}
+
+/// Sends the request, following redirects, and reads the response headers
+///
+/// # Returns
+/// The response body and its `Content-Type`
+fn connect(url: &str) -> io::Result<(BufReader<TcpStream>, String)> {
+    let mut url = url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        let (host, port, path) = parse_url(&url)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("not an http:// URL: {}", url)))?;
+        let address = (host.as_str(), port).to_socket_addrs()?.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no address for {}", host)))?;
+        let mut connection = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT)?;
+        connection.set_read_timeout(Some(READ_TIMEOUT))?;
+        // HTTP/1.0 so the body isn't chunked, and no inline Icecast metadata
+        write!(connection, "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: mokRadio\r\nIcy-MetaData: 0\r\n\r\n", path, host)?;
+
+        let mut response = BufReader::new(connection);
+        let mut status_line = String::new();
+        response.read_line(&mut status_line)?;
+        // Shoutcast answers "ICY 200 OK"
+        let status: u16 = status_line.split_whitespace().nth(1).and_then(|status| status.parse().ok()).unwrap_or(0);
+
+        let mut location = None;
+        let mut content_type = String::new();
+        loop {
+            let mut header = String::new();
+            if response.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            let Some((name, value)) = header.split_once(':') else {continue;};
+            if name.trim().eq_ignore_ascii_case("location") {
+                location = Some(value.trim().to_string());
+            } else if name.trim().eq_ignore_ascii_case("content-type") {
+                content_type = value.trim().to_ascii_lowercase();
+            }
+        }
+
+        match (status, location) {
+            (200, _) => return Ok((response, content_type)),
+            (301 | 302 | 303 | 307 | 308, Some(location)) => url = location,
+            _ => return Err(io::Error::other(format!("server answered {}", status_line.trim()))),
+        }
+    }
+    Err(io::Error::other("too many redirects"))
+}
+
+/// Splits an `http://host[:port]/path` URL
+fn parse_url(url: &str) -> Option<(String, u16, String)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = rest.split_once('/').map_or((rest, "/".to_string()), |(authority, path)| (authority, format!("/{}", path)));
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (authority, 80),
+    };
+    Some((host.to_string(), port, path))
+}
+
+/// File extension Symphonia recognizes for a stream's content type
+fn extension_for(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    match mime {
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/aac" | "audio/aacp" => Some("aac"),
+        "audio/ogg" | "application/ogg" | "audio/vorbis" => Some("ogg"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        _ => None,
+    }
+}
+
+impl HttpStream {
+    /// Moves on to the next chunk, or silence if the network is behind,
+    /// and marks the stream ended once it has closed and every chunk has played
+    fn advance(&mut self) {
+        self.position = 0;
+        // Checked before popping, so a chunk pushed just before closing still plays
+        let closed = self.shared.closed.load(Ordering::Acquire);
+        match self.shared.pop() {
+            Some(chunk) => self.current = chunk,
+            None if closed => self.end(),
+            None => self.current = Chunk::silence(self.current.channels, self.current.sample_rate),
+        }
+    }
+
+    fn end(&mut self) {
+        self.shared.ended.store(true, Ordering::Relaxed);
+        self.current.samples.clear();
+    }
+}
+
+impl Iterator for HttpStream {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.shared.stopped.load(Ordering::Relaxed) {
+            self.end();
+            return None;
+        }
+        let sample = self.current.samples.get(self.position).copied()?;
+        self.position += 1;
+        if self.position >= self.current.samples.len() {
+            self.advance();
+        }
+        Some(sample)
+    }
+}
+
+impl Source for HttpStream {
+    fn current_span_len(&self) -> Option<usize> {
+        // Chunks are never empty, so this is only zero once the stream has ended
+        let remaining = self.current.samples.len() - self.position;
+        (remaining > 0).then_some(remaining)
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.current.channels
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.current.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl Drop for HttpStream {
+    fn drop(&mut self) {
+        // Dropped mid-stream hangs up, and counts as ended
+        self.shared.stopped.store(true, Ordering::Relaxed);
+        self.shared.ended.store(true, Ordering::Relaxed);
+    }
+}
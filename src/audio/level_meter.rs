@@ -0,0 +1,108 @@
+//! Level Meter
+//!
+//! Measures the master output as it leaves for the sound card, after EQ
+//! and volume, so a meter shows what the speaker is actually playing. The
+//! output is wrapped in a `Metered` source that works out the RMS and peak
+//! level of every 20ms of audio and stores them in a `LevelMeter`, which
+//! any thread can read without locking and without slowing the audio
+//! thread down. The cabinet's VU meter (see `vu_meter`) is driven from it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+use serde::Serialize;
+
+/// Measurements made per second
+const WINDOWS_PER_SECOND: usize = 50;
+
+/// Output level over the most recent window, as linear amplitude (1.0 is full scale)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Levels {
+    pub rms: f32,
+    pub peak: f32,
+}
+
+/// Latest output levels, shared between the audio thread and readers
+#[derive(Clone, Default)]
+pub struct LevelMeter {
+    rms: Arc<AtomicU32>,
+    peak: Arc<AtomicU32>,
+}
+
+impl LevelMeter {
+    pub fn new() -> Self {
+        LevelMeter::default()
+    }
+
+    /// Returns the levels of the most recently measured window
+    pub fn levels(&self) -> Levels {
+        Levels {
+            rms: f32::from_bits(self.rms.load(Ordering::Relaxed)),
+            peak: f32::from_bits(self.peak.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn store(&self, rms: f32, peak: f32) {
+        self.rms.store(rms.to_bits(), Ordering::Relaxed);
+        self.peak.store(peak.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Source whose levels are measured as it plays
+pub struct Metered<S> {
+    input: S,
+    meter: LevelMeter,
+    sum_of_squares: f32,
+    peak: f32,
+    measured: usize,
+}
+
+/// Wraps a source to report its levels to a meter
+pub fn metered<S: Source>(source: S, meter: LevelMeter) -> Metered<S> {
+    Metered { input: source, meter, sum_of_squares: 0.0, peak: 0.0, measured: 0 }
+}
+
+impl<S: Source> Iterator for Metered<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        self.sum_of_squares += sample * sample;
+        self.peak = self.peak.max(sample.abs());
+        self.measured += 1;
+
+        let window = self.input.sample_rate() as usize * self.input.channels() as usize / WINDOWS_PER_SECOND;
+        if self.measured >= window.max(1) {
+            self.meter.store((self.sum_of_squares / self.measured as f32).sqrt(), self.peak);
+            self.sum_of_squares = 0.0;
+            self.peak = 0.0;
+            self.measured = 0;
+        }
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for Metered<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)
+    }
+}
@@ -0,0 +1,154 @@
+//! Loudness - Integrated loudness of a whole track
+//!
+//! Measures a source's integrated loudness in LUFS as ITU-R BS.1770
+//! defines it: every channel is K-weighted (a high shelf for the head's
+//! effect, then a high-pass taking out the rumble the ear ignores), the
+//! mean square is taken over 400ms blocks overlapping by 75%, and blocks
+//! quieter than -70 LUFS, or more than 10 LU under the loudness of the
+//! rest, are left out so silence and quiet passages don't drag a track's
+//! figure down. All channels are weighted equally, as for stereo.
+//!
+//! The sample peak is taken on the same pass, so loudness matching can
+//! tell how far a track can be turned up before it clips.
+//!
+//! Used by the loudness matching pass (`radio::station::loudness`), which
+//! decodes each track once and keeps the figures.
+
+use std::f64::consts::PI;
+
+use rodio::Source;
+
+/// Gating block length and step, in 100ms steps
+const BLOCK_STEPS: usize = 4;
+const STEP_SECS: f64 = 0.1;
+
+/// Blocks quieter than this never count
+const ABSOLUTE_GATE: f64 = -70.0;
+
+/// Blocks more than this far under the ungated loudness don't count
+const RELATIVE_GATE: f64 = -10.0;
+
+/// Offset in the loudness formula, so a full-scale 997Hz sine reads -3.01 LUFS
+const LOUDNESS_OFFSET: f64 = -0.691;
+
+/// Direct form I biquad
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Biquad { b, a, x: [0.0; 2], y: [0.0; 2] }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0] - self.a[1] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// K-weighting filters for one channel at a sample rate
+///
+/// Coefficients are derived for the rate rather than taken from the
+/// standard's 48kHz table, so 44.1kHz files are weighted correctly too.
+fn k_weighting(sample_rate: f64) -> [Biquad; 2] {
+    // Stage 1: high shelf, +4dB above about 1.7kHz
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    // Stage 2: high-pass at about 38Hz
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad::new(
+        [1.0, -2.0, 1.0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    [shelf, high_pass]
+}
+
+/// A track's integrated loudness and sample peak
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// Integrated loudness in LUFS
+    pub lufs: f32,
+
+    /// Largest absolute sample, 1.0 at full scale
+    pub peak: f32,
+}
+
+/// Measures the integrated loudness and peak of a source, reading it to the end
+///
+/// # Returns
+/// The measurement, or None if the source is too short to measure or
+/// is silent throughout
+pub fn measure<S: Source>(source: S) -> Option<Measurement> {
+    let channels = source.channels().max(1) as usize;
+    let sample_rate = source.sample_rate().max(1) as f64;
+    let step_length = (sample_rate * STEP_SECS) as usize;
+    if step_length == 0 {
+        return None;
+    }
+
+    let mut filters = vec![k_weighting(sample_rate); channels];
+    let mut steps: Vec<f64> = Vec::new();
+    let (mut energy, mut frames, mut channel) = (0.0f64, 0usize, 0usize);
+    let mut peak = 0.0f32;
+
+    for sample in source {
+        peak = peak.max(sample.abs());
+        let [shelf, high_pass] = &mut filters[channel];
+        let weighted = high_pass.process(shelf.process(sample as f64));
+        energy += weighted * weighted;
+
+        channel += 1;
+        if channel == channels {
+            channel = 0;
+            frames += 1;
+            if frames == step_length {
+                steps.push(energy);
+                (energy, frames) = (0.0, 0);
+            }
+        }
+    }
+
+    // Mean square of each 400ms block, summed over channels
+    let block_length = (step_length * BLOCK_STEPS) as f64;
+    let blocks: Vec<f64> = steps.windows(BLOCK_STEPS)
+        .map(|window| window.iter().sum::<f64>() / block_length)
+        .filter(|power| loudness(*power) > ABSOLUTE_GATE)
+        .collect();
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let relative_gate = loudness(mean(&blocks)) + RELATIVE_GATE;
+    let gated: Vec<f64> = blocks.into_iter().filter(|power| loudness(*power) > relative_gate).collect();
+    if gated.is_empty() {
+        return None;
+    }
+    Some(Measurement { lufs: loudness(mean(&gated)) as f32, peak })
+}
+
+fn loudness(power: f64) -> f64 {
+    LOUDNESS_OFFSET + 10.0 * power.max(f64::MIN_POSITIVE).log10()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
@@ -0,0 +1,221 @@
+//! Monitor Output
+//!
+//! A second output device (a USB dongle driving the headphone jack) can
+//! listen to any station without retuning the speaker. Every source a
+//! station appends is wrapped in a `Tapped` source, which copies samples
+//! onto the shared monitor bus while that station's tap is open. The
+//! monitor device plays the bus through a `MonitorSource`.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Frames copied onto the bus at a time
+const CHUNK_FRAMES: usize = 1024;
+
+/// Chunks held before the oldest is thrown away (about 1.5s at 44.1kHz)
+const MAX_QUEUED_CHUNKS: usize = 64;
+
+/// Format of the filler played while the bus is empty
+const SILENCE_RATE: SampleRate = 44_100;
+const SILENCE_FRAMES: usize = 256;
+
+/// Shared bus, created the first time a tap or the monitor asks for it
+static MONITOR_BUS: OnceLock<MonitorBus> = OnceLock::new();
+
+/// Run of samples in a single format
+struct Chunk {
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    samples: Vec<f32>,
+}
+
+impl Chunk {
+    fn silence() -> Chunk {
+        Chunk { channels: 1, sample_rate: SILENCE_RATE, samples: vec![0.0; SILENCE_FRAMES] }
+    }
+}
+
+/// Queue of audio from the monitored station to the monitor device
+#[derive(Clone)]
+struct MonitorBus {
+    chunks: Arc<Mutex<VecDeque<Chunk>>>,
+}
+
+impl MonitorBus {
+    fn shared() -> MonitorBus {
+        MONITOR_BUS.get_or_init(|| MonitorBus {
+            chunks: Arc::new(Mutex::new(VecDeque::new()))
+        }).clone()
+    }
+
+    fn push(&self, chunk: Chunk) {
+        let mut chunks = self.chunks.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Nobody is draining the bus fast enough, so keep the newest audio
+        if chunks.len() >= MAX_QUEUED_CHUNKS {
+            chunks.pop_front();
+        }
+        chunks.push_back(chunk);
+    }
+
+    fn pop(&self) -> Option<Chunk> {
+        self.chunks.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).pop_front()
+    }
+
+    fn clear(&self) {
+        self.chunks.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+    }
+}
+
+/// A station's switch for copying its audio onto the monitor bus
+#[derive(Clone, Default)]
+pub struct MonitorTap {
+    open: Arc<AtomicBool>,
+}
+
+impl MonitorTap {
+    /// Starts or stops sending this station's audio to the monitor
+    ///
+    /// Switching a tap drops anything still queued on the bus, so the
+    /// monitor doesn't play the tail of the previous station.
+    pub fn set_open(&self, open: bool) {
+        if self.open.swap(open, Ordering::Relaxed) != open {
+            MonitorBus::shared().clear();
+        }
+    }
+
+    /// Returns whether this station is being monitored
+    pub fn is_open(&self) -> bool {
+        self.open.load(Ordering::Relaxed)
+    }
+}
+
+/// Source that copies its samples onto the monitor bus while tapped
+pub struct Tapped<S> {
+    input: S,
+    tap: MonitorTap,
+    buffer: Chunk,
+}
+
+/// Wraps a source so its audio can be monitored
+pub fn tapped<S: Source>(source: S, tap: MonitorTap) -> Tapped<S> {
+    let buffer = Chunk { channels: source.channels(), sample_rate: source.sample_rate(), samples: Vec::new() };
+    Tapped { input: source, tap, buffer }
+}
+
+impl<S: Source> Tapped<S> {
+    /// Sends the buffered samples to the bus and starts a new chunk
+    fn flush(&mut self) {
+        let next = Chunk {
+            channels: self.input.channels(),
+            sample_rate: self.input.sample_rate(),
+            samples: Vec::with_capacity(CHUNK_FRAMES * self.input.channels() as usize),
+        };
+        let full = std::mem::replace(&mut self.buffer, next);
+        if !full.samples.is_empty() {
+            MonitorBus::shared().push(full);
+        }
+    }
+}
+
+impl<S: Source> Iterator for Tapped<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.tap.is_open() {
+            self.buffer.samples.clear();
+            return self.input.next();
+        }
+
+        // Start a new chunk on a frame boundary when the format changes
+        let at_frame_start = self.buffer.samples.len().is_multiple_of(self.buffer.channels.max(1) as usize);
+        if at_frame_start && (self.buffer.channels != self.input.channels() || self.buffer.sample_rate != self.input.sample_rate()) {
+            self.flush();
+        }
+
+        let Some(sample) = self.input.next() else {
+            self.flush();
+            return None;
+        };
+        self.buffer.samples.push(sample);
+        if self.buffer.samples.len() >= CHUNK_FRAMES * self.buffer.channels as usize {
+            self.flush();
+        }
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for Tapped<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        // Don't send the monitor half a chunk from before the jump
+        self.buffer.samples.clear();
+        self.input.try_seek(position)
+    }
+}
+
+/// Endless source playing the monitor bus, with silence when it's empty
+pub struct MonitorSource {
+    current: Chunk,
+    position: usize,
+}
+
+/// Creates the source to append to the monitor device's sink
+pub fn monitor_source() -> MonitorSource {
+    MonitorSource { current: Chunk::silence(), position: 0 }
+}
+
+impl Iterator for MonitorSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.current.samples[self.position];
+        self.position += 1;
+
+        // Move on eagerly so the span always describes the next sample
+        if self.position >= self.current.samples.len() {
+            self.current = MonitorBus::shared().pop()
+                .filter(|chunk| !chunk.samples.is_empty())
+                .unwrap_or_else(Chunk::silence);
+            self.position = 0;
+        }
+        Some(sample)
+    }
+}
+
+impl Source for MonitorSource {
+    fn current_span_len(&self) -> Option<usize> {
+        Some(self.current.samples.len() - self.position)
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.current.channels
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.current.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
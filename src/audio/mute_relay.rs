@@ -0,0 +1,75 @@
+//! Mute Relay
+//!
+//! Many amplifiers thump when the DAC in front of them powers up, starts
+//! or stops streaming. A relay (or the amp's own mute input) on a GPIO
+//! pin can hold the signal path muted while that happens: the output
+//! engages it before the stream opens, releases it once the DAC has had
+//! `settle_ms` to settle, and engages it again before the stream closes,
+//! waiting `release_ms` for the relay to drop out.
+//!
+//! The headphone jack (see `headphone_jack`) also mutes the relay to
+//! silence the speaker while headphones are in.
+//!
+//! The pin is left muted when the radio exits. Wire the relay so that an
+//! unpowered coil means muted, which also covers the Pi booting; the pin
+//! then mutes by going low, the default. Set `active_high` for an amp mute
+//! input that mutes on a high level instead.
+//!
+//! # JSON Format
+//! ```json
+//! "hardware": {
+//!     "mute_relay": { "pin": 26, "active_high": false, "settle_ms": 800, "release_ms": 50 }
+//! }
+//! ```
+
+use std::thread;
+use std::time::Duration;
+
+use rppal::gpio::{Gpio, OutputPin};
+
+use crate::config::hardware::MuteRelayConfig;
+
+/// GPIO-driven mute on the signal path
+pub struct MuteRelay {
+    config: MuteRelayConfig,
+    pin: OutputPin,
+}
+
+impl MuteRelay {
+    /// Claims the relay pin with the signal path muted
+    ///
+    /// # Returns
+    /// The relay, or None (logged) if the GPIO pin can't be claimed
+    pub fn engage(config: &MuteRelayConfig) -> Option<MuteRelay> {
+        let pin = Gpio::new().and_then(|gpio| gpio.get(config.pin));
+        let pin = match pin {
+            Ok(pin) => pin,
+            Err(e) => {
+                eprintln!("Failed to claim mute relay on BCM {}, running without it: {}", config.pin, e);
+                return None;
+            }
+        };
+        let mut pin = if config.active_high {pin.into_output_high()} else {pin.into_output_low()};
+        // Leave the amp muted when the radio exits
+        pin.set_reset_on_drop(false);
+
+        Some(MuteRelay { config: config.clone(), pin })
+    }
+
+    /// Unmutes once the output has had time to settle
+    pub fn release(&mut self) {
+        thread::sleep(Duration::from_millis(self.config.settle_ms));
+        self.unmute();
+    }
+
+    /// Unmutes straight away, for an output that is already settled
+    pub fn unmute(&mut self) {
+        if self.config.active_high {self.pin.set_low()} else {self.pin.set_high()}
+    }
+
+    /// Mutes, and waits for the relay to drop out
+    pub fn mute(&mut self) {
+        if self.config.active_high {self.pin.set_high()} else {self.pin.set_low()}
+        thread::sleep(Duration::from_millis(self.config.release_ms));
+    }
+}
@@ -0,0 +1,132 @@
+//! Noise Gate Wrapper
+//!
+//! Old tape transfers hiss, which nobody notices under the music but
+//! everybody hears in the pauses. Stations with `noise_gate` in
+//! station.info turn down whatever falls below the threshold, working as
+//! a downward expander: a closed gate lowers the audio by `range_db`
+//! rather than muting it, so quiet passages thin out instead of dropping
+//! to dead air.
+//!
+//! The level is a peak envelope over every channel, so a stereo pair
+//! opens and closes together. The gate opens within a couple of
+//! milliseconds, so the start of a note isn't lost, and closes over
+//! `release_ms`.
+
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+use crate::radio::station::config::NoiseGateConfig;
+
+/// Time taken to open the gate
+const ATTACK_SECS: f32 = 0.002;
+
+/// Time the peak envelope takes to fall, bridging the gaps between waveform peaks
+const ENVELOPE_SECS: f32 = 0.02;
+
+/// Per-sample smoothing coefficients, worked out for a sample rate and channel count
+#[derive(Clone, Copy)]
+struct Coefficients {
+    attack: f32,
+    release: f32,
+    envelope_decay: f32,
+}
+
+impl Coefficients {
+    fn new(config: &NoiseGateConfig, sample_rate: SampleRate, channels: ChannelCount) -> Self {
+        let samples_per_second = sample_rate.max(1) as f32 * channels.max(1) as f32;
+        let smoothing = |seconds: f32| 1.0 - (-1.0 / (seconds.max(0.0001) * samples_per_second)).exp();
+        Coefficients {
+            attack: smoothing(ATTACK_SECS),
+            release: smoothing(config.release_ms as f32 / 1000.0),
+            envelope_decay: 1.0 - smoothing(ENVELOPE_SECS),
+        }
+    }
+}
+
+/// Source turned down while its level is under the gate's threshold
+pub struct NoiseGated<S> {
+    input: S,
+    config: Option<NoiseGateConfig>,
+
+    /// Linear level the gate opens at
+    threshold: f32,
+
+    /// Linear gain of a closed gate
+    floor: f32,
+
+    /// Format the coefficients were worked out for
+    format: (SampleRate, ChannelCount),
+    coefficients: Coefficients,
+
+    envelope: f32,
+    gain: f32,
+}
+
+/// Wraps a source in a noise gate, or passes it through untouched with None
+pub fn noise_gated<S: Source>(source: S, config: Option<NoiseGateConfig>) -> NoiseGated<S> {
+    let gate = config.unwrap_or_default();
+    let format = (source.sample_rate(), source.channels());
+    NoiseGated {
+        threshold: 10f32.powf(gate.threshold_db / 20.0),
+        floor: 10f32.powf(-gate.range_db.abs() / 20.0),
+        coefficients: Coefficients::new(&gate, format.0, format.1),
+        format,
+        input: source,
+        config,
+        envelope: 0.0,
+        // Starts open, so a track doesn't fade in from the floor
+        gain: 1.0,
+    }
+}
+
+impl<S: Source> Iterator for NoiseGated<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        let Some(config) = self.config.as_ref() else {return Some(sample);};
+
+        // Spans can change format mid-track
+        let format = (self.input.sample_rate(), self.input.channels());
+        if format != self.format {
+            self.format = format;
+            self.coefficients = Coefficients::new(config, format.0, format.1);
+        }
+
+        self.envelope = sample.abs().max(self.envelope * self.coefficients.envelope_decay);
+        let (target, smoothing) = if self.envelope >= self.threshold {
+            (1.0, self.coefficients.attack)
+        } else {
+            (self.floor, self.coefficients.release)
+        };
+        self.gain += (target - self.gain) * smoothing;
+        Some(sample * self.gain)
+    }
+}
+
+impl<S: Source> Source for NoiseGated<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        // What's after the jump has its own level
+        self.envelope = 0.0;
+        self.gain = 1.0;
+        self.input.try_seek(position)
+    }
+}
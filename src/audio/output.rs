@@ -0,0 +1,174 @@
+//! Audio Output Backends
+//!
+//! Every sink in the radio connects to one master mixer, whose output goes
+//! through the master EQ, volume and level meter and on to the backend. On the radio that is the
+//! sound card's output stream; with the null backend it is drained at
+//! real-time speed by a background thread and the audio is thrown away, so
+//! the whole radio can run headless in tests and on machines without a
+//! sound card. On the sound card, a wired-up headphone jack silences the
+//! speaker while headphones are in (see `headphone_jack`).
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use rodio::mixer::{Mixer, mixer};
+use rodio::source::Zero;
+use rodio::{OutputStream, OutputStreamBuilder, Source};
+use serde::Deserialize;
+
+use crate::audio::equalizer::equalized;
+use crate::audio::headphone_jack;
+use crate::audio::level_meter::{LevelMeter, metered};
+use crate::audio::mute_relay::MuteRelay;
+use crate::audio::volume::{MasterVolume, attenuated};
+use crate::audio::vu_meter;
+use crate::config::radio_config;
+use crate::priority::{ThreadRole, prioritize_audio_threads, prioritize_current_thread};
+
+/// Where the radio's audio goes
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioBackend {
+    /// The default sound card
+    Device,
+
+    /// Nowhere, at real-time speed (tests, headless simulation)
+    Null
+}
+
+/// Format of the null backend's mixer
+const NULL_CHANNELS: u16 = 2;
+const NULL_SAMPLE_RATE: u32 = 44_100;
+
+/// How often the null backend drains its mixer
+const NULL_DRAIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Open audio output that sinks connect to
+pub struct AudioOutput {
+    /// Mixer every sink connects to
+    master: Mixer,
+
+    /// Master volume control
+    volume: MasterVolume,
+
+    /// Levels of the audio leaving for the backend
+    meter: LevelMeter,
+
+    /// Sound card stream (None with the null backend)
+    _stream: Option<OutputStream>,
+
+    /// Keeps the null backend's drain thread going
+    running: Arc<AtomicBool>,
+
+    /// Amp mute held around opening and closing the sound card, shared
+    /// with the headphone jack
+    mute_relay: Option<Arc<Mutex<MuteRelay>>>,
+}
+
+impl AudioOutput {
+    /// Opens the backend selected in radio.info
+    ///
+    /// # Panics
+    /// Panics if the sound card can't be opened with the device backend
+    pub fn open(backend: AudioBackend) -> AudioOutput {
+        let bands = radio_config().eq.bands();
+        let volume = MasterVolume::new(&radio_config().volume);
+        let running = Arc::new(AtomicBool::new(true));
+        let meter = LevelMeter::new();
+        if let Some(vu_meter) = radio_config().hardware.vu_meter.as_ref() {
+            vu_meter::start(vu_meter, meter.clone(), running.clone());
+        }
+
+        match backend {
+            AudioBackend::Device => {
+                let mut mute_relay = radio_config().hardware.mute_relay.as_ref().and_then(MuteRelay::engage);
+                let output_builder = configured(OutputStreamBuilder::from_default_device().unwrap());
+                let stream = output_builder.open_stream().unwrap();
+                let format = stream.config();
+                let (master, master_source) = mixer(format.channel_count(), format.sample_rate());
+                keep_running(&master, format.channel_count(), format.sample_rate());
+                stream.mixer().add(metered(attenuated(equalized(master_source, &bands), &volume), meter.clone()));
+                prioritize_audio_threads();
+                if let Some(mute_relay) = mute_relay.as_mut() {
+                    mute_relay.release();
+                }
+                let mute_relay = mute_relay.map(|mute_relay| Arc::new(Mutex::new(mute_relay)));
+                if let Some(headphone_jack) = radio_config().hardware.headphone_jack.as_ref() {
+                    headphone_jack::start(headphone_jack, mute_relay.clone(), running.clone());
+                }
+                AudioOutput { master, volume, meter, _stream: Some(stream), running, mute_relay }
+            },
+            AudioBackend::Null => {
+                let (master, master_source) = mixer(NULL_CHANNELS, NULL_SAMPLE_RATE);
+                keep_running(&master, NULL_CHANNELS, NULL_SAMPLE_RATE);
+                drain(metered(attenuated(equalized(master_source, &bands), &volume), meter.clone()), running.clone());
+                AudioOutput { master, volume, meter, _stream: None, running, mute_relay: None }
+            }
+        }
+    }
+
+    /// Returns the mixer sinks connect to
+    pub fn mixer(&self) -> &Mixer {
+        &self.master
+    }
+
+    /// Returns the master volume control
+    pub fn volume(&self) -> &MasterVolume {
+        &self.volume
+    }
+
+    /// Returns the meter measuring the output's levels
+    pub fn meter(&self) -> &LevelMeter {
+        &self.meter
+    }
+
+    /// Mutes the amp ahead of the output going away
+    pub fn mute(&mut self) {
+        if let Some(mute_relay) = self.mute_relay.as_ref() {
+            mute_relay.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).mute();
+        }
+    }
+}
+
+impl Drop for AudioOutput {
+    fn drop(&mut self) {
+        // Muted before the stream field closes the sound card
+        self.mute();
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Adds endless silence to the master mixer
+///
+/// A mixer ends its output once nothing is playing, which would take the
+/// master EQ and volume off the output for good.
+fn keep_running(master: &Mixer, channels: u16, sample_rate: u32) {
+    master.add(Zero::new(channels, sample_rate));
+}
+
+/// Pulls a source in real time on a background thread, discarding the audio
+fn drain(mut source: impl Source + Send + 'static, running: Arc<AtomicBool>) {
+    let samples_per_drain = (NULL_SAMPLE_RATE as u64 * NULL_CHANNELS as u64
+        * NULL_DRAIN_INTERVAL.as_millis() as u64 / 1000) as usize;
+    thread::spawn(move || {
+        prioritize_current_thread(ThreadRole::Audio);
+        while running.load(Ordering::Relaxed) {
+            source.by_ref().take(samples_per_drain).for_each(drop);
+            thread::sleep(NULL_DRAIN_INTERVAL);
+        }
+    });
+}
+
+/// Applies the buffer size and sample rate from radio.info
+pub fn configured(builder: OutputStreamBuilder) -> OutputStreamBuilder {
+    let audio_buffer = &radio_config().audio_buffer;
+    let mut builder = builder;
+    if let Some(frames) = audio_buffer.frames() {
+        builder = builder.with_buffer_size(rodio::cpal::BufferSize::Fixed(frames));
+    }
+    if let Some(sample_rate) = audio_buffer.sample_rate {
+        builder = builder.with_sample_rate(sample_rate);
+    }
+    builder
+}
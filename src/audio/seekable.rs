@@ -0,0 +1,84 @@
+//! Seekable Source Wrapper
+//!
+//! Sinks seek by asking the source they're playing to jump to a position.
+//! Decoders that support seeking do that directly; everything else (DJ
+//! intros chained in front of a track, formats without seek support) would
+//! refuse. This wrapper keeps track of how far playback has got so forward
+//! seeks can fall back to decoding and discarding samples up to the target.
+
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Source that can always seek forward, by skip-decoding if it must
+pub struct Seekable<S> {
+    input: S,
+
+    /// Samples played since the start (or the last real seek)
+    samples_played: u64,
+
+    /// Position of `samples_played == 0`
+    base: Duration,
+}
+
+/// Wraps a source so forward seeks always work
+pub fn seekable<S: Source>(source: S) -> Seekable<S> {
+    Seekable { input: source, samples_played: 0, base: Duration::ZERO }
+}
+
+impl<S: Source> Seekable<S> {
+    /// Current playback position of the wrapped source
+    fn playback_position(&self) -> Duration {
+        let samples_per_second = self.input.sample_rate() as u64 * self.input.channels().max(1) as u64;
+        self.base + Duration::from_secs_f64(self.samples_played as f64 / samples_per_second.max(1) as f64)
+    }
+}
+
+impl<S: Source> Iterator for Seekable<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.input.next()?;
+        self.samples_played += 1;
+        Some(sample)
+    }
+}
+
+impl<S: Source> Source for Seekable<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        match self.input.try_seek(position) {
+            Ok(()) => {
+                self.base = position;
+                self.samples_played = 0;
+                Ok(())
+            },
+            // Can't go back without real seek support
+            Err(e) if position < self.playback_position() => Err(e),
+            Err(_) => {
+                while self.playback_position() < position {
+                    if self.next().is_none() {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
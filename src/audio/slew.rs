@@ -0,0 +1,110 @@
+//! Slewed Gain
+//!
+//! Setting a sink's volume takes effect at once, so a fast dial sweep
+//! steps the level every few milliseconds and the steps are audible as
+//! zipper noise. Gains set through a `SlewedGain` are instead approached
+//! a little every frame in the audio thread, reaching any new value within
+//! `SLEW_TIME` however often it changes.
+//!
+//! A station wraps every source it queues with the same gain, so the level
+//! carries over from one track to the next. The static between stations
+//! and the off-air tone are slewed too, as they follow the dial.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Time to slew across the full 0.0 to 1.0 range
+const SLEW_TIME: Duration = Duration::from_millis(30);
+
+/// Gain set from the control side and followed smoothly by the audio side
+#[derive(Clone)]
+pub struct SlewedGain {
+    /// Gain being slewed towards (f32 bits)
+    target: Arc<AtomicU32>,
+
+    /// Gain the audio reached last (f32 bits)
+    current: Arc<AtomicU32>,
+}
+
+impl SlewedGain {
+    /// Creates a gain that starts out at `gain`
+    pub fn new(gain: f32) -> SlewedGain {
+        SlewedGain {
+            target: Arc::new(AtomicU32::new(gain.to_bits())),
+            current: Arc::new(AtomicU32::new(gain.to_bits())),
+        }
+    }
+
+    /// Sets the gain to slew towards
+    pub fn set(&self, gain: f32) {
+        self.target.store(gain.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Returns the gain being slewed towards
+    pub fn target(&self) -> f32 {
+        f32::from_bits(self.target.load(Ordering::Relaxed))
+    }
+}
+
+/// Source scaled by a slewed gain
+pub struct Slewed<S> {
+    input: S,
+    gain: SlewedGain,
+    current: f32,
+
+    /// Channel of the next sample; the gain moves between frames
+    channel: ChannelCount,
+}
+
+/// Wraps a source in a gain that changes smoothly
+pub fn slewed<S: Source>(source: S, gain: SlewedGain) -> Slewed<S> {
+    let current = f32::from_bits(gain.current.load(Ordering::Relaxed));
+    Slewed { input: source, gain, current, channel: 0 }
+}
+
+impl<S: Source> Iterator for Slewed<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+
+        if self.channel == 0 {
+            let target = self.gain.target();
+            if self.current != target {
+                let step = 1.0 / (SLEW_TIME.as_secs_f32() * self.input.sample_rate() as f32);
+                let difference = target - self.current;
+                self.current = if difference.abs() <= step {target} else {self.current + step.copysign(difference)};
+                self.gain.current.store(self.current.to_bits(), Ordering::Relaxed);
+            }
+        }
+        self.channel = (self.channel + 1) % self.input.channels().max(1);
+
+        Some(sample * self.current)
+    }
+}
+
+impl<S: Source> Source for Slewed<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)
+    }
+}
@@ -0,0 +1,121 @@
+//! Dual-Mono Split
+//!
+//! With `dual_mono` on in radio.info, the left and right channels can
+//! carry two different stations: the dial's on the left and one chosen
+//! over the API on the right, for cabinets with two separate speakers or
+//! for A/B monitoring. Every source a station appends is wrapped in a
+//! `Routed` source, which folds it down to mono and sends it to whichever
+//! channel the station's `ChannelRoute` currently names. Mono sources come
+//! out as stereo so they can be sent to one side too.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Which speaker channels a station plays on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channels {
+    Both,
+    Left,
+    Right,
+}
+
+/// A station's channel setting, shared with every source it has queued
+#[derive(Clone, Default)]
+pub struct ChannelRoute {
+    channels: Arc<AtomicU8>,
+}
+
+impl ChannelRoute {
+    /// Sends the station to other channels, including what's already queued
+    pub fn set(&self, channels: Channels) {
+        let value = match channels {
+            Channels::Both => 0,
+            Channels::Left => 1,
+            Channels::Right => 2,
+        };
+        self.channels.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> Channels {
+        match self.channels.load(Ordering::Relaxed) {
+            1 => Channels::Left,
+            2 => Channels::Right,
+            _ => Channels::Both,
+        }
+    }
+}
+
+/// Source sent to the channels its route names
+pub struct Routed<S> {
+    input: S,
+    route: ChannelRoute,
+
+    /// Right sample of the frame whose left was just returned
+    pending_right: Option<f32>,
+}
+
+/// Wraps a source to follow a station's channel route
+pub fn routed<S: Source>(source: S, route: ChannelRoute) -> Routed<S> {
+    Routed { input: source, route, pending_right: None }
+}
+
+impl<S: Source> Iterator for Routed<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(right) = self.pending_right.take() {
+            return Some(right);
+        }
+        let (left, right) = match self.input.channels() {
+            1 => {
+                let sample = self.input.next()?;
+                (sample, sample)
+            },
+            2 => {
+                let left = self.input.next()?;
+                let Some(right) = self.input.next() else {return Some(left);};
+                (left, right)
+            },
+            // Surround audio is left as it is
+            _ => return self.input.next()
+        };
+
+        let mid = (left + right) / 2.0;
+        let (left, right) = match self.route.get() {
+            Channels::Both => (left, right),
+            Channels::Left => (mid, 0.0),
+            Channels::Right => (0.0, mid),
+        };
+        self.pending_right = Some(right);
+        Some(left)
+    }
+}
+
+impl<S: Source> Source for Routed<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        let upmix = if self.input.channels() == 1 {2} else {1};
+        self.input.current_span_len().map(|length| length * upmix + self.pending_right.is_some() as usize)
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels().max(2)
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        // Don't play half a frame from before the jump
+        self.pending_right = None;
+        self.input.try_seek(position)
+    }
+}
@@ -0,0 +1,80 @@
+//! Static Generator - The hiss between stations
+//!
+//! Real radios are never silent off-station. The Station Manager plays
+//! this endless noise through its own sink and turns it up as the dial
+//! leaves a station and down as it lands on one, so station audio
+//! crossfades against static with dial distance. It is white noise
+//! softened by a one-pole low-pass, as a cheap receiver's hiss is, with
+//! the odd crackle of atmospheric interference on top.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Format of the generated audio
+const SAMPLE_RATE: SampleRate = 22_050;
+
+/// Level of the hiss relative to full scale, at full static volume
+const HISS_LEVEL: f32 = 0.3;
+
+/// Low-pass coefficient; smaller is duller
+const HISS_SMOOTHING: f32 = 0.4;
+
+/// Chance per sample of a crackle, about three a second
+const CRACKLE_CHANCE: f64 = 3.0 / SAMPLE_RATE as f64;
+
+/// Loudest crackle, and how quickly each dies away per sample
+const CRACKLE_LEVEL: f32 = 0.5;
+const CRACKLE_DECAY: f32 = 0.93;
+
+/// Endless radio static
+pub struct StaticNoise {
+    rng: StdRng,
+
+    /// Low-passed hiss from the previous sample
+    hiss: f32,
+
+    /// Crackle still ringing
+    crackle: f32,
+}
+
+/// Returns an endless source of static
+pub fn static_noise() -> StaticNoise {
+    StaticNoise { rng: StdRng::from_rng(&mut rand::rng()), hiss: 0.0, crackle: 0.0 }
+}
+
+impl Iterator for StaticNoise {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let white = self.rng.random_range(-1.0..1.0);
+        self.hiss += (white - self.hiss) * HISS_SMOOTHING;
+
+        if self.rng.random_bool(CRACKLE_CHANCE) {
+            self.crackle = self.rng.random_range(-CRACKLE_LEVEL..CRACKLE_LEVEL);
+        }
+        self.crackle *= CRACKLE_DECAY;
+
+        Some((self.hiss * HISS_LEVEL + self.crackle).clamp(-1.0, 1.0))
+    }
+}
+
+impl Source for StaticNoise {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        1
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,102 @@
+//! Stereo Placement Wrapper
+//!
+//! Stations can sit slightly off-center or be narrowed toward mono to suit
+//! the cabinet's speakers (`balance` and `stereo_width` in station.info).
+//! Width scales the side (L-R) signal while keeping the mid (L+R), and
+//! balance turns down the opposite channel. Only stereo audio is
+//! affected; mono sources play centered as they are.
+
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+/// Where a station sits in the stereo field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StereoPlacement {
+    /// -1.0 (left only) to 1.0 (right only), 0.0 centered
+    pub balance: f32,
+
+    /// 0.0 (mono) to 1.0 (as recorded); above 1.0 widens
+    pub width: f32,
+}
+
+impl Default for StereoPlacement {
+    fn default() -> Self {
+        StereoPlacement { balance: 0.0, width: 1.0 }
+    }
+}
+
+impl StereoPlacement {
+    /// Returns whether the placement leaves audio untouched
+    fn is_neutral(&self) -> bool {
+        self.balance == 0.0 && self.width == 1.0
+    }
+
+    /// Left and right gains for the balance setting
+    fn gains(&self) -> (f32, f32) {
+        let balance = self.balance.clamp(-1.0, 1.0);
+        ((1.0 - balance).min(1.0), (1.0 + balance).min(1.0))
+    }
+}
+
+/// Source with balance and width applied to its stereo frames
+pub struct Stereo<S> {
+    input: S,
+    placement: StereoPlacement,
+
+    /// Right sample of the frame whose left was just returned
+    pending_right: Option<f32>,
+}
+
+/// Wraps a source to place it in the stereo field
+pub fn stereo<S: Source>(source: S, placement: StereoPlacement) -> Stereo<S> {
+    Stereo { input: source, placement, pending_right: None }
+}
+
+impl<S: Source> Iterator for Stereo<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(right) = self.pending_right.take() {
+            return Some(right);
+        }
+        if self.placement.is_neutral() || self.input.channels() != 2 {
+            return self.input.next();
+        }
+
+        let left = self.input.next()?;
+        let Some(right) = self.input.next() else {return Some(left);};
+
+        let mid = (left + right) / 2.0;
+        let side = (left - right) / 2.0 * self.placement.width.max(0.0);
+        let (left_gain, right_gain) = self.placement.gains();
+        self.pending_right = Some((mid - side) * right_gain);
+        Some((mid + side) * left_gain)
+    }
+}
+
+impl<S: Source> Source for Stereo<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        // The right sample of the frame in progress has already been read
+        self.input.current_span_len().map(|length| length + self.pending_right.is_some() as usize)
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        // Don't play half a frame from before the jump
+        self.pending_right = None;
+        self.input.try_seek(position)
+    }
+}
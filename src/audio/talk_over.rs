@@ -0,0 +1,98 @@
+//! Talk-Over - A voice bed mixed over the start of a track
+//!
+//! Instead of an intro played in front of the track, a DJ can talk up the
+//! song over its opening bars: the bed file plays on top of the track,
+//! which is ducked to a lower level while the bed runs and brought back
+//! up over `RAMP_TIME` once it's done. The bed is cut off after at most
+//! `max_length`, so a long recording never talks over the vocals.
+
+use std::time::Duration;
+
+use rodio::source::{Mix, SeekError, TakeDuration};
+use rodio::{ChannelCount, SampleRate, Source};
+
+use crate::audio::AudioSource;
+
+/// Time taken to bring the track back up after the bed ends
+const RAMP_TIME: Duration = Duration::from_millis(500);
+
+/// Track with its level lowered for its first few seconds
+pub struct Ducked<S> {
+    input: S,
+
+    /// Track gain while the bed plays
+    duck: f32,
+
+    /// How long the track stays ducked, in seconds
+    length: f64,
+
+    /// Seconds of the track played so far
+    elapsed: f64,
+
+    /// Channel of the next sample; time moves on between frames
+    channel: ChannelCount,
+}
+
+/// Mixes a bed over the start of a track, ducking the track under it
+///
+/// # Arguments
+/// * `track` - Track to talk over
+/// * `bed` - Voice recording to play on top
+/// * `duck` - Track gain while the bed plays, 0.0 to 1.0
+/// * `max_length` - Longest the bed may run
+pub fn talk_over(track: AudioSource, bed: AudioSource, duck: f32, max_length: Duration) -> Mix<Ducked<AudioSource>, TakeDuration<AudioSource>> {
+    let length = bed.total_duration().map_or(max_length, |bed_length| bed_length.min(max_length));
+    let ducked = Ducked { input: track, duck: duck.clamp(0.0, 1.0), length: length.as_secs_f64(), elapsed: 0.0, channel: 0 };
+    ducked.mix(bed.take_duration(length))
+}
+
+impl<S: Source> Ducked<S> {
+    /// Gain for the track at the current point
+    fn gain(&self) -> f32 {
+        let past_bed = self.elapsed - self.length;
+        if past_bed <= 0.0 {
+            return self.duck;
+        }
+        let ramped = (past_bed / RAMP_TIME.as_secs_f64()).min(1.0) as f32;
+        self.duck + (1.0 - self.duck) * ramped
+    }
+}
+
+impl<S: Source> Iterator for Ducked<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        let gain = self.gain();
+
+        self.channel = (self.channel + 1) % self.input.channels().max(1);
+        if self.channel == 0 {
+            self.elapsed += 1.0 / self.input.sample_rate().max(1) as f64;
+        }
+        Some(sample * gain)
+    }
+}
+
+impl<S: Source> Source for Ducked<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(position)?;
+        self.elapsed = position.as_secs_f64();
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! Test Pattern Tone
+//!
+//! Steady tone played on off-air frequencies when radio.info sets
+//! `"off_air": "TestPattern"`, so a dead station is audibly different
+//! from a paused one or the static between stations.
+
+use rodio::Source;
+use rodio::source::SineWave;
+
+/// Frequency of the off-air tone, like a broadcast test card
+const TEST_PATTERN_FREQUENCY: f32 = 1000.0;
+
+/// Level of the tone relative to full scale, kept well below program audio
+const TEST_PATTERN_LEVEL: f32 = 0.2;
+
+/// Returns an endless test-pattern tone
+pub fn off_air_tone() -> impl Source + Send + 'static {
+    SineWave::new(TEST_PATTERN_FREQUENCY).amplify(TEST_PATTERN_LEVEL)
+}
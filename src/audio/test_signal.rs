@@ -0,0 +1,157 @@
+//! Test Signals - Speaker and wiring checkout
+//!
+//! `mokradio --test-signals` skips the stations and loops calibration
+//! audio through the normal output chain (master EQ and volume included),
+//! announcing each signal on stdout:
+//! - a 440 Hz tone on both channels
+//! - pink noise, for judging the balance of a speaker by ear
+//! - a tone on the left channel only, then the right only, to catch
+//!   swapped or dead channels
+//! - a slow logarithmic sweep from 20 Hz to 20 kHz, to find rattles and
+//!   where the speaker runs out
+//!
+//! Useful when restoring a cabinet and checking a new speaker and amp.
+
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use rand::Rng;
+use rodio::buffer::SamplesBuffer;
+use rodio::{SampleRate, Sink};
+
+use crate::audio::output::AudioOutput;
+
+/// Format of the test signals (high enough for the top of the sweep)
+const SAMPLE_RATE: SampleRate = 44_100;
+
+/// Level of every signal relative to full scale, to spare the speaker
+const LEVEL: f32 = 0.3;
+
+/// Length of each signal before moving to the next
+const SIGNAL_LENGTH: Duration = Duration::from_secs(5);
+
+/// Sweep range and length
+const SWEEP_START_HZ: f32 = 20.0;
+const SWEEP_END_HZ: f32 = 20_000.0;
+const SWEEP_LENGTH: Duration = Duration::from_secs(15);
+
+/// Calibration signals, in the order they're played
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestSignal {
+    Tone,
+    PinkNoise,
+    LeftOnly,
+    RightOnly,
+    Sweep,
+}
+
+impl TestSignal {
+    /// Every signal, in playing order
+    pub const ALL: [TestSignal; 5] = [
+        TestSignal::Tone,
+        TestSignal::PinkNoise,
+        TestSignal::LeftOnly,
+        TestSignal::RightOnly,
+        TestSignal::Sweep,
+    ];
+
+    /// What's being played, for the console
+    pub fn description(&self) -> &'static str {
+        match self {
+            TestSignal::Tone => "440 Hz tone, both channels",
+            TestSignal::PinkNoise => "pink noise, both channels",
+            TestSignal::LeftOnly => "440 Hz tone, left channel only",
+            TestSignal::RightOnly => "440 Hz tone, right channel only",
+            TestSignal::Sweep => "sweep, 20 Hz to 20 kHz",
+        }
+    }
+
+    /// Renders the signal as stereo audio
+    pub fn render(&self) -> SamplesBuffer {
+        let (left, right): (Vec<f32>, Vec<f32>) = match self {
+            TestSignal::Tone => {
+                let tone = tone(440.0, SIGNAL_LENGTH);
+                (tone.clone(), tone)
+            },
+            TestSignal::PinkNoise => {
+                let noise = pink_noise(SIGNAL_LENGTH);
+                (noise.clone(), noise)
+            },
+            TestSignal::LeftOnly => {
+                let tone = tone(440.0, SIGNAL_LENGTH);
+                let silence = vec![0.0; tone.len()];
+                (tone, silence)
+            },
+            TestSignal::RightOnly => {
+                let tone = tone(440.0, SIGNAL_LENGTH);
+                let silence = vec![0.0; tone.len()];
+                (silence, tone)
+            },
+            TestSignal::Sweep => {
+                let sweep = sweep(SWEEP_LENGTH);
+                (sweep.clone(), sweep)
+            }
+        };
+
+        let interleaved = left.into_iter()
+            .zip(right)
+            .flat_map(|(left, right)| [left, right])
+            .collect::<Vec<f32>>();
+        SamplesBuffer::new(2, SAMPLE_RATE, interleaved)
+    }
+}
+
+/// Plays the test signals in a loop until the process is stopped
+pub fn run_test_signals(output: &AudioOutput) -> ! {
+    println!("test signal mode, Ctrl-C to stop");
+    let sink = Sink::connect_new(output.mixer());
+    loop {
+        for signal in TestSignal::ALL {
+            println!("test signal: {}", signal.description());
+            sink.append(signal.render());
+            sink.sleep_until_end();
+        }
+    }
+}
+
+/// Number of samples in a duration
+fn samples(length: Duration) -> usize {
+    (length.as_secs_f32() * SAMPLE_RATE as f32) as usize
+}
+
+/// Steady sine tone
+fn tone(frequency: f32, length: Duration) -> Vec<f32> {
+    (0..samples(length))
+        .map(|n| (2.0 * PI * frequency * n as f32 / SAMPLE_RATE as f32).sin() * LEVEL)
+        .collect()
+}
+
+/// Pink noise, from white noise through Paul Kellet's economy filter
+fn pink_noise(length: Duration) -> Vec<f32> {
+    let mut rng = rand::rng();
+    let (mut b0, mut b1, mut b2) = (0.0f32, 0.0f32, 0.0f32);
+    (0..samples(length))
+        .map(|_| {
+            let white: f32 = rng.random_range(-1.0..1.0);
+            b0 = 0.99765 * b0 + white * 0.0990460;
+            b1 = 0.96300 * b1 + white * 0.2965164;
+            b2 = 0.57000 * b2 + white * 1.0526913;
+            // The filter's gain is about 3, bring it back to full scale
+            (b0 + b1 + b2 + white * 0.1848) / 3.0 * LEVEL
+        })
+        .collect()
+}
+
+/// Logarithmic sine sweep, spending equal time in each octave
+fn sweep(length: Duration) -> Vec<f32> {
+    let total = samples(length) as f32;
+    let log_range = (SWEEP_END_HZ / SWEEP_START_HZ).ln();
+    let mut phase = 0.0f32;
+    (0..samples(length))
+        .map(|n| {
+            let frequency = SWEEP_START_HZ * (log_range * n as f32 / total).exp();
+            phase = (phase + 2.0 * PI * frequency / SAMPLE_RATE as f32) % (2.0 * PI);
+            phase.sin() * LEVEL
+        })
+        .collect()
+}
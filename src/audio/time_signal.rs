@@ -0,0 +1,296 @@
+//! Time Signal - Speaking clock for "Time" stations
+//!
+//! Announces the time like a telephone time service ("At the tone, the
+//! time will be ten forty-two and twenty seconds") followed by a pip
+//! exactly on the mark, every `interval_secs`. Announcements are pieced
+//! together from word clips: recordings from the station's `clips/`
+//! folder where they exist, otherwise clips rendered once with
+//! text-to-speech. Clips load on a background thread, and the station
+//! plays the pips alone until they're ready.
+//!
+//! Audio is scheduled against the wall clock one mark at a time. If the
+//! source falls behind the clock (its station was paused, or the sink
+//! stalled) it drops what it had and reschedules, so the pip stays on the
+//! mark whenever it's heard.
+//!
+//! Rendered ahead of time (see `radio::aircheck`), a signal instead keeps
+//! its own clock, starting from a given time and advancing with the
+//! samples taken, and has its clips loaded before the first mark.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, Timelike};
+use rodio::source::UniformSourceIterator;
+use rodio::{ChannelCount, SampleRate, Source};
+
+use crate::config::locale::Phrase;
+use crate::config::radio_config;
+use crate::file_loader::decoder::load_and_decode;
+use crate::file_loader::tts;
+use crate::radio::station::config::TimeServiceConfig;
+
+/// Format of the time signal (speech doesn't need more)
+const SAMPLE_RATE: SampleRate = 22_050;
+
+/// Pip on the mark
+const PIP_FREQUENCY: f32 = 1000.0;
+const PIP_LENGTH: Duration = Duration::from_millis(300);
+const PIP_LEVEL: f32 = 0.3;
+
+/// Silence between the end of the announcement and the pip
+const GAP_BEFORE_PIP: Duration = Duration::from_millis(500);
+
+/// Silence between words
+const GAP_BETWEEN_WORDS: Duration = Duration::from_millis(60);
+
+/// How far playback may drift from the wall clock before rescheduling
+const RESYNC_TOLERANCE: Duration = Duration::from_millis(250);
+
+/// Samples between drift checks (a tenth of a second)
+const RESYNC_CHECK_SAMPLES: usize = SAMPLE_RATE as usize / 10;
+
+/// Word clips at the signal's sample rate, keyed by clip name
+type Clips = HashMap<String, Vec<f32>>;
+
+/// Endless speaking clock source
+pub struct TimeSignal {
+    config: TimeServiceConfig,
+
+    /// Filled in by the loader thread once every clip is ready
+    clips: Arc<OnceLock<Clips>>,
+
+    /// Audio from when it was scheduled through the next pip
+    segment: Vec<f32>,
+    position: usize,
+
+    /// Wall-clock time the first sample of `segment` was meant for
+    segment_start: SystemTime,
+
+    /// Follow the wall clock, rather than the samples taken
+    wall_clock: bool,
+}
+
+/// Creates a speaking clock for a Time station
+///
+/// # Arguments
+/// * `station_path` - Station folder (recorded clips are in `clips/`)
+/// * `config` - Interval, clock format and language from station.info
+pub fn time_signal(station_path: &Path, config: &TimeServiceConfig) -> TimeSignal {
+    let clips = Arc::new(OnceLock::new());
+    let loaded_clips = clips.clone();
+    let clips_path = station_path.join("clips");
+    let language = config.language.clone().unwrap_or_else(|| radio_config().locale.language.clone());
+    thread::spawn(move || {
+        let _ = loaded_clips.set(load_clips(&clips_path, &language));
+    });
+
+    let mut signal = TimeSignal {
+        config: config.clone(),
+        clips,
+        segment: Vec::new(),
+        position: 0,
+        segment_start: SystemTime::now(),
+        wall_clock: true,
+    };
+    signal.schedule();
+    signal
+}
+
+/// Creates a speaking clock that runs from `start` as fast as it's read
+///
+/// Used to render a Time station offline. Clips load before this returns,
+/// so every mark is announced.
+pub fn offline_time_signal(station_path: &Path, config: &TimeServiceConfig, start: SystemTime) -> TimeSignal {
+    let language = config.language.clone().unwrap_or_else(|| radio_config().locale.language.clone());
+    let clips = Arc::new(OnceLock::new());
+    let _ = clips.set(load_clips(&station_path.join("clips"), &language));
+
+    let mut signal = TimeSignal {
+        config: config.clone(),
+        clips,
+        segment: Vec::new(),
+        position: 0,
+        segment_start: start,
+        wall_clock: false,
+    };
+    signal.schedule();
+    signal
+}
+
+impl TimeSignal {
+    /// Builds the audio from now through the pip on the next mark
+    fn schedule(&mut self) {
+        let now = if self.wall_clock {SystemTime::now()} else {self.scheduled_time()};
+        let interval = self.config.interval_secs.max(1);
+        let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mark = UNIX_EPOCH + Duration::from_secs((since_epoch.as_secs() / interval + 1) * interval);
+        let until_mark = samples(mark.duration_since(now).unwrap_or_default());
+
+        let mut segment = vec![0.0; until_mark];
+
+        // The announcement ends just before the pip, if there's room for it
+        if let Some(clips) = self.clips.get() {
+            let speech = self.announcement(clips, mark);
+            let gap = samples(GAP_BEFORE_PIP);
+            if speech.len() + gap <= until_mark {
+                let start = until_mark - gap - speech.len();
+                segment[start..start + speech.len()].copy_from_slice(&speech);
+            }
+        }
+
+        segment.extend(pip());
+        self.segment = segment;
+        self.position = 0;
+        self.segment_start = now;
+    }
+
+    /// Strings together the clips announcing a mark
+    fn announcement(&self, clips: &Clips, mark: SystemTime) -> Vec<f32> {
+        let local_time: DateTime<Local> = mark.into();
+        let hour = if self.config.twenty_four_hour {
+            local_time.hour()
+        } else {
+            local_time.hour12().1
+        };
+
+        let mut words = vec!["at_the_tone".to_string(), hour.to_string()];
+        match local_time.minute() {
+            0 => words.push("oclock".to_string()),
+            minute => words.push(minute.to_string())
+        }
+        if local_time.second() != 0 {
+            words.extend(["and".to_string(), local_time.second().to_string(), "seconds".to_string()]);
+        }
+
+        let gap = vec![0.0; samples(GAP_BETWEEN_WORDS)];
+        let mut speech = Vec::new();
+        for word in words.iter().filter_map(|word| clips.get(word)) {
+            speech.extend_from_slice(word);
+            speech.extend_from_slice(&gap);
+        }
+        speech
+    }
+
+    /// Wall-clock time the next sample is meant for
+    fn scheduled_time(&self) -> SystemTime {
+        self.segment_start + Duration::from_secs_f64(self.position as f64 / SAMPLE_RATE as f64)
+    }
+
+    /// Returns whether playback has drifted off the wall clock
+    fn has_drifted(&self) -> bool {
+        let scheduled = self.scheduled_time();
+        let drift = match SystemTime::now().duration_since(scheduled) {
+            Ok(behind) => behind,
+            Err(ahead) => ahead.duration()
+        };
+        drift > RESYNC_TOLERANCE
+    }
+}
+
+impl Iterator for TimeSignal {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.segment.len()
+            || (self.wall_clock && self.position.is_multiple_of(RESYNC_CHECK_SAMPLES) && self.has_drifted()) {
+            self.schedule();
+        }
+        let sample = self.segment[self.position];
+        self.position += 1;
+        Some(sample)
+    }
+}
+
+impl Source for TimeSignal {
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> ChannelCount {
+        1
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Number of samples in a duration at the signal's rate
+fn samples(duration: Duration) -> usize {
+    (duration.as_secs_f64() * SAMPLE_RATE as f64) as usize
+}
+
+/// One pip of tone
+fn pip() -> Vec<f32> {
+    (0..samples(PIP_LENGTH))
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            (2.0 * std::f32::consts::PI * PIP_FREQUENCY * t).sin() * PIP_LEVEL
+        })
+        .collect()
+}
+
+/// Loads every word clip, recorded where possible and spoken otherwise
+///
+/// Clips that can't be loaded either way are logged and left out, so the
+/// announcement skips that word.
+fn load_clips(clips_path: &Path, language: &str) -> Clips {
+    let recorded = recorded_clips(clips_path);
+    let locale = &radio_config().locale;
+
+    let spoken_words = [
+        ("at_the_tone", locale.phrase(language, Phrase::AtTheTone)),
+        ("oclock", locale.phrase(language, Phrase::OClock)),
+        ("and", locale.phrase(language, Phrase::And)),
+        ("seconds", locale.phrase(language, Phrase::Seconds)),
+    ];
+    let words = spoken_words.into_iter()
+        .map(|(name, text)| (name.to_string(), text))
+        .chain((0..60).map(|number| (number.to_string(), number.to_string())));
+
+    words.filter_map(|(name, text)| {
+        let clip = match recorded.get(&name) {
+            Some(clip_path) => decode_clip(clip_path),
+            None => speak_clip(&text, language)
+        };
+        match clip {
+            Ok(clip) => Some((name, clip)),
+            Err(e) => {
+                eprintln!("Time signal clip {} unavailable: {}", name, e);
+                None
+            }
+        }
+    }).collect()
+}
+
+/// Recorded clips in the station's clips folder, keyed by file stem
+fn recorded_clips(clips_path: &Path) -> HashMap<String, PathBuf> {
+    let Ok(entries) = fs::read_dir(clips_path) else {return HashMap::new();};
+    entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter_map(|path| Some((path.file_stem()?.to_string_lossy().to_string(), path)))
+        .collect()
+}
+
+/// Decodes a clip and converts it to the signal's format
+fn decode_clip(clip_path: &Path) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    let decoder = load_and_decode(clip_path)?;
+    Ok(UniformSourceIterator::new(decoder, 1, SAMPLE_RATE).collect())
+}
+
+/// Renders a clip with text-to-speech
+fn speak_clip(text: &str, language: &str) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    let speech_path = tts::synthesize(text, language)?;
+    let clip = decode_clip(&speech_path);
+    fs::remove_file(&speech_path).ok();
+    clip
+}
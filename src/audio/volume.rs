@@ -0,0 +1,161 @@
+//! Master Volume
+//!
+//! Scaling samples down in software throws away bits: at low listening
+//! levels a 16-bit DAC ends up playing 10-bit audio. Audio HATs with a
+//! hardware volume stage (most I2C-controlled DACs and amps) can do the
+//! attenuation after conversion instead, which keeps the full dynamic
+//! range. With `"control": "Alsa"` in radio.info the master volume is set
+//! on an ALSA mixer element through `amixer`; otherwise, or if `amixer`
+//! fails, the master mix is scaled in software. `amixer` runs on a thread
+//! of its own, so a slow or hung mixer never holds up the Station Manager;
+//! when levels arrive faster than it can set them, only the latest is set.
+//!
+//! Muting silences the master mix in software whichever control is in
+//! use, and leaves the volume where it was for when the radio is unmuted.
+
+use std::process::Command;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{Sender, channel};
+use std::thread;
+
+use rodio::Source;
+
+use crate::audio::slew::{Slewed, SlewedGain, slewed};
+use crate::config::{VolumeConfig, VolumeControl};
+
+/// Radio-wide volume control, shared with the output's gain stage
+#[derive(Clone)]
+pub struct MasterVolume {
+    /// Volume the listener asked for, 0.0 to 1.0 (f32 bits)
+    level: Arc<AtomicU32>,
+
+    /// Whether the output is muted, whatever the level
+    muted: Arc<AtomicBool>,
+
+    /// Gain applied to the master mix in software
+    gain: SlewedGain,
+
+    /// Levels for the `amixer` thread, with `"control": "Alsa"`
+    alsa_levels: Option<Sender<f32>>,
+}
+
+impl MasterVolume {
+    /// Creates the control and applies the starting level from radio.info
+    pub fn new(config: &VolumeConfig) -> MasterVolume {
+        let muted = Arc::new(AtomicBool::new(false));
+        let gain = SlewedGain::new(0.0);
+        let alsa_levels = match config.control {
+            VolumeControl::Software => None,
+            VolumeControl::Alsa => Some(spawn_alsa(config.clone(), gain.clone(), muted.clone()))
+        };
+        let volume = MasterVolume {
+            level: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            muted,
+            gain,
+            alsa_levels,
+        };
+        volume.set(config.level);
+        volume
+    }
+
+    /// Sets the master volume
+    ///
+    /// A muted radio stays silent; the new level is heard once it's unmuted.
+    ///
+    /// # Arguments
+    /// * `level` - 0.0 (silent) to 1.0 (full volume); clamped to that range,
+    ///   and ignored (logged) if it isn't a number
+    pub fn set(&self, level: f32) {
+        if !level.is_finite() {
+            eprintln!("Ignoring volume level {}", level);
+            return;
+        }
+        let level = level.clamp(0.0, 1.0);
+        self.level.store(level.to_bits(), Ordering::Relaxed);
+        if self.is_muted() {
+            return;
+        }
+
+        let Some(alsa_levels) = &self.alsa_levels else {
+            self.gain.set(software_gain(level));
+            return;
+        };
+        if alsa_levels.send(level).is_err() {
+            eprintln!("Mixer thread has stopped, scaling volume in software");
+            self.gain.set(software_gain(level));
+        }
+    }
+
+    /// Returns the master volume, 0.0 to 1.0
+    pub fn level(&self) -> f32 {
+        f32::from_bits(self.level.load(Ordering::Relaxed))
+    }
+
+    /// Mutes or unmutes the output, going back to the same level
+    pub fn set_muted(&self, muted: bool) {
+        self.muted.store(muted, Ordering::Relaxed);
+        if muted {
+            self.gain.set(0.0);
+        } else {
+            self.set(self.level());
+        }
+    }
+
+    /// Returns whether the output is muted
+    pub fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::Relaxed)
+    }
+}
+
+/// Starts the thread that sets the ALSA mixer element
+///
+/// Each level sets the software gain once `amixer` is done: unity if it
+/// worked, or the level's software gain if it didn't. A muted output is
+/// left silent.
+fn spawn_alsa(config: VolumeConfig, gain: SlewedGain, muted: Arc<AtomicBool>) -> Sender<f32> {
+    let (alsa_levels, levels) = channel::<f32>();
+    thread::spawn(move || {
+        while let Ok(level) = levels.recv() {
+            // Only the latest of the levels waiting is worth setting
+            let level = levels.try_iter().last().unwrap_or(level);
+            let software = match set_alsa(&config, level) {
+                Ok(()) => 1.0,
+                Err(e) => {
+                    eprintln!("Failed to set {} volume on card {}, scaling in software: {}",
+                        config.element, config.card, e);
+                    software_gain(level)
+                }
+            };
+            if !muted.load(Ordering::Relaxed) {
+                gain.set(software);
+            }
+        }
+    });
+    alsa_levels
+}
+
+/// Sets the ALSA mixer element with `amixer`
+///
+/// `-M` uses the mapped (perceptual) scale, so equal steps of `level`
+/// sound like equal steps of loudness whatever the chip's dB range.
+fn set_alsa(config: &VolumeConfig, level: f32) -> Result<(), Box<dyn std::error::Error>> {
+    let percent = format!("{}%", (level * 100.0).round() as u32);
+    let status = Command::new("amixer")
+        .args(["-q", "-M", "-c", &config.card, "sset", &config.element, &percent])
+        .status()?;
+    if !status.success() {
+        return Err(format!("amixer exited with {}", status).into());
+    }
+    Ok(())
+}
+
+/// Gain for a volume level, squared so the knob feels even to the ear
+fn software_gain(level: f32) -> f32 {
+    level * level
+}
+
+/// Wraps the master mix in the software gain stage
+pub fn attenuated<S: Source>(source: S, volume: &MasterVolume) -> Slewed<S> {
+    slewed(source, volume.gain.clone())
+}
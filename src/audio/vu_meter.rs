@@ -0,0 +1,89 @@
+//! VU Meter
+//!
+//! Drives a level display on the cabinet from the master output's
+//! `LevelMeter`. With one pin, the pin's PWM duty cycle moves a
+//! moving-coil meter (through a series resistor sized for full scale at
+//! 3.3V); with several, they light an LED bar from the first pin up. The
+//! display rises quickly and falls back slowly, as a real VU needle does,
+//! and shows average loudness unless `peak` is set.
+//!
+//! # JSON Format
+//! ```json
+//! "hardware": {
+//!     "vu_meter": { "pins": [18], "floor_db": -30.0, "peak": false }
+//! }
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use rppal::gpio::{Gpio, OutputPin};
+
+use crate::audio::level_meter::LevelMeter;
+use crate::config::hardware::VuMeterConfig;
+
+/// How often the display is updated
+const UPDATE_INTERVAL: Duration = Duration::from_millis(20);
+
+/// PWM frequency for a moving-coil meter, far above what the needle can follow
+const PWM_FREQUENCY: f64 = 1000.0;
+
+/// Time constants of the display rising to a louder level and falling back
+const RISE_SECS: f32 = 0.065;
+const FALL_SECS: f32 = 0.3;
+
+/// Starts driving the meter on a background thread until `running` is cleared
+///
+/// Pins that can't be claimed are logged and the radio runs without a meter.
+pub fn start(config: &VuMeterConfig, meter: LevelMeter, running: Arc<AtomicBool>) {
+    let pins: Result<Vec<OutputPin>, _> = Gpio::new()
+        .and_then(|gpio| config.pins.iter().map(|pin| gpio.get(*pin).map(|pin| pin.into_output_low())).collect());
+    let mut pins = match pins {
+        Ok(pins) if !pins.is_empty() => pins,
+        Ok(_) => return,
+        Err(e) => {
+            eprintln!("Failed to claim VU meter pins {:?}, running without it: {}", config.pins, e);
+            return;
+        }
+    };
+    let config = config.clone();
+
+    thread::spawn(move || {
+        let step = UPDATE_INTERVAL.as_secs_f32();
+        let (rise, fall) = (1.0 - (-step / RISE_SECS).exp(), 1.0 - (-step / FALL_SECS).exp());
+        let mut shown = 0.0f32;
+        while running.load(Ordering::Relaxed) {
+            let levels = meter.levels();
+            let level = if config.peak {levels.peak} else {levels.rms};
+            let target = scale_position(level, config.floor_db);
+            shown += (target - shown) * if target > shown {rise} else {fall};
+
+            if let Err(e) = show(&mut pins, shown) {
+                eprintln!("VU meter stopped: {}", e);
+                return;
+            }
+            thread::sleep(UPDATE_INTERVAL);
+        }
+        show(&mut pins, 0.0).ok();
+    });
+}
+
+/// Where a level falls on the meter's scale, from 0.0 (floor) to 1.0 (full scale)
+fn scale_position(level: f32, floor_db: f32) -> f32 {
+    let db = 20.0 * level.max(f32::MIN_POSITIVE).log10();
+    (1.0 - db / floor_db.min(-1.0)).clamp(0.0, 1.0)
+}
+
+/// Moves the needle, or lights the LED bar, to a scale position
+fn show(pins: &mut [OutputPin], position: f32) -> rppal::gpio::Result<()> {
+    if let [needle] = pins {
+        return needle.set_pwm_frequency(PWM_FREQUENCY, position as f64);
+    }
+    let lit = (position * pins.len() as f32).round() as usize;
+    for (index, pin) in pins.iter_mut().enumerate() {
+        if index < lit {pin.set_high()} else {pin.set_low()}
+    }
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Warm Start Source
+//!
+//! At boot every station asks the File Loader for its first track at
+//! once, and the last in line can take a while to come back. The loader
+//! answers first with a few seconds of each first track decoded into
+//! memory (see `file_loader::warmer`), which the station plays straight
+//! away through a `WarmStart`. When the whole track arrives it's handed
+//! over through the `Continuation` and the source carries on with it,
+//! picking up where the buffer ended.
+//!
+//! If the rest isn't in by the time the buffer runs out, the source plays
+//! silence until it is. If it never comes (the load failed, or the
+//! station was replaced) the continuation is dropped and the source ends
+//! after the buffer.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rodio::source::SeekError;
+use rodio::{ChannelCount, SampleRate, Source};
+
+use crate::audio::AudioSource;
+
+/// What the warm start is waiting on
+enum Handoff {
+    /// Still loading
+    Waiting,
+
+    /// Loaded and ready to take over from the buffer
+    Ready(AudioSource),
+
+    /// Never coming; end after the buffer
+    Abandoned,
+}
+
+/// Plays a decoded opening, then the rest of the track once it's handed over
+pub struct WarmStart {
+    opening: AudioSource,
+
+    /// The rest of the track, once it's taken over from the opening
+    rest: Option<AudioSource>,
+    handoff: Arc<Mutex<Handoff>>,
+
+    /// Samples played into the current frame, so the rest starts on a frame boundary
+    frame_position: usize,
+}
+
+/// The station's end of a warm start, for handing over the rest of the track
+pub struct Continuation {
+    handoff: Arc<Mutex<Handoff>>,
+}
+
+/// Starts a track from its decoded opening
+///
+/// # Returns
+/// The source to play now, and the continuation to hand the rest to
+pub fn warm_start(opening: AudioSource) -> (WarmStart, Continuation) {
+    let handoff = Arc::new(Mutex::new(Handoff::Waiting));
+    let warm_start = WarmStart { opening, rest: None, handoff: handoff.clone(), frame_position: 0 };
+    (warm_start, Continuation { handoff })
+}
+
+impl Continuation {
+    /// Returns whether the warm start is still playing or queued to play
+    pub fn is_listening(&self) -> bool {
+        Arc::strong_count(&self.handoff) > 1
+    }
+
+    /// Hands over the rest of the track, already moved past the opening
+    pub fn resume(self, rest: AudioSource) {
+        *self.handoff.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Handoff::Ready(rest);
+    }
+}
+
+impl Drop for Continuation {
+    fn drop(&mut self) {
+        let mut handoff = self.handoff.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if matches!(*handoff, Handoff::Waiting) {
+            *handoff = Handoff::Abandoned;
+        }
+    }
+}
+
+impl WarmStart {
+    /// Takes over the rest of the track if it has arrived
+    ///
+    /// # Returns
+    /// false once it's known the rest will never come
+    fn take_rest(&mut self) -> bool {
+        let mut handoff = self.handoff.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match std::mem::replace(&mut *handoff, Handoff::Waiting) {
+            Handoff::Ready(rest) => {
+                self.rest = Some(rest);
+                true
+            },
+            Handoff::Waiting => true,
+            Handoff::Abandoned => {
+                *handoff = Handoff::Abandoned;
+                false
+            }
+        }
+    }
+}
+
+impl Iterator for WarmStart {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(rest) = self.rest.as_mut() {
+            return rest.next();
+        }
+
+        let channels = self.opening.channels().max(1) as usize;
+        let sample = match self.opening.next() {
+            Some(sample) => sample,
+            // Only switch over between frames, so the channels stay in step
+            None if self.frame_position == 0 => {
+                if !self.take_rest() {
+                    return None;
+                }
+                match self.rest.as_mut() {
+                    Some(rest) => return rest.next(),
+                    None => 0.0
+                }
+            },
+            None => 0.0
+        };
+        self.frame_position = (self.frame_position + 1) % channels;
+        Some(sample)
+    }
+}
+
+impl Source for WarmStart {
+    fn current_span_len(&self) -> Option<usize> {
+        match &self.rest {
+            Some(rest) => rest.current_span_len(),
+            // Silence while waiting is checked again a frame at a time
+            None => match self.opening.current_span_len() {
+                Some(0) | None => Some(self.opening.channels().max(1) as usize - self.frame_position),
+                length => length
+            }
+        }
+    }
+
+    fn channels(&self) -> ChannelCount {
+        match &self.rest {
+            Some(rest) => rest.channels(),
+            None => self.opening.channels()
+        }
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        match &self.rest {
+            Some(rest) => rest.sample_rate(),
+            None => self.opening.sample_rate()
+        }
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    fn try_seek(&mut self, position: Duration) -> Result<(), SeekError> {
+        match self.rest.as_mut() {
+            Some(rest) => rest.try_seek(position),
+            None => Err(SeekError::NotSupported { underlying_source: std::any::type_name::<Self>() })
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Backup and Restore - Radio settings in one archive
+//!
+//! `mokradio backup radio.tar.gz` bundles everything that isn't audio from
+//! the stations root: radio.info, plus the files sitting directly in each
+//! station folder (station.info, play history, and any other state the
+//! radio keeps there), and whatever the storage backend keeps in the root. Playlists, DJ intros and clips are left out; they
+//! usually live on a bigger drive than the SD card anyway.
+//!
+//! `mokradio restore radio.tar.gz` unpacks the archive over the stations
+//! root, so after an SD card rebuild the radio comes back as it was once
+//! the music is copied back.
+//!
+//! Archives are gzipped tarballs made with the system `tar`, so they can
+//! also be inspected or unpacked by hand.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::stations_root;
+use crate::radio::station::content::Band;
+use crate::storage::storage;
+
+/// Writes a backup archive of the radio's settings and state
+///
+/// # Returns
+/// The files archived, relative to the stations root
+pub fn backup(archive_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let root = stations_root();
+    let files = settings_files(root);
+    if files.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("nothing to back up in {}", root.display())));
+    }
+
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(absolute(archive_path)?)
+        .arg("-C")
+        .arg(root)
+        .args(&files)
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("tar exited with {}", status)));
+    }
+    Ok(files)
+}
+
+/// Unpacks a backup archive over the stations root
+///
+/// Files in the archive replace the ones on disk; everything else (the
+/// music, and stations added since the backup) is left alone.
+///
+/// # Returns
+/// The files restored, relative to the stations root
+pub fn restore(archive_path: &Path) -> io::Result<Vec<PathBuf>> {
+    let archive_path = absolute(archive_path)?;
+    let listing = Command::new("tar").arg("-tzf").arg(&archive_path).output()?;
+    if !listing.status.success() {
+        return Err(io::Error::other(format!(
+            "{} is not a readable backup: {}",
+            archive_path.display(),
+            String::from_utf8_lossy(&listing.stderr).trim()
+        )));
+    }
+
+    // Refuse archives that would write outside the stations root
+    let files: Vec<PathBuf> = String::from_utf8_lossy(&listing.stdout).lines().map(PathBuf::from).collect();
+    if let Some(unsafe_path) = files.iter().find(|file| file.is_absolute() || file.components().any(|part| part.as_os_str() == "..")) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("backup contains {} outside the stations root", unsafe_path.display())
+        ));
+    }
+
+    let root = stations_root();
+    fs::create_dir_all(root)?;
+    let status = Command::new("tar").arg("-xzf").arg(&archive_path).arg("-C").arg(root).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("tar exited with {}", status)));
+    }
+    Ok(files)
+}
+
+/// Settings and state files under the stations root, relative to it
+fn settings_files(root: &Path) -> Vec<PathBuf> {
+    let radio_info = PathBuf::from("radio.info");
+    let station_files = Band::all().flat_map(|band| {
+        let band_path = root.join(band.directory());
+        let Ok(stations) = fs::read_dir(&band_path) else {return Vec::new();};
+        stations.filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|station_path| station_path.is_dir())
+            .flat_map(|station_path| top_level_files(&station_path))
+            .filter_map(|file_path| file_path.strip_prefix(root).ok().map(Path::to_path_buf))
+            .collect::<Vec<PathBuf>>()
+    });
+
+    let stored_files = storage().files().into_iter()
+        .filter(|file_path| file_path.is_file())
+        .filter_map(|file_path| file_path.strip_prefix(root).ok().map(Path::to_path_buf));
+
+    root.join(&radio_info).is_file().then_some(radio_info)
+        .into_iter()
+        .chain(station_files)
+        .chain(stored_files)
+        .collect()
+}
+
+/// Files directly inside a folder, not in its subfolders
+fn top_level_files(folder: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(folder) else {return Vec::new();};
+    let mut files: Vec<PathBuf> = entries.filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Resolves a path given on the command line, since tar runs from the root
+fn absolute(path: &Path) -> io::Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
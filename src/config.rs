@@ -1,2 +1,785 @@
+//! Global Configuration Module
+//!
+//! Handles loading of the radio-wide `radio.info` JSON file that sits in the
+//! stations root. Station-specific settings stay in each station's own
+//! station.info; this file holds everything that applies to the whole radio.
+//!
+//! # JSON Format
+//! ```json
+//! {
+//!     "bands": [
+//!         { "name": "AM", "directory": "AM" },
+//!         { "name": "FM", "directory": "FM" }
+//!     ],
+//!     "dial": {
+//!         "range": 240,
+//!         "edge": "EndStop",
+//!         "acceleration": 2.0
+//!     },
+//!     "tuning_input": "Touch",
+//!     "touch": { "layout": "Ring", "electrodes": 12, "steps_per_electrode": 20.0 },
+//!     "hardware": {
+//!         "band_switch_pin": 4
+//!     },
+//!     "off_air": "Static",
+//!     "tuning": { "preset": "Classic" },
+//!     "drift": { "enabled": true, "max_ticks": 12.0 },
+//!     "monitor_device": "USB Audio Device",
+//!     "dual_mono": false,
+//!     "api_server": "0.0.0.0:8081",
+//!     "companion_token": "change-me",
+//!     "public_metrics": false,
+//!     "rate_limit": { "requests_per_minute": 30, "burst": 10 },
+//!     "metadata_cache": { "network_mounts": ["/mnt/nas"] },
+//!     "transcode": { "extensions": ["wma", "ape"] },
+//!     "ram_cache": { "directory": "/dev/shm/mokradio", "max_megabytes": 32 },
+//!     "audio_buffer": { "preset": "GlitchResistant" },
+//!     "eq": { "preset": "SmallSpeaker" },
+//!     "volume": { "control": "Alsa", "card": "0", "element": "Digital", "level": 0.6 },
+//!     "audio_backend": "Device",
+//!     "priority": { "enabled": true, "loader_nice": -5, "audio_realtime": 40 },
+//!     "turnover_secs": 300,
+//!     "rewrite_migrated_configs": false,
+//!     "storage": "JsonFile",
+//!     "locale": { "language": "en" },
+//!     "profile": "kids",
+//!     "profiles": { "kids": { "dial": { "FM": { "0": "library/stories" } } } }
+//! }
+//! ```
+
+pub mod cloning;
+pub mod equalizer;
 pub mod folder_scanner;
-pub mod station_loader;
\ No newline at end of file
+pub mod hardware;
+pub mod locale;
+pub mod manifest;
+pub mod profile;
+pub mod setup;
+pub mod station_loader;
+pub mod tuning;
+
+use std::{collections::HashMap, fs::read_to_string, path::{Path, PathBuf}, sync::OnceLock};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, from_str, from_value};
+
+use crate::constants;
+use crate::audio::output::AudioBackend;
+use crate::fault_injection::Fault;
+use crate::storage::StorageBackend;
+use equalizer::EqConfig;
+use hardware::HardwareConfig;
+use locale::LocaleConfig;
+use profile::ProfileConfig;
+use tuning::TuningConfig;
+
+/// Radio-wide configuration, loaded once at startup
+static RADIO_CONFIG: OnceLock<RadioConfig> = OnceLock::new();
+
+/// Stations root, resolved once at startup
+static STATIONS_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Environment variable that moves the stations root (tests, simulators)
+pub const STATIONS_ROOT_VARIABLE: &str = "MOKRADIO_STATIONS";
+
+/// A band on the dial (AM, FM, shortwave, ...)
+#[derive(Deserialize, Debug, Clone)]
+pub struct BandConfig {
+    /// Display name used in logs, messages and the API
+    pub name: String,
+
+    /// Directory under the stations root holding this band's stations
+    pub directory: String,
+}
+
+/// What a virtual dial does when it is turned past either end
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialEdge {
+    /// Stop at the ends like a real tuning capacitor
+    EndStop,
+
+    /// Wrap around from the top of the band to the bottom and back
+    Wrap
+}
+
+/// Virtual dial settings for encoder-based builds
+/// 
+/// Rotary encoders report relative steps rather than an absolute position,
+/// so the input thread keeps a virtual dial position and scales it onto
+/// the same range an ADC potentiometer produces.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DialConfig {
+    /// Number of encoder steps from one end of the dial to the other
+    pub range: usize,
+
+    /// Behavior at the ends of the dial
+    pub edge: DialEdge,
+
+    /// Extra steps per detent when spinning fast (1.0 disables acceleration)
+    pub acceleration: f32,
+
+    /// Steps closer together than this count as spinning fast
+    pub acceleration_window_ms: u64,
+}
+
+impl Default for DialConfig {
+    fn default() -> Self {
+        DialConfig {
+            range: 240,
+            edge: DialEdge::EndStop,
+            acceleration: 1.0,
+            acceleration_window_ms: 40,
+        }
+    }
+}
+
+/// What the listener tunes with
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuningInput {
+    /// Potentiometer read through the ADC (the I2C tuner, or `hardware.tuner_adc`)
+    Adc,
+
+    /// Capacitive slider or ring on an MPR121 (see `input::touch`)
+    Touch,
+
+    /// Quadrature rotary encoder on two GPIO pins (see `input::encoder`)
+    Encoder,
+
+    /// Arrow keys in the terminal, for development without hardware (see `input::keyboard`)
+    Keyboard
+}
+
+/// Shape of a capacitive touch pad
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchLayout {
+    /// Electrodes in a line; sliding off either end stops
+    Slider,
+
+    /// Electrodes in a circle; the last sits next to the first
+    Ring
+}
+
+/// Capacitive touch tuning settings
+/// 
+/// Sliding along the pad turns the virtual dial, so `dial` still sets the
+/// range, end behavior and acceleration.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TouchConfig {
+    /// I2C address of the MPR121 (0x5A-0x5D depending on the ADDR pin)
+    pub address: u16,
+
+    /// Electrodes wired to the pad, starting from ELE0
+    pub electrodes: u8,
+
+    /// Slider or ring
+    pub layout: TouchLayout,
+
+    /// Dial steps per electrode the finger slides across
+    pub steps_per_electrode: f32,
+
+    /// Touch and release sensitivity (lower is more sensitive)
+    pub touch_threshold: u8,
+    pub release_threshold: u8,
+}
+
+impl Default for TouchConfig {
+    fn default() -> Self {
+        TouchConfig {
+            address: 0x5A,
+            electrodes: 12,
+            layout: TouchLayout::Slider,
+            steps_per_electrode: 10.0,
+            touch_threshold: 12,
+            release_threshold: 6,
+        }
+    }
+}
+
+/// Slow random drift of the effective dial position
+/// 
+/// Old sets wander off frequency as they warm up. When enabled, the
+/// effective dial position takes a tiny random step every interval, so a
+/// precisely tuned station slowly picks up static until the dial is nudged.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct DriftConfig {
+    /// Off unless turned on in radio.info
+    pub enabled: bool,
+
+    /// Furthest the drift can wander from the dial, in ticks
+    pub max_ticks: f32,
+
+    /// Largest single step of the random walk, in ticks
+    pub step_ticks: f32,
+
+    /// Time between drift steps
+    pub interval_ms: u64,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        DriftConfig {
+            enabled: false,
+            max_ticks: 12.0,
+            step_ticks: 0.5,
+            interval_ms: 2000,
+        }
+    }
+}
+
+/// Metadata caching for playlists on network mounts
+/// 
+/// See `radio::station::content::metadata_cache`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MetadataCacheConfig {
+    /// Mount points whose playlists are cached (none by default)
+    pub network_mounts: Vec<PathBuf>,
+
+    /// Age after which a cached scan is refreshed in the background
+    pub ttl_secs: u64,
+
+    /// How long to wait for a mount to answer before treating it as down
+    pub mount_timeout_ms: u64,
+}
+
+impl Default for MetadataCacheConfig {
+    fn default() -> Self {
+        MetadataCacheConfig {
+            network_mounts: Vec::new(),
+            ttl_secs: 300,
+            mount_timeout_ms: 500,
+        }
+    }
+}
+
+/// Transcode-on-add settings for formats the decoder can't handle well
+/// 
+/// Files with a listed extension are converted once by `command` (with
+/// `{input}` and `{output}` substituted) into `cache_dir`, and the cached
+/// copy is used for duration probing and playback.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TranscodeConfig {
+    /// File extensions to transcode (Opus by default, which the decoder lacks)
+    pub extensions: Vec<String>,
+
+    /// Where transcoded copies are kept
+    pub cache_dir: PathBuf,
+
+    /// Extension of the transcoded copies; must match what `command` writes
+    pub format: String,
+
+    /// Converter command line
+    pub command: Vec<String>,
+}
+
+impl Default for TranscodeConfig {
+    fn default() -> Self {
+        TranscodeConfig {
+            extensions: vec!["opus".to_string()],
+            cache_dir: stations_root().join(".transcode_cache"),
+            format: "mp3".to_string(),
+            command: ["ffmpeg", "-y", "-loglevel", "error", "-i", "{input}", "-vn", "-c:a", "libmp3lame", "-q:a", "2", "{output}"]
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Named output buffer settings
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferPreset {
+    /// Whatever the audio driver picks
+    Default,
+
+    /// Small buffers so the dial responds instantly (Pi 4 and up)
+    LowLatency,
+
+    /// Large buffers that ride out CPU spikes on a loaded Pi Zero
+    GlitchResistant
+}
+
+/// Output buffer and latency settings
+/// 
+/// cpal exposes a single buffer size per stream (the driver derives its
+/// period from it), so that is what the presets and overrides control.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AudioBufferConfig {
+    /// Starting point for the settings below
+    pub preset: BufferPreset,
+
+    /// Buffer size in frames, overriding the preset
+    pub buffer_frames: Option<u32>,
+
+    /// Output sample rate in Hz (driver default if not set)
+    pub sample_rate: Option<u32>,
+}
+
+impl Default for AudioBufferConfig {
+    fn default() -> Self {
+        AudioBufferConfig {
+            preset: BufferPreset::Default,
+            buffer_frames: None,
+            sample_rate: None,
+        }
+    }
+}
+
+impl AudioBufferConfig {
+    /// Returns the buffer size to request, or None to leave it to the driver
+    pub fn frames(&self) -> Option<u32> {
+        self.buffer_frames.or(match self.preset {
+            BufferPreset::Default => None,
+            BufferPreset::LowLatency => Some(256),
+            BufferPreset::GlitchResistant => Some(4096),
+        })
+    }
+}
+
+/// Where the master volume is applied
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeControl {
+    /// Scale the master mix before it reaches the sound card
+    Software,
+
+    /// Set an ALSA mixer element (hardware volume on DAC and amp HATs)
+    Alsa
+}
+
+/// Master volume settings
+/// 
+/// See `audio::volume`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct VolumeConfig {
+    /// Software scaling or an ALSA mixer element
+    pub control: VolumeControl,
+
+    /// ALSA card number or name, as `amixer -c` takes it
+    pub card: String,
+
+    /// ALSA mixer element with the hardware volume (e.g. "Digital", "Master")
+    pub element: String,
+
+    /// Volume at startup, 0.0 to 1.0
+    pub level: f32,
+}
+
+impl Default for VolumeConfig {
+    fn default() -> Self {
+        VolumeConfig {
+            control: VolumeControl::Software,
+            card: "0".to_string(),
+            element: "PCM".to_string(),
+            level: 1.0,
+        }
+    }
+}
+
+/// Thread scheduling settings
+/// 
+/// See `priority`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PriorityConfig {
+    /// Off unless turned on in radio.info
+    pub enabled: bool,
+
+    /// Nice value of the file loader (negative runs it ahead of other work)
+    pub loader_nice: i32,
+
+    /// Nice value of background playlist rescans
+    pub scan_nice: i32,
+
+    /// SCHED_FIFO priority (1-99) for the sound card thread, if any
+    pub audio_realtime: Option<u8>,
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        PriorityConfig {
+            enabled: false,
+            loader_nice: -5,
+            scan_nice: 10,
+            audio_realtime: None,
+        }
+    }
+}
+
+/// RAM cache for stations that play from tmpfs
+/// 
+/// See `file_loader::ram_cache`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RamCacheConfig {
+    /// Folder on a tmpfs mount; copies left from an earlier run are
+    /// deleted at startup, other files are left alone
+    pub directory: PathBuf,
+
+    /// Most the cache may hold, across all stations
+    pub max_megabytes: u64,
+}
+
+impl Default for RamCacheConfig {
+    fn default() -> Self {
+        RamCacheConfig {
+            directory: default_ram_cache_directory(),
+            max_megabytes: 64,
+        }
+    }
+}
+
+/// Per-client limit on companion requests
+/// 
+/// See `api::rate_limit`.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Requests each client can make per minute, on average
+    pub requests_per_minute: f64,
+
+    /// Requests a client can make in a quick burst
+    pub burst: f64,
+
+    /// Requests' worth of allowance a wrong token costs
+    pub failed_auth_cost: f64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            requests_per_minute: 30.0,
+            burst: 10.0,
+            failed_auth_cost: 5.0,
+        }
+    }
+}
+
+/// What an off-air station sounds like when tuned in
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffAirBehavior {
+    /// Pure static, the same as between stations
+    Static,
+
+    /// Static fades to dead air as you tune onto the off-air frequency
+    Silence,
+
+    /// A steady test-pattern tone replaces the static on the frequency
+    TestPattern
+}
+
+/// Library folder to split into one virtual station per genre
+/// 
+/// Genres are assigned, in alphabetical order, to dial slots that have
+/// no station directory of their own.
+#[derive(Deserialize, Debug, Clone)]
+pub struct GenreLibraryConfig {
+    /// Root of the music library (searched recursively)
+    pub path: String,
+
+    /// Only fill free slots on this band (all bands if not set)
+    #[serde(default)]
+    pub band: Option<String>,
+}
+
+/// Global configuration loaded from radio.info
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct RadioConfig {
+    /// Bands in switch order; the band switch selects by position in this list
+    pub bands: Vec<BandConfig>,
+
+    /// Virtual dial settings for encoder-based tuning
+    pub dial: DialConfig,
+
+    /// Tuning knob, touch pad, rotary encoder or (for development) keyboard
+    pub tuning_input: TuningInput,
+
+    /// Touch pad settings when tuning by touch
+    pub touch: TouchConfig,
+
+    /// GPIO pin assignments, validated at startup
+    pub hardware: HardwareConfig,
+
+    /// What off-air stations sound like on the dial
+    pub off_air: OffAirBehavior,
+
+    /// Station width, bleed, static floor and fade shape
+    pub tuning: TuningConfig,
+
+    /// Slow frequency drift simulation (off by default)
+    pub drift: DriftConfig,
+
+    /// Name of a second output device for monitoring another station
+    pub monitor_device: Option<String>,
+
+    /// Experimental: lets the API put a second station on the right channel
+    pub dual_mono: bool,
+
+    /// Address for the HTTP/WebSocket API server (off if not set)
+    pub api_server: Option<String>,
+
+    /// Bearer token for the companion protocol (off if not set)
+    pub companion_token: Option<String>,
+
+    /// Serve `/metrics` without the companion token, for a scraper on a trusted network
+    pub public_metrics: bool,
+
+    /// How fast each client may send companion requests
+    pub rate_limit: RateLimitConfig,
+
+    /// Caching of playlist scans on network mounts
+    pub metadata_cache: MetadataCacheConfig,
+
+    /// Transcoding of formats the decoder can't play directly
+    pub transcode: TranscodeConfig,
+
+    /// tmpfs space for stations that play from RAM
+    pub ram_cache: RamCacheConfig,
+
+    /// Output buffer size and latency
+    pub audio_buffer: AudioBufferConfig,
+
+    /// Master EQ applied to everything the radio plays
+    pub eq: EqConfig,
+
+    /// Master volume, in software or on the sound card's mixer
+    pub volume: VolumeConfig,
+
+    /// Sound card, or the null backend for headless runs
+    pub audio_backend: AudioBackend,
+
+    /// Thread priorities for a CPU-starved Pi (off by default)
+    pub priority: PriorityConfig,
+
+    /// Seconds on one station before the others turn over
+    pub turnover_secs: u64,
+
+    /// Save station.info files upgraded from an older version
+    pub rewrite_migrated_configs: bool,
+
+    /// Where play history and saved state are kept
+    pub storage: StorageBackend,
+
+    /// Text-to-speech command; `{text}`, `{output}` and `{voice}` are substituted
+    pub tts_command: Vec<String>,
+
+    /// Language of spoken announcements, and per-language voices
+    pub locale: LocaleConfig,
+
+    /// Music library to split into virtual genre stations
+    pub genre_library: Option<GenreLibraryConfig>,
+
+    /// Dial layout used at boot (the standard layout if not set)
+    pub profile: Option<String>,
+
+    /// Named dial layouts that can be switched between
+    pub profiles: HashMap<String, ProfileConfig>,
+
+    /// Simulated fault injection rates (empty on a normal radio)
+    pub faults: HashMap<Fault, f32>,
+}
+
+impl Default for RadioConfig {
+    fn default() -> Self {
+        RadioConfig {
+            bands: vec![
+                BandConfig { name: "AM".to_string(), directory: "AM".to_string() },
+                BandConfig { name: "FM".to_string(), directory: "FM".to_string() },
+            ],
+            dial: DialConfig::default(),
+            tuning_input: TuningInput::Adc,
+            touch: TouchConfig::default(),
+            hardware: HardwareConfig::default(),
+            off_air: OffAirBehavior::Static,
+            tuning: TuningConfig::default(),
+            drift: DriftConfig::default(),
+            monitor_device: None,
+            dual_mono: false,
+            api_server: None,
+            companion_token: None,
+            public_metrics: false,
+            rate_limit: RateLimitConfig::default(),
+            metadata_cache: MetadataCacheConfig::default(),
+            transcode: TranscodeConfig::default(),
+            ram_cache: RamCacheConfig::default(),
+            audio_buffer: AudioBufferConfig::default(),
+            eq: EqConfig::default(),
+            volume: VolumeConfig::default(),
+            audio_backend: AudioBackend::Device,
+            priority: PriorityConfig::default(),
+            turnover_secs: constants::TIME_BETWEEN_SKIPS.as_secs(),
+            rewrite_migrated_configs: false,
+            storage: StorageBackend::JsonFile,
+            genre_library: None,
+            profile: None,
+            profiles: HashMap::new(),
+            tts_command: vec![
+                "espeak-ng".to_string(),
+                "-v".to_string(),
+                "{voice}".to_string(),
+                "-w".to_string(),
+                "{output}".to_string(),
+                "{text}".to_string(),
+            ],
+            locale: LocaleConfig::default(),
+            faults: HashMap::new(),
+        }
+    }
+}
+
+impl RadioConfig {
+    /// Loads the global configuration from a radio.info JSON file
+    ///
+    /// # Arguments
+    /// * `file_path` - Path to the radio.info file
+    ///
+    /// # Returns
+    /// - Parsed RadioConfig if the file exists and is valid JSON
+    /// - Defaults for any top-level field that doesn't parse
+    /// - Default configuration (AM and FM bands) if the file is missing or
+    ///   isn't a JSON object
+    ///
+    /// # Error Handling
+    /// Like station configs, errors are logged rather than propagated so the
+    /// radio still boots with a missing or broken radio.info.
+    pub fn new(file_path: &Path) -> Self {
+        let mut radio_config = match read_to_string(file_path) {
+            Ok(configuration) => match parse_json_object(&configuration) {
+                Ok(mut fields) => {
+                    drop_invalid_fields::<RadioConfig>(&mut fields, &Map::new(), file_path);
+                    from_value(Value::Object(fields)).unwrap_or_else(|e| {
+                        eprintln!("Failed to parse config {}: {}", file_path.display(), e);
+                        RadioConfig::default()
+                    })
+                },
+                Err(e) => {
+                    eprintln!("Failed to parse config {}: {}", file_path.display(), e);
+                    RadioConfig::default()
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to load config from {}: {}", file_path.display(), e);
+                RadioConfig::default()
+            }
+        };
+
+        // A radio with no bands can't tune anything
+        if radio_config.bands.is_empty() {
+            eprintln!("No bands configured in {}, using AM/FM", file_path.display());
+            radio_config.bands = RadioConfig::default().bands;
+        }
+
+        radio_config
+    }
+}
+
+/// Parses a config file that must hold a JSON object
+pub fn parse_json_object(configuration: &str) -> Result<Map<String, Value>, String> {
+    match from_str::<Value>(configuration) {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(_) => Err("expected a JSON object".to_string()),
+        Err(e) => Err(e.to_string())
+    }
+}
+
+/// Drops top-level fields that don't parse, so they fall back to defaults
+///
+/// Each field is tried on its own on top of `baseline` (the smallest
+/// config that parses). Fields that fail are logged and removed, so a
+/// typo costs that one field rather than the whole file. Fields are
+/// checked whole: one bad value inside a section drops the section.
+///
+/// # Arguments
+/// * `fields` - Top-level fields of the config file
+/// * `baseline` - Required fields to parse each field alongside
+/// * `file_path` - Config file, for the log
+pub fn drop_invalid_fields<T: DeserializeOwned>(
+    fields: &mut Map<String, Value>,
+    baseline: &Map<String, Value>,
+    file_path: &Path
+) {
+    // Nothing to do for a file that's fine as it is
+    if from_value::<T>(Value::Object(fields.clone())).is_ok() {
+        return;
+    }
+
+    fields.retain(|name, value| {
+        let mut trial = baseline.clone();
+        trial.insert(name.clone(), value.clone());
+        match from_value::<T>(Value::Object(trial)) {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!("Ignoring {} in {}: {}", name, file_path.display(), e);
+                false
+            }
+        }
+    });
+}
+
+/// Returns the global radio configuration
+///
+/// Loaded from `radio.info` in the stations root the first time it's requested.
+pub fn radio_config() -> &'static RadioConfig {
+    RADIO_CONFIG.get_or_init(|| {
+        RadioConfig::new(&stations_root().join("radio.info"))
+    })
+}
+
+/// Returns the folder holding radio.info and the band directories
+///
+/// `STATION_PATH` unless the `MOKRADIO_STATIONS` environment variable
+/// (or `--stations`, see `set_stations_root`) points somewhere else.
+pub fn stations_root() -> &'static Path {
+    STATIONS_ROOT.get_or_init(|| {
+        std::env::var_os(STATIONS_ROOT_VARIABLE)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(constants::STATION_PATH))
+    })
+}
+
+/// Returns whether a path is inside a folder once both are resolved
+///
+/// Symlinks and `..` are resolved first, so a path can't climb out of the
+/// folder. A path that doesn't exist isn't inside anything.
+pub fn is_within(path: &Path, folder: &Path) -> bool {
+    match (path.canonicalize(), folder.canonicalize()) {
+        (Ok(path), Ok(folder)) => path.starts_with(folder),
+        _ => false
+    }
+}
+
+/// Moves the stations root for this instance
+///
+/// Everything an instance reads and writes (radio.info, station state,
+/// history, crash reports) lives under its stations root, so instances
+/// given different roots can run side by side on one machine. Each
+/// root's radio.info picks its own `api_server` address and
+/// `audio_backend`.
+///
+/// # Returns
+/// false if the stations root was already in use and can't move
+pub fn set_stations_root(path: PathBuf) -> bool {
+    STATIONS_ROOT.set(path).is_ok()
+}
+
+/// Returns the RAM cache folder for this instance
+///
+/// Copies from an earlier run are deleted at startup, so an instance with
+/// a stations root of its own gets a folder of its own rather than
+/// deleting another's.
+fn default_ram_cache_directory() -> PathBuf {
+    let shared = PathBuf::from("/dev/shm/mokradio");
+    if stations_root() == Path::new(constants::STATION_PATH) {
+        return shared;
+    }
+    let instance: String = stations_root().to_string_lossy().chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    shared.with_file_name(format!("mokradio{}", instance))
+}
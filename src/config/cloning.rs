@@ -0,0 +1,100 @@
+//! Station Cloning - New stations from existing ones
+//!
+//! Sets up a station in an empty dial slot from another station's
+//! settings, so a themed variant of a station is one API call instead of
+//! copying folders around. The clone gets a copy of the station.info,
+//! renamed if asked, and links to the original's playlist, DJ, bed and
+//! clip folders, or to another playlist folder in place of the original's.
+//! A playlist given in place of the original's must be inside the stations
+//! root, since a station can purge or quarantine files in its playlist.
+//! Play history, quarantined files and other state stay behind, so the
+//! clone starts fresh.
+//!
+//! The new slot is put together in a hidden folder next to it and renamed
+//! into place, so a clone that fails part way leaves nothing behind.
+//!
+//! ```sh
+//! curl -X POST -H "Authorization: Bearer $TOKEN" \
+//!     -d '{"band":"FM","index":3,"to_band":"FM","to_index":7,"name":"Jazz After Dark","playlist":"/stations/library/late-jazz"}' \
+//!     http://radio:8081/companion/clone
+//! ```
+
+use std::fs;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::config::{is_within, stations_root};
+
+/// Folders shared with the original, besides the playlist
+const SHARED_FOLDERS: [&str; 3] = ["dj", "beds", "clips"];
+
+/// Copies a station's settings into an empty slot
+///
+/// # Arguments
+/// * `from` - Folder of the station to clone
+/// * `to` - Folder of the new station
+/// * `name` - Name for the new station, or None to keep the original's
+/// * `playlist` - Playlist folder for the new station, or None to share the original's
+///
+/// # Errors
+/// Fails without touching `to` if the original has no station.info, `to`
+/// already has one, the playlist isn't a folder inside the stations root,
+/// or a file can't be written
+pub fn clone_station(from: &Path, to: &Path, name: Option<&str>, playlist: Option<&Path>) -> io::Result<()> {
+    let contents = fs::read_to_string(from.join("station.info"))?;
+    let mut station_info: Value = serde_json::from_str(&contents).map_err(io::Error::other)?;
+    if to.join("station.info").exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} is already set up", to.display())));
+    }
+    if let (Some(name), Some(settings)) = (name, station_info.as_object_mut()) {
+        settings.insert("name".to_string(), json!(name));
+    }
+    let playlist = match playlist {
+        Some(playlist) if playlist.is_dir() && is_within(playlist, stations_root()) => Some(fs::canonicalize(playlist)?),
+        Some(playlist) => return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a folder inside {}", playlist.display(), stations_root().display())
+        )),
+        None => linked_folder(&from.join("playlist"))
+    };
+
+    let Some(slot_name) = to.file_name() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a station folder", to.display())));
+    };
+    let building = to.with_file_name(format!(".{}.cloning", slot_name.to_string_lossy()));
+    let built = build_slot(&building, from, &station_info, playlist)
+        .and_then(|_| fs::rename(&building, to));
+    if built.is_err() {
+        // Only links and the new station.info are in there, so nothing shared is lost
+        fs::remove_dir_all(&building).ok();
+    }
+    built
+}
+
+/// Writes the clone's links and station.info into a fresh folder
+fn build_slot(building: &Path, from: &Path, station_info: &Value, playlist: Option<PathBuf>) -> io::Result<()> {
+    if building.exists() {
+        fs::remove_dir_all(building)?;
+    }
+    fs::create_dir_all(building)?;
+    if let Some(playlist) = playlist {
+        symlink(playlist, building.join("playlist"))?;
+    }
+    for folder in SHARED_FOLDERS {
+        if let Some(shared) = linked_folder(&from.join(folder)) {
+            symlink(shared, building.join(folder))?;
+        }
+    }
+
+    let contents = serde_json::to_string_pretty(station_info).map_err(io::Error::other)?;
+    fs::write(building.join("station.info"), contents + "\n")
+}
+
+/// Where a folder really is, so a link to it doesn't depend on the
+/// original station's links staying put; None if there's no folder
+fn linked_folder(folder: &Path) -> Option<PathBuf> {
+    folder.is_dir().then(|| fs::canonicalize(folder).unwrap_or_else(|_| folder.to_path_buf()))
+}
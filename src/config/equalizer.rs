@@ -0,0 +1,129 @@
+//! Master EQ Configuration
+//!
+//! One equalizer sits between the stations and the speaker, so it can
+//! correct for the cabinet (a small paper cone that buzzes on bass, say).
+//! radio.info picks a preset by name: one of the built-in presets, or one
+//! defined in the `presets` section as a list of filter bands.
+//!
+//! # JSON Format
+//! ```json
+//! "eq": {
+//!     "preset": "Cabinet",
+//!     "presets": {
+//!         "Cabinet": [
+//!             { "filter": "HighPass", "frequency_hz": 180.0, "q": 0.7 },
+//!             { "filter": "Peak", "frequency_hz": 2500.0, "gain_db": 3.0, "q": 1.0 }
+//!         ]
+//!     }
+//! }
+//! ```
+//!
+//! # Built-in Presets
+//! - "Flat" - No EQ (default)
+//! - "FullRange" - Gentle loudness contour for a good full-range speaker
+//! - "SmallSpeaker" - Rolls off bass a small driver can't reproduce and
+//!   lifts the presence range to make up for it
+//! - "BassCut" - Steep bass roll-off for speakers that buzz or rattle
+
+use std::collections::HashMap;
+use serde::Deserialize;
+
+/// Filter shapes available to EQ bands
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterKind {
+    /// Removes everything below the frequency (`gain_db` unused)
+    HighPass,
+
+    /// Removes everything above the frequency (`gain_db` unused)
+    LowPass,
+
+    /// Boosts or cuts everything below the frequency
+    LowShelf,
+
+    /// Boosts or cuts everything above the frequency
+    HighShelf,
+
+    /// Boosts or cuts a band around the frequency
+    Peak,
+}
+
+/// One filter in the EQ chain
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct EqBand {
+    /// Filter shape
+    pub filter: FilterKind,
+
+    /// Corner or center frequency
+    pub frequency_hz: f32,
+
+    /// Boost (positive) or cut (negative) for shelves and peaks
+    #[serde(default)]
+    pub gain_db: f32,
+
+    /// Sharpness: 0.707 is a smooth corner, higher is narrower
+    #[serde(default = "default_q")]
+    pub q: f32,
+}
+
+fn default_q() -> f32 {
+    std::f32::consts::FRAC_1_SQRT_2
+}
+
+/// Master EQ settings from radio.info
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct EqConfig {
+    /// Preset to use: a name from `presets` or a built-in preset
+    pub preset: String,
+
+    /// User-defined presets, by name
+    pub presets: HashMap<String, Vec<EqBand>>,
+}
+
+impl Default for EqConfig {
+    fn default() -> Self {
+        EqConfig {
+            preset: "Flat".to_string(),
+            presets: HashMap::new(),
+        }
+    }
+}
+
+impl EqConfig {
+    /// Returns the filter bands of the selected preset
+    ///
+    /// User-defined presets take precedence over built-in ones with the same
+    /// name. Unknown presets are logged and leave the EQ flat.
+    pub fn bands(&self) -> Vec<EqBand> {
+        if let Some(bands) = self.presets.get(&self.preset) {
+            return bands.clone();
+        }
+        built_in_preset(&self.preset).unwrap_or_else(|| {
+            eprintln!("Unknown EQ preset {}, EQ is flat", self.preset);
+            Vec::new()
+        })
+    }
+}
+
+/// Bands of the built-in presets
+fn built_in_preset(name: &str) -> Option<Vec<EqBand>> {
+    let band = |filter, frequency_hz, gain_db, q| EqBand { filter, frequency_hz, gain_db, q };
+    let bands = match name {
+        "Flat" => Vec::new(),
+        "FullRange" => vec![
+            band(FilterKind::LowShelf, 100.0, 2.0, default_q()),
+            band(FilterKind::HighShelf, 8000.0, 1.5, default_q()),
+        ],
+        "SmallSpeaker" => vec![
+            band(FilterKind::HighPass, 120.0, 0.0, default_q()),
+            band(FilterKind::Peak, 2500.0, 2.5, 1.0),
+        ],
+        "BassCut" => vec![
+            band(FilterKind::HighPass, 180.0, 0.0, default_q()),
+            band(FilterKind::HighPass, 180.0, 0.0, default_q()),
+            band(FilterKind::LowShelf, 300.0, -4.0, default_q()),
+        ],
+        _ => return None
+    };
+    Some(bands)
+}
@@ -1,3 +1,36 @@
 // Playlist directory scanning
 // Scans /stations/ folders for audio files
 
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Fingerprints the contents of a playlist directory
+/// 
+/// Hashes every entry's name, size and modification time, so adding,
+/// removing, replacing or touching a file changes the result while a
+/// rescan of an untouched folder doesn't. File contents aren't read.
+/// 
+/// # Returns
+/// - `Some(u64)` - Content hash of the directory
+/// - `None` - Directory can't be read
+pub fn directory_hash(directory_path: &Path) -> Option<u64> {
+    let mut entries: Vec<(String, u64, SystemTime)> = std::fs::read_dir(directory_path).ok()?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            Some((
+                entry.file_name().to_string_lossy().to_string(),
+                metadata.len(),
+                metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)
+            ))
+        })
+        .collect();
+    // read_dir order isn't stable across scans
+    entries.sort();
+
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    Some(hasher.finish())
+}
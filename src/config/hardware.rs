@@ -0,0 +1,312 @@
+//! Hardware Pin Mapping
+//!
+//! Maps every GPIO signal the radio uses to a BCM pin (or SPI channel) so
+//! builds with different wiring don't need code changes. Loaded from the
+//! `hardware` section of radio.info and validated once at startup.
+//!
+//! # JSON Format
+//! ```json
+//! "hardware": {
+//!     "band_switch_pin": 4,
+//!     "band_switch_pins": [5, 6, 12],
+//!     "band_switch_encoding": "OneHot",
+//!     "buttons": { "skip": 17, "seek_back": 22, "seek_forward": 23, "pause": 24, "mute": 25 },
+//!     "leds": { "on_air": 27 },
+//!     "spi_chip_selects": { "tuner_adc": 0 },
+//!     "mute_relay": { "pin": 26, "settle_ms": 800 },
+//!     "vu_meter": { "pins": [18] },
+//!     "headphone_jack": { "pin": 16 },
+//!     "rotary_encoder": { "pin_a": 5, "pin_b": 6 },
+//!     "tuner_adc": { "channel": 0, "sample_rate_hz": 200 },
+//!     "display": { "address": 60 }
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use serde::Deserialize;
+
+use crate::constants;
+
+/// Highest BCM GPIO number broken out on the 40-pin header
+const MAX_BCM_PIN: u8 = 27;
+
+/// Pins claimed by the I2C bus (SDA, SCL) used by the tuner
+const I2C_PINS: [u8; 2] = [2, 3];
+
+/// BCM pins driven by SPI0 chip-select channels CE0 and CE1
+const SPI_CHIP_SELECT_PINS: [u8; 2] = [8, 7];
+
+/// Pins claimed by the SPI0 bus itself (MISO, MOSI, SCLK)
+const SPI_BUS_PINS: [u8; 3] = [9, 10, 11];
+
+/// How a multi-position band switch reports its position
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandSwitchEncoding {
+    /// One pin per position; the closed pin is the band
+    OneHot,
+
+    /// Pins form a binary number, first pin lowest bit
+    Binary
+}
+
+/// Amp mute relay on a GPIO pin (see `audio::mute_relay`)
+#[derive(Deserialize, Debug, Clone)]
+pub struct MuteRelayConfig {
+    /// BCM pin driving the relay or the amp's mute input
+    pub pin: u8,
+
+    /// Whether driving the pin high mutes (otherwise low mutes, so a relay
+    /// wired to mute when its coil is unpowered needs nothing set)
+    #[serde(default)]
+    pub active_high: bool,
+
+    /// Time for the DAC to settle after the stream opens, before unmuting
+    #[serde(default = "default_settle_ms")]
+    pub settle_ms: u64,
+
+    /// Time for the relay to drop out after muting, before the stream closes
+    #[serde(default = "default_release_ms")]
+    pub release_ms: u64,
+}
+
+/// Headphone jack switch (see `audio::headphone_jack`)
+#[derive(Deserialize, Debug, Clone)]
+pub struct HeadphoneJackConfig {
+    /// BCM pin wired to the jack's switch contact
+    pub pin: u8,
+
+    /// Whether the pin reads high with headphones in (otherwise low)
+    #[serde(default)]
+    pub plugged_high: bool,
+
+    /// ALSA mixer switch that turns the speaker off, if the sound card has one
+    #[serde(default)]
+    pub speaker_element: Option<String>,
+}
+
+/// Quadrature tuning encoder (see `input::encoder`)
+#[derive(Deserialize, Debug, Clone)]
+pub struct RotaryEncoderConfig {
+    /// BCM pins of the encoder's A and B outputs
+    pub pin_a: u8,
+    pub pin_b: u8,
+
+    /// Quadrature steps per click of the knob (usually 4, sometimes 2 or 1)
+    #[serde(default = "default_steps_per_detent")]
+    pub steps_per_detent: i32,
+}
+
+fn default_steps_per_detent() -> i32 {
+    4
+}
+
+/// MCP3008 reading the tuning pot (see `input::mcp3008`)
+#[derive(Deserialize, Debug, Clone)]
+pub struct TunerAdcConfig {
+    /// Input channel the pot's wiper is wired to (0-7)
+    #[serde(default)]
+    pub channel: u8,
+
+    /// Readings per second
+    #[serde(default = "default_sample_rate_hz")]
+    pub sample_rate_hz: u32,
+
+    /// SPI clock; the MCP3008 manages 1.35MHz at 3.3V
+    #[serde(default = "default_clock_hz")]
+    pub clock_hz: u32,
+}
+
+fn default_sample_rate_hz() -> u32 {
+    200
+}
+
+fn default_clock_hz() -> u32 {
+    1_000_000
+}
+
+/// SSD1306 OLED on the shared I2C bus (see `display`)
+#[derive(Deserialize, Debug, Clone)]
+pub struct DisplayConfig {
+    /// I2C address of the display (0x3C, or 0x3D with its address pin high)
+    #[serde(default = "default_display_address")]
+    pub address: u16,
+}
+
+fn default_display_address() -> u16 {
+    0x3C
+}
+
+/// Level display on GPIO pins (see `audio::vu_meter`)
+#[derive(Deserialize, Debug, Clone)]
+pub struct VuMeterConfig {
+    /// BCM pins: one drives a moving-coil meter by PWM, several light an LED bar
+    pub pins: Vec<u8>,
+
+    /// Level at the bottom of the scale, in dB below full scale
+    #[serde(default = "default_floor_db")]
+    pub floor_db: f32,
+
+    /// Whether to show peaks rather than average (RMS) loudness
+    #[serde(default)]
+    pub peak: bool,
+}
+
+fn default_floor_db() -> f32 {
+    -30.0
+}
+
+fn default_settle_ms() -> u64 {
+    500
+}
+
+fn default_release_ms() -> u64 {
+    50
+}
+
+/// GPIO pin assignments for every hardware signal
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HardwareConfig {
+    /// BCM pin for the AM/FM band switch
+    pub band_switch_pin: u8,
+
+    /// BCM pins of a rotary band switch, replacing `band_switch_pin`
+    pub band_switch_pins: Vec<u8>,
+
+    /// How the rotary band switch pins encode its position
+    pub band_switch_encoding: BandSwitchEncoding,
+
+    /// Named push buttons and their BCM pins
+    pub buttons: BTreeMap<String, u8>,
+
+    /// Named indicator LEDs and their BCM pins
+    pub leds: BTreeMap<String, u8>,
+
+    /// Named SPI devices and their SPI0 chip-select channel (0 or 1)
+    pub spi_chip_selects: BTreeMap<String, u8>,
+
+    /// Amp mute relay, if fitted
+    pub mute_relay: Option<MuteRelayConfig>,
+
+    /// VU meter or LED level bar, if fitted
+    pub vu_meter: Option<VuMeterConfig>,
+
+    /// Headphone jack switch, if wired up
+    pub headphone_jack: Option<HeadphoneJackConfig>,
+
+    /// Tuning encoder, when tuning with one
+    pub rotary_encoder: Option<RotaryEncoderConfig>,
+
+    /// MCP3008 for the tuning pot, in place of the I2C tuner
+    pub tuner_adc: Option<TunerAdcConfig>,
+
+    /// Now-playing OLED, if fitted
+    pub display: Option<DisplayConfig>,
+}
+
+impl Default for HardwareConfig {
+    fn default() -> Self {
+        HardwareConfig {
+            band_switch_pin: constants::BAND_SWITCH_PIN,
+            band_switch_pins: Vec::new(),
+            band_switch_encoding: BandSwitchEncoding::OneHot,
+            buttons: BTreeMap::new(),
+            leds: BTreeMap::new(),
+            spi_chip_selects: BTreeMap::new(),
+            mute_relay: None,
+            vu_meter: None,
+            headphone_jack: None,
+            rotary_encoder: None,
+            tuner_adc: None,
+            display: None,
+        }
+    }
+}
+
+impl HardwareConfig {
+    /// Checks the pin mapping for invalid pins and conflicting assignments
+    ///
+    /// # Returns
+    /// - `Ok(())` if every signal has its own valid pin
+    /// - `Err(Vec<String>)` with one readable message per problem found
+    ///
+    /// # Checks
+    /// - Pins must exist on the header (BCM 0-27)
+    /// - No two signals may share a pin
+    /// - Signals may not use the I2C pins (BCM 2/3)
+    /// - SPI chip-selects must be channel 0 or 1, and their CE pins are reserved
+    /// - With any SPI chip-select, the SPI0 bus pins (BCM 9/10/11) are reserved
+    /// - A tuning ADC needs a `tuner_adc` chip-select and a channel from 0 to 7
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors: Vec<String> = Vec::new();
+        let mut claimed: HashMap<u8, String> = HashMap::new();
+
+        for pin in I2C_PINS {
+            claimed.insert(pin, "i2c bus".to_string());
+        }
+
+        if !self.spi_chip_selects.is_empty() {
+            for pin in SPI_BUS_PINS {
+                claimed.insert(pin, "spi bus".to_string());
+            }
+        }
+
+        for (name, channel) in &self.spi_chip_selects {
+            match SPI_CHIP_SELECT_PINS.get(*channel as usize) {
+                Some(pin) => claim(&mut claimed, &mut errors, *pin, format!("spi chip-select '{}'", name)),
+                None => errors.push(format!(
+                    "spi chip-select '{}' uses channel {}, but only channels 0 and 1 exist",
+                    name, channel
+                ))
+            }
+        }
+
+        if let Some(tuner_adc) = &self.tuner_adc {
+            if !self.spi_chip_selects.contains_key("tuner_adc") {
+                errors.push("tuner adc is configured, but spi_chip_selects has no 'tuner_adc'".to_string());
+            }
+            if tuner_adc.channel > 7 {
+                errors.push(format!("tuner adc uses channel {}, but the MCP3008 has channels 0-7", tuner_adc.channel));
+            }
+        }
+
+        // A rotary band switch replaces the single pin rather than adding to it
+        let band_switch_pins: Vec<(String, u8)> = if self.band_switch_pins.is_empty() {
+            vec![("band switch".to_string(), self.band_switch_pin)]
+        } else {
+            self.band_switch_pins.iter()
+                .enumerate()
+                .map(|(position, pin)| (format!("band switch pin {}", position), *pin))
+                .collect()
+        };
+
+        let gpio_signals = band_switch_pins.into_iter()
+            .chain(self.buttons.iter().map(|(name, pin)| (format!("button '{}'", name), *pin)))
+            .chain(self.leds.iter().map(|(name, pin)| (format!("led '{}'", name), *pin)))
+            .chain(self.mute_relay.iter().map(|relay| ("mute relay".to_string(), relay.pin)))
+            .chain(self.vu_meter.iter().flat_map(|meter| meter.pins.iter().map(|pin| ("vu meter".to_string(), *pin))))
+            .chain(self.headphone_jack.iter().map(|jack| ("headphone jack".to_string(), jack.pin)))
+            .chain(self.rotary_encoder.iter().flat_map(|encoder| [
+                ("rotary encoder A".to_string(), encoder.pin_a),
+                ("rotary encoder B".to_string(), encoder.pin_b),
+            ]));
+
+        for (signal, pin) in gpio_signals {
+            if pin > MAX_BCM_PIN {
+                errors.push(format!("{} uses BCM {}, which is not on the header (0-{})", signal, pin, MAX_BCM_PIN));
+                continue;
+            }
+            claim(&mut claimed, &mut errors, pin, signal);
+        }
+
+        if errors.is_empty() {Ok(())} else {Err(errors)}
+    }
+}
+
+/// Records a pin as used by a signal, reporting a conflict if it's taken
+fn claim(claimed: &mut HashMap<u8, String>, errors: &mut Vec<String>, pin: u8, signal: String) {
+    match claimed.get(&pin) {
+        Some(owner) => errors.push(format!("{} and {} both use BCM {}", owner, signal, pin)),
+        None => {claimed.insert(pin, signal);}
+    }
+}
@@ -0,0 +1,159 @@
+//! Locale Configuration - Language of spoken announcements
+//!
+//! Everything the radio says out loud (DJ intros, station IDs, time checks,
+//! error reports) is built from phrases looked up by language rather than
+//! hard-coded English. A few languages are built in; radio.info can
+//! override any phrase or add whole new languages, and can give each
+//! language its own text-to-speech voice command.
+//!
+//! # JSON Format
+//! ```json
+//! "locale": {
+//!     "language": "de",
+//!     "voices": {
+//!         "de": ["pico2wave", "-l", "de-DE", "-w", "{output}", "{text}"]
+//!     },
+//!     "phrases": {
+//!         "nl": { "up_next": "Nu komt {title} van {artist}" }
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use serde::Deserialize;
+
+use crate::config::radio_config;
+
+/// Announcement phrases; `{placeholders}` are filled in by the caller
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Phrase {
+    /// DJ intro: `{title}`, `{artist}`
+    UpNext,
+
+    /// Stands in for `{artist}` on untagged tracks
+    UnknownArtist,
+
+    /// Station identification: `{station}`
+    StationId,
+
+    /// Time check: `{time}`
+    TimeCheck,
+
+    /// Spoken error report: `{station}`, `{error}`
+    ErrorReport,
+
+    /// Speaking clock lead-in, followed by the hour and minute
+    AtTheTone,
+
+    /// Speaking clock: on the hour
+    OClock,
+
+    /// Speaking clock: before the seconds
+    And,
+
+    /// Speaking clock: after the seconds
+    Seconds,
+}
+
+/// Language settings from radio.info
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LocaleConfig {
+    /// Language code announcements are made in (e.g. "en", "de", "pt-BR")
+    pub language: String,
+
+    /// Text-to-speech command per language, replacing `tts_command`
+    pub voices: HashMap<String, Vec<String>>,
+
+    /// Phrase overrides per language, on top of the built-in tables
+    pub phrases: HashMap<String, HashMap<Phrase, String>>,
+}
+
+impl Default for LocaleConfig {
+    fn default() -> Self {
+        LocaleConfig {
+            language: "en".to_string(),
+            voices: HashMap::new(),
+            phrases: HashMap::new(),
+        }
+    }
+}
+
+impl LocaleConfig {
+    /// Returns a phrase in the given language
+    ///
+    /// Looks in the radio.info overrides, then the built-in table, first
+    /// for the full language code and then for its base language ("pt"
+    /// for "pt-BR"), and finally falls back to English.
+    pub fn phrase(&self, language: &str, phrase: Phrase) -> String {
+        let base_language = language.split(['-', '_']).next().unwrap_or(language);
+        [language, base_language].into_iter()
+            .find_map(|candidate| {
+                self.phrases.get(candidate)
+                    .and_then(|phrases| phrases.get(&phrase).cloned())
+                    .or_else(|| built_in_phrase(candidate, phrase).map(str::to_string))
+            })
+            .unwrap_or_else(|| built_in_phrase("en", phrase).unwrap_or_default().to_string())
+    }
+
+    /// Returns the text-to-speech command for a language
+    ///
+    /// A voice configured for the language (or its base language) wins,
+    /// otherwise the radio-wide `tts_command` is used with `{voice}` set to
+    /// the language code.
+    pub fn voice(&self, language: &str) -> &[String] {
+        let base_language = language.split(['-', '_']).next().unwrap_or(language);
+        self.voices.get(language)
+            .or_else(|| self.voices.get(base_language))
+            .unwrap_or(&radio_config().tts_command)
+    }
+}
+
+/// Built-in phrase tables
+fn built_in_phrase(language: &str, phrase: Phrase) -> Option<&'static str> {
+    let text = match (language, phrase) {
+        ("en", Phrase::UpNext) => "Up next, {title} by {artist}",
+        ("en", Phrase::UnknownArtist) => "an unknown artist",
+        ("en", Phrase::StationId) => "You're listening to {station}",
+        ("en", Phrase::TimeCheck) => "The time is {time}",
+        ("en", Phrase::ErrorReport) => "{station} is having trouble: {error}",
+        ("en", Phrase::AtTheTone) => "At the tone, the time will be",
+        ("en", Phrase::OClock) => "o'clock",
+        ("en", Phrase::And) => "and",
+        ("en", Phrase::Seconds) => "seconds",
+
+        ("de", Phrase::UpNext) => "Als Nächstes: {title} von {artist}",
+        ("de", Phrase::UnknownArtist) => "einem unbekannten Künstler",
+        ("de", Phrase::StationId) => "Sie hören {station}",
+        ("de", Phrase::TimeCheck) => "Es ist {time}",
+        ("de", Phrase::ErrorReport) => "{station} hat ein Problem: {error}",
+        ("de", Phrase::AtTheTone) => "Beim nächsten Ton ist es",
+        ("de", Phrase::OClock) => "Uhr",
+        ("de", Phrase::And) => "und",
+        ("de", Phrase::Seconds) => "Sekunden",
+
+        ("fr", Phrase::UpNext) => "À suivre, {title} par {artist}",
+        ("fr", Phrase::UnknownArtist) => "un artiste inconnu",
+        ("fr", Phrase::StationId) => "Vous écoutez {station}",
+        ("fr", Phrase::TimeCheck) => "Il est {time}",
+        ("fr", Phrase::ErrorReport) => "{station} rencontre un problème : {error}",
+        ("fr", Phrase::AtTheTone) => "Au top, il sera exactement",
+        ("fr", Phrase::OClock) => "heures",
+        ("fr", Phrase::And) => "et",
+        ("fr", Phrase::Seconds) => "secondes",
+
+        ("es", Phrase::UpNext) => "A continuación, {title} de {artist}",
+        ("es", Phrase::UnknownArtist) => "un artista desconocido",
+        ("es", Phrase::StationId) => "Estás escuchando {station}",
+        ("es", Phrase::TimeCheck) => "Son las {time}",
+        ("es", Phrase::ErrorReport) => "{station} tiene un problema: {error}",
+        ("es", Phrase::AtTheTone) => "Al oír la señal serán las",
+        ("es", Phrase::OClock) => "en punto",
+        ("es", Phrase::And) => "y",
+        ("es", Phrase::Seconds) => "segundos",
+
+        _ => return None
+    };
+    Some(text)
+}
@@ -0,0 +1,208 @@
+//! Station Manifest - Bulk station provisioning
+//!
+//! `mokradio import stations.csv` sets up every station described in a
+//! manifest instead of creating folders and station.info files by hand.
+//! The manifest is CSV with a header row; fields may be quoted to hold
+//! commas, and blank lines and lines starting with `#` are ignored. (CSV
+//! rather than YAML keeps the parser dependency-free, and a spreadsheet can
+//! export it.)
+//!
+//! ```text
+//! band,slot,name,play_type,source
+//! AM,0,Talking Clock,Time,
+//! AM,3,Old Time Radio,Chronologic,/mnt/nas/otr
+//! FM,1,"Jazz, Mostly",Shuffle,/mnt/nas/jazz
+//! FM,2,Jazz After Dark,Network,/mnt/nas/jazz
+//! FM,3,Jazz Live,Live,http://ice.example.org:8000/jazz.mp3
+//! ```
+//!
+//! Each row becomes `<band directory>/<slot>/station.info`. A source folder
+//! is linked in as the station's `playlist/` folder, except for Network
+//! stations, whose shared playlist it names. Stations that already have a
+//! station.info are left alone unless the import is told to overwrite.
+//! A Live station's source is its stream URL, played around the clock.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value, json};
+
+use crate::constants;
+use crate::radio::Radio;
+use crate::radio::station::config::migration::CURRENT_VERSION;
+use crate::radio::station::content::Band;
+
+/// Columns every manifest must have, in any order
+const COLUMNS: [&str; 5] = ["band", "slot", "name", "play_type", "source"];
+
+/// One station described by the manifest
+#[derive(Debug, Clone)]
+pub struct ManifestEntry {
+    /// Line in the manifest, for messages
+    pub line: usize,
+    pub band: Band,
+    pub slot: usize,
+    pub name: String,
+    pub play_type: String,
+
+    /// Playlist folder, shared playlist for Network stations, or nothing
+    pub source: Option<String>,
+}
+
+/// What happened to one manifest row
+#[derive(Debug)]
+pub enum ImportOutcome {
+    /// station.info written
+    Created(PathBuf),
+
+    /// Station already set up; left as it was
+    Skipped(PathBuf),
+
+    /// Row couldn't be imported
+    Failed { line: usize, reason: String },
+}
+
+/// Reads a manifest file
+///
+/// # Returns
+/// The rows that parse, and a failure for every row that doesn't; an
+/// error if the file can't be read or has no usable header
+pub fn read_manifest(manifest_path: &Path) -> io::Result<(Vec<ManifestEntry>, Vec<ImportOutcome>)> {
+    let manifest = fs::read_to_string(manifest_path)?;
+    let mut rows = manifest.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+
+    let Some((_, header)) = rows.next() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "manifest is empty"));
+    };
+    let header: Vec<String> = split_row(header).iter().map(|column| column.to_ascii_lowercase()).collect();
+    let mut positions = [0usize; COLUMNS.len()];
+    for (position, column) in positions.iter_mut().zip(COLUMNS) {
+        *position = header.iter().position(|name| name == column).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("manifest header has no {} column", column))
+        })?;
+    }
+
+    let mut entries = Vec::new();
+    let mut failures = Vec::new();
+    for (line, row) in rows {
+        let fields = split_row(row);
+        let field = |column: usize| fields.get(positions[column]).map(String::as_str).unwrap_or("");
+        match parse_entry(line, [field(0), field(1), field(2), field(3), field(4)]) {
+            Ok(entry) => entries.push(entry),
+            Err(reason) => failures.push(ImportOutcome::Failed { line, reason })
+        }
+    }
+    Ok((entries, failures))
+}
+
+/// Checks and converts one row's fields (in `COLUMNS` order)
+fn parse_entry(line: usize, [band, slot, name, play_type, source]: [&str; 5]) -> Result<ManifestEntry, String> {
+    let band_name = band;
+    let band = Band::all()
+        .find(|band| band.name().eq_ignore_ascii_case(band_name))
+        .ok_or_else(|| format!("no band named {}", band_name))?;
+    let slot: usize = slot.parse().map_err(|_| format!("slot {} is not a number", slot))?;
+    if slot >= constants::NUMBER_OF_STATIONS {
+        return Err(format!("slot {} is off the dial (0-{})", slot, constants::NUMBER_OF_STATIONS - 1));
+    }
+    if play_type.is_empty() {
+        return Err("no play_type".to_string());
+    }
+    if play_type == "Network" && source.is_empty() {
+        return Err("Network stations need a source playlist".to_string());
+    }
+    if play_type == "Live" && !source.starts_with("http://") {
+        return Err("Live stations need an http:// stream source".to_string());
+    }
+    if play_type != "Live" && source.contains("://") {
+        return Err(format!("stream source {} needs a Live station", source));
+    }
+
+    Ok(ManifestEntry {
+        line,
+        band,
+        slot,
+        name: name.to_string(),
+        play_type: play_type.to_string(),
+        source: (!source.is_empty()).then(|| source.to_string()),
+    })
+}
+
+/// Creates the station folders and station.info files for manifest rows
+///
+/// # Arguments
+/// * `entries` - Rows read from the manifest
+/// * `overwrite` - Replace existing station.info files instead of skipping them
+pub fn import_stations(entries: &[ManifestEntry], overwrite: bool) -> Vec<ImportOutcome> {
+    entries.iter().map(|entry| {
+        let station_path = Radio::station_path(entry.band, entry.slot);
+        let info_path = station_path.join("station.info");
+        if info_path.exists() && !overwrite {
+            return ImportOutcome::Skipped(station_path);
+        }
+        match create_station(entry, &station_path) {
+            Ok(()) => ImportOutcome::Created(station_path),
+            Err(e) => ImportOutcome::Failed { line: entry.line, reason: e.to_string() }
+        }
+    }).collect()
+}
+
+/// Writes one station's folder, playlist link and station.info
+pub fn create_station(entry: &ManifestEntry, station_path: &Path) -> io::Result<()> {
+    fs::create_dir_all(station_path)?;
+
+    let mut station_info = Map::new();
+    station_info.insert("version".to_string(), json!(CURRENT_VERSION));
+    if !entry.name.is_empty() {
+        station_info.insert("name".to_string(), json!(entry.name));
+    }
+    station_info.insert("play_type".to_string(), json!(entry.play_type));
+    station_info.insert("purge".to_string(), json!(false));
+
+    match (&entry.source, entry.play_type.as_str()) {
+        (Some(source), "Network") => {
+            station_info.insert("network".to_string(), json!({ "playlist": source }));
+        },
+        (Some(source), "Live") => {
+            station_info.insert("live".to_string(), json!([{ "url": source }]));
+        },
+        (Some(source), _) => {
+            let playlist_path = station_path.join("playlist");
+            if !playlist_path.exists() {
+                std::os::unix::fs::symlink(source, &playlist_path)?;
+            } else {
+                eprintln!("{} already has a playlist folder, not linking {}", station_path.display(), source);
+            }
+        },
+        (None, _) => {}
+    }
+
+    let contents = serde_json::to_string_pretty(&Value::Object(station_info)).map_err(io::Error::other)?;
+    fs::write(station_path.join("station.info"), contents + "\n")
+}
+
+/// Splits a CSV row, honoring double quotes (`""` is a literal quote)
+fn split_row(row: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut characters = row.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match (character, quoted) {
+            ('"', true) if characters.peek() == Some(&'"') => {
+                field.push('"');
+                characters.next();
+            },
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(character)
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
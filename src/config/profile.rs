@@ -0,0 +1,79 @@
+//! Profile Configuration - Alternative dial layouts
+//!
+//! A profile puts a different set of stations on the dial without copying
+//! any music: each slot points at an existing station folder, so a kids'
+//! profile can put the story stations up front while the adult profile
+//! keeps the jazz and news lineup. Station folders (and their play
+//! history) are shared between every layout that uses them.
+//!
+//! The profile in `profile` is used at boot; the API can switch profiles
+//! while the radio runs (`RadioApi::select_profile`).
+//!
+//! # JSON Format
+//! ```json
+//! "profile": "kids",
+//! "profiles": {
+//!     "kids": {
+//!         "dial": {
+//!             "AM": { "0": "AM/04", "1": "AM/07" },
+//!             "FM": { "0": "library/bedtime_stories" }
+//!         }
+//!     },
+//!     "evening": {
+//!         "dial": { "FM": { "11": "FM/02" } },
+//!         "inherit": true,
+//!         "exclude_ratings": ["explicit"]
+//!     }
+//! }
+//! ```
+//!
+//! Station folders are relative to the stations root unless absolute.
+//! `exclude_ratings` keeps rated tracks off every station on the dial
+//! while the profile is selected, on top of each station's own.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use serde::Deserialize;
+
+use crate::config::stations_root;
+use crate::radio::station::content::Band;
+
+/// One named dial layout
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ProfileConfig {
+    /// Station folder for each dial slot, by band name
+    pub dial: HashMap<String, HashMap<usize, PathBuf>>,
+
+    /// Slots not listed keep the standard layout's station instead of
+    /// going off air
+    pub inherit: bool,
+
+    /// Content ratings no station plays under this profile
+    pub exclude_ratings: Vec<String>,
+}
+
+/// Where a dial slot gets its station from under a profile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotAssignment {
+    /// Station folder named by the profile
+    Station(PathBuf),
+
+    /// The slot's own folder, as without a profile
+    Standard,
+
+    /// Nothing on this slot
+    Empty,
+}
+
+impl ProfileConfig {
+    /// Returns what the profile puts on a dial slot
+    pub fn assignment(&self, band: Band, index: usize) -> SlotAssignment {
+        let station_folder = self.dial.get(band.name()).and_then(|slots| slots.get(&index));
+        match station_folder {
+            Some(station_folder) => SlotAssignment::Station(stations_root().join(station_folder)),
+            None if self.inherit => SlotAssignment::Standard,
+            None => SlotAssignment::Empty
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Station Setup - Stations from a plain folder of music
+//!
+//! `mokradio init ~/Music` turns an existing music folder into stations,
+//! for a first radio without writing station.info files or a manifest:
+//!
+//! - Every folder directly inside the music folder becomes a station named
+//!   after it, playing every audio file beneath it. Audio files loose in
+//!   the music folder itself make one more station.
+//! - Stations fill the free dial slots in order, from the first band (or
+//!   `--band`) on into the bands after it. Slots that already have a
+//!   station.info are left alone.
+//! - Playlists are flat, so nested files are linked into the station's
+//!   `playlist/` folder one by one, named for the folders they were in
+//!   (`Artist - Album - 01 Track.mp3`). With `--move` they're moved there
+//!   instead, for an SD card with no room for the library as well. A
+//!   file's `.cue`, `.origin` and `.rating` sidecars go along with it.
+//!
+//! Each station.info is written the way `import` writes one (see
+//! `config::manifest`), with the play type given (`Shuffle` by default).
+//! The command asks for each station's name and play type, and before
+//! writing anything; `--yes` takes the defaults without asking.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::manifest::{ManifestEntry, create_station};
+use crate::config::radio_config;
+use crate::constants;
+use crate::radio::Radio;
+use crate::radio::station::content::Band;
+
+/// File extensions picked up as audio, alongside any configured for transcoding
+const AUDIO_EXTENSIONS: [&str; 8] = ["mp3", "flac", "ogg", "oga", "wav", "m4a", "aac", "opus"];
+
+/// Files kept next to an audio file with the same stem (see `content`)
+const SIDECAR_EXTENSIONS: [&str; 3] = ["cue", "origin", "rating"];
+
+/// Separator between folder names in a flattened file name
+const FLATTEN_SEPARATOR: &str = " - ";
+
+/// A folder of music that can become a station
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// Station name to suggest (the folder's name)
+    pub name: String,
+
+    /// Folder the audio files were found under
+    pub folder: PathBuf,
+
+    /// Audio files for the playlist, sorted
+    pub files: Vec<PathBuf>,
+}
+
+/// A candidate placed on a free dial slot
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub band: Band,
+    pub slot: usize,
+    pub candidate: Candidate,
+}
+
+/// Finds the folders in a music folder that can become stations
+///
+/// # Returns
+/// A candidate for each folder holding audio (sorted by name), then one
+/// for audio loose in the music folder, or the error if the music folder
+/// can't be read
+pub fn find_candidates(music_path: &Path) -> io::Result<Vec<Candidate>> {
+    let music_path = music_path.canonicalize()?;
+    let mut folders: Vec<PathBuf> = Vec::new();
+    let mut loose: Vec<PathBuf> = Vec::new();
+    for entry in fs::read_dir(&music_path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            folders.push(path);
+        } else if is_audio(&path) {
+            loose.push(path);
+        }
+    }
+    folders.sort();
+    loose.sort();
+
+    let mut candidates: Vec<Candidate> = folders.into_iter()
+        .filter_map(|folder| {
+            let mut files = Vec::new();
+            collect_audio(&folder, &mut files);
+            files.sort();
+            (!files.is_empty()).then(|| Candidate { name: folder_name(&folder), folder, files })
+        })
+        .collect();
+    if !loose.is_empty() {
+        candidates.push(Candidate { name: folder_name(&music_path), folder: music_path, files: loose });
+    }
+    Ok(candidates)
+}
+
+/// Places candidates on the free dial slots, starting from a band
+///
+/// # Returns
+/// The candidates that found a slot, and those left over when the dial
+/// filled up
+pub fn place(candidates: Vec<Candidate>, first_band: Band) -> (Vec<Placement>, Vec<Candidate>) {
+    let mut free_slots = Band::all()
+        .filter(|band| band.0 >= first_band.0)
+        .flat_map(|band| (0..constants::NUMBER_OF_STATIONS).map(move |slot| (band, slot)))
+        .filter(|(band, slot)| !Radio::station_path(*band, *slot).join("station.info").exists());
+
+    let mut placements = Vec::new();
+    let mut unplaced = Vec::new();
+    for candidate in candidates {
+        match free_slots.next() {
+            Some((band, slot)) => placements.push(Placement { band, slot, candidate }),
+            None => unplaced.push(candidate)
+        }
+    }
+    (placements, unplaced)
+}
+
+/// Creates a placed station: its folder, station.info and playlist
+///
+/// # Arguments
+/// * `placement` - Where the station goes and what it plays
+/// * `name` - Station name for station.info
+/// * `play_type` - Play type for station.info
+/// * `move_files` - Move the audio into the playlist instead of linking it
+///
+/// # Returns
+/// The station's folder, or the first error; files already linked or
+/// moved stay where they are
+pub fn create(placement: &Placement, name: &str, play_type: &str, move_files: bool) -> io::Result<PathBuf> {
+    let station_path = Radio::station_path(placement.band, placement.slot);
+    let entry = ManifestEntry {
+        line: 0,
+        band: placement.band,
+        slot: placement.slot,
+        name: name.to_string(),
+        play_type: play_type.to_string(),
+        source: None,
+    };
+    create_station(&entry, &station_path)?;
+
+    let playlist_path = station_path.join("playlist");
+    fs::create_dir_all(&playlist_path)?;
+    for file in &placement.candidate.files {
+        let target = playlist_path.join(flattened_name(&placement.candidate.folder, file));
+        if target.exists() {
+            eprintln!("{} is already in the playlist, leaving {}", target.display(), file.display());
+            continue;
+        }
+        let sidecars = SIDECAR_EXTENSIONS.iter()
+            .map(|extension| (file.with_extension(extension), target.with_extension(extension)))
+            .filter(|(sidecar, sidecar_target)| sidecar.exists() && !sidecar_target.exists());
+        for (from, to) in std::iter::once((file.clone(), target.clone())).chain(sidecars) {
+            if move_files {
+                move_file(&from, &to)?;
+            } else {
+                std::os::unix::fs::symlink(&from, &to)?;
+            }
+        }
+    }
+    Ok(station_path)
+}
+
+/// Gathers the audio files anywhere under a folder
+fn collect_audio(folder: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        eprintln!("Failed to read {}, skipping it", folder.display());
+        return;
+    };
+    for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_audio(&path, files);
+        } else if is_audio(&path) {
+            files.push(path);
+        }
+    }
+}
+
+/// Returns whether a file looks like audio the radio can play
+fn is_audio(path: &Path) -> bool {
+    let Some(extension) = path.extension().map(|extension| extension.to_string_lossy().to_ascii_lowercase()) else {
+        return false;
+    };
+    AUDIO_EXTENSIONS.contains(&extension.as_str())
+        || radio_config().transcode.extensions.iter().any(|transcoded| transcoded.eq_ignore_ascii_case(&extension))
+}
+
+/// Names a file for a flat playlist after the folders it was in
+fn flattened_name(folder: &Path, file: &Path) -> String {
+    let relative = file.strip_prefix(folder).unwrap_or(file);
+    relative.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(FLATTEN_SEPARATOR)
+}
+
+fn folder_name(folder: &Path) -> String {
+    folder.file_name().map_or_else(|| "Music".to_string(), |name| name.to_string_lossy().to_string())
+}
+
+/// Moves a file, copying it across when it's on another filesystem
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
@@ -0,0 +1,137 @@
+//! Tuning Feel Configuration
+//!
+//! Parameters that shape how stations fade in and out of the static as the
+//! dial moves. Everyone's pot and taste differ, so radio.info can pick a
+//! named preset and override individual values.
+//!
+//! # JSON Format
+//! ```json
+//! "tuning": {
+//!     "preset": "DriftyVintage",
+//!     "static_floor": 0.1
+//! }
+//! ```
+
+use serde::Deserialize;
+
+/// Shape of the fade between a station and the static around it
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadeCurve {
+    /// Smooth S-curve with a flat top (the original mokRadio feel)
+    Tanh,
+
+    /// Straight-line ramps on either side of the plateau
+    Linear,
+
+    /// Raised-cosine ramps, softer at both ends of the fade
+    Cosine
+}
+
+/// Named starting points for the tuning feel
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TuningPreset {
+    /// Original mokRadio tuning
+    Classic,
+
+    /// Wide, sharp-edged stations with no bleed, like a digital tuner
+    TightModern,
+
+    /// Narrow stations with long soft edges, bleed and a little hiss
+    DriftyVintage
+}
+
+/// Fully resolved tuning parameters used to build volume profiles
+#[derive(Debug, Clone, Copy)]
+pub struct TuningProfile {
+    /// Half width of the full-volume plateau, as a fraction of half a station slot
+    pub station_width: f32,
+
+    /// Length of the fade on each side, as a fraction of a station slot
+    pub softness: f32,
+
+    /// How loud a neighboring station bleeds through at the edge of a slot
+    pub overlap: f32,
+
+    /// Static that never goes away, even when perfectly tuned
+    pub static_floor: f32,
+
+    /// Shape of the fade
+    pub curve: FadeCurve,
+}
+
+impl TuningPreset {
+    /// Returns the parameters for this preset
+    pub fn profile(&self) -> TuningProfile {
+        match self {
+            TuningPreset::Classic => TuningProfile {
+                station_width: 0.06,
+                softness: 0.05,
+                overlap: 0.0,
+                static_floor: 0.0,
+                curve: FadeCurve::Tanh,
+            },
+            TuningPreset::TightModern => TuningProfile {
+                station_width: 0.2,
+                softness: 0.02,
+                overlap: 0.0,
+                static_floor: 0.0,
+                curve: FadeCurve::Linear,
+            },
+            TuningPreset::DriftyVintage => TuningProfile {
+                station_width: 0.03,
+                softness: 0.12,
+                overlap: 0.15,
+                static_floor: 0.04,
+                curve: FadeCurve::Cosine,
+            },
+        }
+    }
+}
+
+impl Default for TuningProfile {
+    fn default() -> Self {
+        TuningPreset::Classic.profile()
+    }
+}
+
+/// Tuning section of radio.info: a preset plus optional overrides
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct TuningConfig {
+    /// Preset to start from
+    pub preset: TuningPreset,
+
+    // Overrides for individual preset values (see TuningProfile)
+    pub station_width: Option<f32>,
+    pub softness: Option<f32>,
+    pub overlap: Option<f32>,
+    pub static_floor: Option<f32>,
+    pub curve: Option<FadeCurve>,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        TuningConfig {
+            preset: TuningPreset::Classic,
+            station_width: None,
+            softness: None,
+            overlap: None,
+            static_floor: None,
+            curve: None,
+        }
+    }
+}
+
+impl TuningConfig {
+    /// Applies the overrides on top of the preset
+    pub fn profile(&self) -> TuningProfile {
+        let preset = self.preset.profile();
+        TuningProfile {
+            station_width: self.station_width.unwrap_or(preset.station_width).clamp(0.0, 1.0),
+            softness: self.softness.unwrap_or(preset.softness).max(0.001),
+            overlap: self.overlap.unwrap_or(preset.overlap).clamp(0.0, 1.0),
+            static_floor: self.static_floor.unwrap_or(preset.static_floor).clamp(0.0, 1.0),
+            curve: self.curve.unwrap_or(preset.curve),
+        }
+    }
+}
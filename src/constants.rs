@@ -6,9 +6,19 @@ pub const NUMBER_OF_STATIONS: usize = 12;
 pub const ENCODER_MAX: usize = 8192;
 pub const TICKS_PER_STATION: usize = ENCODER_MAX / NUMBER_OF_STATIONS / 2;
 pub const ENCODER_HALF: usize = TICKS_PER_STATION * NUMBER_OF_STATIONS;
-pub const STATION_PATH: &'static str = "/stations";
+pub const STATION_PATH: &str = "/stations";
 pub const TIME_BETWEEN_SKIPS: Duration = Duration::new(300, 0);
+pub const PLENTY_QUEUED: Duration = Duration::new(600, 0);
 pub const KNOB_DELAY: Duration = Duration::new(0, 3000000);
 pub const LOOP_DELAY: Duration = Duration::new(0, 10000000);
+pub const SCHEDULE_CHECK_INTERVAL: Duration = Duration::new(1, 0);
+pub const STATION_FADE: Duration = Duration::new(0, 80000000);
+pub const TUNE_AWAY_GRACE: Duration = Duration::new(2, 0);
+pub const ERROR_BUDGET: usize = 5;
+pub const IO_RETRIES: usize = 2;
+pub const IO_RETRY_BACKOFF: Duration = Duration::new(0, 500000000);
+pub const QUARANTINE_AFTER: usize = 3;
+pub const FAULT_RETRY_INTERVAL: Duration = Duration::new(600, 0);
+pub const TUNER_ADDRESS : u16 = 0x06;
 pub const LEADING_REGISTER : u8 = 0x03;
 pub const BAND_SWITCH_PIN : u8 = 4;
\ No newline at end of file
@@ -0,0 +1,189 @@
+//! Crash Reports - What the radio was doing when it fell over
+//!
+//! `install()` replaces the panic hook so that a panic anywhere in the
+//! radio writes a report to `crashes/` in the stations root and aborts,
+//! letting the service manager restart the radio cleanly rather than
+//! leaving it running with a thread missing. A report holds the panic
+//! message and where it happened, a backtrace, where the dial was, the
+//! last `RECENT_EVENTS` events the Station Manager handled and how deep
+//! its queues were, so an intermittent failure in the field can be
+//! diagnosed from the SD card afterwards.
+//!
+//! Panics the radio already recovers from (a decoder choking on a corrupt
+//! file) run inside `recoverable()`, and are only logged as before. The
+//! newest `MAX_REPORTS` reports are kept.
+
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::stations_root;
+use crate::radio::station::content::StationID;
+
+/// Folder in the stations root holding crash reports
+const CRASH_FOLDER: &str = "crashes";
+
+/// Events kept for the next report
+const RECENT_EVENTS: usize = 50;
+
+/// Reports kept before the oldest are deleted
+const MAX_REPORTS: usize = 20;
+
+/// Where the Station Manager's dial is and how deep its queues are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManagerState {
+    /// Station the dial is on
+    pub station: StationID,
+
+    /// Dial position (0..ENCODER_HALF)
+    pub dial_position: usize,
+
+    /// Sources in the current station's sink, including the one playing
+    pub sink_length: usize,
+
+    /// Station fades in progress
+    pub fades: usize,
+
+    /// Loads whose audio will be thrown away when it arrives
+    pub stale_loads: usize,
+
+    /// Loads waiting out their retry backoff
+    pub pending_retries: usize,
+}
+
+/// What the Station Manager was last seen doing
+struct CrashContext {
+    /// Dial and queues, once the manager loop has started
+    manager: Option<ManagerState>,
+
+    /// Latest events handled, oldest first, each with seconds since the Unix epoch
+    recent_events: VecDeque<(u64, String)>,
+}
+
+static CONTEXT: Mutex<CrashContext> = Mutex::new(CrashContext {
+    manager: None,
+    recent_events: VecDeque::new(),
+});
+
+thread_local! {
+    /// Set while running code whose panics are caught and handled
+    static RECOVERABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs the crash report panic hook
+///
+/// Called once at startup, after radio.info has been loaded.
+pub fn install() {
+    let previous_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        previous_hook(info);
+        if RECOVERABLE.with(Cell::get) {
+            return;
+        }
+        match write_report(info) {
+            Ok(report_path) => eprintln!("crash report written to {}", report_path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        std::process::abort();
+    }));
+}
+
+/// Runs code whose panics the caller catches, without writing a crash report
+pub fn recoverable<T>(action: impl FnOnce() -> T) -> T {
+    /// Puts the flag back however `action` ends
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RECOVERABLE.with(|recoverable| recoverable.set(self.0));
+        }
+    }
+    let _restore = Restore(RECOVERABLE.with(|recoverable| recoverable.replace(true)));
+    action()
+}
+
+/// Notes where the dial is and how deep the Station Manager's queues are,
+/// for the next report
+///
+/// Only called when something changed, so the manager loop doesn't take
+/// the lock every tick.
+pub fn note_state(manager: ManagerState) {
+    with_context(|context| context.manager = Some(manager));
+}
+
+/// Adds an event to those listed in the next report
+pub fn remember_event(event: String) {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    with_context(|context| {
+        if context.recent_events.len() >= RECENT_EVENTS {
+            context.recent_events.pop_front();
+        }
+        context.recent_events.push_back((since_epoch.as_secs(), event));
+    });
+}
+
+fn with_context(action: impl FnOnce(&mut CrashContext)) {
+    action(&mut CONTEXT.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+}
+
+/// Writes a report for a panic, returning where it went
+fn write_report(info: &PanicHookInfo) -> std::io::Result<PathBuf> {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let message = info.payload().downcast_ref::<&str>().map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(no message)".to_string());
+    let location = info.location().map_or_else(|| "unknown".to_string(), |location| location.to_string());
+
+    let mut report = String::new();
+    let _ = writeln!(report, "mokRadio crash at {} (seconds since the Unix epoch)", since_epoch.as_secs());
+    let _ = writeln!(report, "thread '{}' panicked at {}: {}", thread::current().name().unwrap_or("unnamed"), location, message);
+
+    // The panic may have happened with the context locked; don't wait on it
+    match CONTEXT.try_lock() {
+        Ok(context) => {
+            match context.manager {
+                Some(manager) => {
+                    let _ = writeln!(report, "\n== Dial ==\n{} at dial position {}", manager.station, manager.dial_position);
+                    let _ = writeln!(report, "\n== Queue depths ==");
+                    let _ = writeln!(report, "current station sink: {}", manager.sink_length);
+                    let _ = writeln!(report, "fades: {}", manager.fades);
+                    let _ = writeln!(report, "stale loads: {}", manager.stale_loads);
+                    let _ = writeln!(report, "pending retries: {}", manager.pending_retries);
+                },
+                None => report.push_str("\n(radio not started)\n"),
+            }
+            let _ = writeln!(report, "\n== Last {} events ==", context.recent_events.len());
+            context.recent_events.iter().for_each(|(time, event)| {let _ = writeln!(report, "{} {}", time, event);});
+        },
+        Err(_) => report.push_str("\n(radio state unavailable)\n"),
+    }
+    let _ = writeln!(report, "\n== Backtrace ==\n{}", Backtrace::force_capture());
+
+    let crash_folder = stations_root().join(CRASH_FOLDER);
+    fs::create_dir_all(&crash_folder)?;
+    let report_path = crash_folder.join(format!("crash-{}.txt", since_epoch.as_secs()));
+    fs::write(&report_path, report)?;
+    prune_reports(&crash_folder);
+    Ok(report_path)
+}
+
+/// Deletes the oldest reports beyond `MAX_REPORTS`
+fn prune_reports(crash_folder: &Path) {
+    let Ok(entries) = fs::read_dir(crash_folder) else {return;};
+    let mut reports: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.file_name().is_some_and(|name| name.to_string_lossy().starts_with("crash-")))
+        .collect();
+    // Named by time, so the oldest sort first
+    reports.sort_by_key(|path| path.file_stem()
+        .and_then(|stem| stem.to_string_lossy().trim_start_matches("crash-").parse::<u64>().ok())
+        .unwrap_or(0));
+    let excess = reports.len().saturating_sub(MAX_REPORTS);
+    reports.iter().take(excess).for_each(|path| {fs::remove_file(path).ok();});
+}
@@ -0,0 +1,317 @@
+//! Now-Playing Display
+//!
+//! Shows what the dial is on on a 128x64 SSD1306 OLED on the shared I2C
+//! bus: the station and its name at the top, the track title wrapped
+//! over the middle, and how far into the track it is along the bottom.
+//! The Station Manager sends a `NowPlaying` whenever the dial moves, a
+//! track starts or the radio is paused; between messages the elapsed
+//! time counts up on its own, and the screen is redrawn once a second.
+//!
+//! # JSON Format
+//! ```json
+//! "hardware": {
+//!     "display": { "address": 60 }
+//! }
+//! ```
+
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rppal::i2c::{Error, I2c};
+
+use crate::config::hardware::DisplayConfig;
+use crate::i2c_bus::I2cBus;
+use crate::messages::NowPlaying;
+
+/// Panel size in pixels; each page is 8 rows, one text line
+const WIDTH: usize = 128;
+const PAGES: usize = 8;
+
+/// Characters per line, with a column of space after each 5-pixel glyph
+const CELL_WIDTH: usize = 6;
+const COLUMNS: usize = WIDTH / CELL_WIDTH;
+
+/// Lines of the layout
+const STATION_LINE: usize = 0;
+const NAME_LINES: std::ops::Range<usize> = 1..3;
+const TITLE_LINES: std::ops::Range<usize> = 3..6;
+const STATUS_LINE: usize = 7;
+
+/// How often the elapsed time is redrawn
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Control bytes starting a command or a run of pixel data
+const COMMAND: u8 = 0x00;
+const DATA: u8 = 0x40;
+
+/// Pixel bytes written per I2C transfer
+const DATA_CHUNK: usize = 16;
+
+/// Power-up sequence for a 128x64 panel with the charge pump on,
+/// from the SSD1306 application note
+const INIT_SEQUENCE: [u8; 25] = [
+    0xAE,       // display off
+    0xD5, 0x80, // clock divide
+    0xA8, 0x3F, // multiplex, 64 rows
+    0xD3, 0x00, // no display offset
+    0x40,       // start line 0
+    0x8D, 0x14, // charge pump on
+    0x20, 0x00, // horizontal addressing
+    0xA1,       // columns mirrored, so column 0 is on the left
+    0xC8,       // rows scanned top down
+    0xDA, 0x12, // COM pins for 64 rows
+    0x81, 0xCF, // contrast
+    0xD9, 0xF1, // precharge
+    0xDB, 0x40, // VCOMH deselect level
+    0xA4,       // show the frame buffer
+    0xA6,       // not inverted
+    0xAF,       // display on
+];
+
+/// Starts drawing now-playing messages on a background thread until the
+/// Station Manager hangs up
+///
+/// A display that doesn't answer is logged; writes keep retrying through
+/// the shared bus, and setup is repeated on the next start.
+pub fn start(config: &DisplayConfig, now_playing: Receiver<NowPlaying>) {
+    let display = Ssd1306 { bus: I2cBus::shared(), address: config.address };
+    if let Err(e) = display.configure() {
+        eprintln!("Failed to set up display at 0x{:02x}: {}", config.address, e);
+    }
+
+    thread::spawn(move || {
+        let mut showing: Option<(NowPlaying, Instant)> = None;
+        loop {
+            match now_playing.recv_timeout(REFRESH_INTERVAL) {
+                Ok(message) => showing = Some((message, Instant::now())),
+                Err(RecvTimeoutError::Timeout) => {},
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+            let Some((message, received)) = &showing else {continue;};
+            if let Err(e) = display.draw(&render(message, received.elapsed())) {
+                eprintln!("Failed to update display: {}", e);
+            }
+        }
+        display.draw(&[0; WIDTH * PAGES]).ok();
+    });
+}
+
+/// SSD1306 controller at an address on the shared bus
+struct Ssd1306 {
+    bus: I2cBus,
+    address: u16,
+}
+
+impl Ssd1306 {
+    /// Powers up the panel and clears it
+    fn configure(&self) -> Result<(), Error> {
+        self.bus.transaction(self.address, |i2c| send_commands(i2c, &INIT_SEQUENCE))?;
+        self.draw(&[0; WIDTH * PAGES])
+    }
+
+    /// Writes a whole frame, page by page from the top left
+    ///
+    /// Each page is its own bus transaction, so the tuner and RTC get the
+    /// bus between pages rather than waiting out the whole frame.
+    fn draw(&self, frame: &[u8; WIDTH * PAGES]) -> Result<(), Error> {
+        for (page, pixels) in frame.chunks(WIDTH).enumerate() {
+            self.bus.transaction(self.address, |i2c| {
+                send_commands(i2c, &[0x21, 0, (WIDTH - 1) as u8, 0x22, page as u8, page as u8])?;
+                for chunk in pixels.chunks(DATA_CHUNK) {
+                    let mut transfer = Vec::with_capacity(chunk.len() + 1);
+                    transfer.push(DATA);
+                    transfer.extend_from_slice(chunk);
+                    i2c.write(&transfer)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn send_commands(i2c: &mut I2c, commands: &[u8]) -> Result<(), Error> {
+    for command in commands {
+        i2c.write(&[COMMAND, *command])?;
+    }
+    Ok(())
+}
+
+/// Lays out a frame for a now-playing message
+///
+/// # Arguments
+/// * `now_playing` - What the dial is on
+/// * `since` - Time since the message was sent, added to its elapsed time
+fn render(now_playing: &NowPlaying, since: Duration) -> [u8; WIDTH * PAGES] {
+    let mut frame = [0; WIDTH * PAGES];
+    draw_line(&mut frame, STATION_LINE, &now_playing.station);
+    wrap(&now_playing.name, NAME_LINES.len()).iter()
+        .zip(NAME_LINES)
+        .for_each(|(text, line)| draw_line(&mut frame, line, text));
+
+    let status = if !now_playing.on_air {
+        "OFF AIR".to_string()
+    } else {
+        if let Some(title) = &now_playing.title {
+            wrap(title, TITLE_LINES.len()).iter()
+                .zip(TITLE_LINES)
+                .for_each(|(text, line)| draw_line(&mut frame, line, text));
+        }
+        let elapsed = if now_playing.paused {now_playing.elapsed} else {now_playing.elapsed + since};
+        let seconds = elapsed.as_secs();
+        let clock = format!("{}:{:02}", seconds / 60, seconds % 60);
+        if now_playing.paused {format!("{} PAUSED", clock)} else {clock}
+    };
+    draw_line(&mut frame, STATUS_LINE, &status);
+    frame
+}
+
+/// Splits text into at most `lines` lines that fit the width, breaking
+/// between words where it can; the last line is cut short if it overflows
+fn wrap(text: &str, lines: usize) -> Vec<String> {
+    let mut wrapped: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        loop {
+            let separator = if current.is_empty() {0} else {1};
+            if current.chars().count() + separator + word.len() <= COLUMNS {
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.extend(word.drain(..));
+                break;
+            }
+            // Words longer than a line are broken wherever they hit the edge
+            if current.is_empty() {
+                current.extend(word.drain(..COLUMNS));
+            }
+            wrapped.push(std::mem::take(&mut current));
+            if word.is_empty() {
+                break;
+            }
+        }
+    }
+    if !current.is_empty() {
+        wrapped.push(current);
+    }
+    wrapped.truncate(lines);
+    wrapped
+}
+
+/// Draws text on a line, from the left edge, cutting it off at the right
+fn draw_line(frame: &mut [u8; WIDTH * PAGES], line: usize, text: &str) {
+    let page = &mut frame[line * WIDTH..(line + 1) * WIDTH];
+    for (cell, character) in page.chunks_mut(CELL_WIDTH).zip(text.chars()).take(COLUMNS) {
+        cell[..5].copy_from_slice(glyph(character));
+    }
+}
+
+/// Columns of a character in the font, '?' for anything outside printable ASCII
+fn glyph(character: char) -> &'static [u8; 5] {
+    let index = match character {
+        ' '..='~' => character as usize - ' ' as usize,
+        _ => '?' as usize - ' ' as usize,
+    };
+    &FONT[index]
+}
+
+/// 5x7 font for printable ASCII, one byte per column, top row in the low bit
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
@@ -0,0 +1,56 @@
+//! Errors - What can go wrong reading stations and tracks
+//!
+//! Loaders return a `MokRadioError` rather than panicking, so one bad
+//! file costs that file (or that station) instead of the whole radio.
+//! Most callers log the error and carry on with what did load; the
+//! message names the file so the log says what to fix.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Errors from loading station folders, configs and audio files
+#[derive(Debug)]
+pub enum MokRadioError {
+    /// A file or folder couldn't be read
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+
+    /// A config file was read but isn't usable, and says why
+    Config {
+        path: PathBuf,
+        message: String,
+    },
+
+    /// A file has no audio the probe can find a length for (cover art,
+    /// notes, a download still in progress)
+    NotAudio { path: PathBuf },
+}
+
+impl MokRadioError {
+    /// Wraps an IO error with the path it happened on
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        MokRadioError::Io { path: path.into(), source }
+    }
+}
+
+impl fmt::Display for MokRadioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MokRadioError::Io { path, source } => write!(f, "failed to read {}: {}", path.display(), source),
+            MokRadioError::Config { path, message } => write!(f, "bad config {}: {}", path.display(), message),
+            MokRadioError::NotAudio { path } => write!(f, "{} is not a playable audio file", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for MokRadioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MokRadioError::Io { source, .. } => Some(source),
+            _ => None
+        }
+    }
+}
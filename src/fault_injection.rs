@@ -0,0 +1,109 @@
+//! Fault Injection Module
+//!
+//! Simulation mode for exercising recovery paths that are hard to trigger on
+//! real hardware. Each fault has an injection rate (0.0 = never, 1.0 = always)
+//! that can be set from the `faults` section of radio.info or changed at
+//! runtime, and the loader, decoder and device code ask `should_inject()`
+//! at the point where the real failure would happen.
+//!
+//! # JSON Format
+//! ```json
+//! "faults": {
+//!     "MissingFile": 0.1,
+//!     "SlowDisk": 0.25
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use serde::Deserialize;
+
+use crate::config::radio_config;
+
+/// How long an injected slow disk read stalls
+pub const SLOW_DISK_DELAY: Duration = Duration::from_secs(2);
+
+/// Rates are stored as parts per million so they fit in an atomic
+const RATE_SCALE: f32 = 1_000_000.0;
+
+/// Hardware and file faults that can be simulated
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// Tuner/ADC read returns an error
+    AdcReadError,
+
+    /// Requested audio file can't be found
+    MissingFile,
+
+    /// File reads stall before completing
+    SlowDisk,
+
+    /// Decoder panics while opening a file
+    DecoderPanic,
+
+    /// I2C device drops off the bus mid-transaction
+    DeviceDisconnect,
+}
+
+impl Fault {
+    /// Every fault, in storage order
+    pub const ALL: [Fault; 5] = [
+        Fault::AdcReadError,
+        Fault::MissingFile,
+        Fault::SlowDisk,
+        Fault::DecoderPanic,
+        Fault::DeviceDisconnect,
+    ];
+}
+
+/// Injection rate per fault in parts per million, indexed by `Fault as usize`
+static FAULT_RATES: [AtomicU32; Fault::ALL.len()] = [const { AtomicU32::new(0) }; Fault::ALL.len()];
+
+/// Loads the fault rates configured in radio.info
+///
+/// Called once at startup. Any configured fault puts the radio in
+/// simulation mode, which is announced so it isn't left on by accident.
+pub fn initialize() {
+    let faults = &radio_config().faults;
+    if faults.is_empty() {
+        return;
+    }
+
+    println!("fault injection enabled: {:?}", faults);
+    faults.iter().for_each(|(fault, rate)| set_rate(*fault, *rate));
+}
+
+/// Sets how often a fault is injected (0.0 disables it)
+pub fn set_rate(fault: Fault, rate: f32) {
+    let parts_per_million = (rate.clamp(0.0, 1.0) * RATE_SCALE) as u32;
+    FAULT_RATES[fault as usize].store(parts_per_million, Ordering::Relaxed);
+}
+
+/// Returns the current injection rate for a fault
+pub fn rate(fault: Fault) -> f32 {
+    FAULT_RATES[fault as usize].load(Ordering::Relaxed) as f32 / RATE_SCALE
+}
+
+/// Error returned in place of a real failure when a fault is injected
+///
+/// Kept apart from real errors so recovery that acts on the file itself
+/// (forgetting or quarantining a track) never touches a good file.
+#[derive(Debug, Clone, Copy)]
+pub struct InjectedFault(pub Fault);
+
+impl std::fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "injected {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InjectedFault {}
+
+/// Rolls the dice for a fault at an injection point
+///
+/// # Returns
+/// `true` if the caller should simulate the failure this time
+pub fn should_inject(fault: Fault) -> bool {
+    let parts_per_million = FAULT_RATES[fault as usize].load(Ordering::Relaxed);
+    parts_per_million > 0 && rand::random_range(0..1_000_000) < parts_per_million
+}
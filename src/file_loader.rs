@@ -2,3 +2,8 @@
 pub mod thread;
 pub mod scanner;
 pub mod decoder;
+pub mod probe;
+pub mod ram_cache;
+pub mod transcode;
+pub mod tts;
+pub mod warmer;
@@ -1,16 +1,100 @@
 // Audio file loading and decoding
-// Loads MP3 files and decodes them for rodio
+// Loads MP3, FLAC, OGG Vorbis, WAV, AAC/M4A and (transcoded) Opus files
+// and decodes them for rodio, which decodes with Symphonia
 
-use std::path::Path;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{self, BufReader};
 use rodio::Decoder;
+use rodio::decoder::DecoderError;
+
+use crate::fault_injection::{self, Fault, InjectedFault};
+use crate::messages::LoadErrorKind;
+use crate::file_loader::ram_cache::resident_path;
+use crate::file_loader::transcode::playable_path;
+
+/// A RAM-cache or transcoded copy of a track failed to open or decode
+/// 
+/// Says nothing about the original file, which may be fine.
+#[derive(Debug)]
+pub struct CopyError {
+    pub copy: PathBuf,
+    pub source: Box<dyn Error>,
+}
+
+impl fmt::Display for CopyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "copy {} failed: {}", self.copy.display(), self.source)
+    }
+}
+
+impl Error for CopyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
 
 /// Loads and decodes an audio file
 /// 
-/// Returns a rodio Decoder that can be appended to a Sink
-pub fn load_and_decode(path: &Path) -> Result<Decoder<BufReader<File>>, Box<dyn std::error::Error>> {
-    let file = File::open(path)?;
-    let decoder = Decoder::new(BufReader::new(file))?;
+/// Returns a rodio Decoder that can be appended to a Sink. Failures of a
+/// RAM-cache or transcoded copy come back as a `CopyError`.
+pub fn load_and_decode(path: &Path) -> Result<Decoder<BufReader<File>>, Box<dyn Error>> {
+    // Simulated failures for exercising recovery paths
+    if fault_injection::should_inject(Fault::MissingFile) {
+        return Err(Box::new(InjectedFault(Fault::MissingFile)));
+    }
+    if fault_injection::should_inject(Fault::SlowDisk) {
+        std::thread::sleep(fault_injection::SLOW_DISK_DELAY);
+    }
+    if fault_injection::should_inject(Fault::DecoderPanic) {
+        panic!("injected decoder panic for {}", path.display());
+    }
+
+    // Hot content plays from its RAM copy; exotic formats from their transcoded copy
+    let playable_path = resident_path(path).unwrap_or_else(|| playable_path(path));
+    if playable_path == path {
+        return decode(path);
+    }
+    decode(&playable_path).map_err(|source| Box::new(CopyError { copy: playable_path, source }) as Box<dyn Error>)
+}
+
+/// Opens and decodes the file at exactly this path
+fn decode(playable_path: &Path) -> Result<Decoder<BufReader<File>>, Box<dyn Error>> {
+    let file = File::open(playable_path)?;
+    let byte_len = file.metadata()?.len();
+
+    // MP4 containers need the extension hint and a known length to probe
+    let mut builder = Decoder::builder()
+        .with_data(BufReader::new(file))
+        .with_byte_len(byte_len)
+        .with_seekable(true);
+    if let Some(extension) = playable_path.extension().and_then(|extension| extension.to_str()) {
+        builder = builder.with_hint(extension);
+    }
+    let decoder = builder.build()?;
     Ok(decoder)
 }
+
+/// Sorts a load error into the kind the Station Manager acts on
+///
+/// IO errors other than a missing file are treated as transient, since
+/// network mounts and tired SD cards usually read fine a moment later.
+pub fn classify(error: &(dyn Error + 'static)) -> LoadErrorKind {
+    // Not the original file's fault, so nothing is done to it
+    if error.is::<InjectedFault>() || error.is::<CopyError>() {
+        return LoadErrorKind::Inconclusive;
+    }
+    if let Some(io_error) = error.downcast_ref::<io::Error>() {
+        return match io_error.kind() {
+            io::ErrorKind::NotFound => LoadErrorKind::NotFound,
+            _ => LoadErrorKind::IoTimeout
+        };
+    }
+    match error.downcast_ref::<DecoderError>() {
+        Some(DecoderError::UnrecognizedFormat) => LoadErrorKind::UnsupportedFormat,
+        Some(DecoderError::IoError(_)) => LoadErrorKind::IoTimeout,
+        _ => LoadErrorKind::DecodeFailed
+    }
+}
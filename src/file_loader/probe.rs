@@ -0,0 +1,56 @@
+// Duration probing
+// Reads how long an audio file plays for with Symphonia, the same library
+// rodio decodes with, so every format that plays also has a duration
+
+use std::fs::File;
+use std::path::Path;
+use std::time::Duration;
+
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::core::units::{Time, TimeBase};
+
+/// Reads the playing time of an audio file
+///
+/// MP3, FLAC, OGG Vorbis, WAV and AAC/M4A are probed from their container
+/// or codec headers. Files whose headers don't give a length (MP3s without
+/// a Xing/Info frame, some OGG streams) are read through packet by packet
+/// instead, which is slower but exact.
+///
+/// # Returns
+/// The duration, or None if the file can't be read or isn't audio
+pub fn probe_duration(path: &Path) -> Option<Duration> {
+    let file = File::open(path).ok()?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .ok()?;
+    let mut format = probed.format;
+
+    let track = format.default_track()?;
+    let track_id = track.id;
+    let time_base = track.codec_params.time_base
+        .or_else(|| track.codec_params.sample_rate.map(|sample_rate| TimeBase::new(1, sample_rate)))?;
+    if let Some(frames) = track.codec_params.n_frames {
+        return Some(to_duration(time_base.calc_time(frames))).filter(|duration| !duration.is_zero());
+    }
+
+    // No length in the headers; the last packet's end is the length
+    let mut end = 0;
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() == track_id {
+            end = end.max(packet.ts() + packet.dur());
+        }
+    }
+    Some(to_duration(time_base.calc_time(end))).filter(|duration| !duration.is_zero())
+}
+
+fn to_duration(time: Time) -> Duration {
+    Duration::from_secs(time.seconds) + Duration::from_secs_f64(time.frac)
+}
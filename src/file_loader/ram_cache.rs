@@ -0,0 +1,172 @@
+// RAM cache
+// Copies small, often-played playlists into tmpfs so playing them never touches the SD card
+// Copies are held per station, and deleted once no station plays them
+
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::config::radio_config;
+use crate::file_loader::transcode::playable_path;
+
+/// A track's copy in the cache directory
+struct CachedCopy {
+    path: PathBuf,
+    length: u64,
+}
+
+/// Files copied into RAM, the stations holding them, and the space they take
+struct RamCache {
+    /// Original track path to its copy in the cache directory
+    copies: HashMap<PathBuf, CachedCopy>,
+
+    /// Tracks each station has in the cache, by station folder; a copy is
+    /// deleted once no station holds it
+    stations: HashMap<PathBuf, HashSet<PathBuf>>,
+
+    /// Bytes used in the cache directory
+    used: u64,
+}
+
+static RAM_CACHE: OnceLock<Mutex<RamCache>> = OnceLock::new();
+
+/// Returns the cache, deleting copies left in the directory by an earlier run
+///
+/// Only files named like copies are deleted, so a directory shared with
+/// anything else is left as it was.
+fn ram_cache() -> &'static Mutex<RamCache> {
+    RAM_CACHE.get_or_init(|| {
+        let directory = &radio_config().ram_cache.directory;
+        if let Err(e) = fs::create_dir_all(directory) {
+            eprintln!("Failed to create RAM cache {}: {}", directory.display(), e);
+        }
+        let stale = fs::read_dir(directory).into_iter().flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && is_copy_name(path));
+        for path in stale {
+            if let Err(e) = fs::remove_file(&path) {
+                eprintln!("Failed to remove stale RAM cache copy {}: {}", path.display(), e);
+            }
+        }
+        Mutex::new(RamCache { copies: HashMap::new(), stations: HashMap::new(), used: 0 })
+    })
+}
+
+impl RamCache {
+    /// Lets go of a station's tracks other than `keep`, deleting copies no
+    /// other station holds
+    fn release(&mut self, station_path: &Path, keep: &HashSet<PathBuf>) {
+        let Some(held) = self.stations.remove(station_path) else {return;};
+        let (kept, released): (HashSet<PathBuf>, HashSet<PathBuf>) = held.into_iter().partition(|track| keep.contains(track));
+        if !kept.is_empty() {
+            self.stations.insert(station_path.to_path_buf(), kept);
+        }
+        for track in released {
+            if self.stations.values().any(|tracks| tracks.contains(&track)) {
+                continue;
+            }
+            let Some(copy) = self.copies.remove(&track) else {continue;};
+            self.used = self.used.saturating_sub(copy.length);
+            if let Err(e) = fs::remove_file(&copy.path) {
+                eprintln!("Failed to remove {} from the RAM cache: {}", copy.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Copies a station's tracks into the RAM cache
+///
+/// Stations with `"ram_cache": true` in station.info call this whenever
+/// their playlist is loaded. A station is cached whole or not at all: if
+/// its tracks don't fit in what's left of `max_megabytes`, it's logged and
+/// played from disk as usual. Tracks already in RAM aren't copied twice,
+/// and tracks the station no longer plays are let go of (see
+/// `release_station`). Formats that are transcoded are cached as their
+/// transcoded copy.
+///
+/// # Arguments
+/// * `station_path` - Station folder, the copies are held for
+/// * `tracks` - Paths of the station's tracks
+pub fn cache_tracks<'a>(station_path: &Path, tracks: impl Iterator<Item = &'a Path>) {
+    let config = &radio_config().ram_cache;
+    let mut cache = ram_cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let tracks: HashSet<PathBuf> = tracks.map(Path::to_path_buf).collect();
+    cache.release(station_path, &tracks);
+
+    let uncached: Vec<(PathBuf, PathBuf, u64)> = tracks.iter()
+        .filter(|track| !cache.copies.contains_key(*track))
+        .filter_map(|track| {
+            let source = playable_path(track);
+            let length = fs::metadata(&source).ok()?.len();
+            Some((track.clone(), source, length))
+        })
+        .collect();
+
+    let needed: u64 = uncached.iter().map(|(_, _, length)| length).sum();
+    let budget = config.max_megabytes * 1024 * 1024;
+    if cache.used + needed > budget {
+        eprintln!(
+            "{} needs {} KiB of RAM cache but only {} KiB is free, playing it from disk",
+            station_path.display(), needed / 1024, budget.saturating_sub(cache.used) / 1024
+        );
+        cache.release(station_path, &HashSet::new());
+        return;
+    }
+
+    for (track, source, length) in uncached {
+        let copy = config.directory.join(copy_name(&track, &source, length));
+        match fs::copy(&source, &copy) {
+            Ok(copied) => {
+                cache.used += copied;
+                cache.copies.insert(track, CachedCopy { path: copy, length: copied });
+            },
+            Err(e) => eprintln!("Failed to copy {} into the RAM cache: {}", source.display(), e)
+        }
+    }
+    let held: HashSet<PathBuf> = tracks.into_iter().filter(|track| cache.copies.contains_key(track)).collect();
+    cache.stations.insert(station_path.to_path_buf(), held);
+}
+
+/// Lets go of everything a station has in the RAM cache
+///
+/// For a station reloaded without `ram_cache`; its copies are deleted
+/// unless another station holds them too.
+pub fn release_station(station_path: &Path) {
+    // Nothing is cached unless some station asked for it
+    let Some(cache) = RAM_CACHE.get() else {return;};
+    cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).release(station_path, &HashSet::new());
+}
+
+/// Returns the RAM copy of a track, if it has one
+pub fn resident_path(path: &Path) -> Option<PathBuf> {
+    // Nothing is cached unless some station asked for it
+    let cache = RAM_CACHE.get()?.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    cache.copies.get(path).map(|copy| copy.path.clone())
+}
+
+/// Names a copy after the track's path, length and date, keeping the extension
+fn copy_name(track: &Path, source: &Path, length: u64) -> String {
+    let modified = fs::metadata(source).and_then(|metadata| metadata.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut hasher = DefaultHasher::new();
+    track.hash(&mut hasher);
+    length.hash(&mut hasher);
+    modified.hash(&mut hasher);
+
+    match source.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{:016x}.{}", hasher.finish(), extension),
+        None => format!("{:016x}", hasher.finish())
+    }
+}
+
+/// Returns whether a file is named the way `copy_name` names copies
+fn is_copy_name(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.len() == 16 && stem.chars().all(|character| character.is_ascii_hexdigit()))
+}
@@ -6,7 +6,7 @@ use std::path::Path;
 /// Scans a playlist directory and returns metadata for all audio files
 /// 
 /// Used by File Loader thread during initialization to build station playlists
-pub fn scan_playlist_directory(path: &Path) {
+pub fn scan_playlist_directory(_path: &Path) {
     // TODO: Scan directory for MP3 files
     // TODO: Extract metadata (duration, title, modified time)
     // TODO: Return Track metadata
@@ -1,15 +1,30 @@
 // File Loader Thread
 // Loads and decodes audio files, sends them back to Station Manager
 
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::{Receiver, Sender, TryRecvError};
 use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::error::Error;
+use std::path::{Path, PathBuf};
 
-use crate::messages::{FileRequest,FileResponse};
+use rand::rng;
+use rand::seq::IndexedRandom;
+use rodio::source::from_iter;
+
+use crate::audio::AudioSource;
+use crate::audio::talk_over::talk_over;
+use crate::crash_report;
+use crate::file_loader::decoder::{classify, load_and_decode};
+use crate::file_loader::tts;
+use crate::file_loader::warmer::warm_track;
+use crate::messages::{FileRequest, FileResponse, Intro, LoadErrorKind};
+use crate::priority::{ThreadRole, prioritize_current_thread};
 
 /// Runs the file loader thread
 /// 
 /// Responsibilities:
-/// - Receives file load requests (FIFO queue)
+/// - Receives file load requests (FIFO queue, with warm starts ahead of
+///   everything else)
 /// - Loads audio files from disk
 /// - Decodes audio into rodio sources
 /// - Sends decoded audio back to Station Manager
@@ -17,21 +32,153 @@ pub fn run_file_loader(
     request_rx: Receiver<FileRequest>,
     response_tx: Sender<FileResponse>
 ) {
+    prioritize_current_thread(ThreadRole::FileLoader);
     let mut request_queue: VecDeque<FileRequest> = VecDeque::new();
+    let mut warm_queue: VecDeque<FileRequest> = VecDeque::new();
     
     loop {
         // Check for new requests
-        while let Ok(request) = request_rx.try_recv() {
-            request_queue.push_back(request);
+        loop {
+            match request_rx.try_recv() {
+                Ok(request @ FileRequest::WarmTrack { .. }) => warm_queue.push_back(request),
+                Ok(request) => request_queue.push_back(request),
+                Err(TryRecvError::Empty) => break,
+                // Station Manager is gone; finish what was already asked for
+                Err(TryRecvError::Disconnected) if request_queue.is_empty() && warm_queue.is_empty() => return,
+                Err(TryRecvError::Disconnected) => break
+            }
         }
         
-        // Process next request in FIFO order
-        if let Some(request) = request_queue.pop_front() {
-            // TODO: Load and decode file
-            // TODO: Send response
+        // Warm starts are quick and make a station audible, so they go first;
+        // everything else in FIFO order
+        if let Some(request) = warm_queue.pop_front().or_else(|| request_queue.pop_front())
+            && let Some(response) = handle_request(request)
+            && response_tx.send(response).is_err() {
+            // Station Manager is gone; nothing left to load for
+            return;
         }
         
         // Small sleep to avoid busy-waiting
         std::thread::sleep(std::time::Duration::from_millis(10));
     }
 }
+
+/// Loads the file for a single request
+/// 
+/// Decoder panics are caught and reported as a `LoadError` so one corrupt
+/// file can't take the loader thread down with it.
+/// 
+/// # Returns
+/// The response to send back, or None for requests not handled here yet
+fn handle_request(request: FileRequest) -> Option<FileResponse> {
+    match request {
+        FileRequest::LoadTrack { station_id, file_path, intro } => {
+            let retry_intro = intro.clone();
+            let decoded = panic::catch_unwind(AssertUnwindSafe(|| crash_report::recoverable(|| load_track(&file_path, intro))));
+
+            let response = match decoded {
+                Ok(Ok(audio_content)) => FileResponse::TrackLoaded { station_id, file_path, audio_content },
+                Ok(Err(error)) => FileResponse::LoadError {
+                    station_id,
+                    kind: classify(error.as_ref()),
+                    error_message: format!("{}: {}", file_path.display(), error),
+                    file_path,
+                    intro: retry_intro
+                },
+                // Injected panics land here too, so a panic alone isn't held against the file
+                Err(_) => FileResponse::LoadError {
+                    station_id,
+                    kind: LoadErrorKind::Inconclusive,
+                    error_message: format!("{}: decoder panicked", file_path.display()),
+                    file_path,
+                    intro: retry_intro
+                }
+            };
+            Some(response)
+        },
+        FileRequest::WarmTrack { station_id, file_path } => {
+            let warmed = panic::catch_unwind(AssertUnwindSafe(|| crash_report::recoverable(|| warm_track(&file_path))));
+            match warmed {
+                Ok(Ok((audio_content, length))) => Some(FileResponse::TrackWarmed { station_id, file_path, audio_content, length }),
+                // The full load reports whatever is wrong with the file
+                Ok(Err(e)) => {
+                    eprintln!("Failed to warm {}: {}", file_path.display(), e);
+                    None
+                },
+                Err(_) => {
+                    eprintln!("Failed to warm {}: decoder panicked", file_path.display());
+                    None
+                }
+            }
+        },
+        // TODO: Scan directory and return track metadata
+        FileRequest::ScanDirectory { .. } => None
+    }
+}
+
+/// Decodes a track with its DJ intro, if it has one, in front of it
+/// 
+/// A talk-over bed is mixed over the start of the track instead. An intro
+/// that fails to load is logged and the track plays without it.
+pub fn load_track(file_path: &Path, intro: Option<Intro>) -> Result<AudioSource, Box<dyn Error>> {
+    let track: AudioSource = Box::new(load_and_decode(file_path)?);
+    Ok(match intro {
+        Some(intro) => with_intro(track, file_path, intro),
+        None => track
+    })
+}
+
+/// Puts a DJ intro in front of a track, synthesizing it first if it's
+/// spoken text, or talks over the start of the track
+/// 
+/// A talk-over with no bed to use falls back to its usual intro.
+fn with_intro(track: AudioSource, file_path: &Path, intro: Intro) -> AudioSource {
+    let loaded = match intro {
+        Intro::Recorded(intro_path) => load_and_decode(&intro_path),
+        Intro::Spoken { text, language } => tts::synthesize(&text, &language).and_then(|speech_path| {
+            let decoded = load_and_decode(&speech_path);
+            // The open decoder keeps the data readable after the file is unlinked
+            std::fs::remove_file(&speech_path).ok();
+            decoded
+        }),
+        Intro::TalkOver { beds, duck, max_length, fallback } => {
+            if let Some(bed) = choose_bed(&beds, file_path) {
+                match load_and_decode(&bed) {
+                    Ok(bed_source) => return Box::new(talk_over(track, Box::new(bed_source), duck, max_length)),
+                    Err(e) => eprintln!("Skipping talk-over bed {}: {}", bed.display(), e)
+                }
+            }
+            return match fallback {
+                Some(fallback) => with_intro(track, file_path, *fallback),
+                None => track
+            };
+        }
+    };
+
+    match loaded {
+        Ok(intro_source) => Box::new(from_iter(vec![Box::new(intro_source) as AudioSource, track])),
+        Err(e) => {
+            eprintln!("Skipping DJ intro: {}", e);
+            track
+        }
+    }
+}
+
+/// Finds the bed to talk over a track with
+/// 
+/// # Returns
+/// `<track name>.mp3` in the beds folder if there is one, otherwise any
+/// file there, or None if the folder is empty or missing
+fn choose_bed(beds_path: &Path, file_path: &Path) -> Option<PathBuf> {
+    let own_bed = file_path.file_stem()
+        .map(|stem| beds_path.join(format!("{}.mp3", stem.to_string_lossy())))
+        .filter(|bed_path| bed_path.exists());
+    if own_bed.is_some() {
+        return own_bed;
+    }
+    let beds: Vec<PathBuf> = std::fs::read_dir(beds_path).ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')))
+        .collect();
+    beds.choose(&mut rng()).cloned()
+}
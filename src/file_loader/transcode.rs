@@ -0,0 +1,81 @@
+// Transcode cache
+// Converts formats rodio can't decode (or decodes slowly) to cached copies
+
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use crate::config::radio_config;
+
+/// Returns the file that should actually be decoded for a track
+/// 
+/// Files whose extension is listed under `transcode` in radio.info are
+/// converted once with the configured command and the cached copy is used
+/// from then on. Called when tracks are scanned, so the conversion happens
+/// as files are added, and again by the loader, which finds the cache warm.
+/// 
+/// # Returns
+/// - Path of the cached copy for transcoded formats
+/// - The original path for everything else, or if transcoding fails (logged)
+pub fn playable_path(path: &Path) -> PathBuf {
+    if !needs_transcode(path) {
+        return path.to_path_buf();
+    }
+
+    match cached_copy(path) {
+        Ok(cached_path) => cached_path,
+        Err(e) => {
+            eprintln!("Failed to transcode {}: {}", path.display(), e);
+            path.to_path_buf()
+        }
+    }
+}
+
+/// Checks the file extension against the configured list
+fn needs_transcode(path: &Path) -> bool {
+    let Some(extension) = path.extension() else {return false;};
+    radio_config().transcode.extensions.iter()
+        .any(|configured| extension.eq_ignore_ascii_case(configured.as_str()))
+}
+
+/// Returns the cached copy of a file, transcoding it if there isn't one yet
+fn cached_copy(path: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let transcode = &radio_config().transcode;
+    let metadata = std::fs::metadata(path)?;
+
+    // A replaced or edited file gets a new cache entry
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).hash(&mut hasher);
+    let file_name = format!("{:016x}.{}", hasher.finish(), transcode.format);
+    let cached_path = transcode.cache_dir.join(&file_name);
+
+    if cached_path.exists() {
+        return Ok(cached_path);
+    }
+
+    // Convert to a temporary name so a half-written file is never used
+    std::fs::create_dir_all(&transcode.cache_dir)?;
+    let partial_path = transcode.cache_dir.join(format!("partial_{}", file_name));
+    let (program, arguments) = transcode.command.split_first().ok_or("transcode command is empty")?;
+    let input_string = path.display().to_string();
+    let output_string = partial_path.display().to_string();
+
+    let status = Command::new(program)
+        .args(arguments.iter().map(|argument| {
+            argument.replace("{input}", &input_string).replace("{output}", &output_string)
+        }))
+        .status()?;
+
+    if !status.success() {
+        std::fs::remove_file(&partial_path).ok();
+        return Err(format!("{} exited with {}", program, status).into());
+    }
+    std::fs::rename(&partial_path, &cached_path)?;
+    println!("transcoded {} to {}", path.display(), cached_path.display());
+    Ok(cached_path)
+}
@@ -0,0 +1,43 @@
+// Text-to-speech synthesis
+// Renders DJ announcements to WAV files with an external TTS program
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::config::radio_config;
+
+/// Distinguishes temporary WAV files from successive announcements
+static ANNOUNCEMENT_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Synthesizes speech to a temporary WAV file
+/// 
+/// Runs the voice configured for the language in radio.info, or the
+/// `tts_command` (espeak-ng by default), substituting `{text}`, `{output}`
+/// and `{voice}` (the language code) in its arguments.
+/// 
+/// # Returns
+/// Path of the WAV file; the caller removes it once decoded
+pub fn synthesize(text: &str, language: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let tts_command = radio_config().locale.voice(language);
+    let (program, arguments) = tts_command.split_first().ok_or("tts_command is empty")?;
+
+    let counter = ANNOUNCEMENT_COUNTER.fetch_add(1, Ordering::Relaxed);
+    // Named for the process too, so radios running side by side don't share files
+    let output_path = std::env::temp_dir().join(format!("mokradio_tts_{}_{}.wav", std::process::id(), counter));
+    let output_string = output_path.display().to_string();
+
+    let status = Command::new(program)
+        .args(arguments.iter().map(|argument| {
+            argument.replace("{text}", text)
+                .replace("{output}", &output_string)
+                .replace("{voice}", language)
+        }))
+        .status()?;
+
+    if !status.success() {
+        return Err(format!("{} exited with {}", program, status).into());
+    }
+    Ok(output_path)
+}
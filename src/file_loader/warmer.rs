@@ -0,0 +1,45 @@
+//! Content Warmer - The first seconds of every station, ready at boot
+//!
+//! Right after boot every station's first track is queued for loading at
+//! once, and a station near the back of the queue would be static until
+//! its turn came. The Station Manager asks for a warm start of each first
+//! track as well, which the File Loader serves ahead of everything else:
+//! the opening `WARM_SECONDS` are decoded into memory and sent back
+//! straight away, so the station has audio wherever the dial lands. The
+//! full track follows through the normal queue and takes over from the
+//! buffer (see `audio::warm_start`).
+
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+
+use rodio::Source;
+use rodio::buffer::SamplesBuffer;
+
+use crate::audio::AudioSource;
+use crate::file_loader::decoder::load_and_decode;
+
+/// Seconds of each first track decoded ahead
+pub const WARM_SECONDS: u32 = 5;
+
+/// Decodes the opening seconds of a track into memory
+///
+/// # Returns
+/// The decoded opening and its length, or the error if the file can't be decoded
+pub fn warm_track(file_path: &Path) -> Result<(AudioSource, Duration), Box<dyn Error>> {
+    let decoder = load_and_decode(file_path)?;
+    let channels = decoder.channels();
+    let sample_rate = decoder.sample_rate();
+
+    let wanted = (sample_rate as usize * channels as usize) * WARM_SECONDS as usize;
+    let mut samples: Vec<f32> = decoder.take(wanted).collect();
+    // Whole frames only, so the rest of the track carries on in the right channel
+    samples.truncate(samples.len() - samples.len() % channels.max(1) as usize);
+    if samples.is_empty() {
+        return Err(format!("{} has no audio to warm", file_path.display()).into());
+    }
+
+    let samples_per_second = sample_rate.max(1) as usize * channels.max(1) as usize;
+    let length = Duration::from_secs_f64(samples.len() as f64 / samples_per_second as f64);
+    Ok((Box::new(SamplesBuffer::new(channels, sample_rate, samples)), length))
+}
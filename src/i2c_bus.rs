@@ -0,0 +1,100 @@
+//! I2C Bus Manager
+//!
+//! Builds with an I2C tuner, OLED and RTC share a single bus. Rather than
+//! each module opening `/dev/i2c-1` on its own, they all go through one
+//! shared `I2cBus` that owns the device, serializes transactions between
+//! threads, and reopens the bus after an error so a glitch on one device
+//! doesn't wedge the others.
+
+use std::sync::{Arc, Mutex, OnceLock};
+use rppal::i2c::{Error, I2c};
+
+use crate::fault_injection::{self, Fault};
+
+/// Shared bus handle, created the first time a module asks for it
+static SHARED_BUS: OnceLock<I2cBus> = OnceLock::new();
+
+/// Number of times a failed transaction is retried on a reopened bus
+const RETRIES: usize = 1;
+
+/// State guarded by the bus lock
+struct BusState {
+    /// Open bus, or None if it needs to be (re)opened
+    i2c: Option<I2c>,
+
+    /// Consecutive failed transactions, for diagnostics
+    consecutive_failures: u32,
+}
+
+/// Cloneable handle to the shared I2C bus
+#[derive(Clone)]
+pub struct I2cBus {
+    state: Arc<Mutex<BusState>>,
+}
+
+impl I2cBus {
+    /// Returns a handle to the radio's shared I2C bus
+    pub fn shared() -> I2cBus {
+        SHARED_BUS.get_or_init(|| I2cBus {
+            state: Arc::new(Mutex::new(BusState { i2c: None, consecutive_failures: 0 }))
+        }).clone()
+    }
+
+    /// Runs a transaction against one device while holding the bus
+    ///
+    /// Selects the device address before running `transaction`, so callers
+    /// never see another module's address. If the transaction fails, the bus
+    /// is closed and reopened and the transaction retried once.
+    ///
+    /// # Arguments
+    /// * `address` - 7-bit I2C address of the device
+    /// * `transaction` - Reads/writes to perform with the device selected
+    ///
+    /// # Returns
+    /// Result of the transaction, or the last bus error if every attempt failed
+    pub fn transaction<T, F>(&self, address: u16, mut transaction: F) -> Result<T, Error>
+    where
+        F: FnMut(&mut I2c) -> Result<T, Error>
+    {
+        // A panic in another thread's transaction leaves the bus usable
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut last_error: Option<Error> = None;
+
+        for _ in 0..=RETRIES {
+            let result = Self::open(&mut state).and_then(|i2c| {
+                if fault_injection::should_inject(Fault::DeviceDisconnect) {
+                    return Err(Error::Io(std::io::Error::other("injected device disconnect")));
+                }
+                i2c.set_slave_address(address)?;
+                transaction(i2c)
+            });
+
+            match result {
+                Ok(value) => {
+                    state.consecutive_failures = 0;
+                    return Ok(value);
+                },
+                Err(e) => {
+                    // Drop the handle so the next attempt starts from a fresh bus
+                    state.consecutive_failures += 1;
+                    eprintln!(
+                        "I2C error at 0x{:02x} ({} in a row): {}",
+                        address, state.consecutive_failures, e
+                    );
+                    state.i2c = None;
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap())
+    }
+
+    /// Opens the bus if it isn't already open
+    fn open(state: &mut BusState) -> Result<&mut I2c, Error> {
+        if state.i2c.is_none() {
+            state.i2c = Some(I2c::new()?);
+        }
+        Ok(state.i2c.as_mut().unwrap())
+    }
+}
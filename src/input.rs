@@ -1,5 +1,15 @@
 // Input module - handles ADC and GPIO input reading
 pub mod thread;
 pub mod events;
+pub mod device;
+pub mod recording;
+pub mod radio_buttons;
 pub mod band_switch;
+pub mod buttons;
+pub mod dial;
+pub mod encoder;
+pub mod keyboard;
+pub mod mcp3008;
+pub mod seek_buttons;
+pub mod touch;
 pub mod tuner;
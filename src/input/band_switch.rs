@@ -1,28 +1,96 @@
+// Band switch
+// A two-position AM/FM switch on one pin, or a rotary switch across several
+
+use std::time::{Duration, Instant};
+
 use rppal::gpio::{Gpio, InputPin};
+use crate::config::hardware::{BandSwitchEncoding, HardwareConfig};
+use crate::input::device::InputDevice;
+use crate::messages::InputEvent;
 use crate::radio::station::content::Band;
 
+/// How long a new switch position must hold before the band changes
+const DEBOUNCE: Duration = Duration::from_millis(30);
+
 pub struct BandSwitchPinHandler {
-    pin: InputPin,
-    current_band: Band
+    pins: Vec<InputPin>,
+    encoding: Option<BandSwitchEncoding>,
+    current_band: Band,
+
+    /// Band the switch has read since `pending_since`, not yet accepted
+    pending_band: Band,
+    pending_since: Instant
 }
 
 impl BandSwitchPinHandler {
-    pub fn new(gpio_pins: Gpio, pin_number: u8) -> BandSwitchPinHandler {
-        let pin = gpio_pins.get(pin_number).ok().unwrap().into_input();
-        let current_band = if pin.is_high() {Band::AM} else {Band::FM};
-        BandSwitchPinHandler { pin, current_band }
+    /// Claims the band switch pins mapped in radio.info
+    ///
+    /// With no `band_switch_pins`, the single `band_switch_pin` picks AM
+    /// (high) or FM (low). A rotary switch's pins are wired to ground with
+    /// the internal pull-ups enabled, so the closed contact reads low.
+    pub fn new(gpio_pins: Gpio, hardware: &HardwareConfig) -> BandSwitchPinHandler {
+        let (pins, encoding) = if hardware.band_switch_pins.is_empty() {
+            let pin = gpio_pins.get(hardware.band_switch_pin).ok().unwrap().into_input();
+            (vec![pin], None)
+        } else {
+            let pins = hardware.band_switch_pins.iter()
+                .map(|pin_number| gpio_pins.get(*pin_number).ok().unwrap().into_input_pullup())
+                .collect();
+            (pins, Some(hardware.band_switch_encoding))
+        };
+
+        let mut band_switch = BandSwitchPinHandler {
+            pins,
+            encoding,
+            current_band: Band::AM,
+            pending_band: Band::AM,
+            pending_since: Instant::now()
+        };
+        if let Some(band) = band_switch.read_position() {
+            band_switch.current_band = band;
+            band_switch.pending_band = band;
+        }
+        band_switch
     }
     pub fn initial_read(&self) -> Band {
         self.current_band
     }
+    /// Returns the new band once the switch has settled on a different one
     pub fn read_change(&mut self) -> Option<Band> {
-        let band = if self.pin.is_high() {
-            Band::AM}
-            else {Band::FM};
-        if band != self.current_band {
+        // Between detents a rotary switch may read nothing; keep the last band
+        let band = self.read_position()?;
+        if band != self.pending_band {
+            self.pending_band = band;
+            self.pending_since = Instant::now();
+            return None;
+        }
+        if band != self.current_band && self.pending_since.elapsed() >= DEBOUNCE {
             self.current_band = band;
             Some(band)
         }
         else {None}
     }
-}
\ No newline at end of file
+    /// Decodes the switch position into a configured band
+    fn read_position(&self) -> Option<Band> {
+        let position = match self.encoding {
+            None => return Some(if self.pins[0].is_high() {Band::AM} else {Band::FM}),
+            Some(BandSwitchEncoding::OneHot) => self.pins.iter().position(|pin| pin.is_low())?,
+            Some(BandSwitchEncoding::Binary) => self.pins.iter()
+                .enumerate()
+                .filter(|(_, pin)| pin.is_low())
+                .map(|(bit, _)| 1 << bit)
+                .sum()
+        };
+        Band::all().nth(position)
+    }
+}
+
+impl InputDevice for BandSwitchPinHandler {
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        vec![InputEvent::BandSwitched { new_band: self.initial_read() }]
+    }
+
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.read_change().map(|new_band| InputEvent::BandSwitched { new_band })
+    }
+}
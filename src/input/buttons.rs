@@ -0,0 +1,87 @@
+//! Push Buttons - Debounced GPIO buttons from the hardware config
+//!
+//! Shared by the seek buttons and the radio-wide pause and mute buttons.
+//! Buttons are wired to ground with the internal pull-up enabled, so a
+//! press reads low. A contact bounces for a few milliseconds as it opens
+//! and closes, so a reading only counts once it has held for `DEBOUNCE`;
+//! each press is reported once, when it settles.
+
+use std::time::{Duration, Instant};
+
+use rppal::gpio::{Gpio, InputPin};
+
+use crate::config::radio_config;
+
+/// How long a reading must hold before it counts
+const DEBOUNCE: Duration = Duration::from_millis(20);
+
+/// A button, what it stands for, and its debounced state
+struct Button<T> {
+    pin: InputPin,
+    value: T,
+
+    /// Settled state
+    pressed: bool,
+
+    /// Last raw reading, and when it changed
+    reading: bool,
+    changed_at: Instant,
+}
+
+/// A set of named buttons, each standing for a value
+pub struct PushButtons<T> {
+    buttons: Vec<Button<T>>,
+}
+
+impl<T: Copy> PushButtons<T> {
+    /// Claims whichever of the named buttons are mapped in radio.info
+    ///
+    /// # Arguments
+    /// * `gpio_pins` - GPIO to claim the pins from
+    /// * `named` - Names in the `buttons` config and the value each reports
+    ///
+    /// Buttons that aren't mapped are left out; ones whose pin can't be
+    /// claimed are logged and left out.
+    pub fn new(gpio_pins: &Gpio, named: impl IntoIterator<Item = (&'static str, T)>) -> PushButtons<T> {
+        let buttons = named.into_iter()
+            .filter_map(|(name, value)| {
+                let pin_number = *radio_config().hardware.buttons.get(name)?;
+                match gpio_pins.get(pin_number) {
+                    Ok(pin) => Some(Button {
+                        pin: pin.into_input_pullup(),
+                        value,
+                        pressed: false,
+                        reading: false,
+                        changed_at: Instant::now(),
+                    }),
+                    Err(e) => {
+                        eprintln!("Failed to claim {} button on pin {}: {}", name, pin_number, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        PushButtons { buttons }
+    }
+
+    /// Returns the value of a button whose press settled since the last read
+    pub fn read_press(&mut self) -> Option<T> {
+        let now = Instant::now();
+        let mut pressed = None;
+        for button in self.buttons.iter_mut() {
+            let reading = button.pin.is_low();
+            if reading != button.reading {
+                button.reading = reading;
+                button.changed_at = now;
+                continue;
+            }
+            if reading != button.pressed && now.duration_since(button.changed_at) >= DEBOUNCE {
+                button.pressed = reading;
+                if reading {
+                    pressed = Some(button.value);
+                }
+            }
+        }
+        pressed
+    }
+}
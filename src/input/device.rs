@@ -0,0 +1,32 @@
+//! Input Devices - Controls the input thread reads
+//!
+//! Every source of control events (the tuning pot, a rotary encoder or
+//! touch pad, the band switch, push buttons, the keyboard) is an
+//! `InputDevice`. The input thread polls whichever devices are set up in
+//! turn and sends on what they report, so several can be used at once:
+//! a bench radio can tune from the keyboard while its band switch is
+//! wired, and a remote control or network input is one more device in
+//! the list.
+//!
+//! Devices are polled in a tight loop, so `poll` must not block; a device
+//! that waits on something slow (stdin, a socket) reads it on a thread of
+//! its own and hands over what it got.
+
+use crate::messages::InputEvent;
+
+/// A control that reports changes as input events
+pub trait InputDevice {
+    /// Events that tell the Station Manager where the control is at startup
+    ///
+    /// Called once, before the first `poll`. Controls with no position of
+    /// their own (buttons) have none.
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        Vec::new()
+    }
+
+    /// Reads the control once
+    ///
+    /// # Returns
+    /// An event if the control changed since the last poll
+    fn poll(&mut self) -> Option<InputEvent>;
+}
@@ -0,0 +1,91 @@
+//! Virtual Dial Module
+//!
+//! Rotary encoders only report relative steps, so encoder-based builds keep
+//! a virtual dial position here. The position is scaled onto the same
+//! `0..ENCODER_HALF` range the Station Manager expects from an ADC build, so
+//! the dial mapping and fade logic doesn't care where tuning came from.
+
+use std::time::{Duration, Instant};
+
+use crate::config::{DialConfig, DialEdge};
+use crate::constants;
+
+/// Virtual dial position driven by relative encoder steps
+pub struct VirtualDial {
+    /// Current position in encoder steps (0..range)
+    position: usize,
+
+    /// Number of steps from one end of the dial to the other
+    range: usize,
+
+    /// Behavior at the ends of the dial
+    edge: DialEdge,
+
+    /// Step multiplier when spinning fast
+    acceleration: f32,
+
+    /// Steps closer together than this are accelerated
+    acceleration_window: Duration,
+
+    /// When the last step arrived, for acceleration
+    last_step: Instant,
+}
+
+impl VirtualDial {
+    /// Creates a virtual dial from the `dial` section of radio.info
+    ///
+    /// # Arguments
+    /// * `dial_config` - Range, edge behavior and acceleration settings
+    /// * `initial_position` - Starting position in dial units (0..ENCODER_HALF)
+    pub fn new(dial_config: &DialConfig, initial_position: usize) -> Self {
+        let range = dial_config.range.max(1);
+        let position = initial_position.min(constants::ENCODER_HALF - 1) * range / constants::ENCODER_HALF;
+
+        VirtualDial {
+            position,
+            range,
+            edge: dial_config.edge,
+            acceleration: dial_config.acceleration.max(1.0),
+            acceleration_window: Duration::from_millis(dial_config.acceleration_window_ms),
+            last_step: Instant::now(),
+        }
+    }
+
+    /// Returns the current position scaled to dial units (0..ENCODER_HALF)
+    pub fn dial_position(&self) -> usize {
+        self.position * constants::ENCODER_HALF / self.range
+    }
+
+    /// Applies a number of encoder steps (negative turns down the dial)
+    ///
+    /// # Returns
+    /// - `Some(usize)` - New dial position in dial units if the dial moved
+    /// - `None` - Dial is pinned against an end stop or no steps were given
+    pub fn turn(&mut self, steps: i32) -> Option<usize> {
+        if steps == 0 {
+            return None;
+        }
+
+        // Spinning fast covers more of the dial per detent
+        let now = Instant::now();
+        let steps = if now.duration_since(self.last_step) < self.acceleration_window {
+            (steps as f32 * self.acceleration).round() as isize
+        } else {
+            steps as isize
+        };
+        self.last_step = now;
+
+        let range = self.range as isize;
+        let target = self.position as isize + steps;
+        let new_position = match self.edge {
+            DialEdge::EndStop => target.clamp(0, range - 1),
+            DialEdge::Wrap => target.rem_euclid(range),
+        } as usize;
+
+        if new_position == self.position {
+            return None;
+        }
+        self.position = new_position;
+        Some(self.dial_position())
+    }
+}
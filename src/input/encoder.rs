@@ -0,0 +1,139 @@
+//! Rotary Encoder Tuning
+//!
+//! Tunes with a quadrature rotary encoder instead of a potentiometer. The
+//! encoder's A and B pins raise GPIO interrupts on every edge, which are
+//! decoded into steps as they happen, so fast spins aren't missed between
+//! reads of the input loop. The loop collects the steps and turns a
+//! `VirtualDial` by a step per detent, so the Station Manager gets the same
+//! `DialMoved` events as from the ADC.
+//!
+//! Both pins are wired to ground through the encoder's common pin and use
+//! the internal pull-ups.
+//!
+//! # JSON Format
+//! ```json
+//! "tuning_input": "Encoder",
+//! "hardware": {
+//!     "rotary_encoder": { "pin_a": 5, "pin_b": 6, "steps_per_detent": 4 }
+//! }
+//! ```
+
+use std::sync::{Arc, Mutex};
+
+use rppal::gpio::{Event, Gpio, InputPin, Trigger};
+
+use crate::config::hardware::RotaryEncoderConfig;
+use crate::input::device::InputDevice;
+use crate::input::dial::VirtualDial;
+use crate::messages::InputEvent;
+
+/// Step for each change of the A/B state, indexed by (old state << 2 | new state)
+///
+/// Changes that skip a state (both pins at once) are noise and count as 0.
+const TRANSITIONS: [i32; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+/// Pin levels and steps decoded by the interrupt handlers
+#[derive(Default)]
+struct Quadrature {
+    /// Pin A level in bit 1, pin B in bit 0
+    state: u8,
+
+    /// Steps decoded since the input loop last collected them
+    steps: i32,
+}
+
+impl Quadrature {
+    /// Records a new level on one pin and counts the step it makes
+    fn edge(&mut self, bit: u8, high: bool) {
+        let state = if high {self.state | bit} else {self.state & !bit};
+        self.steps += TRANSITIONS[((self.state << 2) | state) as usize];
+        self.state = state;
+    }
+}
+
+/// Quadrature encoder driving a virtual dial
+pub struct RotaryEncoder {
+    /// Kept so their interrupts stay registered
+    _pins: [InputPin; 2],
+    quadrature: Arc<Mutex<Quadrature>>,
+    dial: VirtualDial,
+    steps_per_detent: i32,
+
+    /// Steps not yet worth a whole detent
+    remainder: i32,
+}
+
+impl RotaryEncoder {
+    /// Claims the encoder pins and starts decoding
+    ///
+    /// # Arguments
+    /// * `gpio_pins` - GPIO handle
+    /// * `config` - The `rotary_encoder` section of the hardware config
+    /// * `dial` - Virtual dial the encoder turns
+    ///
+    /// # Returns
+    /// The encoder, or None (logged) if its pins can't be claimed
+    pub fn new(gpio_pins: &Gpio, config: &RotaryEncoderConfig, dial: VirtualDial) -> Option<RotaryEncoder> {
+        let quadrature = Arc::new(Mutex::new(Quadrature::default()));
+        let pin_a = claim(gpio_pins, config.pin_a, 0b10, &quadrature)?;
+        let pin_b = claim(gpio_pins, config.pin_b, 0b01, &quadrature)?;
+
+        Some(RotaryEncoder {
+            _pins: [pin_a, pin_b],
+            quadrature,
+            dial,
+            steps_per_detent: config.steps_per_detent.max(1),
+            remainder: 0,
+        })
+    }
+
+    /// Returns the dial position in dial units (0..ENCODER_HALF)
+    pub fn dial_position(&self) -> usize {
+        self.dial.dial_position()
+    }
+
+    /// Turns the dial by the detents clicked since the last read
+    ///
+    /// # Returns
+    /// - `Some(usize)` - New dial position in dial units if the dial moved
+    /// - `None` - No whole detent, or the dial is against an end stop
+    pub fn read_change(&mut self) -> Option<usize> {
+        let steps = std::mem::take(&mut self.quadrature.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).steps);
+        let steps = steps + self.remainder;
+        self.remainder = steps % self.steps_per_detent;
+        self.dial.turn(steps / self.steps_per_detent)
+    }
+}
+
+impl InputDevice for RotaryEncoder {
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        vec![InputEvent::DialMoved { new_dial_position: self.dial_position() }]
+    }
+
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.read_change().map(|new_dial_position| InputEvent::DialMoved { new_dial_position })
+    }
+}
+
+/// Claims an encoder pin and decodes its edges as they happen
+fn claim(gpio_pins: &Gpio, pin_number: u8, bit: u8, quadrature: &Arc<Mutex<Quadrature>>) -> Option<InputPin> {
+    let mut pin = match gpio_pins.get(pin_number) {
+        Ok(pin) => pin.into_input_pullup(),
+        Err(e) => {
+            eprintln!("Failed to claim rotary encoder pin {}: {}", pin_number, e);
+            return None;
+        }
+    };
+    quadrature.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).edge(bit, pin.is_high());
+
+    let quadrature = quadrature.clone();
+    let interrupt = pin.set_async_interrupt(Trigger::Both, None, move |event: Event| {
+        let high = event.trigger == Trigger::RisingEdge;
+        quadrature.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).edge(bit, high);
+    });
+    if let Err(e) = interrupt {
+        eprintln!("Failed to watch rotary encoder pin {}: {}", pin_number, e);
+        return None;
+    }
+    Some(pin)
+}
@@ -0,0 +1,134 @@
+//! Keyboard Input - Tuning from a terminal for development and demos
+//!
+//! Stands in for the knobs and switches when there's no ADC or GPIO to
+//! read: run with `--no-hardware`, or set `"tuning_input": "Keyboard"` in
+//! radio.info, and keystrokes on stdin become the same `InputEvent`s the
+//! hardware sends. With `"tuning_input": "Keyboard"` on a wired radio the
+//! keys tune while the band switch and buttons keep working.
+//!
+//! | Key              | Control                        |
+//! |------------------|--------------------------------|
+//! | ← / ↓            | Turn the dial down             |
+//! | → / ↑            | Turn the dial up               |
+//! | `b`              | Switch to the next band        |
+//! | `,` / `.`        | Seek back / forward 10 seconds |
+//! | `p`              | Pause button                   |
+//! | `m`              | Mute button                    |
+//!
+//! The dial is a `VirtualDial`, so `dial` in radio.info sets its range and
+//! holding an arrow key down accelerates like spinning an encoder. The
+//! terminal is put into non-canonical mode without echo with `stty`, and
+//! set back when stdin closes; a radio killed with Ctrl-C leaves it for
+//! `stty sane` to fix.
+
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+
+use crate::config::radio_config;
+use crate::input::device::InputDevice;
+use crate::input::dial::VirtualDial;
+use crate::messages::{InputEvent, RadioButton};
+use crate::radio::station::content::Band;
+use crate::state;
+
+/// Seconds a seek key jumps
+const SEEK_SECONDS: f64 = 10.0;
+
+/// Escape sequence prefix sent by the arrow keys
+const ESCAPE: u8 = 0x1b;
+
+/// Keystrokes from stdin, read on a thread of their own
+pub struct KeyboardInput {
+    /// Dial position when reading started
+    initial_dial_position: usize,
+
+    keystrokes: Receiver<InputEvent>,
+}
+
+impl KeyboardInput {
+    /// Starts reading keystrokes from stdin
+    pub fn new() -> KeyboardInput {
+        let dial = VirtualDial::new(&radio_config().dial, state::starting_dial_position());
+        let initial_dial_position = dial.dial_position();
+        let (keystroke_sender, keystrokes) = channel();
+        thread::spawn(move || read_keys(dial, keystroke_sender));
+        KeyboardInput { initial_dial_position, keystrokes }
+    }
+}
+
+impl Default for KeyboardInput {
+    fn default() -> Self {
+        KeyboardInput::new()
+    }
+}
+
+impl InputDevice for KeyboardInput {
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        vec![InputEvent::DialMoved { new_dial_position: self.initial_dial_position }]
+    }
+
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.keystrokes.try_recv().ok()
+    }
+}
+
+/// Turns keystrokes into input events until stdin closes or nobody is listening
+fn read_keys(mut dial: VirtualDial, keystroke_sender: Sender<InputEvent>) {
+    let bands: Vec<Band> = Band::all().collect();
+    let mut band_index = state::starting_band().0;
+
+    let saved_terminal = enter_raw_mode();
+    println!("Keyboard tuning: arrows turn the dial, b switches band, , and . seek, p pauses, m mutes");
+
+    let mut keys = io::stdin().lock().bytes().map_while(Result::ok);
+    while let Some(key) = keys.next() {
+        let input_event = match key {
+            ESCAPE => match (keys.next(), keys.next()) {
+                (Some(b'['), Some(b'A' | b'C')) => dial.turn(1).map(|new_dial_position| InputEvent::DialMoved { new_dial_position }),
+                (Some(b'['), Some(b'B' | b'D')) => dial.turn(-1).map(|new_dial_position| InputEvent::DialMoved { new_dial_position }),
+                _ => None
+            },
+            b'b' | b'B' if !bands.is_empty() => {
+                band_index = (band_index + 1) % bands.len();
+                Some(InputEvent::BandSwitched { new_band: bands[band_index] })
+            },
+            b',' => Some(InputEvent::Seek { offset_seconds: -SEEK_SECONDS }),
+            b'.' => Some(InputEvent::Seek { offset_seconds: SEEK_SECONDS }),
+            b'p' | b'P' => Some(InputEvent::ButtonPressed { button: RadioButton::Pause }),
+            b'm' | b'M' => Some(InputEvent::ButtonPressed { button: RadioButton::Mute }),
+            _ => None
+        };
+        let Some(input_event) = input_event else {continue;};
+        if keystroke_sender.send(input_event).is_err() {
+            break;
+        }
+    }
+
+    if let Some(saved_terminal) = saved_terminal {
+        stty(&[saved_terminal.as_str()]);
+    }
+}
+
+/// Switches the terminal to single keystrokes without echo
+///
+/// # Returns
+/// The terminal's previous settings to restore, or None if stdin isn't a
+/// terminal (keys piped in from a file work as they are)
+fn enter_raw_mode() -> Option<String> {
+    let saved = stty(&["-g"])?;
+    stty(&["-icanon", "-echo", "min", "1"])?;
+    Some(saved.trim().to_string())
+}
+
+/// Runs `stty` on the terminal behind stdin
+fn stty(arguments: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(arguments)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,142 @@
+//! MCP3008 Tuning ADC
+//!
+//! Reads the tuning potentiometer through an MCP3008 on SPI0, for builds
+//! without the I2C tuner. The pot's wiper goes to one of the eight input
+//! channels, and each 10-bit reading (0-1023) is scaled onto the dial
+//! range the Station Manager expects. Readings are taken at most
+//! `sample_rate_hz` times a second, and changes of a single count are
+//! ignored so a pot resting between two values doesn't jitter the dial.
+//!
+//! The chip select is the `tuner_adc` entry of `spi_chip_selects`.
+//!
+//! # JSON Format
+//! ```json
+//! "tuning_input": "Adc",
+//! "hardware": {
+//!     "spi_chip_selects": { "tuner_adc": 0 },
+//!     "tuner_adc": { "channel": 0, "sample_rate_hz": 200 }
+//! }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use rppal::spi::{Bus, Mode, SlaveSelect, Spi};
+
+use crate::config::hardware::TunerAdcConfig;
+use crate::constants;
+use crate::fault_injection::{self, Fault};
+use crate::input::device::InputDevice;
+use crate::messages::InputEvent;
+
+/// Full scale of a 10-bit reading
+const ADC_STEPS: usize = 1024;
+
+/// Changes this small are noise, not the knob turning
+const DEADBAND: usize = 1;
+
+/// Potentiometer on an MCP3008 channel
+pub struct Mcp3008 {
+    spi: Spi,
+    channel: u8,
+    sample_interval: Duration,
+    last_sample: Option<Instant>,
+
+    /// Last reading that moved the dial
+    value: Option<u16>,
+}
+
+impl Mcp3008 {
+    /// Opens the ADC on SPI0
+    ///
+    /// # Arguments
+    /// * `chip_select` - SPI0 chip-select channel (0 or 1)
+    /// * `config` - The `tuner_adc` section of the hardware config
+    ///
+    /// # Returns
+    /// The ADC, or None (logged) if SPI can't be opened
+    pub fn new(chip_select: u8, config: &TunerAdcConfig) -> Option<Mcp3008> {
+        let slave_select = if chip_select == 1 {SlaveSelect::Ss1} else {SlaveSelect::Ss0};
+        match Spi::new(Bus::Spi0, slave_select, config.clock_hz, Mode::Mode0) {
+            Ok(spi) => Some(Mcp3008 {
+                spi,
+                channel: config.channel.min(7),
+                sample_interval: Duration::from_secs_f64(1.0 / config.sample_rate_hz.max(1) as f64),
+                last_sample: None,
+                value: None,
+            }),
+            Err(e) => {
+                eprintln!("Failed to open the tuning ADC on SPI0 CE{}: {}", chip_select, e);
+                None
+            }
+        }
+    }
+
+    /// Reads the channel once
+    ///
+    /// # Returns
+    /// The 10-bit reading (0-1023), or the SPI error (or an injected `AdcReadError`)
+    pub fn sample(&mut self) -> rppal::spi::Result<u16> {
+        if fault_injection::should_inject(Fault::AdcReadError) {
+            return Err(rppal::spi::Error::Io(std::io::Error::other("injected tuning ADC read error")));
+        }
+        // Start bit, then single-ended mode and the channel, then 10 bits clocked back
+        let write_buffer = [0x01, (0x08 | self.channel) << 4, 0x00];
+        let mut read_buffer = [0u8; 3];
+        self.spi.transfer(&mut read_buffer, &write_buffer)?;
+        Ok((((read_buffer[1] & 0x03) as u16) << 8) | read_buffer[2] as u16)
+    }
+
+    /// Returns the dial position at startup, in dial units (0..ENCODER_HALF)
+    pub fn initial_read(&mut self) -> usize {
+        self.last_sample = Some(Instant::now());
+        match self.sample() {
+            Ok(value) => {
+                self.value = Some(value);
+                dial_position(value)
+            },
+            Err(e) => {
+                eprintln!("Tuning ADC error: {}", e);
+                constants::ENCODER_HALF / 2
+            }
+        }
+    }
+
+    /// Samples the pot if a sample is due
+    ///
+    /// # Returns
+    /// - `Some(usize)` - New dial position in dial units if the knob moved
+    /// - `None` - Not due yet, no real change, or the read failed (logged)
+    pub fn read_change(&mut self) -> Option<usize> {
+        if self.last_sample.is_some_and(|last_sample| last_sample.elapsed() < self.sample_interval) {
+            return None;
+        }
+        self.last_sample = Some(Instant::now());
+        let value = match self.sample() {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("Tuning ADC error: {}", e);
+                return None;
+            }
+        };
+        if self.value.is_some_and(|last| (value as usize).abs_diff(last as usize) <= DEADBAND) {
+            return None;
+        }
+        self.value = Some(value);
+        Some(dial_position(value))
+    }
+}
+
+impl InputDevice for Mcp3008 {
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        vec![InputEvent::DialMoved { new_dial_position: self.initial_read() }]
+    }
+
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.read_change().map(|new_dial_position| InputEvent::DialMoved { new_dial_position })
+    }
+}
+
+/// Scales a 10-bit reading onto the dial
+fn dial_position(value: u16) -> usize {
+    value as usize * constants::ENCODER_HALF / ADC_STEPS
+}
@@ -0,0 +1,32 @@
+// Radio buttons
+// Optional "pause"/"mute" push buttons from the hardware config
+
+use rppal::gpio::Gpio;
+use crate::input::buttons::PushButtons;
+use crate::input::device::InputDevice;
+use crate::messages::{InputEvent, RadioButton};
+
+pub struct RadioButtons {
+    buttons: PushButtons<RadioButton>
+}
+
+impl RadioButtons {
+    /// Claims whichever of the pause and mute buttons are mapped in radio.info
+    ///
+    /// Wired and debounced like the seek buttons (see `input::buttons`).
+    pub fn new(gpio_pins: &Gpio) -> RadioButtons {
+        let buttons = PushButtons::new(gpio_pins, [("pause", RadioButton::Pause), ("mute", RadioButton::Mute)]);
+        RadioButtons { buttons }
+    }
+
+    /// Returns a button pressed since the last read
+    pub fn read_press(&mut self) -> Option<RadioButton> {
+        self.buttons.read_press()
+    }
+}
+
+impl InputDevice for RadioButtons {
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.read_press().map(|button| InputEvent::ButtonPressed { button })
+    }
+}
@@ -0,0 +1,130 @@
+//! Input Recording - Capture and replay of the control stream
+//!
+//! Tuning bugs ("it crackles when I sweep fast past FM 7") depend on
+//! exactly how the knobs were turned. Run the radio with
+//! `--record-input FILE` and every `InputEvent` the Station Manager gets
+//! is written to FILE with the time it arrived; run it again (on the
+//! radio, or headless with the null backend) with `--replay-input FILE`
+//! and the same events are sent with the same timing in place of the
+//! real controls.
+//!
+//! Recordings are plain text, one event per line: milliseconds since
+//! recording started, the event and its value, separated by tabs (spaces below).
+//!
+//! ```text
+//! 0 BandSwitched 1
+//! 0 DialMoved 812
+//! 1460 DialMoved 830
+//! 5210 Seek -10
+//! 7800 ButtonPressed Mute
+//! ```
+
+use std::fs::{File, read_to_string};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::messages::{InputEvent, RadioButton};
+use crate::radio::station::content::Band;
+
+/// Passes input events through, writing each to a recording as it goes
+///
+/// # Arguments
+/// * `input_events` - Events from the input thread
+/// * `recording_path` - File to write, replacing any already there
+///
+/// # Returns
+/// A receiver that gets every event, to hand to the Station Manager
+///
+/// # Errors
+/// Returns the error if the recording can't be created
+pub fn record_inputs(input_events: Receiver<InputEvent>, recording_path: &Path) -> io::Result<Receiver<InputEvent>> {
+    let mut recording = BufWriter::new(File::create(recording_path)?);
+    let recording_path = recording_path.to_path_buf();
+    let (forward, forwarded) = channel();
+
+    thread::spawn(move || {
+        let started = Instant::now();
+        for input_event in input_events {
+            let line = format!("{}\t{}\n", started.elapsed().as_millis(), encode(&input_event));
+            // Flushed every event, so a crash doesn't lose the moments before it
+            if let Err(e) = recording.write_all(line.as_bytes()).and_then(|_| recording.flush()) {
+                eprintln!("Failed to record input to {}: {}", recording_path.display(), e);
+            }
+            if forward.send(input_event).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(forwarded)
+}
+
+/// Sends a recording's events on their original schedule
+///
+/// Unreadable lines are logged and skipped.
+///
+/// # Arguments
+/// * `recording_path` - Recording made with `record_inputs`
+/// * `input_sender` - Channel to the Station Manager, in place of the input thread
+///
+/// # Returns
+/// Handle to the replay thread, which ends after the last event
+///
+/// # Errors
+/// Returns the error if the recording can't be read
+pub fn replay_inputs(recording_path: &Path, input_sender: Sender<InputEvent>) -> io::Result<JoinHandle<()>> {
+    let recording = read_to_string(recording_path)?;
+    let events: Vec<(Duration, InputEvent)> = recording.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(number, line)| {
+            let decoded = decode(line);
+            if decoded.is_none() {
+                eprintln!("Skipping unreadable line {} of {}: {}", number + 1, recording_path.display(), line);
+            }
+            decoded
+        })
+        .collect();
+    println!("replaying {} input events from {}", events.len(), recording_path.display());
+
+    Ok(thread::spawn(move || {
+        let started = Instant::now();
+        for (at, input_event) in events {
+            thread::sleep(at.saturating_sub(started.elapsed()));
+            if input_sender.send(input_event).is_err() {
+                return;
+            }
+        }
+    }))
+}
+
+/// Writes an event as its name and value
+fn encode(input_event: &InputEvent) -> String {
+    match input_event {
+        InputEvent::DialMoved { new_dial_position } => format!("DialMoved\t{}", new_dial_position),
+        InputEvent::BandSwitched { new_band } => format!("BandSwitched\t{}", new_band.0),
+        InputEvent::Seek { offset_seconds } => format!("Seek\t{}", offset_seconds),
+        InputEvent::ButtonPressed { button } => format!("ButtonPressed\t{:?}", button),
+    }
+}
+
+/// Reads a recording line back into its time and event
+fn decode(line: &str) -> Option<(Duration, InputEvent)> {
+    let mut fields = line.trim().split('\t');
+    let (Some(at), Some(name), Some(value)) = (fields.next(), fields.next(), fields.next()) else {return None;};
+    let at = Duration::from_millis(at.parse().ok()?);
+    let input_event = match name {
+        "DialMoved" => InputEvent::DialMoved { new_dial_position: value.parse().ok()? },
+        "BandSwitched" => InputEvent::BandSwitched { new_band: Band(value.parse().ok()?) },
+        "Seek" => InputEvent::Seek { offset_seconds: value.parse().ok()? },
+        "ButtonPressed" => InputEvent::ButtonPressed { button: match value {
+            "Pause" => RadioButton::Pause,
+            "Mute" => RadioButton::Mute,
+            _ => return None
+        } },
+        _ => return None
+    };
+    Some((at, input_event))
+}
@@ -0,0 +1,36 @@
+// Seek buttons
+// Optional "seek_back"/"seek_forward" push buttons from the hardware config
+
+use rppal::gpio::Gpio;
+use crate::input::buttons::PushButtons;
+use crate::input::device::InputDevice;
+use crate::messages::InputEvent;
+
+/// Seconds each press jumps within the current track
+const SEEK_STEP_SECONDS: f64 = 15.0;
+
+pub struct SeekButtons {
+    buttons: PushButtons<f64>
+}
+
+impl SeekButtons {
+    /// Claims whichever seek buttons are mapped in radio.info
+    /// 
+    /// Builds without seek buttons get an empty set; see `input::buttons`
+    /// for wiring and debouncing.
+    pub fn new(gpio_pins: &Gpio) -> SeekButtons {
+        let buttons = PushButtons::new(gpio_pins, [("seek_back", -SEEK_STEP_SECONDS), ("seek_forward", SEEK_STEP_SECONDS)]);
+        SeekButtons { buttons }
+    }
+
+    /// Returns the seek offset for a button pressed since the last read
+    pub fn read_press(&mut self) -> Option<f64> {
+        self.buttons.read_press()
+    }
+}
+
+impl InputDevice for SeekButtons {
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.read_press().map(|offset_seconds| InputEvent::Seek { offset_seconds })
+    }
+}
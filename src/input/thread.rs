@@ -1,54 +1,119 @@
 // Input Thread
-// Reads ADC (tuning pot) and GPIO (AM/FM switch) and sends events
+// Polls every configured input device and sends their events
 
 
 use std::sync::mpsc::Sender;
-use crate::constants;
+use std::thread;
+use std::time::Duration;
+use crate::config::{TuningInput, radio_config};
+use crate::state;
 use crate::messages::InputEvent;
 use crate::input::band_switch::BandSwitchPinHandler;
+use crate::input::device::InputDevice;
+use crate::input::dial::VirtualDial;
+use crate::input::encoder::RotaryEncoder;
+use crate::input::keyboard::KeyboardInput;
+use crate::input::mcp3008::Mcp3008;
+use crate::input::radio_buttons::RadioButtons;
+use crate::input::seek_buttons::SeekButtons;
+use crate::input::touch::TouchDial;
 use crate::input::tuner::Tuner;
 use rppal::gpio::Gpio;
 
+/// Pause between rounds of polling, so a quiet radio doesn't spin a core
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
 /// Runs the input thread
-/// 
+///
 /// Responsibilities:
-/// - Reads ADC potentiometer continuously
-/// - Monitors AM/FM GPIO switch
+/// - Sets up the input devices radio.info asks for (see `input_devices`)
+/// - Sends each device's starting position, then polls them all in turn
 /// - Sends InputEvent messages to Station Manager
-pub fn run_input_thread(input_sender: Sender<InputEvent>) {
-    let mut tuner: Tuner = Tuner::new();
-    let gpio_pins = Gpio::new().ok().unwrap();
-    let mut band_switch = BandSwitchPinHandler::new(gpio_pins, constants::BAND_SWITCH_PIN);
-    let mut unsent_band_events: Vec<InputEvent> = Vec::new();
-    let mut unsent_tuner_events: Vec<InputEvent> = Vec::new();
-
-    while let Err(send_error) = input_sender.send(InputEvent::DialMoved(tuner.initial_read())) {
-        print!(send_error);
+///
+/// Returns once the Station Manager stops listening.
+///
+/// # Arguments
+/// * `input_sender` - Channel to the Station Manager
+/// * `hardware` - Whether to read the radio's ADC and GPIO controls
+///   (false for `--no-hardware`, which tunes from the keyboard)
+pub fn run_input_thread(input_sender: Sender<InputEvent>, hardware: bool) {
+    run_devices(input_devices(hardware), input_sender);
+}
+
+/// Sets up the input devices for this radio
+///
+/// The dial is read from whichever of the I2C tuner, MCP3008, touch pad,
+/// rotary encoder or keyboard `tuning_input` picks, alongside the band
+/// switch and the optional seek, pause and mute buttons. Without hardware
+/// only the keyboard is read.
+pub fn input_devices(hardware: bool) -> Vec<Box<dyn InputDevice>> {
+    if !hardware {
+        return vec![Box::new(KeyboardInput::new())];
     }
-    while let Err(send_error) = input_sender.send(InputEvent::BandSwitched { new_band: band_switch.initial_read() }) {
-        print!(send_error);
+    let gpio_pins = match Gpio::new() {
+        Ok(gpio_pins) => Some(gpio_pins),
+        Err(e) => {
+            eprintln!("Failed to open GPIO, band switch and buttons won't be read: {}", e);
+            None
+        }
+    };
+
+    let dial: Box<dyn InputDevice> = match radio_config().tuning_input {
+        TuningInput::Keyboard => Box::new(KeyboardInput::new()),
+        // A touch pad has no position of its own, so it starts where the dial was left
+        TuningInput::Touch => Box::new(TouchDial::new(&radio_config().touch, VirtualDial::new(&radio_config().dial, state::starting_dial_position()))),
+        // An encoder has no position of its own either; without its pins the pot tunes
+        TuningInput::Encoder => match encoder(gpio_pins.as_ref()) {
+            Some(encoder) => Box::new(encoder),
+            None => Box::new(Tuner::new())
+        },
+        // Without the MCP3008 the pot is read through the I2C tuner
+        TuningInput::Adc => match mcp3008() {
+            Some(adc) => Box::new(adc),
+            None => Box::new(Tuner::new())
+        }
+    };
+
+    let mut devices = vec![dial];
+    if let Some(gpio_pins) = gpio_pins {
+        devices.push(Box::new(SeekButtons::new(&gpio_pins)));
+        devices.push(Box::new(RadioButtons::new(&gpio_pins)));
+        devices.push(Box::new(BandSwitchPinHandler::new(gpio_pins, &radio_config().hardware)));
     }
-    
-    
+    devices
+}
 
-    loop {
-        if let Some(new_band) = band_switch.read_change() {
-            let input_event = InputEvent::BandSwitched { new_band };
-            if let Err( send_error ) = input_sender.send(input_event){
-                print!(send_error);
-                unsent_band_events.push(input_event);
-            }
-            else {unsent_band_events.clear();}
+/// Sends each device's starting position, then polls them until the Station Manager is gone
+pub fn run_devices(mut devices: Vec<Box<dyn InputDevice>>, input_sender: Sender<InputEvent>) {
+    let initial_events: Vec<InputEvent> = devices.iter_mut().flat_map(|device| device.initial_events()).collect();
+    for input_event in initial_events {
+        if input_sender.send(input_event).is_err() {
+            return;
         }
-        if let Some(new_dial_position) = tuner.read_change() {
-            let input_event = InputEvent::DialMoved { new_dial_position };
-            if let Err( send_error ) = input_sender.send(input_event){
-                print!(send_error);
-                unsent_band_events.push(input_event);
+    }
+
+    loop {
+        for device in devices.iter_mut() {
+            let Some(input_event) = device.poll() else {continue;};
+            if let Err(send_error) = input_sender.send(input_event) {
+                eprintln!("{}", send_error);
+                return;
             }
-            else {unsent_tuner_events.clear();}
         }
+        thread::sleep(POLL_INTERVAL);
     }
 }
 
+fn encoder(gpio_pins: Option<&Gpio>) -> Option<RotaryEncoder> {
+    let Some(encoder_config) = radio_config().hardware.rotary_encoder.as_ref() else {
+        eprintln!("Tuning by encoder, but no rotary_encoder pins are configured");
+        return None;
+    };
+    RotaryEncoder::new(gpio_pins?, encoder_config, VirtualDial::new(&radio_config().dial, state::starting_dial_position()))
+}
 
+fn mcp3008() -> Option<Mcp3008> {
+    let adc_config = radio_config().hardware.tuner_adc.as_ref()?;
+    let chip_select = *radio_config().hardware.spi_chip_selects.get("tuner_adc")?;
+    Mcp3008::new(chip_select, adc_config)
+}
@@ -0,0 +1,185 @@
+//! Capacitive Touch Tuning
+//!
+//! Tunes with a finger on a capacitive slider or ring instead of a knob.
+//! The pads are electrodes on an MPR121 touch controller on the shared I2C
+//! bus. Like an encoder, a touch pad has no position of its own once the
+//! finger lifts, so sliding along it turns a `VirtualDial`: each electrode
+//! crossed is `steps_per_electrode` steps, with the dial's usual range,
+//! end stops and acceleration.
+
+use rppal::i2c::{Error, I2c};
+
+use crate::config::{TouchConfig, TouchLayout};
+use crate::i2c_bus::I2cBus;
+use crate::input::device::InputDevice;
+use crate::input::dial::VirtualDial;
+use crate::messages::InputEvent;
+
+/// MPR121 registers
+const TOUCH_STATUS: u8 = 0x00;
+const TOUCH_THRESHOLD: u8 = 0x41;
+const ELECTRODE_CONFIG: u8 = 0x5E;
+const SOFT_RESET: u8 = 0x80;
+
+/// Value written to SOFT_RESET to reset the chip
+const RESET_COMMAND: u8 = 0x63;
+
+/// Baseline filter settings (rising, falling and touched), from the
+/// MPR121 quick start guide
+const FILTER_SETTINGS: [(u8, u8); 11] = [
+    (0x2B, 0x01), (0x2C, 0x01), (0x2D, 0x0E), (0x2E, 0x00),
+    (0x2F, 0x01), (0x30, 0x05), (0x31, 0x01), (0x32, 0x00),
+    (0x33, 0x00), (0x34, 0x00), (0x35, 0x00),
+];
+
+/// Baseline tracking on, loaded from the first reading
+const BASELINE_TRACKING: u8 = 0x80;
+
+/// Electrodes the MPR121 has
+const MAX_ELECTRODES: u8 = 12;
+
+/// Touch slider or ring driving a virtual dial
+pub struct TouchDial {
+    bus: I2cBus,
+    config: TouchConfig,
+    dial: VirtualDial,
+
+    /// Finger position on the pad in electrodes, while touched
+    last_position: Option<f32>,
+
+    /// Movement not yet worth a whole dial step
+    remainder: f32,
+}
+
+impl TouchDial {
+    /// Sets up the MPR121 and a virtual dial for it
+    ///
+    /// # Arguments
+    /// * `config` - The `touch` section of radio.info
+    /// * `dial` - Virtual dial the pad turns
+    ///
+    /// A controller that doesn't answer is logged; reads keep retrying
+    /// through the shared bus, and setup is repeated on the next start.
+    pub fn new(config: &TouchConfig, dial: VirtualDial) -> TouchDial {
+        let touch_dial = TouchDial {
+            bus: I2cBus::shared(),
+            config: config.clone(),
+            dial,
+            last_position: None,
+            remainder: 0.0,
+        };
+        if let Err(e) = touch_dial.configure() {
+            eprintln!("Failed to set up touch controller at 0x{:02x}: {}", config.address, e);
+        }
+        touch_dial
+    }
+
+    /// Returns the dial position in dial units (0..ENCODER_HALF)
+    pub fn dial_position(&self) -> usize {
+        self.dial.dial_position()
+    }
+
+    /// Resets the controller and enables the pad's electrodes
+    fn configure(&self) -> Result<(), Error> {
+        let electrodes = self.electrodes();
+        let touch_threshold = self.config.touch_threshold;
+        let release_threshold = self.config.release_threshold;
+        self.bus.transaction(self.config.address, |i2c| {
+            i2c.smbus_write_byte(SOFT_RESET, RESET_COMMAND)?;
+            // Electrodes can only be configured while stopped
+            i2c.smbus_write_byte(ELECTRODE_CONFIG, 0x00)?;
+            for electrode in 0..electrodes {
+                i2c.smbus_write_byte(TOUCH_THRESHOLD + 2 * electrode, touch_threshold)?;
+                i2c.smbus_write_byte(TOUCH_THRESHOLD + 2 * electrode + 1, release_threshold)?;
+            }
+            for (register, value) in FILTER_SETTINGS {
+                i2c.smbus_write_byte(register, value)?;
+            }
+            i2c.smbus_write_byte(ELECTRODE_CONFIG, BASELINE_TRACKING | electrodes)
+        })
+    }
+
+    /// Number of electrodes in use
+    fn electrodes(&self) -> u8 {
+        self.config.electrodes.clamp(2, MAX_ELECTRODES)
+    }
+
+    /// Reads the pad and turns the dial by however far the finger slid
+    ///
+    /// # Returns
+    /// - `Some(usize)` - New dial position in dial units if the dial moved
+    /// - `None` - No touch, no movement, or the controller didn't answer
+    pub fn read_change(&mut self) -> Option<usize> {
+        let touched = match self.bus.transaction(self.config.address, read_touch_status) {
+            Ok(touched) => touched & ((1 << self.electrodes()) - 1),
+            Err(e) => {
+                eprintln!("Touch read error: {}", e);
+                return None;
+            }
+        };
+
+        // Lifting the finger ends the slide; the next touch starts a new one
+        let ring = (self.config.layout == TouchLayout::Ring).then(|| self.electrodes());
+        let Some(position) = centroid(touched, ring) else {
+            self.last_position = None;
+            self.remainder = 0.0;
+            return None;
+        };
+        let last_position = self.last_position.replace(position)?;
+
+        let mut moved = position - last_position;
+        if self.config.layout == TouchLayout::Ring {
+            // Take the short way round where the ring joins up
+            let electrodes = self.electrodes() as f32;
+            if moved > electrodes / 2.0 {
+                moved -= electrodes;
+            } else if moved < -electrodes / 2.0 {
+                moved += electrodes;
+            }
+        }
+
+        let steps = moved * self.config.steps_per_electrode + self.remainder;
+        let whole_steps = steps.trunc();
+        self.remainder = steps - whole_steps;
+        self.dial.turn(whole_steps as i32)
+    }
+}
+
+impl InputDevice for TouchDial {
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        vec![InputEvent::DialMoved { new_dial_position: self.dial_position() }]
+    }
+
+    fn poll(&mut self) -> Option<InputEvent> {
+        self.read_change().map(|new_dial_position| InputEvent::DialMoved { new_dial_position })
+    }
+}
+
+/// Reads the touched electrodes as a bit mask
+fn read_touch_status(i2c: &mut I2c) -> Result<u16, Error> {
+    let mut status = [0u8; 2];
+    i2c.write_read(&[TOUCH_STATUS], &mut status)?;
+    Ok(u16::from_le_bytes(status) & 0x0FFF)
+}
+
+/// Position of the finger in electrodes, from the middle of the touched ones
+///
+/// On a ring of `ring` electrodes, a finger over the join touches both the
+/// last and first electrodes; those are counted as one group across it.
+fn centroid(touched: u16, ring: Option<u8>) -> Option<f32> {
+    let mut electrodes: Vec<f32> = (0..MAX_ELECTRODES)
+        .filter(|electrode| touched & (1 << electrode) != 0)
+        .map(f32::from)
+        .collect();
+    if electrodes.is_empty() {
+        return None;
+    }
+
+    if let Some(size) = ring.filter(|size| touched & 1 != 0 && touched & (1 << (size - 1)) != 0) {
+        let size = f32::from(size);
+        electrodes.iter_mut().filter(|electrode| **electrode < size / 2.0).for_each(|electrode| *electrode += size);
+        let middle = electrodes.iter().sum::<f32>() / electrodes.len() as f32;
+        return Some(middle % size);
+    }
+    Some(electrodes.iter().sum::<f32>() / electrodes.len() as f32)
+}
@@ -0,0 +1,96 @@
+//! I2C Tuner
+//!
+//! Reads the tuning potentiometer through the tuner ADC on the shared I2C
+//! bus (see `i2c_bus`), the original mokRadio wiring. Each reading is two
+//! registers from `LEADING_REGISTER` on, combined into a 14-bit position;
+//! only readings that differ from the last one move the dial.
+
+use rppal::i2c::Error;
+
+use crate::constants;
+use crate::fault_injection::{self, Fault};
+use crate::i2c_bus::I2cBus;
+use crate::input::device::InputDevice;
+use crate::messages::InputEvent;
+
+/// Potentiometer read through the I2C tuner ADC
+pub struct Tuner {
+    rotary_encoder: I2cBus,
+    buffer: [u8; 2]
+}
+
+impl Tuner {
+    pub fn new() -> Self {
+        let rotary_encoder = I2cBus::shared();
+        let buffer = [0u8; 2];
+        Tuner {rotary_encoder, buffer}
+    }
+
+    /// Returns the dial position at startup
+    ///
+    /// A failed read is logged and the dial starts mid-band, to be moved
+    /// by the first reading that works.
+    pub fn initial_read(&mut self) -> usize {
+        match self.read_change() {
+            Ok(Some(value)) => value,
+            Ok(None) => Tuner::position(&self.buffer),
+            Err(e) => {
+                eprintln!("Tuner error: {}", e);
+                constants::ENCODER_HALF / 2
+            }
+        }
+    }
+
+    /// Reads the tuner once
+    ///
+    /// # Returns
+    /// - `Ok(Some(usize))` - New dial position if the knob moved
+    /// - `Ok(None)` - Same reading as last time
+    /// - `Err` - The bus error, after the bus has had its retry (or an
+    ///   injected `AdcReadError`)
+    pub fn read_change(&mut self) -> Result<Option<usize>, Error> {
+        if fault_injection::should_inject(Fault::AdcReadError) {
+            return Err(Error::Io(std::io::Error::other("injected tuner read error")));
+        }
+        let write_buffer = [constants::LEADING_REGISTER, constants::LEADING_REGISTER + 1];
+        let mut read_buffer = [0u8; 2];
+        self.rotary_encoder.transaction(constants::TUNER_ADDRESS, |i2c| {
+            i2c.write_read(&write_buffer, &mut read_buffer)
+        })?;
+        if read_buffer == self.buffer {
+            return Ok(None);
+        }
+        self.buffer = read_buffer;
+        Ok(Some(Tuner::position(&self.buffer)))
+    }
+
+    /// Combines the two register bytes into a dial position
+    fn position(buffer: &[u8; 2]) -> usize {
+        let top = (buffer[0] as u16) << 6;
+        let bottom = (buffer[1] as u16) >> 2;
+        (top | bottom) as usize
+    }
+}
+
+impl Default for Tuner {
+    fn default() -> Self {
+        Tuner::new()
+    }
+}
+
+impl InputDevice for Tuner {
+    fn initial_events(&mut self) -> Vec<InputEvent> {
+        vec![InputEvent::DialMoved { new_dial_position: self.initial_read() }]
+    }
+
+    fn poll(&mut self) -> Option<InputEvent> {
+        match self.read_change() {
+            Ok(new_dial_position) => new_dial_position.map(|new_dial_position| InputEvent::DialMoved { new_dial_position }),
+            // Logged and skipped; the next poll tries again
+            Err(e) => {
+                eprintln!("Tuner error: {}", e);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// mokRadio - Vintage Radio with Modern Playlists
+// Library crate shared by the radio binary, benchmarks and tools
+
+pub mod api;
+pub mod audio;
+pub mod backup;
+pub mod config;
+pub mod crash_report;
+pub mod display;
+pub mod error;
+pub mod fault_injection;
+pub mod i2c_bus;
+pub mod priority;
+pub mod radio;
+pub mod state;
+pub mod storage;
+pub mod input;
+pub mod file_loader;
+pub mod messages;
+pub mod constants;
@@ -1,24 +1,97 @@
 // mokRadio - Vintage Radio with Modern Playlists
 // A Raspberry Pi project to turn a vintage radio into a playlist player
 
-mod radio;
-mod input;
-mod file_loader;
-mod messages;
-mod constants;
-
-use std::fs::File;
+use std::io::Write;
 use std::path::{PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
-use crate::radio::Radio;
-use crate::radio::station::content::Band;
+use mokradio::{backup, config, crash_report, display, fault_injection, file_loader, input, state};
+use mokradio::api::RadioApi;
+use mokradio::api::server;
+use mokradio::audio::output::AudioOutput;
+use mokradio::audio::test_signal::run_test_signals;
+use mokradio::config::manifest::{self, ImportOutcome};
+use mokradio::config::setup;
+use mokradio::radio::aircheck::render_aircheck;
+use mokradio::radio::report::StationReport;
+use mokradio::radio::Radio;
+use mokradio::radio::station::config::PLAY_TYPES;
+use mokradio::radio::station::content::Band;
 
-use rodio::Decoder;
-use crate::messages::{FileRequest, FileResponse, InputEvent};
+use mokradio::messages::{FileRequest, FileResponse, InputEvent, RadioCommand};
 
 fn main() {
     println!("mokRadio starting...");
+
+    // A stations root of its own, for running more than one radio on a machine: --stations DIR
+    // Taken out of the arguments, so it can come before a subcommand
+    let mut arguments: Vec<String> = std::env::args().collect();
+    if let Some(position) = arguments.iter().position(|argument| argument == "--stations") {
+        if position + 1 >= arguments.len() {
+            eprintln!("usage: mokradio --stations DIR");
+            std::process::exit(2);
+        }
+        let stations_root = arguments.remove(position + 1);
+        arguments.remove(position);
+        println!("Stations root: {}", stations_root);
+        config::set_stations_root(PathBuf::from(stations_root));
+    }
+
+    // Calibration audio for checking the speaker and amp, no stations
+    if arguments.iter().any(|argument| argument == "--test-signals") {
+        let output = AudioOutput::open(config::radio_config().audio_backend);
+        run_test_signals(&output);
+    }
+
+    // Playlist statistics: report BAND INDEX (or report STATION_FOLDER)
+    if arguments.get(1).is_some_and(|command| command == "report") {
+        std::process::exit(report(&arguments[2..]));
+    }
+
+    // First-time setup from a music folder: init MUSIC_DIR [--band NAME] [--play-type TYPE] [--move] [--yes]
+    if arguments.get(1).is_some_and(|command| command == "init") {
+        std::process::exit(init(&arguments[2..]));
+    }
+
+    // Bulk station setup: import MANIFEST.csv [--overwrite]
+    if arguments.get(1).is_some_and(|command| command == "import") {
+        std::process::exit(import(&arguments[2..]));
+    }
+
+    // Settings archive for SD card rebuilds: backup FILE / restore FILE
+    if let [_, command, archive_path, ..] = arguments.as_slice() {
+        let result = match command.as_str() {
+            "backup" => Some(backup::backup(&PathBuf::from(archive_path))),
+            "restore" => Some(backup::restore(&PathBuf::from(archive_path))),
+            _ => None
+        };
+        if let Some(result) = result {
+            match result {
+                Ok(files) => {
+                    files.iter().for_each(|file| println!("{}", file.display()));
+                    println!("{} done, {} files", command, files.len());
+                    std::process::exit(0);
+                },
+                Err(e) => {
+                    eprintln!("{} failed: {}", command, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    // Offline render of a station's programming: --aircheck BAND INDEX MINUTES FILE
+    if let Some(position) = arguments.iter().position(|argument| argument == "--aircheck") {
+        std::process::exit(aircheck(&arguments[position + 1..]));
+    }
+
+    // Refuse to touch GPIO with a conflicting pin mapping
+    if let Err(errors) = config::radio_config().hardware.validate() {
+        errors.iter().for_each(|error| eprintln!("Hardware config error: {}", error));
+        std::process::exit(1);
+    }
+    fault_injection::initialize();
+    crash_report::install();
     
     // Create communication channels
     let (input_tx, input_rx):
@@ -27,12 +100,251 @@ fn main() {
         (Sender<FileRequest>, Receiver<FileRequest>) = channel();
     let (file_response_tx, file_response_rx):
         (Sender<FileResponse>, Receiver<FileResponse>) = channel();
+    let (command_tx, command_rx):
+        (Sender<RadioCommand>, Receiver<RadioCommand>) = channel();
 
-    thread::spawn(|| input::thread::run_input_thread(input_tx));
+    let no_hardware = arguments.iter().any(|argument| argument == "--no-hardware");
+
+    // Controls replayed from a recording stand in for the real ones: --replay-input FILE
+    let replay_path = arguments.iter().position(|argument| argument == "--replay-input")
+        .and_then(|position| arguments.get(position + 1));
+    match replay_path {
+        Some(replay_path) => {
+            if let Err(e) = input::recording::replay_inputs(&PathBuf::from(replay_path), input_tx) {
+                eprintln!("Failed to replay {}: {}", replay_path, e);
+                std::process::exit(1);
+            }
+        },
+        // Keystrokes stand in for the knobs on a bench with no ADC wired: --no-hardware
+        None => {thread::spawn(move || input::thread::run_input_thread(input_tx, !no_hardware));}
+    }
+    // Every control event written down for a bug report: --record-input FILE
+    let record_path = arguments.iter().position(|argument| argument == "--record-input")
+        .and_then(|position| arguments.get(position + 1));
+    let input_rx = match record_path {
+        Some(record_path) => input::recording::record_inputs(input_rx, &PathBuf::from(record_path))
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to record input to {}: {}", record_path, e);
+                std::process::exit(1);
+            }),
+        None => input_rx
+    };
     thread::spawn(|| file_loader::thread::run_file_loader(file_request_rx, file_response_tx));
         
-    let current_dial_position= input::rotary_encoder_reader;
-    let current_band= Band::AM;
-        
-    let mut _radio_ = Radio::new(current_dial_position, current_band);
+    // Back where the dial was before the restart, until the input thread's
+    // first events move it to where the knob really is
+    let current_dial_position = state::starting_dial_position();
+    let current_band = state::starting_band();
+
+    let mut radio = Radio::new(current_dial_position, current_band);
+
+    // Station, title and elapsed time on the OLED, if one is fitted
+    if let Some(display_config) = config::radio_config().hardware.display.as_ref().filter(|_| !no_hardware) {
+        display::start(display_config, radio.subscribe_now_playing());
+    }
+
+    // The radio stops once every API handle is gone, so keep one for its lifetime
+    let api = RadioApi::new(command_tx);
+
+    // Live events and the companion protocol, if radio.info gives an address
+    if let Some(address) = config::radio_config().api_server.as_deref() {
+        server::serve(address, radio.events(), api.clone())
+            .unwrap_or_else(|e| eprintln!("Failed to start API server on {}: {}", address, e));
+    }
+
+    radio.run(input_rx, file_request_tx, file_response_rx, command_rx);
+    drop(api);
+}
+
+/// Renders an aircheck from command line arguments, returning the exit code
+fn aircheck(arguments: &[String]) -> i32 {
+    let [band_name, index, minutes, output_path, ..] = arguments else {
+        eprintln!("usage: mokradio --aircheck BAND INDEX MINUTES FILE.wav");
+        return 2;
+    };
+    let Some(band) = Band::all().find(|band| band.name().eq_ignore_ascii_case(band_name)) else {
+        eprintln!("No band named {}", band_name);
+        return 2;
+    };
+    let (Ok(index), Ok(minutes)) = (index.parse::<usize>(), minutes.parse::<u64>()) else {
+        eprintln!("INDEX and MINUTES must be whole numbers");
+        return 2;
+    };
+
+    let station_path = Radio::station_path(band, index);
+    match render_aircheck(&station_path, std::time::Duration::from_secs(minutes.saturating_mul(60)), &PathBuf::from(output_path)) {
+        Ok(aircheck) => {
+            aircheck.tracks.iter().for_each(|track| println!("{}", track));
+            println!("wrote {}s to {}", aircheck.duration.as_secs(), output_path);
+            0
+        },
+        Err(e) => {
+            eprintln!("Aircheck failed: {}", e);
+            1
+        }
+    }
+}
+
+/// Creates the stations listed in a manifest, returning the exit code
+fn import(arguments: &[String]) -> i32 {
+    let Some(manifest_path) = arguments.iter().find(|argument| !argument.starts_with("--")) else {
+        eprintln!("usage: mokradio import MANIFEST.csv [--overwrite]");
+        return 2;
+    };
+    let overwrite = arguments.iter().any(|argument| argument == "--overwrite");
+
+    let (entries, mut outcomes) = match manifest::read_manifest(&PathBuf::from(manifest_path)) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", manifest_path, e);
+            return 1;
+        }
+    };
+    outcomes.extend(manifest::import_stations(&entries, overwrite));
+
+    let mut failed = false;
+    for outcome in &outcomes {
+        match outcome {
+            ImportOutcome::Created(station_path) => println!("created {}", station_path.display()),
+            ImportOutcome::Skipped(station_path) => println!("skipped {} (already set up)", station_path.display()),
+            ImportOutcome::Failed { line, reason } => {
+                eprintln!("line {}: {}", line, reason);
+                failed = true;
+            }
+        }
+    }
+    if failed { 1 } else { 0 }
+}
+
+/// Builds stations from a music folder, asking along the way, returning the exit code
+fn init(arguments: &[String]) -> i32 {
+    let usage = "usage: mokradio init MUSIC_DIR [--band NAME] [--play-type TYPE] [--move] [--yes]";
+    let option = |name: &str| arguments.iter().position(|argument| argument == name).and_then(|position| arguments.get(position + 1));
+    let values: Vec<&String> = ["--band", "--play-type"].into_iter().filter_map(option).collect();
+    let Some(music_path) = arguments.iter().find(|argument| !argument.starts_with("--") && !values.contains(argument)) else {
+        eprintln!("{}", usage);
+        return 2;
+    };
+    let first_band = match option("--band") {
+        Some(band_name) => match Band::all().find(|band| band.name().eq_ignore_ascii_case(band_name)) {
+            Some(band) => band,
+            None => {
+                eprintln!("No band named {}", band_name);
+                return 2;
+            }
+        },
+        None => Band::AM
+    };
+    let default_play_type = match option("--play-type") {
+        Some(play_type) => match known_play_type(play_type) {
+            Some(play_type) => play_type,
+            None => {
+                eprintln!("No play type {} (one of {})", play_type, PLAY_TYPES.join(", "));
+                return 2;
+            }
+        },
+        None => "Shuffle"
+    };
+    let move_files = arguments.iter().any(|argument| argument == "--move");
+    let ask = !arguments.iter().any(|argument| argument == "--yes");
+
+    let candidates = match setup::find_candidates(&PathBuf::from(music_path)) {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", music_path, e);
+            return 1;
+        }
+    };
+    if candidates.is_empty() {
+        eprintln!("No audio found in {}", music_path);
+        return 1;
+    }
+    let (placements, unplaced) = setup::place(candidates, first_band);
+    unplaced.iter().for_each(|candidate| eprintln!("No free slot for {}", candidate.folder.display()));
+
+    // Name and play type for each station, or the defaults with --yes
+    let mut stations = Vec::new();
+    for placement in &placements {
+        println!("{} {:02}: {} ({} tracks)", placement.band.name(), placement.slot, placement.candidate.folder.display(), placement.candidate.files.len());
+        if !ask {
+            stations.push((placement, placement.candidate.name.clone(), default_play_type));
+            continue;
+        }
+        let name = prompt(&format!("  Name [{}], - to skip: ", placement.candidate.name));
+        if name == "-" {
+            continue;
+        }
+        let play_type = loop {
+            let play_type = prompt(&format!("  Play type [{}]: ", default_play_type));
+            if play_type.is_empty() {
+                break default_play_type;
+            }
+            match known_play_type(&play_type) {
+                Some(play_type) => break play_type,
+                None => println!("  No play type {} (one of {})", play_type, PLAY_TYPES.join(", "))
+            }
+        };
+        let name = if name.is_empty() { placement.candidate.name.clone() } else { name };
+        stations.push((placement, name, play_type));
+    }
+    let action = if move_files { "moving" } else { "linking" };
+    if ask && !prompt(&format!("Create {} stations, {} their music? [y/N] ", stations.len(), action)).eq_ignore_ascii_case("y") {
+        println!("Nothing created");
+        return 0;
+    }
+
+    let mut failed = false;
+    for (placement, name, play_type) in stations {
+        match setup::create(placement, &name, play_type, move_files) {
+            Ok(station_path) => println!("created {} ({})", station_path.display(), name),
+            Err(e) => {
+                eprintln!("{} {:02}: {}", placement.band.name(), placement.slot, e);
+                failed = true;
+            }
+        }
+    }
+    if failed { 1 } else { 0 }
+}
+
+/// Returns the play type a name stands for, in any case
+fn known_play_type(name: &str) -> Option<&'static str> {
+    PLAY_TYPES.into_iter().find(|play_type| play_type.eq_ignore_ascii_case(name))
+}
+
+/// Asks a question on the terminal, returning the trimmed answer (empty at end of input)
+fn prompt(question: &str) -> String {
+    print!("{}", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    let _ = std::io::stdin().read_line(&mut answer);
+    answer.trim().to_string()
+}
+
+/// Prints a station's playlist report, returning the exit code
+fn report(arguments: &[String]) -> i32 {
+    let station_path = match arguments {
+        [station_folder] => PathBuf::from(station_folder),
+        [band_name, index, ..] => {
+            let Some(band) = Band::all().find(|band| band.name().eq_ignore_ascii_case(band_name)) else {
+                eprintln!("No band named {}", band_name);
+                return 2;
+            };
+            let Ok(index) = index.parse::<usize>() else {
+                eprintln!("INDEX must be a whole number");
+                return 2;
+            };
+            Radio::station_path(band, index)
+        },
+        [] => {
+            eprintln!("usage: mokradio report BAND INDEX | mokradio report STATION_FOLDER");
+            return 2;
+        }
+    };
+    if !station_path.is_dir() {
+        eprintln!("No station at {}", station_path.display());
+        return 1;
+    }
+
+    print!("{}", StationReport::new(&station_path));
+    0
 }
@@ -1,10 +1,13 @@
 // Centralized message types for inter-thread communication
 
 use std::path::PathBuf;
-use std::fs::File;
-use rodio::Decoder;
-use std::io::BufReader;
+use std::sync::mpsc::Sender;
+use std::time::Duration;
 
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::audio::AudioSource;
 use crate::radio::station::content::track::Track;
 use crate::radio::station::content::{Band, StationID};
 
@@ -16,21 +19,309 @@ pub enum InputEvent {
     /// Tuning dial moved to new_dial_position value (0-4095 or similar)
     DialMoved { new_dial_position: usize },
     
-    /// AM/FM band switch toggled
-    BandSwitched { new_band: Band }
+    /// Band switch moved to one of the bands configured in radio.info
+    BandSwitched { new_band: Band },
+
+    /// Seek button pressed; jump within the current track by this many seconds
+    Seek { offset_seconds: f64 },
+
+    /// A radio-wide control button pressed
+    ButtonPressed { button: RadioButton }
+}
+
+/// Radio-wide push buttons from the hardware config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadioButton {
+    /// Pauses every station, or resumes them
+    Pause,
+
+    /// Mutes the output, or unmutes it
+    Mute
+}
+
+// ===== API → Station Manager =====
+
+/// Commands from the API layer (web dashboard, companion apps)
+pub enum RadioCommand {
+    /// Send a station to the monitor output, or silence it with None
+    MonitorStation { station_id: Option<StationID> },
+
+    /// Put a station on the right channel in dual-mono mode, or end the split with None
+    SplitStation { station_id: Option<StationID> },
+
+    /// Change what a station plays next
+    EditQueue {
+        station_id: StationID,
+        edit: QueueEdit,
+        reply: Sender<Result<(), String>>,
+    },
+
+    /// Rescan playlist folders in the background, rebuilding only the ones
+    /// that changed and loading Dead stations that have become playable
+    ReloadPlaylists,
+
+    /// Move the dial to the middle of a station's slot
+    Tune { station_id: StationID },
+
+    /// Skip to the next track on the current station
+    SkipTrack,
+
+    /// Seek within the track playing on the current station
+    Seek { to: SeekTo },
+
+    /// Set the master volume (0.0 to 1.0)
+    SetVolume { level: f32 },
+
+    /// Pause every station, freezing radio time, or resume them
+    PauseAll { paused: bool },
+
+    /// Silence the output while stations play on underneath, or unmute it
+    Mute { muted: bool },
+
+    /// Switch dial layouts; None is the standard layout
+    SelectProfile { name: Option<String> },
+
+    /// Report the dial position and what's playing
+    Status { reply: Sender<RadioStatus> },
+
+    /// Report everything the Station Manager holds, for troubleshooting
+    Snapshot { reply: Sender<RadioSnapshot> },
+
+    /// Silence the radio and return from the Station Manager loop
+    Shutdown,
+
+    /// Restart a Chronologic station from a date or episode
+    StartFrom {
+        station_id: StationID,
+        start_from: String,
+    },
+
+    /// Report where a Chronologic station has got to
+    ChronologicPosition {
+        station_id: StationID,
+        reply: Sender<Option<ChronologicPosition>>,
+    },
+
+    /// List a station's upcoming tracks without affecting playback
+    PreviewStation {
+        station_id: StationID,
+        count: usize,
+        reply: Sender<Vec<TrackPreview>>,
+    },
+
+    /// Set up an empty slot as a copy of another station and put it on the dial
+    CloneStation {
+        from: StationID,
+        to: StationID,
+        /// Name for the copy, or None to keep the original's
+        name: Option<String>,
+        /// Playlist folder for the copy, or None to share the original's
+        playlist: Option<PathBuf>,
+        reply: Sender<Result<(), String>>,
+    },
+
+    /// Change fields of a station's station.info and reload it
+    EditStation {
+        station_id: StationID,
+        /// Top-level station.info fields to set
+        settings: Map<String, Value>,
+        reply: Sender<Result<(), String>>,
+    },
+}
+
+/// Edit to a station's upcoming queue
+#[derive(Debug, Clone)]
+pub enum QueueEdit {
+    /// Play this file right after the current track
+    Pin(PathBuf),
+
+    /// Play this file once, after everything already queued
+    Inject(PathBuf),
+
+    /// Play this file once, after everything already queued, and always to
+    /// the end: turnover, seeks and cue skips leave it alone (news, jingles,
+    /// emergency announcements)
+    Announce(PathBuf),
+
+    /// Take this file out of the upcoming queue
+    Remove(PathBuf),
+}
+
+/// Snapshot of what the radio is tuned to
+#[derive(Debug, Clone, Serialize)]
+pub struct RadioStatus {
+    /// Band and dial slot, e.g. "FM 03"
+    pub station: String,
+
+    /// Band name
+    pub band: String,
+
+    /// Dial slot within the band
+    pub index: usize,
+
+    /// Raw dial position
+    pub dial_position: usize,
+
+    /// Station display name
+    pub name: String,
+
+    /// Whether the station is broadcasting (otherwise the dial hears static)
+    pub on_air: bool,
+
+    /// Title of what's playing, if anything
+    pub now_playing: Option<String>,
+
+    /// Where what's playing came from, e.g. "synced: nas:/music/incoming"
+    pub now_playing_origin: Option<String>,
+
+    /// Master volume, 0.0 to 1.0
+    pub volume: f32,
+
+    /// Whether every station is paused
+    pub paused: bool,
+
+    /// Whether the output is muted
+    pub muted: bool,
+
+    /// Dial layout in use (None for the standard layout)
+    pub profile: Option<String>,
+
+    /// Stations off air after repeated load errors, e.g. "FM 03"
+    pub faulted: Vec<String>,
+}
+
+/// Everything the Station Manager holds, for troubleshooting stuck stations
+#[derive(Debug, Clone, Serialize)]
+pub struct RadioSnapshot {
+    /// Where the dial is and what's playing there
+    pub status: RadioStatus,
+
+    /// Neighbor heard through the edge of the current slot, e.g. "FM 04"
+    pub bleeding_station: Option<String>,
+
+    /// Station on the monitor output
+    pub monitored_station: Option<String>,
+
+    /// Station on the right channel of a dual-mono split
+    pub split_station: Option<String>,
+
+    /// Stations part way through a volume ramp
+    pub fading_stations: Vec<String>,
+
+    /// Loads requested before a profile switch, still to be thrown away when they arrive
+    pub stale_loads: Vec<(String, PathBuf)>,
+
+    /// Every station on every band, in dial order
+    pub stations: Vec<StationSnapshot>,
+}
+
+/// One station's state as the Station Manager sees it
+#[derive(Debug, Clone, Serialize)]
+pub struct StationSnapshot {
+    /// Band and dial slot, e.g. "FM 03"
+    pub station: String,
+    pub name: String,
+
+    /// Station folder
+    pub path: PathBuf,
+
+    /// `play_type` the station runs as
+    pub play_type: String,
+
+    /// Tracks left in the playlist (all of them for Random stations)
+    pub playlist_remaining: usize,
+
+    /// Where the playlist picks up next, for stations that play in order
+    pub playlist_cursor: Option<PathBuf>,
+
+    /// Track playing, and the one queued behind it
+    pub current: Option<PathBuf>,
+    pub next: Option<PathBuf>,
+
+    /// Sources waiting in the sink, including the one playing
+    pub sink_length: usize,
+
+    /// Queued tracks whose audio hasn't come back from the File Loader
+    pub awaiting_load: usize,
+
+    /// Tracks pinned or injected through the API
+    pub queue_edits: Vec<PathBuf>,
+    pub announcements: Vec<PathBuf>,
+
+    pub on_air: bool,
+    pub signed_off: bool,
+    pub restricted: bool,
+    pub paused: bool,
+    pub volume: f32,
+    pub consecutive_errors: usize,
+    pub faulted: bool,
+}
+
+/// Target of a seek, in seconds
+#[derive(Debug, Clone, Copy)]
+pub enum SeekTo {
+    /// Position from the start of the track
+    Absolute(f64),
+
+    /// Offset from the current position (negative seeks back)
+    Relative(f64),
+}
+
+/// How far a Chronologic station has got through its archive
+#[derive(Debug, Clone, Serialize)]
+pub struct ChronologicPosition {
+    /// Track playing now
+    pub file_path: PathBuf,
+
+    /// Its modification date, YYYY-MM-DD
+    pub date: String,
+
+    /// Tracks left to play after it
+    pub remaining: usize,
+}
+
+/// Upcoming track as shown on the dashboard's schedule view
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackPreview {
+    /// Title tag, or the file name for untagged tracks
+    pub title: String,
+
+    /// Track length in whole seconds
+    pub duration_seconds: i64,
+
+    /// Where the track came from, e.g. "local" or "feed: Tech Talk Weekly"
+    pub origin: String,
+}
+
+impl TrackPreview {
+    pub fn from_track(track: &Track) -> Self {
+        TrackPreview {
+            title: track.display_title(),
+            duration_seconds: track.get_duration().num_seconds(),
+            origin: track.origin().to_string(),
+        }
+    }
 }
 
 // ===== Station Manager → File Loader =====
 
 /// Requests from Station Manager to File Loader thread
-
 pub enum FileRequest {
     /// Request to load a specific track for a station
     LoadTrack {
         station_id: StationID,
         file_path: PathBuf,
+        /// DJ intro to play right before the track
+        intro: Option<Intro>,
     },
     
+    /// Request to decode the opening seconds of a station's first track,
+    /// ahead of the queue (see `file_loader::warmer`)
+    WarmTrack {
+        station_id: StationID,
+        file_path: PathBuf,
+    },
+
     /// Request to scan a directory and return track metadata
     ScanDirectory {
         station_id: StationID,
@@ -38,6 +329,107 @@ pub enum FileRequest {
     },
 }
 
+/// DJ intro announcing the upcoming track
+#[derive(Debug, Clone)]
+pub enum Intro {
+    /// Pre-recorded intro file from the station's `dj/` folder
+    Recorded(PathBuf),
+
+    /// Text for the File Loader to synthesize with text-to-speech
+    Spoken { text: String, language: String },
+
+    /// Bed from the station's `beds/` folder, mixed over the start of the
+    /// track with the track ducked to `duck` (see `audio::talk_over`). The
+    /// File Loader picks the bed, and plays `fallback` if there's none.
+    TalkOver { beds: PathBuf, duck: f32, max_length: Duration, fallback: Option<Box<Intro>> },
+}
+
+// ===== Audio Thread → Station Manager =====
+
+/// Playback events raised by sources as the audio thread plays them
+#[derive(Debug, Clone)]
+pub enum PlaybackEvent {
+    /// A station's track (with any DJ intro in front of it) started playing
+    TrackStarted {
+        station_id: StationID,
+        file_path: PathBuf,
+    },
+
+    /// A station's track (with any DJ intro in front of it) played to the end
+    TrackFinished {
+        station_id: StationID,
+        file_path: PathBuf,
+    },
+}
+
+// ===== Station Manager → Event Stream =====
+
+/// Live events for the dashboard and visualizers
+/// 
+/// Sent to event stream clients as JSON objects tagged by `"event"`, e.g.
+/// `{"event":"volume_changed","level":0.6}`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RadioEvent {
+    /// The dial moved onto another station or band
+    Tuned {
+        station: String,
+        name: String,
+        on_air: bool,
+    },
+
+    /// A station started playing a track
+    TrackStarted {
+        station: String,
+        title: String,
+    },
+
+    /// A station's track played to the end
+    TrackFinished {
+        station: String,
+        title: String,
+    },
+
+    /// Master volume changed (0.0 to 1.0)
+    VolumeChanged { level: f32 },
+
+    /// The whole radio was paused or resumed
+    Paused { paused: bool },
+
+    /// The output was muted or unmuted
+    Muted { muted: bool },
+
+    /// A station failed to load a track
+    Error {
+        station: String,
+        message: String,
+    },
+}
+
+// ===== Station Manager → Display =====
+
+/// What the dial is on, for the now-playing display
+#[derive(Debug, Clone)]
+pub struct NowPlaying {
+    /// Station the dial is on, e.g. "FM 03"
+    pub station: String,
+
+    /// Station's name from its config
+    pub name: String,
+
+    /// Title of the track playing, if the station has one
+    pub title: Option<String>,
+
+    /// Whether the station is broadcasting (otherwise the dial hears static)
+    pub on_air: bool,
+
+    /// Whether the whole radio is paused
+    pub paused: bool,
+
+    /// How far into the track the station was when this was sent
+    pub elapsed: Duration,
+}
+
 // ===== File Loader → Station Manager =====
 
 /// Responses from File Loader back to Station Manager
@@ -45,9 +437,19 @@ pub enum FileResponse {
     /// Decoded audio file ready to append to sink
     TrackLoaded {
         station_id: StationID,
-        audio_content: Decoder<BufReader<File>>,
+        file_path: PathBuf,
+        audio_content: AudioSource,
     },
     
+    /// Opening seconds of a station's first track, decoded into memory
+    TrackWarmed {
+        station_id: StationID,
+        file_path: PathBuf,
+        audio_content: AudioSource,
+        /// How much of the track the opening covers
+        length: Duration,
+    },
+
     /// Directory scan complete with track metadata
     DirectoryScanned {
         station_id: StationID,
@@ -58,6 +460,33 @@ pub enum FileResponse {
     /// Error loading file
     LoadError {
         station_id: StationID,
+        file_path: PathBuf,
+        kind: LoadErrorKind,
         error_message: String,
+
+        /// The intro the track was asked for with, to ask again with
+        intro: Option<Intro>,
     },
 }
+
+/// Why a track failed to load, which decides what the Station Manager does next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadErrorKind {
+    /// The file has gone; it's dropped from the playlist
+    NotFound,
+
+    /// The file is there but its audio is corrupt; it's quarantined once
+    /// it has failed `QUARANTINE_AFTER` times
+    DecodeFailed,
+
+    /// Reading the file failed or stalled; it's requested again
+    IoTimeout,
+
+    /// No decoder understands the file; it's dropped from the playlist
+    UnsupportedFormat,
+
+    /// Failed for a reason that may not be the file's (a decoder panic, an
+    /// injected fault, a bad RAM-cache or transcoded copy); it counts
+    /// against the error budget but stays in the playlist
+    Inconclusive,
+}
@@ -0,0 +1,105 @@
+//! Thread Priority Tuning
+//!
+//! On a loaded Pi Zero everything competes for one slow core, and a
+//! background rescan of a big network playlist can starve the file loader
+//! long enough for the audible station to run dry. With `priority` enabled
+//! in radio.info, each thread is given a nice value for its role (the file
+//! loader ahead of everything else, background scans behind), and the
+//! sound card's callback thread can be given realtime priority.
+//!
+//! Priorities are set with the `renice` and `chrt` tools on the thread's
+//! Linux thread id. Raising priority (a negative nice, or any realtime
+//! priority) needs root or CAP_SYS_NICE; without it the change is logged
+//! and the thread keeps running as it was. On systems without `/proc`
+//! nothing is changed.
+//!
+//! # JSON Format
+//! ```json
+//! "priority": {
+//!     "enabled": true,
+//!     "loader_nice": -5,
+//!     "scan_nice": 10,
+//!     "audio_realtime": 40
+//! }
+//! ```
+
+use std::fs;
+use std::process::Command;
+
+use crate::config::radio_config;
+
+/// Name cpal gives the thread running the ALSA output callback
+const ALSA_OUTPUT_THREAD: &str = "cpal_alsa_out";
+
+/// What a thread does, deciding how it's scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadRole {
+    /// Decodes tracks for the stations, including the one on the speaker
+    FileLoader,
+
+    /// Rescans playlists nobody is waiting on
+    BackgroundScan,
+
+    /// Pulls the master mix in real time
+    Audio,
+}
+
+/// Applies the configured priority for a role to the calling thread
+///
+/// Does nothing unless priority tuning is enabled in radio.info.
+pub fn prioritize_current_thread(role: ThreadRole) {
+    let priority = &radio_config().priority;
+    if !priority.enabled {
+        return;
+    }
+    let Some(thread_id) = current_thread_id() else {return;};
+    apply(thread_id, role);
+}
+
+/// Gives the sound card's output thread its configured priority
+///
+/// Called once the output stream is open, since cpal starts the thread.
+pub fn prioritize_audio_threads() {
+    if !radio_config().priority.enabled {
+        return;
+    }
+    let Ok(tasks) = fs::read_dir("/proc/self/task") else {return;};
+    tasks.filter_map(|task| task.ok())
+        .filter(|task| {
+            fs::read_to_string(task.path().join("comm")).is_ok_and(|name| name.trim() == ALSA_OUTPUT_THREAD)
+        })
+        .filter_map(|task| task.file_name().to_str().and_then(|id| id.parse().ok()))
+        .for_each(|thread_id| apply(thread_id, ThreadRole::Audio));
+}
+
+/// Sets a thread's nice value or realtime priority for its role
+fn apply(thread_id: u32, role: ThreadRole) {
+    let priority = &radio_config().priority;
+    let thread_id = thread_id.to_string();
+    let (tool, arguments): (&str, Vec<String>) = match role {
+        ThreadRole::FileLoader => ("renice", vec!["-n".to_string(), priority.loader_nice.to_string(), "-p".to_string(), thread_id]),
+        ThreadRole::BackgroundScan => ("renice", vec!["-n".to_string(), priority.scan_nice.to_string(), "-p".to_string(), thread_id]),
+        ThreadRole::Audio => match priority.audio_realtime {
+            Some(realtime) => ("chrt", vec!["-f".to_string(), "-p".to_string(), realtime.to_string(), thread_id]),
+            None => return
+        }
+    };
+
+    let result = Command::new(tool).args(&arguments).output();
+    match result {
+        Ok(output) if output.status.success() => {},
+        Ok(output) => eprintln!(
+            "Failed to set {:?} thread priority: {}",
+            role,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => eprintln!("Failed to set {:?} thread priority, can't run {}: {}", role, tool, e)
+    }
+}
+
+/// Linux thread id of the calling thread, from `/proc/thread-self`
+fn current_thread_id() -> Option<u32> {
+    // The link reads "<pid>/task/<tid>"
+    let link = fs::read_link("/proc/thread-self").ok()?;
+    link.file_name()?.to_str()?.parse().ok()
+}
@@ -1,58 +1,137 @@
 // Station Manager Thread
 // Manages all radio stations, receives input events, sends file requests
+pub mod aircheck;
+pub mod report;
 pub mod station;
 pub mod utilities;
-use std::{array, path::Path, sync::mpsc::{Receiver, Sender}, thread::sleep, time::{Duration, Instant}};
+use std::{array, path::{Path, PathBuf}, sync::mpsc::{Receiver, Sender, TryRecvError, channel}, thread::sleep, time::{Duration, Instant}};
 
-use rand::seq::index;
-use rodio::{OutputStream, OutputStreamBuilder, Sink, source::TrackPosition};
+use rodio::{OutputStream, OutputStreamBuilder, Sink, Source, mixer::Mixer};
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+use serde_json::{Map, Value};
 
 use station::Station;
 
-use crate::{constants::STATION_PATH, input, messages::{FileRequest, FileResponse, InputEvent}, radio::{station::content::{Band, StationID}, utilities::{skip_dormant_stations_in_band, skip_dormant_stations_in_band_except_current}}};
-use crate::messages;
+use crate::{messages::{FileRequest, FileResponse, InputEvent}, radio::{station::content::{Band, StationID}, utilities::{skip_dormant_stations_in_band, skip_dormant_stations_in_band_except_current}}};
+use crate::api::events::EventHub;
+use crate::crash_report::{self, ManagerState};
+use crate::state;
+use crate::audio::AudioSource;
+use crate::audio::finished::{notify_when_finished, notify_when_started};
+use crate::audio::monitor::monitor_source;
+use crate::audio::output::{self, AudioOutput};
+use crate::audio::slew::{SlewedGain, slewed};
+use crate::audio::split::{ChannelRoute, Channels, routed};
+use crate::audio::static_gen::static_noise;
+use crate::audio::test_pattern::off_air_tone;
+use crate::audio::warm_start::warm_start;
+use crate::radio::station::content::genre::split_library_by_genre;
+use crate::config::{OffAirBehavior, cloning, radio_config, stations_root};
+use crate::config::profile::{ProfileConfig, SlotAssignment};
+use crate::config::tuning::TuningProfile;
+use crate::messages::{self, Intro, LoadErrorKind, NowPlaying, PlaybackEvent, RadioButton, QueueEdit, RadioCommand, RadioEvent, RadioSnapshot, RadioStatus, SeekTo};
+use crate::radio::station::config::{StartFrom, StationConfig};
+use crate::radio::station::content::track::Track;
+use crate::radio::station::rescan::{RescanJob, Rescanned};
+use crate::priority::{ThreadRole, prioritize_current_thread};
 use crate::constants;
 
+/// Second output device listening to a station of its own
+struct Monitor {
+    _output: OutputStream,
+    _sink: Sink,
+    station: Option<StationID>
+}
+
+/// Volume ramp on a station's sink, stepped by the manager loop
+struct Fade {
+    station_id: StationID,
+    from: f32,
+    to: f32,
+    started: Instant,
+
+    /// Pause the station once it has faded out and the grace period is over
+    then_pause: bool,
+}
+
 pub struct Radio {
     current_station:StationID,
     current_dial_position:usize,
     last_station_switch:Instant,
     has_skipped_since_last_station_switch:bool,
-    am:[Station; constants::NUMBER_OF_STATIONS],
-    fm:[Station; constants::NUMBER_OF_STATIONS],
-    am_volume_profile:[f32; constants::ENCODER_HALF],
-    fm_volume_profile:[f32; constants::ENCODER_HALF],
+    bands:Vec<[Station; constants::NUMBER_OF_STATIONS]>,
+    volume_profiles:Vec<[f32; constants::ENCODER_HALF]>,
     station_volume_profile:[f32; constants::TICKS_PER_STATION],
-    output:OutputStream,
-    white_noise: Sink
+    tuning: TuningProfile,
+    bleeding_station: Option<StationID>,
+    drift_offset: f32,
+    last_drift_step: Instant,
+    last_schedule_check: Option<Instant>,
+    last_state_save: Instant,
+    paused_since: Option<Instant>,
+    output:AudioOutput,
+    white_noise: Sink,
+    white_noise_level: SlewedGain,
+    test_pattern: Sink,
+    test_pattern_level: SlewedGain,
+    dial_route: ChannelRoute,
+    monitor: Option<Monitor>,
+    split_station: Option<StationID>,
+    profile: Option<String>,
+    stale_loads: Vec<(StationID, PathBuf)>,
+    /// Loads to request again once their backoff is over
+    pending_retries: Vec<(Instant, StationID, PathBuf, Option<Intro>)>,
+    fades: Vec<Fade>,
+    playback_events: Sender<PlaybackEvent>,
+    playback_event_receiver: Receiver<PlaybackEvent>,
+    /// Playlist rescans finished in the background, to be swapped in
+    rescans: Sender<(StationID, Rescanned)>,
+    rescan_receiver: Receiver<(StationID, Rescanned)>,
+    events: EventHub,
+    now_playing: Vec<Sender<NowPlaying>>,
+    /// Dial and queues as last given to the crash report
+    noted_state: Option<ManagerState>
 }
 
 impl Radio {
     pub fn new (current_dial_position:usize, current_band:Band) -> Self {
 
-        let output_builder = OutputStreamBuilder::from_default_device().unwrap();
-        let output = output_builder.open_stream().unwrap();
+        let output = AudioOutput::open(radio_config().audio_backend);
 
-        let am = Radio::initialize_station_array(Band::AM, &output);
-        let fm = Radio::initialize_station_array(Band::FM, &output);
+        let profile = radio_config().profile.clone().filter(|name| Radio::profile_config(name).is_some());
+        let bands = Radio::initialize_bands(profile.as_deref(), output.mixer());
         
-        let station_volume_profile = utilities::generate_station_volume_profile();
-        let am_volume_profile = Radio::initialize_volume_profile(
-            &am,
-            &station_volume_profile
-        );
-        let fm_volume_profile = Radio::initialize_volume_profile(
-            &fm,
-            &station_volume_profile
-        );
+        let tuning = radio_config().tuning.profile();
+        let station_volume_profile = utilities::generate_station_volume_profile(&tuning);
+        let volume_profiles: Vec<[f32; constants::ENCODER_HALF]> = bands.iter()
+            .map(|band| Radio::initialize_volume_profile(band, &station_volume_profile))
+            .collect();
         
+        // Static and the off-air tone belong to the dial, so take its side of a dual-mono split
+        let dial_route = ChannelRoute::default();
+
+        // Static between stations, crossfaded against station audio as the dial moves
         let white_noise = Sink::connect_new(output.mixer());
-        white_noise.set_volume( 
-            if current_band == Band::AM { 1.0 - am_volume_profile.get(current_dial_position).unwrap() }
-            else { 1.0 - fm_volume_profile.get(current_dial_position).unwrap() }
+        let white_noise_level = SlewedGain::new(
+            1.0 - volume_profiles[current_band.0].get(current_dial_position).unwrap()
         );
+        white_noise.append(routed(slewed(static_noise(), white_noise_level.clone()), dial_route.clone()));
 
-        let radio = Radio {
+        // Off-air tone, silent until the dial lands on an off-air frequency
+        let test_pattern = Sink::connect_new(output.mixer());
+        let test_pattern_level = SlewedGain::new(0.0);
+        test_pattern.append(routed(slewed(off_air_tone(), test_pattern_level.clone()), dial_route.clone()));
+
+        // Sources report their own start and end of playback on this channel
+        let (playback_events, playback_event_receiver) = channel();
+
+        // Hot reloads scan folders on their own thread and report back here
+        let (rescans, rescan_receiver) = channel();
+
+        // Live events for the dashboard, served by whoever starts the API server
+        let events = EventHub::new();
+
+        Radio {
             current_station: StationID {
                 band: current_band,
                 index: current_dial_position / constants::TICKS_PER_STATION,
@@ -60,38 +139,190 @@ impl Radio {
             current_dial_position,
             last_station_switch:Instant::now(),
             has_skipped_since_last_station_switch:false,
-            am,
-            fm,
-            am_volume_profile,
-            fm_volume_profile,
+            bands,
+            volume_profiles,
             station_volume_profile,
+            tuning,
+            bleeding_station: None,
+            drift_offset: 0.0,
+            last_drift_step: Instant::now(),
+            last_schedule_check: None,
+            last_state_save: Instant::now(),
+            paused_since: None,
             output,
-            white_noise
+            white_noise,
+            white_noise_level,
+            test_pattern,
+            test_pattern_level,
+            dial_route,
+            monitor: radio_config().monitor_device.as_deref().and_then(Radio::open_monitor),
+            split_station: None,
+            profile,
+            stale_loads: Vec::new(),
+            pending_retries: Vec::new(),
+            fades: Vec::new(),
+            playback_events,
+            playback_event_receiver,
+            rescans,
+            rescan_receiver,
+            events,
+            now_playing: Vec::new(),
+            noted_state: None
+        }
+    }
+    /// Returns the hub live events are published on
+    pub fn events(&self) -> EventHub {
+        self.events.clone()
+    }
+    /// Returns a channel that hears what the dial is on whenever it changes,
+    /// for the now-playing display
+    pub fn subscribe_now_playing(&mut self) -> Receiver<NowPlaying> {
+        let (sender, receiver) = channel();
+        self.now_playing.push(sender);
+        let title = self.get_current_station().current_title();
+        self.publish_now_playing(title);
+        receiver
+    }
+    /// Tells event stream clients where the dial is now
+    fn publish_tuned(&mut self) {
+        let station = self.current_station.to_string();
+        let current = self.get_current_station();
+        let event = RadioEvent::Tuned {
+            station,
+            name: current.name(),
+            on_air: current.is_on_air() && !current.is_restricted(),
+        };
+        self.events.publish(&event);
+        let title = self.get_current_station().current_title();
+        self.publish_now_playing(title);
+    }
+    /// Tells now-playing subscribers what the current station is playing
+    /// 
+    /// Subscribers that have gone away are dropped.
+    fn publish_now_playing(&mut self, title: Option<String>) {
+        if self.now_playing.is_empty() {
+            return;
+        }
+        let station = self.current_station.to_string();
+        let paused = self.paused_since.is_some();
+        let current = self.get_current_station();
+        let now_playing = NowPlaying {
+            station,
+            name: current.name(),
+            title,
+            on_air: current.is_on_air() && !current.is_restricted(),
+            paused,
+            elapsed: current.position(),
         };
+        self.now_playing.retain(|subscriber| subscriber.send(now_playing.clone()).is_ok());
+    }
+    /// Opens the monitor output on the named device
+    /// 
+    /// A missing monitor device is logged and the radio runs speaker-only.
+    fn open_monitor(device_name: &str) -> Option<Monitor> {
+        let device = rodio::cpal::default_host().output_devices().ok()?
+            .find(|device| device.name().is_ok_and(|name| name == device_name));
+        let Some(device) = device else {
+            eprintln!("Monitor device {} not found", device_name);
+            return None;
+        };
+
+        match OutputStreamBuilder::from_device(device).and_then(|builder| output::configured(builder).open_stream()) {
+            Ok(output) => {
+                let sink = Sink::connect_new(output.mixer());
+                sink.append(monitor_source());
+                Some(Monitor { _output: output, _sink: sink, station: None })
+            },
+            Err(e) => {
+                eprintln!("Failed to open monitor device {}: {}", device_name, e);
+                None
+            }
+        }
+    }
+    /// Looks up a profile in radio.info, logging names that aren't there
+    fn profile_config(name: &str) -> Option<&'static ProfileConfig> {
+        let profile_config = radio_config().profiles.get(name);
+        if profile_config.is_none() {
+            eprintln!("No profile named {}", name);
+        }
+        profile_config
+    }
+    /// Builds every band's stations for a dial layout
+    fn initialize_bands(
+        profile: Option<&str>,
+        output: &Mixer
+    ) -> Vec<[Station; constants::NUMBER_OF_STATIONS]> {
+        let profile_config = profile.and_then(Radio::profile_config);
+
+        // One station array per band configured in radio.info
+        let mut bands: Vec<[Station; constants::NUMBER_OF_STATIONS]> = Band::all()
+            .map(|band| Radio::initialize_station_array(band, profile_config, output))
+            .collect();
+        Radio::assign_genre_stations(&mut bands, output);
+        if let Some(profile_config) = profile_config {
+            bands.iter_mut().for_each(|band| band.iter_mut().for_each(|station| {
+                station.exclude_ratings(&profile_config.exclude_ratings);
+            }));
+        }
 
-        radio
+        bands
     }
     fn initialize_station_array( 
         band: Band,
-        output: &OutputStream
+        profile_config: Option<&ProfileConfig>,
+        output: &Mixer
     ) -> [Station; constants::NUMBER_OF_STATIONS] {
 
-        let station_array = array::from_fn(|station_number: usize| {
-            let station_path_string = format!(
-                "{}/{:?}/{:02}/",
-                STATION_PATH,
-                band,
-                station_number
-            );
-            let station_path = Path::new(&station_path_string);
+        array::from_fn(|station_number: usize| {
+            let slot_path = Radio::station_path(band, station_number);
+            let assignment = profile_config.map_or(SlotAssignment::Standard, |profile_config| {
+                profile_config.assignment(band, station_number)
+            });
+            let station_path = match assignment {
+                SlotAssignment::Station(station_path) => station_path,
+                SlotAssignment::Standard => slot_path.clone(),
+                SlotAssignment::Empty => return Station::new_dead(&slot_path)
+            };
             if station_path.exists() {
-                Station::new(station_path, output)
+                Station::new(&station_path, output)
             } else {
-                Station::new_dead(station_path)
+                Station::new_dead(&station_path)
             }
-        });
+        })
+    }
+    /// Returns the folder of the station at a dial slot
+    pub fn station_path(band: Band, index: usize) -> PathBuf {
+        PathBuf::from(format!(
+            "{}/{}/{:02}/",
+            stations_root().display(),
+            band.directory(),
+            index
+        ))
+    }
+    /// Fills vacant dial slots with one virtual station per library genre
+    fn assign_genre_stations(
+        bands: &mut [[Station; constants::NUMBER_OF_STATIONS]],
+        output: &Mixer
+    ) {
+        let Some(genre_library) = &radio_config().genre_library else {return;};
+        let mut genres = split_library_by_genre(Path::new(&genre_library.path)).into_iter();
 
-        station_array
+        for band in Band::all() {
+            if genre_library.band.as_ref().is_some_and(|name| name != band.name()) {
+                continue;
+            }
+            for (index, station) in bands[band.0].iter_mut().enumerate() {
+                if !station.is_vacant() {continue;}
+                let Some((genre, tracks)) = genres.next() else {return;};
+                println!("genre {} on {}", genre, StationID { band, index });
+                *station = Station::new_virtual(genre, tracks, &Radio::station_path(band, index), output);
+            }
+        }
+
+        let unplaced: Vec<String> = genres.map(|(genre, _)| genre).collect();
+        if !unplaced.is_empty() {
+            eprintln!("No free dial slots for genres: {}", unplaced.join(", "));
+        }
     }
     fn initialize_volume_profile(
         band:&[Station; constants::NUMBER_OF_STATIONS],
@@ -120,104 +351,510 @@ impl Radio {
     pub fn station_off_air(&mut self, station_id:StationID) {
         self.update_volume_profile(station_id, false);
         self.get_station(station_id).go_off_air();
+
+        if self.current_station == station_id {
+            self.tune(self.current_dial_position);
+        }
     }
     fn update_volume_profile(&mut self, station_id:StationID, on_air:bool) {
+        // Restricted stations are static on the dial, whatever they're playing
+        let on_air = on_air && !self.get_station(station_id).is_restricted();
         let start = station_id.index * constants::TICKS_PER_STATION;
         let end = ( 1 + station_id.index ) * constants::TICKS_PER_STATION;
         let updated_profile = if on_air {&self.station_volume_profile}else{&[0.0f32;constants::TICKS_PER_STATION]};
-        if station_id.band == Band::AM {
-            self.am_volume_profile[start..end].clone_from_slice(updated_profile);
-        }else{
-            self.fm_volume_profile[start..end].clone_from_slice(updated_profile);
-        };
+        self.volume_profiles[station_id.band.0][start..end].clone_from_slice(updated_profile);
     }
     pub fn tune(&mut self, new_dial_position:usize) {
         self.current_dial_position = new_dial_position;
         let station_index = new_dial_position/constants::TICKS_PER_STATION;
         if station_index != self.current_station.index {
-            self.get_current_station().pause();
+            let previous_station = self.current_station;
             self.current_station.index = station_index;
-            self.get_current_station().unpause();
+            self.release_station(previous_station);
+            self.fade_in(self.current_station);
             self.update_skip_conditions();
+            self.route_channels();
+            self.publish_tuned();
         }
-        let volume = self.get_station_volume();
-        self.get_current_station().set_volume(volume);
-        self.white_noise.set_volume(1.0 - volume);
+        self.update_dial_volumes();
+    }
+    /// Moves the dial to the middle of a station's slot
+    pub fn tune_to(&mut self, station_id: StationID) {
+        if station_id.band != self.current_station.band {
+            self.switch_band(station_id.band);
+        }
+        self.tune(station_id.index * constants::TICKS_PER_STATION + constants::TICKS_PER_STATION / 2);
     }
     pub fn switch_band(&mut self, new_band: Band) {
-        self.get_current_station().pause();
+        if new_band.0 >= self.bands.len() {
+            eprintln!("Ignoring switch to unconfigured band {}", new_band.0);
+            return;
+        }
+        println!("switched to {}", new_band);
+        let previous_station = self.current_station;
         self.current_station.band = new_band;
-        let volume = self.get_station_volume();
-        self.white_noise.set_volume(1.0 - volume);
-        let current_station = self.get_current_station();
-        current_station.set_volume(volume);
-        current_station.unpause();
+        self.release_station(previous_station);
+        self.update_dial_volumes();
+        self.fade_in(self.current_station);
         self.update_skip_conditions();
+        self.route_channels();
+        self.publish_tuned();
+    }
+    /// Sets station, static and test-pattern volumes for the dial position
+    /// 
+    /// Off-air stations are handled here according to `off_air` in
+    /// radio.info, so a dead frequency can sound different from the static
+    /// between stations.
+    fn update_dial_volumes(&mut self) {
+        let volume = self.get_station_volume();
+        self.set_station_volume(self.current_station, volume);
+
+        // How squarely the dial sits on the current frequency, on air or not
+        let tuned_in = self.station_volume_profile[self.effective_dial_position() % constants::TICKS_PER_STATION];
+        let off_air_level = if self.get_current_station().is_on_air() {0.0} else {tuned_in};
+
+        let (static_volume, tone_volume) = match radio_config().off_air {
+            OffAirBehavior::Static => (1.0 - volume, 0.0),
+            OffAirBehavior::Silence => (1.0 - volume - off_air_level, 0.0),
+            OffAirBehavior::TestPattern => (1.0 - volume - off_air_level, off_air_level),
+        };
+        self.white_noise_level.set(static_volume.max(self.tuning.static_floor));
+        self.test_pattern_level.set(tone_volume);
+
+        self.update_bleed();
+    }
+    /// Lets the nearer neighboring station bleed through near a slot's edge
+    fn update_bleed(&mut self) {
+        let tick = self.effective_dial_position() % constants::TICKS_PER_STATION;
+        let (direction, bleed_volume) = utilities::neighbor_bleed(tick, &self.tuning);
+
+        let neighbor = self.current_station.index.checked_add_signed(direction)
+            .filter(|index| *index < constants::NUMBER_OF_STATIONS)
+            .map(|index| StationID { band: self.current_station.band, index })
+            .filter(|_| bleed_volume > 0.0);
+        let neighbor = neighbor.filter(|id| self.get_station(*id).is_on_air() && !self.get_station(*id).is_restricted())
+            .filter(|id| self.split_station != Some(*id));
+
+        if self.bleeding_station != neighbor {
+            if let Some(previous) = self.bleeding_station.take() {
+                self.release_station(previous);
+            }
+            if let Some(id) = neighbor {
+                self.fade_in(id);
+            }
+            self.bleeding_station = neighbor;
+        }
+        if let Some(id) = neighbor {
+            self.set_station_volume(id, bleed_volume);
+        }
+    }
+    /// Quiets a station the dial has moved away from
+    /// 
+    /// A station that is still current, bleeding through, or on the right
+    /// channel of a split keeps playing; a monitored station just drops out
+    /// of the speaker. Others fade out and are paused once the grace period
+    /// is over, so swinging the dial back and forth doesn't stop and start
+    /// them.
+    fn release_station(&mut self, station_id: StationID) {
+        if station_id == self.current_station || self.bleeding_station == Some(station_id) || self.split_station == Some(station_id) {
+            return;
+        }
+        let then_pause = self.monitored_station() != Some(station_id);
+        self.start_fade(station_id, 0.0, then_pause);
+    }
+    /// Unpauses a station the dial has reached, fading it in from silence
+    /// 
+    /// A station still fading out picks up from where it had got to. The
+    /// fade heads for the station's current volume until
+    /// `set_station_volume` gives it the dial's. While the whole radio is
+    /// paused the station stays paused until it resumes.
+    fn fade_in(&mut self, station_id: StationID) {
+        let paused = self.paused_since.is_some();
+        let station = self.get_station(station_id);
+        let to = station.volume();
+        if station.is_paused() {
+            station.set_volume(0.0);
+        }
+        if !paused {
+            station.unpause();
+        }
+        self.start_fade(station_id, to, false);
+    }
+    /// Starts a ramp from a station's current volume, replacing any in progress
+    fn start_fade(&mut self, station_id: StationID, to: f32, then_pause: bool) {
+        self.fades.retain(|fade| fade.station_id != station_id);
+        let from = self.get_station(station_id).volume();
+        self.fades.push(Fade { station_id, from, to, started: Instant::now(), then_pause });
+    }
+    /// Sets a station's volume, or retargets the fade-in it's part way through
+    fn set_station_volume(&mut self, station_id: StationID, volume: f32) {
+        match self.fades.iter_mut().find(|fade| fade.station_id == station_id && !fade.then_pause) {
+            Some(fade) => fade.to = volume,
+            None => self.get_station(station_id).set_volume(volume)
+        }
+    }
+    /// Moves every fade along, pausing stations that have faded out
+    fn step_fades(&mut self) {
+        let fades = std::mem::take(&mut self.fades);
+        for fade in fades {
+            let elapsed = fade.started.elapsed();
+            let progress = (elapsed.as_secs_f32() / constants::STATION_FADE.as_secs_f32()).min(1.0);
+            self.get_station(fade.station_id).set_volume(fade.from + (fade.to - fade.from) * progress);
+
+            if progress < 1.0 || (fade.then_pause && elapsed < constants::STATION_FADE + constants::TUNE_AWAY_GRACE) {
+                self.fades.push(fade);
+            } else if fade.then_pause {
+                self.get_station(fade.station_id).pause();
+            }
+        }
+    }
+    fn monitored_station(&self) -> Option<StationID> {
+        self.monitor.as_ref().and_then(|monitor| monitor.station)
+    }
+    /// Puts a station on the monitor output, or silences the monitor
+    /// 
+    /// The monitored station plays regardless of the dial, muted on the
+    /// speaker unless the dial is on it too.
+    pub fn monitor_station(&mut self, station_id: Option<StationID>) {
+        let Some(monitor) = self.monitor.as_mut() else {
+            eprintln!("No monitor output configured");
+            return;
+        };
+        if let Some(id) = station_id.filter(|id| id.band.0 >= self.bands.len() || id.index >= constants::NUMBER_OF_STATIONS) {
+            eprintln!("Ignoring monitor request for unknown station {}", id);
+            return;
+        }
+
+        let previous = std::mem::replace(&mut monitor.station, station_id);
+        if let Some(previous) = previous {
+            self.get_station(previous).set_monitored(false);
+            self.release_station(previous);
+        }
+        if let Some(id) = station_id {
+            println!("monitoring {}", id);
+            // A station fading out after the dial left it keeps playing now
+            self.fades.retain(|fade| fade.station_id != id || !fade.then_pause);
+            let is_audible = id == self.current_station || self.bleeding_station == Some(id);
+            let paused = self.paused_since.is_some();
+            let station = self.get_station(id);
+            station.set_monitored(true);
+            if !paused {
+                station.unpause();
+            }
+            if !is_audible {
+                station.set_volume(0.0);
+            }
+        }
+    }
+    /// Puts a station on the right channel, leaving the dial the left, or
+    /// ends the split
+    /// 
+    /// Experimental, and only with `dual_mono` on in radio.info. The split
+    /// station plays at full volume whatever the dial does; if the dial
+    /// lands on it, it plays on both channels until the dial moves on.
+    pub fn split_station(&mut self, station_id: Option<StationID>) {
+        if !radio_config().dual_mono {
+            eprintln!("Dual-mono output is not enabled");
+            return;
+        }
+        if let Some(id) = station_id.filter(|id| id.band.0 >= self.bands.len() || id.index >= constants::NUMBER_OF_STATIONS) {
+            eprintln!("Ignoring split request for unknown station {}", id);
+            return;
+        }
+
+        let previous = std::mem::replace(&mut self.split_station, station_id);
+        if let Some(id) = station_id {
+            println!("{} on the right channel", id);
+            // A station fading out after the dial left it keeps playing now
+            self.fades.retain(|fade| fade.station_id != id || !fade.then_pause);
+            if id != self.current_station {
+                self.fade_in(id);
+            }
+        } else {
+            println!("dual-mono split ended");
+        }
+        self.route_channels();
+        if let Some(previous) = previous.filter(|previous| Some(*previous) != station_id) {
+            self.release_station(previous);
+        }
+    }
+    /// Sends every station, the static and the off-air tone to their speaker channels
+    /// 
+    /// Without a split everything plays on both. With one, the split
+    /// station takes the right channel at full volume (both, if the dial
+    /// is on it) and everything else on the dial is folded onto the left.
+    fn route_channels(&mut self) {
+        let (split, current) = (self.split_station, self.current_station);
+        self.dial_route.set(if split.is_some() {Channels::Left} else {Channels::Both});
+        self.bands.iter_mut().enumerate().for_each(|(band_index, band)| {
+            band.iter_mut().enumerate().for_each(|(index, station)| {
+                let station_id = StationID { band: Band(band_index), index };
+                let channels = match split {
+                    Some(split) if split == station_id && split != current => Channels::Right,
+                    Some(split) if split != station_id => Channels::Left,
+                    _ => Channels::Both
+                };
+                station.set_channels(channels);
+            });
+        });
+        if let Some(split) = split.filter(|split| *split != current) {
+            self.set_station_volume(split, 1.0);
+        }
     }
     fn update_skip_conditions(&mut self) {
         self.has_skipped_since_last_station_switch = false;
         self.last_station_switch = Instant::now();
     }
     fn get_station_volume(&self) -> f32 {
-        if self.current_station.band == Band::AM {
-            self.am_volume_profile[self.current_dial_position]
-        }
-        else{
-            self.fm_volume_profile[self.current_dial_position]
+        self.volume_profiles[self.current_station.band.0][self.effective_dial_position()]
+    }
+    /// Dial position after frequency drift, kept within the current station's slot
+    fn effective_dial_position(&self) -> usize {
+        let tick = self.current_dial_position % constants::TICKS_PER_STATION;
+        let drifted_tick = (tick as f32 + self.drift_offset)
+            .round()
+            .clamp(0.0, (constants::TICKS_PER_STATION - 1) as f32) as usize;
+        self.current_station.index * constants::TICKS_PER_STATION + drifted_tick
+    }
+    /// Takes one step of the frequency drift random walk when it's due
+    fn drift(&mut self) {
+        let drift = &radio_config().drift;
+        if !drift.enabled || self.last_drift_step.elapsed() < Duration::from_millis(drift.interval_ms) {
+            return;
         }
+        self.last_drift_step = Instant::now();
+
+        let step_ticks = drift.step_ticks.abs();
+        let step = rand::random_range(-step_ticks..=step_ticks);
+        self.drift_offset = (self.drift_offset + step).clamp(-drift.max_ticks.abs(), drift.max_ticks.abs());
+        self.update_dial_volumes();
     }
     fn get_current_station(&mut self) -> &mut Station {
-        if self.current_station.band == Band::AM {
-            self.am.get_mut(self.current_station.index).unwrap()
-        }
-        else {
-            self.fm.get_mut(self.current_station.index).unwrap()
-        }
+        self.bands[self.current_station.band.0].get_mut(self.current_station.index).unwrap()
     }
     fn get_station(&mut self, id: StationID) -> &mut Station {
-        if id.band == Band::AM {
-            self.am.get_mut(id.index).unwrap()
-        }
-        else {
-            self.fm.get_mut(id.index).unwrap()
-        }
+        self.bands[id.band.0].get_mut(id.index).unwrap()
     }
     pub fn run(
         &mut self, 
         input_events: Receiver<messages::InputEvent>,
         file_requester: Sender<messages::FileRequest>,
-        file_returns: Receiver<messages::FileResponse>
+        file_returns: Receiver<messages::FileResponse>,
+        commands: Receiver<messages::RadioCommand>
     ) {
         self.prime_stations(&file_requester);
         println!("radio on and ready");
         loop {
             while let Ok(input_event) = input_events.try_recv() {
+                crash_report::remember_event(format!("{:?}", input_event));
                 self.resolve_input_event(input_event);
                 sleep(constants::KNOB_DELAY);
             }
+            // The radio runs until its API handles are all dropped or it's told to stop
+            loop {
+                match commands.try_recv() {
+                    Ok(RadioCommand::Shutdown) | Err(TryRecvError::Disconnected) => {
+                        self.shut_down();
+                        return;
+                    },
+                    Ok(command) => self.resolve_command(command, &file_requester),
+                    Err(TryRecvError::Empty) => break
+                }
+            }
+            while let Ok(playback_event) = self.playback_event_receiver.try_recv() {
+                crash_report::remember_event(format!("{:?}", playback_event));
+                self.handle_playback_event(playback_event);
+            }
+            while let Ok((station_id, rescanned)) = self.rescan_receiver.try_recv() {
+                self.apply_rescan(station_id, rescanned, &file_requester);
+            }
             if let Ok(file_response) = file_returns.try_recv(){
                 self.handle_file_return(file_response);
             }
-            if self.get_current_station().is_on_air() {self.manage_current_station(&file_requester);}
-            if !self.has_skipped_since_last_station_switch && self.last_station_switch.elapsed() > constants::TIME_BETWEEN_SKIPS {
+            self.drift();
+            self.step_fades();
+            self.apply_broadcast_hours();
+            self.retry_faulted_stations(&file_requester);
+            self.send_due_retries(&file_requester);
+            self.manage_station(self.current_station, &file_requester);
+            if let Some(monitored) = self.monitored_station().filter(|id| *id != self.current_station) {
+                self.manage_station(monitored, &file_requester);
+            }
+            if let Some(split) = self.split_station.filter(|id| *id != self.current_station) {
+                self.manage_station(split, &file_requester);
+            }
+            // Radio time stands still while paused, so nothing turns over
+            if self.paused_since.is_none() && !self.has_skipped_since_last_station_switch && self.last_station_switch.elapsed() > Duration::from_secs(radio_config().turnover_secs) {
                 self.skip_dormant_stations(&file_requester);
                 self.has_skipped_since_last_station_switch = true;
             }
+            if self.last_state_save.elapsed() >= state::SAVE_INTERVAL {
+                self.save_state();
+            }
+            self.note_crash_context();
             sleep(constants::LOOP_DELAY);
         }
         
     }
-    fn manage_current_station( &mut self, file_requester: &Sender<messages::FileRequest> ) {
-        let current_station = self.get_current_station();
-        if current_station.needs_next() {
-            if let Some(file_path) = current_station.next() {
+    /// Pauses every station and the static, or resumes them
+    /// 
+    /// Unlike tuning away, a pause stops radio time: the turnover clock is
+    /// held, so dormant stations aren't skipped ahead for the time spent
+    /// paused. The dial can still be turned; the stations it lands on
+    /// start when the radio resumes.
+    pub fn pause_all(&mut self, paused: bool) {
+        if paused == self.paused_since.is_some() {
+            return;
+        }
+        if paused {
+            println!("radio paused");
+            self.paused_since = Some(Instant::now());
+            self.bands.iter_mut().for_each(|band| band.iter_mut().for_each(|station| station.pause()));
+            self.white_noise.pause();
+            self.test_pattern.pause();
+        } else if let Some(paused_since) = self.paused_since.take() {
+            println!("radio resumed");
+            self.last_station_switch += paused_since.elapsed();
+            let audible = [Some(self.current_station), self.bleeding_station, self.monitored_station(), self.split_station];
+            audible.into_iter().flatten().for_each(|station_id| self.get_station(station_id).unpause());
+            self.white_noise.play();
+            self.test_pattern.play();
+        }
+        self.events.publish(&RadioEvent::Paused { paused });
+        let title = self.get_current_station().current_title();
+        self.publish_now_playing(title);
+    }
+    /// Silences the output while every station plays on, or unmutes it
+    pub fn mute(&mut self, muted: bool) {
+        if muted == self.output.volume().is_muted() {
+            return;
+        }
+        println!("radio {}", if muted {"muted"} else {"unmuted"});
+        self.output.volume().set_muted(muted);
+        self.events.publish(&RadioEvent::Muted { muted });
+    }
+    /// Keeps what a crash report would say about the radio up to date
+    /// 
+    /// The crash report is only updated when the dial or a queue changed.
+    fn note_crash_context(&mut self) {
+        let manager_state = ManagerState {
+            station: self.current_station,
+            dial_position: self.current_dial_position,
+            sink_length: self.get_current_station().queued_sources(),
+            fades: self.fades.len(),
+            stale_loads: self.stale_loads.len(),
+            pending_retries: self.pending_retries.len(),
+        };
+        if self.noted_state != Some(manager_state) {
+            self.noted_state = Some(manager_state);
+            crash_report::note_state(manager_state);
+        }
+    }
+    /// Silences every station before the manager loop returns
+    fn shut_down(&mut self) {
+        println!("radio shutting down");
+        self.save_state();
+        self.bands.iter_mut().for_each(|band| band.iter_mut().for_each(|station| station.pause()));
+        self.white_noise.pause();
+        self.test_pattern.pause();
+        self.output.mute();
+    }
+    /// Requests again the loads whose retry backoff is over
+    fn send_due_retries(&mut self, file_requester: &Sender<messages::FileRequest>) {
+        if self.pending_retries.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending_retries).into_iter()
+            .partition(|(due_at, ..)| *due_at <= now);
+        self.pending_retries = waiting;
+        for (_, station_id, file_path, intro) in due {
+            file_requester.send(FileRequest::LoadTrack { station_id, file_path, intro }).ok();
+        }
+    }
+    /// Saves where the dial is and how far every station has got (see `state`)
+    fn save_state(&mut self) {
+        self.last_state_save = Instant::now();
+        state::save_radio_state(self.current_station.band, self.current_dial_position);
+        self.bands.iter_mut().for_each(|band| band.iter_mut().for_each(|station| station.save_playback()));
+    }
+    /// Signs stations on and off according to their broadcast hours
+    /// 
+    /// Live stations start and stop their scheduled streams here too.
+    /// Listening restrictions are checked at the same time; a station that
+    /// becomes restricted or available again just changes how it sounds on
+    /// the dial.
+    fn apply_broadcast_hours(&mut self) {
+        if self.last_schedule_check.is_some_and(|checked| checked.elapsed() < constants::SCHEDULE_CHECK_INTERVAL) {
+            return;
+        }
+        self.last_schedule_check = Some(Instant::now());
 
-                let request = FileRequest::LoadTrack { 
-                    station_id: self.current_station, 
-                    file_path
-                };
-                file_requester.send(request);
+        let now = chrono::Local::now().time();
+        let utc_now = chrono::Utc::now();
+        let profile = self.profile.as_deref();
+        let mut changes: Vec<(StationID, bool)> = Vec::new();
+        let mut restriction_changes: Vec<StationID> = Vec::new();
+        self.bands.iter_mut().enumerate().for_each(|(band_index, band)| {
+            band.iter_mut().enumerate().for_each(|(index, station)| {
+                if let Some(signed_on) = station.update_broadcast_hours(now) {
+                    changes.push((StationID { band: Band(band_index), index }, signed_on));
+                }
+                if let Some(streaming) = station.update_live(utc_now) {
+                    changes.push((StationID { band: Band(band_index), index }, streaming));
+                }
+                if station.update_restrictions(now, profile).is_some() {
+                    restriction_changes.push(StationID { band: Band(band_index), index });
+                }
+            });
+        });
+
+        restriction_changes.into_iter().for_each(|station_id| {
+            let station = self.get_station(station_id);
+            println!("{} {}", station_id, if station.is_restricted() {"restricted"} else {"available"});
+            let is_on_air = station.is_on_air();
+            self.update_volume_profile(station_id, is_on_air);
+            if station_id == self.current_station {
+                self.update_dial_volumes();
             }
+        });
+
+        changes.into_iter().for_each(|(station_id, signed_on)| {
+            if signed_on {
+                println!("{} signing on", station_id);
+                self.station_on_air(station_id);
+                if station_id == self.current_station {
+                    self.fade_in(station_id);
+                }
+            } else {
+                println!("{} signing off", station_id);
+                self.station_off_air(station_id);
+            }
+        });
+    }
+    /// Requests a track for each faulted station whose retry time has come
+    /// 
+    /// A station that loads it goes back on air; one that fails again is
+    /// faulted for another retry interval.
+    fn retry_faulted_stations(&mut self, file_requester: &Sender<messages::FileRequest>) {
+        self.bands.iter_mut().enumerate().for_each(|(band_index, band)| {
+            band.iter_mut().enumerate().for_each(|(index, station)| {
+                let Some(file_path) = station.retry_faulted() else {return;};
+                let station_id = StationID { band: Band(band_index), index };
+                println!("{} retrying after load errors", station_id);
+                let intro = station.take_intro();
+                file_requester.send(station.load_request(station_id, file_path, intro)).ok();
+            });
+        });
+    }
+    /// Keeps a playing station's queue topped up
+    fn manage_station( &mut self, station_id: StationID, file_requester: &Sender<messages::FileRequest> ) {
+        let current_station = self.get_station(station_id);
+        if !current_station.is_on_air() {return;}
+        if current_station.needs_next() && let Some(file_path) = current_station.next_track() {
+            let intro = current_station.take_intro();
+            let request = current_station.load_request(station_id, file_path, intro);
+            file_requester.send(request).ok();
         }
     }
     fn resolve_input_event(&mut self, input_event:InputEvent) {
@@ -227,66 +864,493 @@ impl Radio {
             },
             InputEvent::BandSwitched { new_band } => {
                 self.switch_band(new_band);
+            },
+            InputEvent::Seek { offset_seconds } => {
+                self.get_current_station().seek(SeekTo::Relative(offset_seconds));
+            },
+            InputEvent::ButtonPressed { button: RadioButton::Pause } => {
+                self.pause_all(self.paused_since.is_none());
+            },
+            InputEvent::ButtonPressed { button: RadioButton::Mute } => {
+                self.mute(!self.output.volume().is_muted());
+            }
+        }
+    }
+    fn resolve_command(&mut self, command: RadioCommand, file_requester: &Sender<messages::FileRequest>) {
+        match command {
+            RadioCommand::MonitorStation { station_id } => {
+                self.monitor_station(station_id);
+            },
+            RadioCommand::SplitStation { station_id } => {
+                self.split_station(station_id);
+            },
+            RadioCommand::EditQueue { station_id, edit, reply } => {
+                let result = self.edit_queue(station_id, edit, file_requester);
+                let _ = reply.send(result);
+            },
+            RadioCommand::ReloadPlaylists => {
+                self.reload_playlists();
+            },
+            RadioCommand::Tune { station_id } => {
+                self.tune_to(station_id);
+            },
+            RadioCommand::SkipTrack => {
+                self.get_current_station().skip_track();
+            },
+            RadioCommand::Seek { to } => {
+                self.get_current_station().seek(to);
+            },
+            RadioCommand::SetVolume { level } => {
+                self.output.volume().set(level);
+                self.events.publish(&RadioEvent::VolumeChanged { level: self.output.volume().level() });
+            },
+            RadioCommand::PauseAll { paused } => {
+                self.pause_all(paused);
+            },
+            RadioCommand::Mute { muted } => {
+                self.mute(muted);
+            },
+            RadioCommand::SelectProfile { name } => {
+                self.select_profile(name, file_requester);
+            },
+            RadioCommand::Status { reply } => {
+                let _ = reply.send(self.status());
+            },
+            RadioCommand::Snapshot { reply } => {
+                let _ = reply.send(self.snapshot());
+            },
+            RadioCommand::Shutdown => {},
+            RadioCommand::StartFrom { station_id, start_from } => {
+                self.start_chronologic_from(station_id, &start_from, file_requester);
+            },
+            RadioCommand::ChronologicPosition { station_id, reply } => {
+                let position = self.bands.get(station_id.band.0)
+                    .and_then(|band| band.get(station_id.index))
+                    .and_then(|station| station.chronologic_position());
+                let _ = reply.send(position);
+            },
+            RadioCommand::PreviewStation { station_id, count, reply } => {
+                let preview = match self.bands.get(station_id.band.0).and_then(|band| band.get(station_id.index)) {
+                    Some(station) => station.preview(count),
+                    None => Vec::new()
+                };
+                // The caller may have given up waiting
+                let _ = reply.send(preview);
+            },
+            RadioCommand::CloneStation { from, to, name, playlist, reply } => {
+                let result = self.clone_station(from, to, name.as_deref(), playlist.as_deref(), file_requester);
+                let _ = reply.send(result);
+            },
+            RadioCommand::EditStation { station_id, settings, reply } => {
+                let result = self.edit_station(station_id, settings, file_requester);
+                let _ = reply.send(result);
+            }
+        }
+    }
+    /// Switches to another dial layout
+    /// 
+    /// Every station is rebuilt from the profile and primed again; the dial
+    /// stays where it is. Tracks the old stations were still loading are
+    /// discarded when they arrive rather than played on the new stations.
+    /// 
+    /// # Arguments
+    /// * `profile` - Profile from radio.info, or `None` for the standard layout
+    pub fn select_profile(&mut self, profile: Option<String>, file_requester: &Sender<messages::FileRequest>) {
+        if profile.as_deref().is_some_and(|name| Radio::profile_config(name).is_none()) || profile == self.profile {
+            return;
+        }
+        println!("switching to {} profile", profile.as_deref().unwrap_or("standard"));
+
+        self.stale_loads = self.bands.iter().enumerate()
+            .flat_map(|(band_index, band)| band.iter().enumerate().flat_map(move |(index, station)| {
+                station.queued_paths().into_iter().map(move |file_path| (StationID { band: Band(band_index), index }, file_path))
+            }))
+            .collect();
+        self.bands.iter_mut().for_each(|band| band.iter_mut().for_each(|station| station.pause()));
+
+        self.bands = Radio::initialize_bands(profile.as_deref(), self.output.mixer());
+        self.volume_profiles = self.bands.iter()
+            .map(|band| Radio::initialize_volume_profile(band, &self.station_volume_profile))
+            .collect();
+        self.profile = profile;
+        self.bleeding_station = None;
+        self.fades.clear();
+        if let Some(monitored) = self.monitored_station() {
+            let is_current = monitored == self.current_station;
+            let paused = self.paused_since.is_some();
+            let station = self.get_station(monitored);
+            station.set_monitored(true);
+            if !paused {
+                station.unpause();
+            }
+            if !is_current {
+                station.set_volume(0.0);
             }
         }
+
+        self.split_station = self.split_station.filter(|id| id.band.0 < self.bands.len());
+        if let Some(split) = self.split_station.filter(|id| *id != self.current_station) {
+            self.fade_in(split);
+        }
+
+        self.prime_stations(file_requester);
+        self.last_schedule_check = None;
+        self.apply_broadcast_hours();
+        self.fade_in(self.current_station);
+        self.route_channels();
+        self.update_skip_conditions();
+        self.update_dial_volumes();
+    }
+    /// Sets up an empty slot as a copy of another station and puts it on the dial
+    /// 
+    /// With a profile selected, the slot may be showing another folder, so
+    /// the copy only turns up once the standard layout is selected again.
+    /// 
+    /// # Returns
+    /// Why the station couldn't be cloned, if it couldn't
+    pub fn clone_station(
+        &mut self,
+        from: StationID,
+        to: StationID,
+        name: Option<&str>,
+        playlist: Option<&Path>,
+        file_requester: &Sender<messages::FileRequest>
+    ) -> Result<(), String> {
+        if from == to {
+            return Err(format!("{} can't be cloned onto itself", from));
+        }
+        let to_path = Radio::station_path(to.band, to.index);
+        cloning::clone_station(&Radio::station_path(from.band, from.index), &to_path, name, playlist)
+            .map_err(|e| format!("failed to clone {} to {}: {}", from, to, e))?;
+        println!("cloned {} to {}", from, to);
+
+        if self.profile.is_none() {
+            self.load_station(to, file_requester);
+        }
+        Ok(())
+    }
+    /// Changes fields of a station's station.info and puts the edited station on the dial
+    /// # Returns
+    /// Why the station couldn't be edited, if it couldn't
+    pub fn edit_station(
+        &mut self,
+        station_id: StationID,
+        settings: Map<String, Value>,
+        file_requester: &Sender<messages::FileRequest>
+    ) -> Result<(), String> {
+        StationConfig::edit(&Radio::station_path(station_id.band, station_id.index), settings)
+            .map_err(|e| format!("failed to edit {}: {}", station_id, e))?;
+        println!("edited {}", station_id);
+
+        if self.profile.is_none() {
+            self.load_station(station_id, file_requester);
+        }
+        Ok(())
+    }
+    /// Replaces a slot's station with a fresh one read from its folder
+    fn load_station(&mut self, station_id: StationID, file_requester: &Sender<messages::FileRequest>) {
+        let mut station = Station::new(&Radio::station_path(station_id.band, station_id.index), self.output.mixer());
+        let primed_paths = station.prime_content();
+        let generated = station.is_generated();
+        primed_paths.iter().for_each(|file_path| {
+            file_requester.send(station.load_request(station_id, file_path.clone(), None)).ok();
+        });
+        *self.get_station(station_id) = station;
+
+        // Stations with tracks go on air as the first one loads
+        if generated {
+            self.station_on_air(station_id);
+        } else if primed_paths.is_empty() {
+            self.station_off_air(station_id);
+        }
+        if station_id == self.current_station {
+            self.fade_in(station_id);
+        }
+        self.route_channels();
+        self.last_schedule_check = None;
+    }
+    /// Hot-reloads playlists whose folders changed on disk
+    /// 
+    /// The folders are scanned on a background thread (see
+    /// `station::rescan`), so the dial keeps working through a big rescan;
+    /// each changed playlist is swapped in as its result comes back.
+    pub fn reload_playlists(&mut self) {
+        let jobs: Vec<(StationID, RescanJob)> = self.bands.iter().enumerate()
+            .flat_map(|(band_index, band)| band.iter().enumerate()
+                .filter_map(move |(index, station)| Some((StationID { band: Band(band_index), index }, station.rescan_job()?))))
+            .collect();
+        println!("playlist reload started, {} stations to check", jobs.len());
+
+        let rescans = self.rescans.clone();
+        std::thread::spawn(move || {
+            prioritize_current_thread(ThreadRole::BackgroundScan);
+            let mut changed = 0;
+            for (station_id, job) in jobs {
+                let Some(rescanned) = job.run() else {continue;};
+                changed += 1;
+                // The radio has shut down
+                if rescans.send((station_id, rescanned)).is_err() {
+                    return;
+                }
+            }
+            println!("playlist reload done, {} changed", changed);
+        });
+    }
+    /// Swaps in what a background rescan found for a station
+    /// 
+    /// A revived Dead station is loaded from scratch, unless a profile
+    /// has put a different folder on its slot.
+    fn apply_rescan(&mut self, station_id: StationID, rescanned: Rescanned, file_requester: &Sender<messages::FileRequest>) {
+        match rescanned {
+            Rescanned::Playlist { playlist_hash, tracks } => {
+                if self.get_station(station_id).reload(playlist_hash, tracks) {
+                    println!("reloaded playlist for {}", station_id);
+                }
+            },
+            Rescanned::Revived => {
+                let still_dead = self.get_station(station_id).rescan_job().is_some_and(|job| job.dead);
+                if still_dead && self.profile.is_none() {
+                    println!("{} has a playable station.info now, loading it", station_id);
+                    self.load_station(station_id, file_requester);
+                }
+            }
+        }
+    }
+    /// Reports where the dial is and what's playing there
+    pub fn status(&mut self) -> RadioStatus {
+        let station_id = self.current_station;
+        let dial_position = self.current_dial_position;
+        let volume = self.output.volume().level();
+        let paused = self.paused_since.is_some();
+        let muted = self.output.volume().is_muted();
+        let profile = self.profile.clone();
+        let faulted = self.bands.iter().enumerate()
+            .flat_map(|(band_index, band)| band.iter().enumerate().filter(|(_, station)| station.is_faulted())
+                .map(move |(index, _)| StationID { band: Band(band_index), index }.to_string()))
+            .collect();
+        let station = self.get_current_station();
+        RadioStatus {
+            station: station_id.to_string(),
+            band: station_id.band.name().to_string(),
+            index: station_id.index,
+            dial_position,
+            name: station.name(),
+            on_air: station.is_on_air() && !station.is_restricted(),
+            now_playing: station.current_title(),
+            now_playing_origin: station.current_origin().map(ToString::to_string),
+            volume,
+            paused,
+            muted,
+            profile,
+            faulted,
+        }
+    }
+    /// Describes everything the manager holds, for troubleshooting stuck stations
+    pub fn snapshot(&mut self) -> RadioSnapshot {
+        let stations = self.bands.iter().enumerate()
+            .flat_map(|(band_index, band)| band.iter().enumerate()
+                .map(move |(index, station)| station.snapshot(StationID { band: Band(band_index), index })))
+            .collect();
+        RadioSnapshot {
+            status: self.status(),
+            bleeding_station: self.bleeding_station.map(|id| id.to_string()),
+            monitored_station: self.monitored_station().map(|id| id.to_string()),
+            split_station: self.split_station.map(|id| id.to_string()),
+            fading_stations: self.fades.iter().map(|fade| fade.station_id.to_string()).collect(),
+            stale_loads: self.stale_loads.iter().map(|(id, file_path)| (id.to_string(), file_path.clone())).collect(),
+            stations,
+        }
+    }
+    /// Moves a Chronologic station to a new start point from the API
+    fn start_chronologic_from(&mut self, station_id: StationID, start_from: &str, file_requester: &Sender<messages::FileRequest>) {
+        let Some(station) = self.bands.get_mut(station_id.band.0).and_then(|band| band.get_mut(station_id.index)) else {
+            eprintln!("Ignoring start point for unknown station {}", station_id);
+            return;
+        };
+        println!("{} starting from {}", station_id, start_from);
+        if let Some(file_path) = station.start_from(&StartFrom::parse(start_from)) {
+            let intro = station.take_intro();
+            file_requester.send(station.load_request(station_id, file_path, intro)).ok();
+        }
+    }
+    /// Applies an API queue edit and requests any track it puts in the sink
+    fn edit_queue(&mut self, station_id: StationID, edit: QueueEdit, file_requester: &Sender<messages::FileRequest>) -> Result<(), String> {
+        let Some(station) = self.bands.get_mut(station_id.band.0).and_then(|band| band.get_mut(station_id.index)) else {
+            return Err(format!("no station {}", station_id));
+        };
+
+        let request_path = match edit {
+            QueueEdit::Remove(file_path) => station.remove_queued(&file_path),
+            QueueEdit::Pin(ref file_path) | QueueEdit::Inject(ref file_path) | QueueEdit::Announce(ref file_path) => {
+                // Queued files can be purged or quarantined, so only the station's own
+                if !station.can_queue(file_path) {
+                    return Err(format!("{} is not in {}'s playlist", file_path.display(), station_id));
+                }
+                let Some(track) = Track::from_path(file_path) else {
+                    return Err(format!("{} is not a readable audio file", file_path.display()));
+                };
+                match edit {
+                    QueueEdit::Pin(_) => station.pin_next(track),
+                    QueueEdit::Announce(_) => {
+                        station.announce(track);
+                        None
+                    },
+                    _ => {
+                        station.inject(track);
+                        None
+                    }
+                }
+            }
+        };
+
+        if let Some(file_path) = request_path {
+            let intro = station.take_intro();
+            let request = station.load_request(station_id, file_path, intro);
+            file_requester.send(request).ok();
+        }
+        Ok(())
     }
     fn handle_file_return(&mut self, file_response:FileResponse) {
         match file_response {
-            FileResponse::TrackLoaded { station_id, audio_content } => {
-                self.get_station(station_id).push_to_sink(audio_content);
+            FileResponse::TrackLoaded { station_id, file_path, audio_content } => {
+                self.get_station(station_id).load_returned(&file_path);
+                // Requested by a station from before a profile switch
+                if let Some(position) = self.stale_loads.iter().position(|(id, path)| *id == station_id && *path == file_path) {
+                    self.stale_loads.swap_remove(position);
+                    if !self.get_station(station_id).queued_paths().contains(&file_path) {
+                        return;
+                    }
+                }
+                // The rest of a first track already playing from its warmed opening
+                let Some(audio_content) = self.get_station(station_id).continue_warm(&file_path, audio_content) else {
+                    self.get_station(station_id).load_succeeded(&file_path);
+                    return;
+                };
+                self.get_station(station_id).load_succeeded(&file_path);
+                let audio_content = self.playback_source(station_id, file_path, audio_content);
+                let station = self.get_station(station_id);
+                station.push_to_sink(audio_content);
                 self.station_on_air(station_id);
                 
             },
+            FileResponse::TrackWarmed { station_id, file_path, audio_content, length } => {
+                if self.get_station(station_id).warm_start_path().as_ref() != Some(&file_path) {
+                    return;
+                }
+                // Wrapped whole, so the track starts and finishes once however it's split
+                let (opening, continuation) = warm_start(audio_content);
+                let opening = self.playback_source(station_id, file_path.clone(), Box::new(opening));
+                if self.get_station(station_id).start_warm(&file_path, opening, continuation, length) {
+                    self.station_on_air(station_id);
+                }
+            },
+            FileResponse::LoadError { station_id, file_path, kind, error_message, intro } => {
+                eprintln!("{}: {}", station_id, error_message);
+                self.events.publish(&RadioEvent::Error { station: station_id.to_string(), message: error_message });
+                let station = self.get_station(station_id);
+                match kind {
+                    // Transient, so the same track gets another go (after a pause) before it
+                    // counts, and stays on its way so the station doesn't ask for another meanwhile
+                    LoadErrorKind::IoTimeout => if let Some(backoff) = station.retry_load(&file_path) {
+                        println!("{} retrying {} in {}ms", station_id, file_path.display(), backoff.as_millis());
+                        self.pending_retries.push((Instant::now() + backoff, station_id, file_path, intro));
+                        return;
+                    },
+                    LoadErrorKind::DecodeFailed => station.decode_failed(&file_path),
+                    LoadErrorKind::NotFound | LoadErrorKind::UnsupportedFormat => station.forget_track(&file_path),
+                    LoadErrorKind::Inconclusive => {}
+                }
+                self.get_station(station_id).load_returned(&file_path);
+                // A warmed opening can't carry on, so it ends after its buffer
+                self.get_station(station_id).abandon_warm(&file_path);
+                if self.get_station(station_id).load_failed() {
+                    eprintln!("{} off air after {} load errors in a row", station_id, constants::ERROR_BUDGET);
+                    self.station_off_air(station_id);
+                }
+            },
             _ => {}
         }
     }
-    fn prime_stations(&mut self, file_requester: &Sender<messages::FileRequest>) {
-        self.am.iter_mut().enumerate().for_each(|(index, station)| {
-            station.prime_content().iter().for_each(|request_path| {
-                let request = FileRequest::LoadTrack { 
-                    station_id: StationID { band: Band::AM, index }, 
-                    file_path: request_path.clone()
-                };
-                file_requester.send(request);
-            });
+    /// Wraps a station's track for playback
+    /// 
+    /// Applies the station's loudness matching, and reports the track
+    /// starting and finishing as playback events.
+    fn playback_source(&mut self, station_id: StationID, file_path: PathBuf, audio_content: AudioSource) -> AudioSource {
+        // Loudness matching turns any DJ intro up or down along with its track
+        let gain = self.get_station(station_id).loudness_gain(&file_path);
+        let audio_content = audio_content.amplify(gain);
+        let playback_events = self.playback_events.clone();
+        let (started_events, started_path) = (self.playback_events.clone(), file_path.clone());
+        let audio_content = notify_when_started(audio_content, move || {
+            let _ = started_events.send(PlaybackEvent::TrackStarted { station_id, file_path: started_path });
         });
-        self.fm.iter_mut().enumerate().for_each(|(index, station)| {
-            station.prime_content().iter().for_each(|request_path| {
-                let request = FileRequest::LoadTrack { 
-                    station_id: StationID { band: Band::FM, index }, 
-                    file_path: request_path.clone()
-                };
-                file_requester.send(request);
+        let audio_content = notify_when_finished(audio_content, move || {
+            // The manager may already be shutting down
+            let _ = playback_events.send(PlaybackEvent::TrackFinished { station_id, file_path });
+        });
+        Box::new(audio_content)
+    }
+    fn handle_playback_event(&mut self, playback_event: PlaybackEvent) {
+        match playback_event {
+            PlaybackEvent::TrackStarted { station_id, file_path } => {
+                self.events.publish(&RadioEvent::TrackStarted { station: station_id.to_string(), title: track_title(&file_path) });
+                if station_id == self.current_station {
+                    self.publish_now_playing(Some(track_title(&file_path)));
+                }
+            },
+            PlaybackEvent::TrackFinished { station_id, file_path } => {
+                self.get_station(station_id).track_finished(&file_path);
+                self.events.publish(&RadioEvent::TrackFinished { station: station_id.to_string(), title: track_title(&file_path) });
+            }
+        }
+    }
+    fn prime_stations(&mut self, file_requester: &Sender<messages::FileRequest>) {
+        let mut dead_stations: Vec<StationID> = Vec::new();
+        let mut generated_stations: Vec<StationID> = Vec::new();
+        self.bands.iter_mut().enumerate().for_each(|(band_index, band)| {
+            band.iter_mut().enumerate().for_each(|(index, station)| {
+                let station_id = StationID { band: Band(band_index), index };
+                let primed_paths = station.prime_content();
+                // The opening of the first track goes ahead of every full load
+                if let Some(file_path) = station.warm_start_path() {
+                    file_requester.send(FileRequest::WarmTrack { station_id, file_path }).ok();
+                }
+                if station.is_generated() {
+                    generated_stations.push(station_id);
+                } else if primed_paths.is_empty() {
+                    dead_stations.push(station_id);
+                }
+                primed_paths.iter().for_each(|request_path| {
+                    let request = station.load_request(station_id, request_path.clone(), None);
+                    file_requester.send(request).ok();
+                });
             });
         });
+        // Stations with nothing to play stay as static on the dial
+        dead_stations.into_iter().for_each(|station_id| self.station_off_air(station_id));
+        generated_stations.into_iter().for_each(|station_id| self.station_on_air(station_id));
     }
     fn skip_dormant_stations(&mut self, file_requester: &Sender<messages::FileRequest>) {
-        match self.current_station.band {
-            Band::AM => {
-                skip_dormant_stations_in_band_except_current(
-                    &mut self.am, 
-                    &file_requester, Band::AM, 
-                    self.current_station.index
-                );
-                skip_dormant_stations_in_band(
-                    &mut self.fm, 
-                    &file_requester, 
-                    Band::FM
-                );
-            },
-            Band::FM => {
+        let current_station = self.current_station;
+        self.bands.iter_mut().enumerate().for_each(|(band_index, band)| {
+            if band_index == current_station.band.0 {
                 skip_dormant_stations_in_band_except_current(
-                    &mut self.fm, 
-                    &file_requester, Band::FM, 
-                    self.current_station.index
+                    band, 
+                    file_requester, current_station.band, 
+                    current_station.index
                 );
+            } else {
                 skip_dormant_stations_in_band(
-                    &mut self.am, 
-                    &file_requester, 
-                    Band::AM
+                    band, 
+                    file_requester, 
+                    Band(band_index)
                 );
             }
-        }
+        });
     }    
 }
 
+/// Title for a track in live events, from its file name
+fn track_title(file_path: &Path) -> String {
+    file_path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().to_string())
+}
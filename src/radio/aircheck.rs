@@ -0,0 +1,165 @@
+//! Aircheck Export - A station's upcoming programming, rendered offline
+//!
+//! Renders what a station would broadcast for the next N minutes into a
+//! single WAV file, without the radio running: tracks picked by the
+//! station's playlist rules, with DJ intros, stereo placement and the
+//! master EQ, as fast as the files decode. Generated stations (speaking
+//! clocks, numbers stations) are rendered for the full length; a speaking
+//! clock announces the times it would from now, not the time it's
+//! rendered at.
+//!
+//! A WAV file holds at most 4 GiB of audio, a little under seven hours at
+//! the rendered format, so longer airchecks are turned away.
+//!
+//! Picks are made on a fresh copy of the station, so a Shuffle station's
+//! aircheck is one possible hour rather than the hour the radio will
+//! actually play.
+//!
+//! ```text
+//! mokradio --aircheck FM 03 60 fm03.wav
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use rodio::mixer::mixer;
+use rodio::source::{UniformSourceIterator, from_iter};
+use rodio::{ChannelCount, SampleRate};
+
+use crate::audio::AudioSource;
+use crate::audio::equalizer::equalized;
+use crate::audio::stereo::stereo;
+use crate::config::radio_config;
+use crate::file_loader::thread::load_track;
+use crate::radio::station::Station;
+
+/// Format of the rendered file
+const CHANNELS: ChannelCount = 2;
+const SAMPLE_RATE: SampleRate = 44_100;
+
+/// Most samples a WAV file's 32-bit lengths can describe
+const MAX_SAMPLES: u64 = (u32::MAX as u64 - 36) / 2;
+
+/// What went into an aircheck
+#[derive(Debug, Clone)]
+pub struct Aircheck {
+    /// Track file names, in playing order, or the station's name for a
+    /// generated station
+    pub tracks: Vec<String>,
+
+    /// Length of the rendered audio
+    pub duration: Duration,
+}
+
+/// Renders a station's next `length` of programming to a WAV file
+///
+/// # Arguments
+/// * `station_path` - Station folder (as on the dial)
+/// * `length` - How much programming to render; the last track is cut off
+/// * `output_path` - WAV file to write (overwritten if it exists)
+///
+/// # Returns
+/// The tracks rendered and the length of the file. Tracks that fail to
+/// load are logged and left out; running out of tracks ends the file early.
+///
+/// # Errors
+/// `InvalidInput` if `length` is too long for a WAV file, before anything
+/// is written, or the error writing the file
+pub fn render_aircheck(station_path: &Path, length: Duration, output_path: &Path) -> io::Result<Aircheck> {
+    let total_samples = (length.as_secs_f64() * SAMPLE_RATE as f64) as u64 * CHANNELS as u64;
+    if total_samples > MAX_SAMPLES {
+        let longest_minutes = MAX_SAMPLES / CHANNELS as u64 / SAMPLE_RATE as u64 / 60;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("an aircheck can be at most {} minutes long", longest_minutes)
+        ));
+    }
+
+    // The station's sink is never played; only its picks are used
+    let (scratch_mixer, _) = mixer(CHANNELS, SAMPLE_RATE);
+    let mut station = Station::new(station_path, &scratch_mixer);
+    let placement = station.stereo_placement();
+    let generated = station.offline_source(SystemTime::now());
+
+    let mut tracks = Vec::new();
+    let programming: Box<dyn Iterator<Item = AudioSource> + '_> = match generated {
+        Some(generated) => {
+            tracks.push(station.name());
+            Box::new(std::iter::once(generated))
+        },
+        None => Box::new(std::iter::from_fn(|| {
+            loop {
+                let file_path = station.next_track()?;
+                match load_track(&file_path, station.take_intro()) {
+                    Ok(audio_content) => {
+                        tracks.push(file_path.file_name().unwrap_or_default().to_string_lossy().to_string());
+                        return Some(audio_content);
+                    },
+                    Err(e) => eprintln!("Leaving {} out of the aircheck: {}", file_path.display(), e)
+                }
+            }
+        }))
+    };
+
+    // Each item is placed and converted on its own, then EQ runs over the lot
+    let program = from_iter(programming.map(|audio_content| {
+        UniformSourceIterator::new(stereo(audio_content, placement), CHANNELS, SAMPLE_RATE)
+    }));
+    let mut writer = WavWriter::create(output_path)?;
+    for sample in equalized(program, &radio_config().eq.bands()).take(total_samples as usize) {
+        writer.write_sample(sample)?;
+    }
+    let duration = writer.finish()?;
+
+    Ok(Aircheck { tracks, duration })
+}
+
+/// 16-bit stereo WAV file written as samples arrive
+struct WavWriter {
+    file: BufWriter<File>,
+    samples: u32,
+}
+
+impl WavWriter {
+    /// Creates the file with a header whose lengths are filled in by `finish`
+    fn create(path: &Path) -> io::Result<WavWriter> {
+        let mut file = BufWriter::new(File::create(path)?);
+        let bytes_per_frame = CHANNELS as u32 * 2;
+        file.write_all(b"RIFF")?;
+        file.write_all(&0u32.to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?; // PCM
+        file.write_all(&CHANNELS.to_le_bytes())?;
+        file.write_all(&SAMPLE_RATE.to_le_bytes())?;
+        file.write_all(&(SAMPLE_RATE * bytes_per_frame).to_le_bytes())?;
+        file.write_all(&(bytes_per_frame as u16).to_le_bytes())?;
+        file.write_all(&16u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&0u32.to_le_bytes())?;
+        Ok(WavWriter { file, samples: 0 })
+    }
+
+    /// Appends one sample, clipped to 16 bits
+    fn write_sample(&mut self, sample: f32) -> io::Result<()> {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        self.samples += 1;
+        self.file.write_all(&value.to_le_bytes())
+    }
+
+    /// Fills in the header lengths
+    ///
+    /// # Returns
+    /// Length of the audio written
+    fn finish(mut self) -> io::Result<Duration> {
+        let data_length = self.samples * 2;
+        self.file.seek(SeekFrom::Start(4))?;
+        self.file.write_all(&(36 + data_length).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(40))?;
+        self.file.write_all(&data_length.to_le_bytes())?;
+        self.file.flush()?;
+        Ok(Duration::from_secs_f64(self.samples as f64 / CHANNELS as f64 / SAMPLE_RATE as f64))
+    }
+}
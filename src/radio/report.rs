@@ -0,0 +1,213 @@
+//! Station Report - Playlist statistics for content planning
+//!
+//! `mokradio report FM 03` prints what a station has to play and how it's
+//! going through it: playlist size and length, how long one rotation
+//! takes for its play_type, its most and least played tracks (from the
+//! play history), and for Chronologic and Reverse stations, when the
+//! playlist is projected to run out at the pace of the last week.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local};
+
+use crate::radio::station::config::StationConfig;
+use crate::radio::station::content::PlayType;
+use crate::radio::station::content::track::Track;
+use crate::radio::station::history::PlayRecord;
+use crate::storage::storage;
+
+/// Tracks listed under most and least played
+const RANKING_LENGTH: usize = 5;
+
+/// History window used to measure a station's pace
+const PACE_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// Playlist statistics for one station
+pub struct StationReport {
+    /// Station folder
+    pub station_path: PathBuf,
+
+    /// Display name, if station.info has one
+    pub name: Option<String>,
+
+    /// play_type from station.info
+    pub play_type: String,
+
+    /// Tracks in the playlist
+    pub track_count: usize,
+
+    /// Length of the whole playlist
+    pub total_duration: Duration,
+
+    /// What one rotation through the playlist looks like
+    pub cycle: String,
+
+    /// Playlist tracks with their play counts, most played first
+    pub play_counts: Vec<(PathBuf, usize)>,
+
+    /// Projected date a Chronologic or Reverse playlist runs out
+    pub exhaustion: Option<Exhaustion>,
+}
+
+/// When a one-way playlist is expected to run out
+pub struct Exhaustion {
+    /// Playlist time not yet played
+    pub remaining: Duration,
+
+    /// Audio played per day over the last week (zero with no recent history)
+    pub daily_pace: Duration,
+
+    /// Projected last day, if there's a pace to project from
+    pub date: Option<DateTime<Local>>,
+}
+
+impl StationReport {
+    /// Builds the report for a station folder
+    pub fn new(station_path: &Path) -> StationReport {
+        let station_config = StationConfig::new(station_path);
+        let play_list = PlayType::from_config(&station_config, station_path);
+        let tracks = playlist(&play_list);
+        let history = storage().load_history(station_path);
+
+        let total_duration: Duration = tracks.iter().map(|track| track_length(track)).sum();
+
+        let cycle = match &play_list {
+            PlayType::Shuffle(_) => format!("every track once per {}, then reshuffled", format_duration(total_duration)),
+            // Picking one of n tracks at a time, a given track comes up every n picks
+            PlayType::Random(_) => format!(
+                "no fixed cycle; a track comes round every {} on average",
+                format_duration(total_duration)
+            ),
+            PlayType::Chronologic(_) => format!("oldest to newest once ({}), then off air", format_duration(total_duration)),
+            PlayType::Reverse(_) => format!("newest to oldest once ({}), then off air", format_duration(total_duration)),
+            PlayType::Network(_) => "shared network playlist, wraps forever".to_string(),
+            PlayType::Rotation(_) => format!("folders mixed by share, each reshuffled when played through ({} in all)", format_duration(total_duration)),
+            PlayType::Live(_) => "scheduled live streams".to_string(),
+            PlayType::Time(_) | PlayType::Generated(..) => "generated on the fly, no playlist".to_string(),
+            PlayType::Dead => "dead station, nothing to play".to_string(),
+        };
+
+        let mut counts: HashMap<&Path, usize> = tracks.iter().map(|track| (track.get_location(), 0)).collect();
+        // Skipped tracks don't count as plays
+        history.iter().filter(|record| record.completed).for_each(|record| {
+            if let Some(count) = counts.get_mut(record.file_path.as_path()) {
+                *count += 1;
+            }
+        });
+        let mut play_counts: Vec<(PathBuf, usize)> = counts.into_iter()
+            .map(|(file_path, count)| (file_path.to_path_buf(), count))
+            .collect();
+        play_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let exhaustion = matches!(play_list, PlayType::Chronologic(_) | PlayType::Reverse(_))
+            .then(|| project_exhaustion(&tracks, &history));
+
+        StationReport {
+            station_path: station_path.to_path_buf(),
+            name: station_config.name.clone(),
+            play_type: station_config.play_type.clone(),
+            track_count: tracks.len(),
+            total_duration,
+            cycle,
+            play_counts,
+            exhaustion,
+        }
+    }
+}
+
+impl fmt::Display for StationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({})", self.name.as_deref().unwrap_or("Unnamed station"), self.station_path.display())?;
+        writeln!(f, "Play type: {}", self.play_type)?;
+        writeln!(f, "Playlist: {} tracks, {}", self.track_count, format_duration(self.total_duration))?;
+        writeln!(f, "Cycle: {}", self.cycle)?;
+
+        if !self.play_counts.is_empty() {
+            writeln!(f, "Most played:")?;
+            for (file_path, count) in self.play_counts.iter().take(RANKING_LENGTH) {
+                writeln!(f, "  {:>5}  {}", count, file_name(file_path))?;
+            }
+            writeln!(f, "Least played:")?;
+            for (file_path, count) in self.play_counts.iter().rev().take(RANKING_LENGTH) {
+                writeln!(f, "  {:>5}  {}", count, file_name(file_path))?;
+            }
+        }
+
+        if let Some(exhaustion) = &self.exhaustion {
+            write!(f, "Remaining: {}", format_duration(exhaustion.remaining))?;
+            match exhaustion.date {
+                Some(date) => writeln!(
+                    f, ", runs out around {} at {} a day",
+                    date.format("%Y-%m-%d"), format_duration(exhaustion.daily_pace)
+                )?,
+                None => writeln!(f, ", no plays in the last week to project from")?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tracks in a station's playlist
+fn playlist(play_list: &PlayType) -> Vec<&Track> {
+    match play_list {
+        PlayType::Random(tracks) | PlayType::Shuffle(tracks) => tracks.iter().collect(),
+        PlayType::Chronologic(tracks) | PlayType::Reverse(tracks) => tracks.iter().collect(),
+        PlayType::Network(cursor) => cursor.upcoming().collect(),
+        PlayType::Rotation(rotation) => rotation.tracks().collect(),
+        PlayType::Live(_) | PlayType::Time(_) | PlayType::Generated(..) | PlayType::Dead => Vec::new()
+    }
+}
+
+/// Projects when the unplayed part of a one-way playlist runs out
+///
+/// Tracks in the history count as played. The pace is the playlist time
+/// played over the last week, averaged per day.
+fn project_exhaustion(tracks: &[&Track], history: &[PlayRecord]) -> Exhaustion {
+    let lengths: HashMap<&Path, Duration> = tracks.iter()
+        .map(|track| (track.get_location(), track_length(track)))
+        .collect();
+
+    let remaining: Duration = tracks.iter()
+        .filter(|track| !history.iter().any(|record| record.file_path == track.get_location()))
+        .map(|track| track_length(track))
+        .sum();
+
+    let now = SystemTime::now();
+    let played_recently: Duration = history.iter()
+        .filter(|record| record.completed && now.duration_since(record.played_at).is_ok_and(|age| age <= PACE_WINDOW))
+        .filter_map(|record| lengths.get(record.file_path.as_path()))
+        .sum();
+    let daily_pace = Duration::from_secs_f64(played_recently.as_secs_f64() / (PACE_WINDOW.as_secs_f64() / SECONDS_PER_DAY));
+
+    let date = (!daily_pace.is_zero()).then(|| {
+        let days = remaining.as_secs_f64() / daily_pace.as_secs_f64();
+        DateTime::<Local>::from(now + Duration::from_secs_f64(days * SECONDS_PER_DAY))
+    });
+
+    Exhaustion { remaining, daily_pace, date }
+}
+
+/// Length of a track as a std Duration
+fn track_length(track: &Track) -> Duration {
+    track.get_duration().to_std().unwrap_or_default()
+}
+
+/// Formats a duration as hours and minutes ("8h 12m"), or seconds if short
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60)
+    }
+}
+
+/// File name of a track for listing
+fn file_name(file_path: &Path) -> String {
+    file_path.file_name().unwrap_or(file_path.as_os_str()).to_string_lossy().to_string()
+}
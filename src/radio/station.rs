@@ -12,25 +12,69 @@
 
 pub mod config;
 pub mod content;
+pub mod history;
+pub mod loudness;
+pub mod played;
+pub mod rescan;
 pub mod utilities;
 
-use std::fs::File;
-use std::io::BufReader;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
-use rodio::{Decoder, OutputStream, Sink};
+use rodio::{Sink, Source};
+use rodio::mixer::Mixer;
 
-use content::{PlayType, Content};
-use config::StationConfig;
+use rand::{Rng, rng};
+use rand::seq::SliceRandom;
 
+use content::{PlayType, Content, StationID};
+use config::{BroadcastHours, DjConfig, NoiseGateConfig, Restrictions, StartFrom, StationConfig};
+
+use crate::audio::AudioSource;
+use crate::audio::ambience::ambience;
+use crate::audio::broadcast_delay::{delay_from_secs, delayed};
+use crate::constants;
+use crate::config::folder_scanner::directory_hash;
+use crate::config::locale::Phrase;
+use crate::config::{is_within, radio_config, stations_root};
+use crate::file_loader::ram_cache;
+use crate::audio::droppable::{droppable, DropHandle};
+use crate::audio::http_stream::{self, StreamStatus};
+use crate::audio::monitor::{tapped, MonitorTap};
+use crate::audio::noise_gate::noise_gated;
+use crate::audio::seekable::seekable;
+use crate::audio::slew::{slewed, SlewedGain};
+use crate::audio::split::{routed, ChannelRoute, Channels};
+use crate::audio::stereo::{stereo, StereoPlacement};
+use crate::audio::time_signal::{offline_time_signal, time_signal};
+use crate::audio::warm_start::Continuation;
+use crate::messages::{ChronologicPosition, FileRequest, Intro, SeekTo, StationSnapshot, TrackPreview};
+use crate::state::station::PlaybackState;
+use crate::storage::storage;
+use crate::radio::station::content::cue::CueTrack;
+use crate::radio::station::content::origin::TrackOrigin;
+use crate::radio::station::content::rating;
 use crate::radio::station::content::track::Track;
-use crate::radio::station::utilities::whats_next::{self, next_chronologic, next_random, next_shuffle};
+use crate::radio::station::loudness::LoudnessMatch;
+use crate::radio::station::rescan::RescanJob;
+use crate::radio::station::utilities::whats_next::{self, drop_aged_out, next_chronologic, next_random, next_shuffle, next_shuffle_separated};
+
+/// Folder in the stations root that tracks which won't decode are moved to
+const QUARANTINE_FOLDER: &str = "quarantine";
+
+/// Furthest into its first track a `random_start` station joins, as a fraction of its length
+const RANDOM_START_LIMIT: f64 = 0.8;
 
 /// Radio station with playlist management and audio sink
 /// 
 /// Represents a single station that can play audio content according to
 /// different playlist strategies. Owned and controlled by Station Manager.
 pub struct Station {
+    /// Display name from station.info (or the genre, for virtual stations)
+    name: Option<String>,
+
     /// Currently playing content (track or live stream)
     current_content: Option<Content>,
     
@@ -42,6 +86,9 @@ pub struct Station {
     
     /// Whether to delete audio files after playing (for ephemeral content)
     purge: bool,
+
+    /// Purge into the station's `.trash/` folder instead of deleting
+    purge_to_trash: bool,
     
     /// Station has valid configuration and can broadcast
     on_air: bool,
@@ -51,9 +98,113 @@ pub struct Station {
     
     /// Audio output sink for this station's playback
     sink: Option<Sink>,
+
+    /// Drop handles for the sources in the sink, oldest (playing) first
+    queued_sources: VecDeque<DropHandle>,
     
     /// Path to station directory (for reloading playlists)
-    station_path: PathBuf
+    station_path: PathBuf,
+
+    /// Chronologic only: rolling window of track ages to play
+    chronologic_window: Option<Duration>,
+
+    /// Shuffle only: tracks before an artist may repeat (0 disables)
+    artist_separation: usize,
+
+    /// Artists of the most recent tracks, newest last
+    recent_artists: VecDeque<String>,
+
+    /// Tracks of a virtual station, which has no playlist folder to reload from
+    virtual_playlist: Option<Vec<Track>>,
+
+    /// Virtual DJ settings, if this station has a DJ
+    dj: Option<DjConfig>,
+
+    /// Tracks queued since the DJ last spoke
+    tracks_since_intro: usize,
+
+    /// Intro planned for the most recently queued track
+    pending_intro: Option<Intro>,
+
+    /// Copies this station's audio to the monitor output when open
+    monitor_tap: MonitorTap,
+
+    /// Balance and stereo width applied to everything this station plays
+    stereo: StereoPlacement,
+
+    /// Gate quieting hiss between passages, if station.info sets one
+    noise_gate: Option<NoiseGateConfig>,
+
+    /// Speaker channels this station plays on in dual-mono mode
+    channel_route: ChannelRoute,
+
+    /// Tracks pinned or injected through the API, played before the playlist
+    queue_edits: VecDeque<Track>,
+
+    /// Announcements queued or playing, which always play to the end
+    announcements: Vec<PathBuf>,
+
+    /// Daily on-air hours, if the station signs off
+    broadcast_hours: Option<BroadcastHours>,
+
+    /// Outside broadcast hours; keeps the station off air until sign-on
+    signed_off: bool,
+
+    /// When and in which profiles listeners can tune in
+    restrictions: Option<Restrictions>,
+
+    /// Whether the dial currently hears static here because of `restrictions`
+    restricted: bool,
+
+    /// Content ratings the playlist skips over, the station's and its profile's
+    excluded_ratings: Vec<String>,
+
+    /// Gain bringing each track to the station's loudness target, if it has one
+    loudness: Option<LoudnessMatch>,
+
+    /// Station volume, applied smoothly to every queued source
+    gain: SlewedGain,
+
+    /// Plays its playlist from copies in RAM
+    ram_cache: bool,
+
+    /// Joins its first track part-way through
+    random_start: bool,
+
+    /// Where the next source pushed to the sink starts, when joining mid-track
+    start_offset: Option<Duration>,
+
+    /// Playback state as last saved, so an unchanged station isn't saved again
+    saved_playback: Option<PlaybackState>,
+
+    /// First track playing from its warmed opening, waiting for the rest:
+    /// its path, where to hand the rest over and how much the opening covers
+    warm_start: Option<(PathBuf, Continuation, Duration)>,
+
+    /// Tracks in a row that failed to load
+    consecutive_errors: usize,
+
+    /// Tracks requested from the File Loader that haven't come back yet
+    loading: Vec<PathBuf>,
+
+    /// Times the track being loaded has been requested again after IO errors
+    /// IO retries used so far, per track
+    io_retries: HashMap<PathBuf, usize>,
+
+    /// Times each track has failed to decode, for quarantining repeat offenders
+    decode_failures: HashMap<PathBuf, usize>,
+
+    /// Off air for too many load errors until this time, then retried
+    faulted_until: Option<Instant>,
+
+    /// Content hash of the playlist folder when it was last loaded
+    playlist_hash: Option<u64>,
+
+    /// Live only: the stream playing now, if one is
+    live_stream: Option<StreamStatus>,
+
+    /// Live only: how far the station plays behind its stream
+    broadcast_delay: Duration
 }
 
 impl Station {
@@ -61,7 +212,7 @@ impl Station {
     /// 
     /// # Arguments
     /// * `station_path` - Path to station folder (e.g., `/stations/am/00/`)
-    /// * `band` - Shared audio output stream to connect this station's sink to
+    /// * `output` - Shared output mixer to connect this station's sink to
     /// 
     /// # Station Directory Structure
     /// ```text
@@ -77,59 +228,583 @@ impl Station {
     /// - Sink connected to the output stream
     /// - Playlist loaded according to station.info
     /// - Content fields initialized as None (call `prime_content()` to load)
-    pub fn new(station_path: &Path, output: &OutputStream) -> Self {
+    pub fn new(station_path: &Path, output: &Mixer) -> Self {
         // Create dedicated audio sink for this station
-        let station_sink = Sink::connect_new(output.mixer());
+        let station_sink = Sink::connect_new(output);
         
         // Load station configuration from JSON
         let station_configurations = StationConfig::new(station_path);
         
         // Initialize playlist based on play_type
-        let play_list = PlayType::new(&station_configurations.play_type, station_path);
+        let play_list = PlayType::from_config(&station_configurations, station_path);
         
-        let new_station = Station {
+        let mut new_station = Station {
+            name: station_configurations.name.clone(),
             current_content: None,
             next_content: None,
             play_list,
             purge: station_configurations.purge,
+            purge_to_trash: station_configurations.purge_to_trash,
             on_air: false,
             has_skipped: false,
             sink: Some(station_sink),
-            station_path: station_path.to_path_buf()
+            queued_sources: VecDeque::new(),
+            station_path: station_path.to_path_buf(),
+            chronologic_window: station_configurations.window_days
+                .map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+            artist_separation: station_configurations.artist_separation.unwrap_or(0),
+            recent_artists: VecDeque::new(),
+            virtual_playlist: None,
+            dj: station_configurations.dj.clone(),
+            tracks_since_intro: 0,
+            pending_intro: None,
+            monitor_tap: MonitorTap::default(),
+            stereo: StereoPlacement {
+                balance: station_configurations.balance,
+                width: station_configurations.stereo_width
+            },
+            noise_gate: station_configurations.noise_gate,
+            channel_route: ChannelRoute::default(),
+            queue_edits: VecDeque::new(),
+            announcements: Vec::new(),
+            broadcast_hours: station_configurations.broadcast_hours.clone(),
+            signed_off: false,
+            restrictions: station_configurations.restrictions.clone(),
+            restricted: false,
+            excluded_ratings: station_configurations.exclude_ratings.clone(),
+            loudness: None,
+            gain: SlewedGain::new(1.0),
+            ram_cache: station_configurations.ram_cache,
+            random_start: station_configurations.random_start,
+            start_offset: None,
+            saved_playback: None,
+            warm_start: None,
+            consecutive_errors: 0,
+            loading: Vec::new(),
+            io_retries: HashMap::new(),
+            decode_failures: HashMap::new(),
+            faulted_until: None,
+            playlist_hash: directory_hash(&station_path.join("playlist")),
+            live_stream: None,
+            broadcast_delay: delay_from_secs(station_configurations.broadcast_delay_secs)
         };
+        new_station.restore_start_from(station_configurations.start_from.as_deref());
+        new_station.drop_excluded();
+        new_station.drop_played_episodes();
+        new_station.restore_shuffle();
+        new_station.cache_in_ram();
+        new_station.loudness = station_configurations.loudness_target
+            .map(|target| LoudnessMatch::start(station_path, target, new_station.playlist_paths()));
 
         new_station
     }
 
     pub fn new_dead(station_path: &Path) -> Self {
 
-        let dead_station = Station {
+        Station {
+            name: None,
             current_content: None, 
             next_content: None,
             play_list: PlayType::Dead,
             purge: false,
+            purge_to_trash: false,
             on_air: false,
             has_skipped: true,
             sink: None,
-            station_path: station_path.to_path_buf()
+            queued_sources: VecDeque::new(),
+            station_path: station_path.to_path_buf(),
+            chronologic_window: None,
+            artist_separation: 0,
+            recent_artists: VecDeque::new(),
+            virtual_playlist: None,
+            dj: None,
+            tracks_since_intro: 0,
+            pending_intro: None,
+            monitor_tap: MonitorTap::default(),
+            stereo: StereoPlacement::default(),
+            noise_gate: None,
+            channel_route: ChannelRoute::default(),
+            queue_edits: VecDeque::new(),
+            announcements: Vec::new(),
+            broadcast_hours: None,
+            signed_off: false,
+            restrictions: None,
+            restricted: false,
+            excluded_ratings: Vec::new(),
+            loudness: None,
+            gain: SlewedGain::new(1.0),
+            ram_cache: false,
+            random_start: false,
+            start_offset: None,
+            saved_playback: None,
+            warm_start: None,
+            consecutive_errors: 0,
+            loading: Vec::new(),
+            io_retries: HashMap::new(),
+            decode_failures: HashMap::new(),
+            faulted_until: None,
+            playlist_hash: None,
+            live_stream: None,
+            broadcast_delay: Duration::ZERO
+        }
+    }
+
+    /// Creates a virtual Shuffle station from tracks gathered elsewhere
+    /// 
+    /// Used for genre stations split from a music library. The station has
+    /// no folder of its own, so reshuffles come from `tracks`.
+    /// 
+    /// # Arguments
+    /// * `name` - Display name (the genre)
+    /// * `tracks` - Tracks to shuffle through
+    /// * `station_path` - Dial slot path the station occupies
+    /// * `output` - Shared output mixer to connect this station's sink to
+    pub fn new_virtual(name: String, tracks: Vec<Track>, station_path: &Path, output: &Mixer) -> Self {
+        let mut virtual_station = Station::new_dead(station_path);
+        virtual_station.name = Some(name);
+        virtual_station.sink = Some(Sink::connect_new(output));
+        virtual_station.has_skipped = false;
+        virtual_station.virtual_playlist = Some(tracks);
+        virtual_station.play_list = virtual_station.reloaded_shuffle();
+
+        virtual_station
+    }
+
+    /// Builds a freshly shuffled playlist for this station, without excluded tracks
+    fn reloaded_shuffle(&self) -> PlayType {
+        let mut play_list = match &self.virtual_playlist {
+            Some(tracks) => {
+                let mut play_list = tracks.clone();
+                play_list.shuffle(&mut rng());
+                play_list
+            },
+            None => match PlayType::new("Shuffle", &self.station_path) {
+                PlayType::Shuffle(play_list) => play_list,
+                other => return other
+            }
+        };
+        play_list.retain(|track| !rating::is_excluded(track.ratings(), &self.excluded_ratings));
+        PlayType::Shuffle(play_list)
+    }
+
+    /// Returns what a hot reload needs to check for this station
+    /// 
+    /// # Returns
+    /// - The playlist folder and its hash when last loaded, for Random,
+    ///   Shuffle, Chronologic and Reverse stations
+    /// - The station folder, for a Dead station, to see whether its
+    ///   station.info has become playable
+    /// - None for Network, Rotation, Live, generated and virtual stations,
+    ///   which aren't reloaded (Rotation folders rescan as they reshuffle)
+    pub fn rescan_job(&self) -> Option<RescanJob> {
+        if self.virtual_playlist.is_some() {
+            return None;
+        }
+        let dead = match &self.play_list {
+            PlayType::Random(_) | PlayType::Shuffle(_) | PlayType::Chronologic(_) | PlayType::Reverse(_) => false,
+            PlayType::Dead => true,
+            PlayType::Network(_) | PlayType::Rotation(_) | PlayType::Live(_) | PlayType::Time(_) | PlayType::Generated(..) => {
+                return None
+            }
+        };
+        Some(RescanJob { station_path: self.station_path.clone(), playlist_hash: self.playlist_hash, dead })
+    }
+
+    /// Swaps in a playlist rescanned after its folder changed
+    /// 
+    /// # Behavior
+    /// - **Random/Shuffle**: Replaced by the new tracks (shuffled)
+    /// - **Chronologic**: Keeps only tracks newer than what's queued, so
+    ///   tracks already played don't come back
+    /// - **Reverse**: Keeps only tracks older than what's queued
+    /// - Anything else (the station changed since the rescan started) is
+    ///   left alone
+    /// 
+    /// # Returns
+    /// `true` if the playlist was rebuilt
+    pub fn reload(&mut self, playlist_hash: Option<u64>, tracks: Vec<Track>) -> bool {
+        if self.virtual_playlist.is_some() {
+            return false;
+        }
+
+        // Newest queued track marks how far a sorted playlist has got
+        let latest_queued: Vec<Track> = [&self.current_content, &self.next_content].into_iter()
+            .filter_map(|content| match content {
+                Some(Content::Track(track)) => Some(track.clone()),
+                _ => None
+            })
+            .collect();
+
+        let reloaded = match &self.play_list {
+            PlayType::Random(_) => PlayType::Random(tracks),
+            PlayType::Shuffle(_) => {
+                let mut tracks = tracks;
+                tracks.shuffle(&mut rng());
+                PlayType::Shuffle(tracks)
+            },
+            PlayType::Chronologic(_) => {
+                let newest = latest_queued.into_iter().max();
+                PlayType::Chronologic(tracks.into_iter().filter(|track| newest.as_ref().is_none_or(|newest| track > newest)).collect())
+            },
+            PlayType::Reverse(_) => {
+                let oldest = latest_queued.into_iter().min();
+                PlayType::Reverse(tracks.into_iter().filter(|track| oldest.as_ref().is_none_or(|oldest| track < oldest)).collect())
+            },
+            PlayType::Network(_) | PlayType::Rotation(_) | PlayType::Live(_) | PlayType::Time(_) | PlayType::Generated(..) | PlayType::Dead => {
+                return false
+            }
+        };
+
+        self.play_list = reloaded;
+        self.playlist_hash = playlist_hash;
+        self.drop_excluded();
+        self.drop_played_episodes();
+        self.cache_in_ram();
+        if let Some(loudness) = &self.loudness {
+            loudness.measure_new(self.playlist_paths());
+        }
+        true
+    }
+
+    /// Takes tracks with an excluded rating out of the playlist
+    /// 
+    /// Called whenever the playlist is built, so picks never have to pass
+    /// over them. A network station's shared playlist is left alone; see
+    /// `what_next()`.
+    fn drop_excluded(&mut self) {
+        if self.excluded_ratings.is_empty() {
+            return;
+        }
+        let excluded = &self.excluded_ratings;
+        let keep = |track: &Track| !rating::is_excluded(track.ratings(), excluded);
+        match &mut self.play_list {
+            PlayType::Random(play_list) | PlayType::Shuffle(play_list) => play_list.retain(keep),
+            PlayType::Chronologic(play_list) | PlayType::Reverse(play_list) => play_list.retain(keep),
+            PlayType::Rotation(rotation) => rotation.exclude_ratings(excluded),
+            _ => {}
+        }
+    }
+
+    /// Takes episodes a purge station has already played out of its playlist
+    /// 
+    /// Matched by content fingerprint, so an episode that was downloaded
+    /// again under another name stays played.
+    fn drop_played_episodes(&mut self) {
+        if !self.purge {
+            return;
+        }
+        let played = played::played_fingerprints(&self.station_path);
+        if played.is_empty() {
+            return;
+        }
+        let is_unplayed = |track: &Track| {
+            played::fingerprint(track.get_location()).is_none_or(|fingerprint| !played.contains(&fingerprint))
+        };
+        match &mut self.play_list {
+            PlayType::Random(play_list) | PlayType::Shuffle(play_list) => play_list.retain(is_unplayed),
+            PlayType::Chronologic(play_list) | PlayType::Reverse(play_list) => play_list.retain(is_unplayed),
+            PlayType::Rotation(rotation) => rotation.retain(is_unplayed),
+            _ => {}
+        }
+    }
+
+    /// Returns whether a file really is in this station's folder or its
+    /// playlist (which may be linked in from elsewhere), for purging
+    fn owns_file(&self, file_path: &Path) -> bool {
+        is_within(file_path, &self.station_path) || is_within(file_path, &self.station_path.join("playlist"))
+    }
+
+    /// Returns whether a file may be queued from outside (see `Radio::edit_queue`)
+    ///
+    /// Queued files can be purged or quarantined, so only tracks really in
+    /// this station's playlist folder, or among a virtual station's tracks,
+    /// are accepted.
+    pub fn can_queue(&self, file_path: &Path) -> bool {
+        match &self.virtual_playlist {
+            Some(tracks) => tracks.iter().any(|track| track.get_location() == file_path),
+            None => is_within(file_path, &self.station_path.join("playlist"))
+        }
+    }
+
+    /// Storage key for a kind of state this station saves
+    fn state_key(&self, kind: &str) -> String {
+        let station = self.station_path.strip_prefix(stations_root()).unwrap_or(&self.station_path);
+        format!("{} {}", kind, station.display())
+    }
+
+    /// Saves the tracks left in the current shuffle cycle
+    /// 
+    /// Called after every pick, so a restart mid-cycle carries on with the
+    /// tracks that haven't played yet. Virtual stations reshuffle instead.
+    fn save_shuffle(&self) {
+        let PlayType::Shuffle(play_list) = &self.play_list else {return;};
+        if self.virtual_playlist.is_some() {
+            return;
+        }
+        let remaining: Vec<String> = play_list.iter()
+            .map(|track| track.get_location().to_string_lossy().to_string())
+            .collect();
+        storage().save_state(&self.state_key("shuffle"), &serde_json::json!(remaining));
+    }
+
+    /// Narrows a freshly scanned shuffle to the tracks left from before a restart
+    /// 
+    /// Tracks added since are held back until the next cycle. If nothing
+    /// saved is still in the folder, a new cycle starts with everything.
+    fn restore_shuffle(&mut self) {
+        if !matches!(self.play_list, PlayType::Shuffle(_)) {
+            return;
+        }
+        let Some(saved) = storage().load_state(&self.state_key("shuffle")) else {return;};
+        let remaining: HashSet<PathBuf> = saved.as_array()
+            .map(|paths| paths.iter().filter_map(|path| path.as_str()).map(PathBuf::from).collect())
+            .unwrap_or_default();
+
+        let PlayType::Shuffle(play_list) = &mut self.play_list else {return;};
+        let unplayed: Vec<Track> = play_list.iter()
+            .filter(|track| remaining.contains(track.get_location()))
+            .cloned()
+            .collect();
+        if !unplayed.is_empty() {
+            *play_list = unplayed;
+        }
+    }
+
+    /// Saves the track playing and how far in, for resuming after a restart
+    /// 
+    /// Only file-playing stations with a track under way are saved, and
+    /// only when something has changed since the last save.
+    pub fn save_playback(&mut self) {
+        let Some(Content::Track(track)) = &self.current_content else {return;};
+        if self.sink.is_none() || self.virtual_playlist.is_some() {
+            return;
+        }
+        let playback = PlaybackState {
+            track: track.get_location().to_path_buf(),
+            offset_secs: self.position().as_secs_f64(),
+        };
+        if self.saved_playback.as_ref() == Some(&playback) {
+            return;
+        }
+        storage().save_state(&self.state_key("playback"), &serde_json::json!(playback));
+        self.saved_playback = Some(playback);
+    }
+
+    /// Loads the playback state saved before a restart
+    /// 
+    /// # Returns
+    /// The saved state, or None if there isn't one or its track is no
+    /// longer in the playlist
+    fn saved_playback_state(&self) -> Option<PlaybackState> {
+        if self.virtual_playlist.is_some() {
+            return None;
+        }
+        let saved = storage().load_state(&self.state_key("playback"))?;
+        let playback: PlaybackState = serde_json::from_value(saved)
+            .inspect_err(|e| eprintln!("{}: ignoring saved playback state: {}", self.station_path.display(), e))
+            .ok()?;
+        self.playlist_paths().contains(&playback.track).then_some(playback)
+    }
+
+    /// Puts a track cut off by a restart first in line
+    /// 
+    /// Sorted playlists are moved back to the track, so they carry on in
+    /// order after it as they would have; the others, which have already
+    /// taken it out of their cycle, queue it ahead of their next pick.
+    fn resume_at(&mut self, file_path: &Path) {
+        let reverse = matches!(self.play_list, PlayType::Reverse(_));
+        match &mut self.play_list {
+            PlayType::Chronologic(play_list) | PlayType::Reverse(play_list) => {
+                let Some(resumed) = play_list.iter().find(|track| track.get_location() == file_path).cloned() else {return;};
+                play_list.retain(|track| if reverse {*track <= resumed} else {*track >= resumed});
+            },
+            _ => {
+                if let Some(track) = Track::from_path(file_path) {
+                    self.queue_edits.push_front(track);
+                }
+            }
+        }
+    }
+
+    /// Moves a Chronologic playlist to its start point at boot
+    /// 
+    /// A start point set through the API (kept in storage) wins over the
+    /// `start_from` in station.info. It's applied this once and cleared, so
+    /// later restarts carry on from the saved playback state instead of
+    /// going back to it.
+    fn restore_start_from(&mut self, configured: Option<&str>) {
+        if !matches!(self.play_list, PlayType::Chronologic(_)) {
+            return;
+        }
+        let key = self.state_key("start_from");
+        let saved = storage().load_state(&key);
+        let requested = saved.as_ref().and_then(|saved| saved.as_str());
+        if requested.is_some() {
+            storage().save_state(&key, &serde_json::Value::Null);
+        }
+        let Some(start_from) = requested.or(configured) else {return;};
+        if let Some(play_list) = self.chronologic_from(&StartFrom::parse(start_from)) {
+            self.play_list = PlayType::Chronologic(play_list);
+        }
+    }
+
+    /// Scans the playlist again, keeping tracks from a start point on
+    /// 
+    /// # Returns
+    /// The tracks, or None (logged) if nothing in the folder matches the start
+    fn chronologic_from(&self, start_from: &StartFrom) -> Option<BTreeSet<Track>> {
+        let PlayType::Chronologic(mut play_list) = PlayType::new("Chronologic", &self.station_path) else {return None;};
+        if let Some(window) = self.chronologic_window {
+            drop_aged_out(&mut play_list, window);
+        }
+        let first = match start_from {
+            StartFrom::Date(date) => {
+                let since: SystemTime = date.and_hms_opt(0, 0, 0)
+                    .and_then(|midnight| midnight.and_local_timezone(chrono::Local).earliest())?
+                    .into();
+                play_list.iter().find(|track| *track.was_modified_on() >= since).cloned()
+            },
+            StartFrom::Episode(_) => play_list.iter().find(|track| start_from.is_episode(track.get_location())).cloned()
+        };
+        let Some(first) = first else {
+            eprintln!("{}: nothing to start from at {}", self.station_path.display(), start_from);
+            return None;
         };
+        play_list.retain(|track| *track >= first);
+        Some(play_list)
+    }
+
+    /// Moves a Chronologic station to a new start point
+    /// 
+    /// The playlist is rescanned from `start_from` on, and the start point
+    /// is saved so it holds after a restart too. The track playing now
+    /// finishes first; a queued next track is swapped for the new start
+    /// when it's already in the sink.
+    /// 
+    /// # Returns
+    /// - `Some(PathBuf)` - Path of a track to request now
+    /// - `None` - Nothing to request, or the start point didn't match (logged)
+    pub fn start_from(&mut self, start_from: &StartFrom) -> Option<PathBuf> {
+        if !matches!(self.play_list, PlayType::Chronologic(_)) {
+            eprintln!("{}: only Chronologic stations have a start point", self.station_path.display());
+            return None;
+        }
+        let play_list = self.chronologic_from(start_from)?;
+        self.play_list = PlayType::Chronologic(play_list);
+        self.drop_excluded();
+        self.drop_played_episodes();
+        storage().save_state(&self.state_key("start_from"), &serde_json::json!(start_from.to_string()));
+
+        self.prune_finished_sources();
+        match self.queued_sources.len() {
+            // Nothing left playing, e.g. the station ran off the end of its archive
+            0 => self.next_track(),
+            1 => None,
+            _ => {
+                if self.announcement_queued() {
+                    return None;
+                }
+                self.queued_sources.pop_back()?.drop_source();
+                self.replace_next()
+            }
+        }
+    }
+
+    /// Reports where a Chronologic station has got to
+    /// 
+    /// # Returns
+    /// The track playing and how many are left after it, or None for
+    /// other play types and stations that haven't started
+    pub fn chronologic_position(&self) -> Option<ChronologicPosition> {
+        let PlayType::Chronologic(play_list) = &self.play_list else {return None;};
+        let Some(Content::Track(track)) = &self.current_content else {return None;};
+        let dated: chrono::DateTime<chrono::Local> = (*track.was_modified_on()).into();
+        Some(ChronologicPosition {
+            file_path: track.get_location().to_path_buf(),
+            date: dated.format("%Y-%m-%d").to_string(),
+            remaining: play_list.len(),
+        })
+    }
+
+    /// Copies the playlist into the RAM cache if station.info asks for it,
+    /// or lets go of anything the station had cached if it doesn't
+    fn cache_in_ram(&self) {
+        if !self.ram_cache {
+            ram_cache::release_station(&self.station_path);
+            return;
+        }
+        let tracks = self.playlist_paths();
+        ram_cache::cache_tracks(&self.station_path, tracks.iter().map(PathBuf::as_path));
+    }
 
-        dead_station
+    /// Paths of every track in the playlist, for passes over the whole station
+    fn playlist_paths(&self) -> Vec<PathBuf> {
+        match &self.play_list {
+            PlayType::Random(play_list) | PlayType::Shuffle(play_list) => {
+                play_list.iter().map(|track| track.get_location().to_path_buf()).collect()
+            },
+            PlayType::Chronologic(play_list) | PlayType::Reverse(play_list) => {
+                play_list.iter().map(|track| track.get_location().to_path_buf()).collect()
+            },
+            PlayType::Rotation(rotation) => rotation.tracks().map(|track| track.get_location().to_path_buf()).collect(),
+            _ => Vec::new()
+        }
+    }
+
+    /// Returns the gain that brings a track to the station's loudness target
+    ///
+    /// # Returns
+    /// Linear gain; 1.0 without a target, or for a track not yet measured
+    pub fn loudness_gain(&self, file_path: &Path) -> f32 {
+        self.loudness.as_ref().map_or(1.0, |loudness| loudness.gain(file_path))
+    }
+
+    /// Returns the station's display name
+    /// 
+    /// Falls back to the station directory when station.info has no name.
+    pub fn name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => self.station_path.display().to_string()
+        }
+    }
+
+    /// Returns whether this slot has no station at all (free for virtual stations)
+    pub fn is_vacant(&self) -> bool {
+        self.sink.is_none() && matches!(self.play_list, PlayType::Dead)
     }
     
     /// Gets the next track according to the station's playlist strategy
     /// 
     /// Behavior depends on playlist type:
     /// - **Random**: Picks any random track from the list
-    /// - **Shuffle**: Removes and returns next track; reloads when empty
-    /// - **Chronologic**: Returns oldest unplayed track; goes off-air when empty
+    /// - **Shuffle**: Removes and returns next track (keeping artists apart
+    ///   if `artist_separation` is set); reloads when empty
+    /// - **Chronologic**: Returns oldest unplayed track (within `window_days`,
+    ///   if set); goes off-air when empty
     /// - **Reverse**: Returns newest unplayed track; goes off-air when empty
+    /// - **Network**: Returns the next track of a shared playlist; wraps around
     /// - **Dead**: Always returns None
     /// 
+    /// Whatever the strategy, tracks with a rating in `exclude_ratings`
+    /// never play: they're left out when the playlist is built, and passed
+    /// over as they come up in a network station's shared playlist. Queue
+    /// edits from the API play regardless.
+    /// 
     /// # Returns
     /// - `Some(Track)` - Next track to queue
     /// - `None` - Playlist exhausted or station is Dead
     pub fn what_next(&mut self) -> Option<Track> {
+        // Queue edits from the API jump ahead of the playlist
+        if let Some(track) = self.queue_edits.pop_front() {
+            return Some(track);
+        }
+
+        self.pick_from_playlist()
+    }
+
+    /// Takes the next track from the playlist strategy
+    fn pick_from_playlist(&mut self) -> Option<Track> {
         match &mut self.play_list {
             // Dead stations have no content
             PlayType::Dead => None,
@@ -141,11 +816,20 @@ impl Station {
             
             // Shuffle: remove and return track, reload when empty
             PlayType::Shuffle(playlist) => {
-                let next_track = next_shuffle(playlist);
+                let next_track = if self.artist_separation > 0 {
+                    next_shuffle_separated(playlist, &self.recent_artists)
+                } else {
+                    next_shuffle(playlist)
+                };
                 
                 // Reload shuffle playlist when exhausted
                 if playlist.is_empty() {
-                    self.play_list = PlayType::new("Shuffle", &self.station_path);
+                    self.play_list = self.reloaded_shuffle();
+                }
+                self.save_shuffle();
+
+                if let Some(artist) = next_track.as_ref().and_then(|track| track.artist()) {
+                    self.remember_artist(artist.to_string());
                 }
                 
                 next_track
@@ -153,6 +837,10 @@ impl Station {
             
             // Chronologic: play oldest first, go off-air when done
             PlayType::Chronologic(playlist) => {
+                // Age out tracks that fell outside the rolling window
+                if let Some(window) = self.chronologic_window {
+                    drop_aged_out(playlist, window);
+                }
                 let next_track = next_chronologic(playlist);
                 
                 if playlist.is_empty() {
@@ -173,11 +861,32 @@ impl Station {
                 next_track
             },
             
+            // Network: follow the shared playlist from this station's cursor;
+            // it can't be filtered for one station, so rated tracks are
+            // passed over as if they'd played, once round at most
+            PlayType::Network(cursor) => {
+                let excluded = &self.excluded_ratings;
+                (0..cursor.playlist_length())
+                    .filter_map(|_| cursor.advance())
+                    .find(|track| !rating::is_excluded(track.ratings(), excluded))
+            },
+
+            // Rotation: the folder furthest behind its share picks next
+            PlayType::Rotation(rotation) => rotation.advance(),
+            
             // Catch-all for future playlist types
             _ => None
         }
     }
     
+    /// Records an artist for Shuffle artist separation
+    fn remember_artist(&mut self, artist: String) {
+        self.recent_artists.push_back(artist);
+        while self.recent_artists.len() > self.artist_separation {
+            self.recent_artists.pop_front();
+        }
+    }
+    
     /// Advances the content queue and returns the path for the new next track
     /// 
     /// State transitions:
@@ -193,93 +902,745 @@ impl Station {
     /// Called by Station Manager when:
     /// - Sink needs more audio (`needs_next()` returns true)
     /// - Station is skipped during turnover
-    pub fn next(&mut self) -> Option<PathBuf> {
+    pub fn next_track(&mut self) -> Option<PathBuf> {
         // Get next track from playlist
         let what_next = self.what_next()?;
         
+        self.plan_intro(&what_next);
+        
         // Shift content queue forward
         self.current_content = self.next_content.take();
         self.next_content = Some(Content::Track(what_next));
         
         // Return path for file request
-        match &self.next_content {
-            None => None,
-            Some(content) => match content {
-                Content::Track(track) => Some(track.get_location().to_path_buf()),
-                _ => None
-            }
-        }
+        self.next_content_path()
     }
-    
-    /// Initializes the station with first two tracks
+
+    /// Replaces the queued next track without touching the current one
     /// 
-    /// Loads:
-    /// 1. First track → `current_content`
-    /// 2. Second track → `next_content`
+    /// Used by turnover skips: the queued track is thrown away and a new one
+    /// takes its place, so `current_content` still matches what's playing.
     /// 
     /// # Returns
-    /// Vector of file paths for Station Manager to send to File Loader
+    /// - `Some(PathBuf)` - Path of the replacement track to request
+    /// - `None` - Playlist exhausted
+    fn replace_next(&mut self) -> Option<PathBuf> {
+        let what_next = self.what_next()?;
+        self.plan_intro(&what_next);
+        self.next_content = Some(Content::Track(what_next));
+        self.next_content_path()
+    }
+
+    /// Pins a track to play right after the current one
     /// 
-    /// # Usage
-    /// Called by Station Manager during initialization to start loading
-    /// audio files for this station. Station is not ready for playback
-    /// until File Loader returns decoded audio via `push_to_sink()`.
-    pub fn prime_content(&mut self) -> Vec<PathBuf> {
-        let mut content_vector: Vec<PathBuf> = Vec::new();
-        
-        // Get first track
-        let next = self.next();
-        if next.is_some() {
-            content_vector.push(next.unwrap());
+    /// If the old next track is already in the sink, its source is dropped
+    /// and it goes back in the queue behind the pinned track. A next track
+    /// that is still being loaded can't be recalled, so the pinned track
+    /// follows it instead.
+    /// 
+    /// # Returns
+    /// - `Some(PathBuf)` - Path of the pinned track to request now
+    /// - `None` - The pinned track will be picked up by the next `next_track()`
+    pub fn pin_next(&mut self, track: Track) -> Option<PathBuf> {
+        self.queue_edits.push_front(track);
+
+        // Announcements aren't displaced, so the pinned track follows one
+        if self.announcement_queued() {
+            return None;
         }
-        
-        // Get second track
-        let next = self.next();
-        if next.is_some() {
-            content_vector.push(next.unwrap());
+        self.prune_finished_sources();
+        if self.queued_sources.len() < 2 {
+            return None;
         }
+        self.queued_sources.pop_back()?.drop_source();
+        if let Some(Content::Track(displaced)) = self.next_content.take() {
+            self.queue_edits.insert(1, displaced);
+        }
+        self.replace_next()
+    }
 
-        content_vector
+    /// Queues a one-off track after everything already queued
+    pub fn inject(&mut self, track: Track) {
+        self.queue_edits.push_back(track);
     }
-    
-    /// Appends decoded audio to this station's sink
-    /// 
-    /// Called by Station Manager when File Loader returns a decoded track.
-    /// The audio is added to the sink's queue and will play when:
-    /// - This is the active station (sink is playing)
-    /// - Previous audio in the queue finishes
+
+    /// Queues an announcement after everything already queued
     /// 
-    /// # Arguments
-    /// * `audio_content` - Decoded audio stream ready for playback
-    pub fn push_to_sink(&mut self, audio_content: Decoder<BufReader<File>>) {
-        if let Some(sink) = self.sink.as_mut() {
-            sink.append(audio_content);
+    /// Unlike an injected track, an announcement can't be interrupted once
+    /// it reaches the sink: turnover won't drop it, pinned tracks follow it
+    /// instead of displacing it, and seeks and cue skips are ignored while
+    /// it plays. The playlist carries on once it has finished.
+    pub fn announce(&mut self, track: Track) {
+        self.announcements.push(track.get_location().to_path_buf());
+        self.queue_edits.push_back(track);
+    }
+
+    /// Returns whether a file is an announcement that hasn't finished
+    fn is_announcement(&self, file_path: &Path) -> bool {
+        self.announcements.iter().any(|announcement| announcement == file_path)
+    }
+
+    /// Returns whether the track playing now is an announcement
+    fn announcement_playing(&self) -> bool {
+        match &self.current_content {
+            Some(Content::Track(track)) => self.is_announcement(track.get_location()),
+            _ => false
         }
     }
-    
-    /// Marks station as on-air (has valid configuration and content)
-    /// 
-    /// Sets the `on_air` flag to true. This indicates the station:
-    /// - Successfully loaded its configuration
-    /// - Has a valid playlist
-    /// - Can broadcast when selected
-    /// 
-    /// Note: Station may be on-air but paused (not the currently active station)
-    pub fn go_on_air(&mut self) -> bool {
-        self.on_air = match self.play_list {
-            PlayType::Dead => false,
-            _ => true
-        };
-        self.on_air
+
+    /// Returns whether the track queued in the sink next is an announcement
+    fn announcement_queued(&self) -> bool {
+        self.next_content_path().is_some_and(|file_path| self.is_announcement(&file_path))
     }
-    
-    /// Takes station off-air and pauses playback
+
+    /// Takes a file out of the upcoming queue
     /// 
-    /// Called when:
-    /// - Chronologic/Reverse playlists are exhausted
-    /// - Station encounters unrecoverable errors
+    /// Removes it from queue edits and from the playlist itself, and drops
+    /// it from the sink if it is the queued next track. Random and Network
+    /// playlists aren't changed, since neither has a queue of its own. An
+    /// announcement already in the sink is left to play.
     /// 
-    /// Effects:
+    /// # Returns
+    /// - `Some(PathBuf)` - Path of the replacement next track to request
+    /// - `None` - Nothing in the sink needed replacing
+    pub fn remove_queued(&mut self, file_path: &Path) -> Option<PathBuf> {
+        let edits = self.queue_edits.len();
+        self.queue_edits.retain(|track| track.get_location() != file_path);
+        // Announcements that haven't reached the sink can still be withdrawn
+        for _ in self.queue_edits.len()..edits {
+            if let Some(position) = self.announcements.iter().position(|announcement| announcement == file_path) {
+                self.announcements.remove(position);
+            }
+        }
+        match &mut self.play_list {
+            PlayType::Shuffle(play_list) => play_list.retain(|track| track.get_location() != file_path),
+            PlayType::Chronologic(play_list) | PlayType::Reverse(play_list) => {
+                play_list.retain(|track| track.get_location() != file_path)
+            },
+            PlayType::Rotation(rotation) => rotation.retain(|track| track.get_location() != file_path),
+            _ => {}
+        }
+
+        if self.next_content_path().as_deref() != Some(file_path) || self.is_announcement(file_path) {
+            return None;
+        }
+        self.prune_finished_sources();
+        if self.queued_sources.len() < 2 {
+            return None;
+        }
+        self.queued_sources.pop_back()?.drop_source();
+        self.replace_next()
+    }
+
+    /// Decides whether the DJ introduces a newly queued track
+    /// 
+    /// Sets `pending_intro` on every `every`-th track: a recording from the
+    /// station's `dj/` folder if one matches the file name, otherwise the
+    /// spoken template (only for tracks with a title tag). A DJ with
+    /// `talk_over` talks over the track with a bed from `beds/` instead,
+    /// falling back to that intro when the File Loader finds no bed.
+    fn plan_intro(&mut self, track: &Track) {
+        self.pending_intro = None;
+        let Some(dj) = &self.dj else {return;};
+
+        self.tracks_since_intro += 1;
+        if self.tracks_since_intro < dj.every.max(1) {
+            return;
+        }
+        self.tracks_since_intro = 0;
+
+        let recorded_intro = track.get_location()
+            .file_stem()
+            .map(|stem| self.station_path.join("dj").join(format!("{}.mp3", stem.to_string_lossy())))
+            .filter(|intro_path| intro_path.exists());
+
+        let locale = &radio_config().locale;
+        let language = dj.language.clone().unwrap_or_else(|| locale.language.clone());
+        let intro = match recorded_intro {
+            Some(intro_path) => Some(Intro::Recorded(intro_path)),
+            None => track.title().map(|title| {
+                let template = dj.template.clone().unwrap_or_else(|| locale.phrase(&language, Phrase::UpNext));
+                let artist = track.artist().map(str::to_string)
+                    .unwrap_or_else(|| locale.phrase(&language, Phrase::UnknownArtist));
+                Intro::Spoken {
+                    text: template.replace("{title}", title).replace("{artist}", &artist),
+                    language
+                }
+            })
+        };
+
+        self.pending_intro = match &dj.talk_over {
+            Some(talk_over) => Some(Intro::TalkOver {
+                beds: self.station_path.join("beds"),
+                duck: talk_over.duck,
+                // Anything too long for a Duration just means no limit
+                max_length: Duration::try_from_secs_f32(talk_over.max_secs.max(0.0)).unwrap_or(Duration::MAX),
+                fallback: intro.map(Box::new)
+            }),
+            None => intro
+        };
+    }
+
+    /// Takes the DJ intro planned for the track path just returned
+    /// 
+    /// Station Manager sends it along with the track's FileRequest so the
+    /// intro is decoded and queued right in front of the track.
+    pub fn take_intro(&mut self) -> Option<Intro> {
+        self.pending_intro.take()
+    }
+
+    /// Returns the file path of `next_content`, if it is a track
+    fn next_content_path(&self) -> Option<PathBuf> {
+        match &self.next_content {
+            Some(Content::Track(track)) => Some(track.get_location().to_path_buf()),
+            _ => None
+        }
+    }
+
+    /// Returns the files this station has asked to be loaded and not yet played
+    pub fn queued_paths(&self) -> Vec<PathBuf> {
+        [&self.current_content, &self.next_content].into_iter()
+            .filter_map(|content| match content {
+                Some(Content::Track(track)) => Some(track.get_location().to_path_buf()),
+                _ => None
+            })
+            .collect()
+    }
+    
+    /// Builds the request for a track this station wants loaded
+    /// 
+    /// The track counts as on its way until `load_returned()`, so
+    /// `needs_next()` doesn't ask for more while loads are outstanding.
+    pub fn load_request(&mut self, station_id: StationID, file_path: PathBuf, intro: Option<Intro>) -> FileRequest {
+        self.loading.push(file_path.clone());
+        FileRequest::LoadTrack { station_id, file_path, intro }
+    }
+
+    /// Notes that a requested track came back from the File Loader, loaded or not
+    pub fn load_returned(&mut self, file_path: &Path) {
+        if let Some(position) = self.loading.iter().position(|loading| loading == file_path) {
+            self.loading.swap_remove(position);
+        }
+    }
+
+    /// Initializes the station with first two tracks
+    /// 
+    /// Loads:
+    /// 1. First track → `current_content`
+    /// 2. Second track → `next_content`
+    /// 
+    /// # Returns
+    /// Vector of file paths for Station Manager to send to File Loader.
+    /// An empty vector means the station has nothing to play and is Dead.
+    /// 
+    /// # Short Playlists
+    /// - **Empty**: Logs a diagnostic and the station becomes Dead
+    /// - **Single track**: The station switches to looping that one track
+    /// 
+    /// # Usage
+    /// Called by Station Manager during initialization to start loading
+    /// audio files for this station. Station is not ready for playback
+    /// until File Loader returns decoded audio via `push_to_sink()`.
+    pub fn prime_content(&mut self) -> Vec<PathBuf> {
+        let mut content_vector: Vec<PathBuf> = Vec::new();
+
+        // Generated stations make their own audio, endlessly
+        if let Some(generated) = self.generated_source() {
+            self.push_to_sink(generated);
+            return content_vector;
+        }
+
+        // Live stations stay off air until their first stream starts (see `update_live`)
+        if matches!(self.play_list, PlayType::Live(_)) {
+            self.go_off_air();
+            return content_vector;
+        }
+        
+        // A track cut off by a restart goes first, from where it got to
+        let resume = self.saved_playback_state();
+        if let Some(resume) = &resume {
+            self.resume_at(&resume.track);
+        }
+
+        // Get first track
+        let Some(first) = self.next_track() else {
+            // Nothing to play at all, so this frequency is just static
+            if !matches!(self.play_list, PlayType::Dead) {
+                eprintln!("No playable tracks in {}, station is dead", self.station_path.display());
+                self.play_list = PlayType::Dead;
+            }
+            self.go_off_air();
+            return content_vector;
+        };
+        self.start_offset = match resume {
+            Some(resume) if resume.track == first => Some(resume.resume_offset()),
+            _ => self.random_start_offset()
+        };
+        content_vector.push(first);
+        
+        // Get second track
+        match self.next_track() {
+            Some(second) => content_vector.push(second),
+            None => {
+                // Single-track station: loop the one track forever
+                if let Some(Content::Track(track)) = &self.next_content {
+                    eprintln!("Only one track in {}, looping it", self.station_path.display());
+                    self.play_list = PlayType::Random(vec![track.clone()]);
+                }
+                if let Some(second) = self.next_track() {
+                    content_vector.push(second);
+                }
+            }
+        }
+
+        // The first tracks start without a DJ intro
+        self.pending_intro = None;
+
+        content_vector
+    }
+    
+    /// Picks where a `random_start` station joins its first track
+    /// 
+    /// # Returns
+    /// A random point in the first part of the queued track, or None if the
+    /// station starts at the top or the track's length isn't known
+    fn random_start_offset(&self) -> Option<Duration> {
+        if !self.random_start {
+            return None;
+        }
+        let Some(Content::Track(track)) = self.next_content.as_ref() else {return None;};
+        let length = track.get_duration().to_std().ok()?.as_secs_f64() * RANDOM_START_LIMIT;
+        (length > 0.0).then(|| Duration::from_secs_f64(rng().random_range(0.0..length)))
+    }
+    
+    /// Returns the first track to warm at boot, if this station can use a warm start
+    /// 
+    /// Only a station that has primed its content and has nothing in its
+    /// sink yet can; a station joining its first track part-way through
+    /// (`random_start`, or resuming after a restart) would waste the opening.
+    pub fn warm_start_path(&self) -> Option<PathBuf> {
+        if self.start_offset.is_some() || self.sink.is_none() || !self.queued_sources.is_empty() {
+            return None;
+        }
+        match &self.current_content {
+            Some(Content::Track(track)) => Some(track.get_location().to_path_buf()),
+            _ => None
+        }
+    }
+
+    /// Plays a first track's warmed opening until the whole track is loaded
+    /// 
+    /// # Arguments
+    /// * `file_path` - The track the opening belongs to
+    /// * `opening` - The warm start (see `audio::warm_start`), wrapped for
+    ///   playback like any loaded track
+    /// * `continuation` - Where the rest of the track is handed over
+    /// * `length` - How much of the track the opening covers
+    /// 
+    /// # Returns
+    /// false, leaving the station alone, if the track has already loaded
+    /// or the station has moved on from it
+    pub fn start_warm(&mut self, file_path: &Path, opening: AudioSource, continuation: Continuation, length: Duration) -> bool {
+        if self.warm_start_path().as_deref() != Some(file_path) {
+            return false;
+        }
+        self.push_to_sink(opening);
+        self.warm_start = Some((file_path.to_path_buf(), continuation, length));
+        true
+    }
+
+    /// Hands a loaded track to its warm start, if it's still playing one
+    /// 
+    /// The track is moved past the opening (decoding and discarding up to
+    /// there if the format can't seek) and carries on from the buffer. A
+    /// track whose opening was skipped or dropped has already had its turn,
+    /// so it's dropped too rather than played again from the top.
+    /// 
+    /// # Returns
+    /// The track back if it never had a warm start, to be pushed as usual
+    pub fn continue_warm(&mut self, file_path: &Path, audio_content: AudioSource) -> Option<AudioSource> {
+        let Some((_, continuation, length)) = self.warm_start.take_if(|(warm_path, _, _)| warm_path == file_path) else {
+            return Some(audio_content);
+        };
+        if !continuation.is_listening() {
+            return None;
+        }
+
+        let mut rest = seekable(audio_content);
+        if let Err(e) = rest.try_seek(length) {
+            eprintln!("Failed to carry {} on from its opening: {}", file_path.display(), e);
+        }
+        continuation.resume(Box::new(rest));
+        None
+    }
+
+    /// Gives up on a track's warm start after its load failed
+    /// 
+    /// Dropping the continuation lets the opening end after its buffer
+    /// instead of holding the station in silence.
+    pub fn abandon_warm(&mut self, file_path: &Path) {
+        self.warm_start.take_if(|(warm_path, _, _)| warm_path == file_path);
+    }
+
+    /// Creates the endless audio of a generated station
+    /// 
+    /// # Returns
+    /// The station's source, or None for stations that play files
+    pub fn generated_source(&self) -> Option<AudioSource> {
+        match &self.play_list {
+            PlayType::Time(time_service) => Some(Box::new(time_signal(&self.station_path, time_service))),
+            PlayType::Generated(kind, generator) => Some(Box::new(ambience(*kind, generator))),
+            _ => None
+        }
+    }
+
+    /// Creates a generated station's audio for rendering ahead of time
+    /// 
+    /// As `generated_source`, except a Time station's clock starts at
+    /// `start` and keeps time with the audio rather than the wall clock.
+    pub fn offline_source(&self, start: SystemTime) -> Option<AudioSource> {
+        match &self.play_list {
+            PlayType::Time(time_service) => Some(Box::new(offline_time_signal(&self.station_path, time_service, start))),
+            _ => self.generated_source()
+        }
+    }
+
+    /// Returns where this station sits in the stereo field
+    pub fn stereo_placement(&self) -> StereoPlacement {
+        self.stereo
+    }
+
+    /// Appends decoded audio to this station's sink
+    /// 
+    /// Called by Station Manager when File Loader returns a decoded track.
+    /// The audio is added to the sink's queue and will play when:
+    /// - This is the active station (sink is playing)
+    /// - Previous audio in the queue finishes
+    /// 
+    /// A `random_start` or resuming station's first track is moved to where
+    /// the station joins it before it goes in the sink (decoding and
+    /// discarding up to there if the format can't seek, here rather than on
+    /// the audio thread). The sink is then seeked to the same point, which
+    /// costs nothing more, so its position stays absolute for seeks, cue
+    /// sheets and saved state.
+    /// 
+    /// # Arguments
+    /// * `audio_content` - Decoded audio stream ready for playback
+    pub fn push_to_sink(&mut self, audio_content: AudioSource) {
+        let mut audio_content = seekable(audio_content);
+        let start_offset = self.start_offset.take().filter(|offset| {
+            audio_content.try_seek(*offset)
+                .inspect_err(|e| eprintln!("Failed to start {} part-way through: {}", self.station_path.display(), e))
+                .is_ok()
+        });
+        if let Some(sink) = self.sink.as_mut() {
+            // Keep a handle so turnover can drop this source while it's queued
+            let (source, handle) = droppable(slewed(
+                routed(tapped(stereo(noise_gated(audio_content, self.noise_gate), self.stereo), self.monitor_tap.clone()), self.channel_route.clone()),
+                self.gain.clone()
+            ));
+            sink.append(source);
+            self.queued_sources.push_back(handle);
+
+            // Only the first source may move the sink's position, or another track would jump
+            if let Some(offset) = start_offset {
+                if sink.len() == 1 {
+                    if let Err(e) = sink.try_seek(offset) {
+                        eprintln!("Failed to move {} to where it joined: {}", self.station_path.display(), e);
+                    }
+                } else {
+                    eprintln!("{} joined part-way through behind other audio", self.station_path.display());
+                }
+            }
+        }
+    }
+
+    /// Forgets drop handles for sources the sink has finished playing
+    fn prune_finished_sources(&mut self) {
+        let sink_length = self.sink.as_ref().map_or(0, |sink| sink.len());
+        while self.queued_sources.len() > sink_length {
+            self.queued_sources.pop_front();
+        }
+    }
+    
+    /// Bookkeeping for a track that just played to the end
+    /// 
+    /// Called by Station Manager on a `TrackFinished` event, at the moment
+    /// the sink moves on rather than when the manager next polls it. The
+    /// play goes in the station's history with where the track came from
+    /// (virtual stations have no folder to keep one in), and purge stations remember the episode's content
+    /// fingerprint so it isn't played again, then purge the file. Only
+    /// files in the station's own folder are purged, never announcements
+    /// or a shared network playlist.
+    pub fn track_finished(&mut self, file_path: &Path) {
+        self.prune_finished_sources();
+        if self.virtual_playlist.is_none() {
+            storage().record_play(&self.station_path, file_path, &self.origin_of(file_path), true);
+        }
+        if self.purge {
+            played::record_played(&self.station_path, file_path);
+            if self.owns_file(file_path) && !self.is_announcement(file_path) {
+                self.forget_track(file_path);
+                played::purge(&self.station_path, file_path, self.purge_to_trash);
+            }
+        }
+        if let Some(position) = self.announcements.iter().position(|announcement| announcement == file_path) {
+            self.announcements.remove(position);
+        }
+    }
+    
+    /// Marks station as on-air (has valid configuration and content)
+    /// 
+    /// Sets the `on_air` flag to true. This indicates the station:
+    /// - Successfully loaded its configuration
+    /// - Has a valid playlist
+    /// - Can broadcast when selected
+    /// 
+    /// Note: Station may be on-air but paused (not the currently active station)
+    pub fn go_on_air(&mut self) -> bool {
+        self.on_air = match self.play_list {
+            PlayType::Dead => false,
+            PlayType::Live(_) if self.live_stream.is_none() => false,
+            _ => !self.signed_off && self.faulted_until.is_none()
+        };
+        self.on_air
+    }
+
+    /// Counts a track that failed to load against the error budget
+    /// 
+    /// # Returns
+    /// `true` if the station has now used up its budget and is faulted:
+    /// the Station Manager takes it off air until `retry_faulted()`
+    pub fn load_failed(&mut self) -> bool {
+        self.consecutive_errors += 1;
+        if self.consecutive_errors < constants::ERROR_BUDGET || self.faulted_until.is_some() {
+            return false;
+        }
+        self.faulted_until = Some(Instant::now() + constants::FAULT_RETRY_INTERVAL);
+        true
+    }
+
+    /// Clears the error counts after a track loads
+    pub fn load_succeeded(&mut self, file_path: &Path) {
+        self.consecutive_errors = 0;
+        self.io_retries.remove(file_path);
+        self.decode_failures.remove(file_path);
+        self.faulted_until = None;
+    }
+
+    /// Decides whether a track that hit an IO error is worth requesting again
+    /// 
+    /// # Returns
+    /// How long to wait before requesting it, doubling with each retry, if
+    /// the track is still queued and has retries left; otherwise None and
+    /// the failure counts against the error budget
+    pub fn retry_load(&mut self, file_path: &Path) -> Option<Duration> {
+        let queued = self.queued_paths().iter().any(|queued| queued == file_path);
+        let retries = self.io_retries.entry(file_path.to_path_buf()).or_default();
+        if *retries >= constants::IO_RETRIES || !queued {
+            self.io_retries.remove(file_path);
+            return None;
+        }
+        *retries += 1;
+        Some(constants::IO_RETRY_BACKOFF * 2u32.pow(*retries as u32 - 1))
+    }
+
+    /// Counts a track that failed to decode, quarantining it once it has
+    /// failed `QUARANTINE_AFTER` times
+    /// 
+    /// A single failure may be a bad read rather than a bad file, so the
+    /// track stays in the playlist until then.
+    pub fn decode_failed(&mut self, file_path: &Path) {
+        let failures = self.decode_failures.entry(file_path.to_path_buf()).or_default();
+        *failures += 1;
+        if *failures >= constants::QUARANTINE_AFTER {
+            self.decode_failures.remove(file_path);
+            self.quarantine_track(file_path);
+        }
+    }
+
+    /// Takes a track that can never play out of the playlist
+    /// 
+    /// Unlike `remove_queued`, Random playlists lose it too. A station
+    /// playing the track already moves on by itself.
+    pub fn forget_track(&mut self, file_path: &Path) {
+        self.queue_edits.retain(|track| track.get_location() != file_path);
+        self.announcements.retain(|announcement| announcement != file_path);
+        match &mut self.play_list {
+            PlayType::Random(play_list) | PlayType::Shuffle(play_list) => play_list.retain(|track| track.get_location() != file_path),
+            PlayType::Chronologic(play_list) | PlayType::Reverse(play_list) => {
+                play_list.retain(|track| track.get_location() != file_path)
+            },
+            PlayType::Rotation(rotation) => rotation.retain(|track| track.get_location() != file_path),
+            _ => {}
+        }
+        if let Some(tracks) = self.virtual_playlist.as_mut() {
+            tracks.retain(|track| track.get_location() != file_path);
+        }
+    }
+
+    /// Moves a track that won't decode into the stations root's `quarantine`
+    /// folder, out of every playlist, so it can be repaired or deleted by hand
+    fn quarantine_track(&mut self, file_path: &Path) {
+        self.forget_track(file_path);
+        let relative_path = file_path.strip_prefix(stations_root())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| file_path.file_name().map(PathBuf::from).unwrap_or_default());
+        let quarantine_path = stations_root().join(QUARANTINE_FOLDER).join(relative_path);
+        let moved = quarantine_path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::rename(file_path, &quarantine_path));
+        match moved {
+            Ok(()) => println!("quarantined {} as {}", file_path.display(), quarantine_path.display()),
+            Err(e) => eprintln!("Failed to quarantine {}: {}", file_path.display(), e),
+        }
+    }
+
+    /// Returns whether the station is off air for too many load errors
+    pub fn is_faulted(&self) -> bool {
+        self.faulted_until.is_some()
+    }
+
+    /// Gives a faulted station another try once its retry time comes
+    /// 
+    /// The error count is left as it was, so a single further failure
+    /// faults the station again.
+    /// 
+    /// # Returns
+    /// Path of a track to request, or None if no retry is due
+    pub fn retry_faulted(&mut self) -> Option<PathBuf> {
+        if self.faulted_until.is_none_or(|until| Instant::now() < until) {
+            return None;
+        }
+        self.faulted_until = None;
+        self.next_track()
+    }
+    
+    /// Signs the station on or off according to its broadcast hours
+    /// 
+    /// # Arguments
+    /// * `now` - Current local time of day
+    /// 
+    /// # Returns
+    /// - `Some(true)` - Station just signed on
+    /// - `Some(false)` - Station just signed off
+    /// - `None` - No change, or the station broadcasts around the clock
+    /// 
+    /// # Behavior
+    /// Signing on starts the broadcast day with a fresh track rather than
+    /// the tail of whatever was playing at sign-off. The Station Manager
+    /// takes the station on or off air based on the result.
+    pub fn update_broadcast_hours(&mut self, now: chrono::NaiveTime) -> Option<bool> {
+        let in_hours = self.broadcast_hours.as_ref()?.contains(now);
+        if in_hours != self.signed_off {
+            return None;
+        }
+        self.signed_off = !in_hours;
+
+        if in_hours {
+            self.prune_finished_sources();
+            if self.queued_sources.len() >= 2 && let Some(sink) = self.sink.as_ref() {
+                sink.skip_one();
+            }
+            self.has_skipped = false;
+        }
+        Some(in_hours)
+    }
+    
+    /// Starts and stops a Live station's scheduled streams
+    /// 
+    /// # Arguments
+    /// * `now` - Current time
+    /// 
+    /// # Returns
+    /// - `Some(true)` - A stream just started
+    /// - `Some(false)` - The stream just ended or ran out its duration
+    /// - `None` - No change, or the station isn't Live
+    /// 
+    /// # Behavior
+    /// A stream that ends is cut to static and dropped from the schedule;
+    /// streams whose time passed while another was playing are dropped
+    /// too. The Station Manager takes the station on or off air based on
+    /// the result.
+    pub fn update_live(&mut self, now: chrono::DateTime<chrono::Utc>) -> Option<bool> {
+        let PlayType::Live(streams) = &mut self.play_list else {return None;};
+
+        if let Some(status) = &self.live_stream {
+            let out_of_time = match &self.current_content {
+                Some(Content::Live(stream)) => stream.ends_at().is_some_and(|ends_at| ends_at <= now),
+                _ => false
+            };
+            if !status.has_ended() && !out_of_time {
+                return None;
+            }
+            // A stream that ended still has its broadcast delay to play out
+            if !out_of_time && self.sink.as_ref().is_some_and(|sink| !sink.empty()) {
+                return None;
+            }
+            status.stop();
+            self.queued_sources.drain(..).for_each(|queued| queued.drop_source());
+            self.current_content = None;
+            self.live_stream = None;
+            self.go_off_air();
+            return Some(false);
+        }
+
+        streams.retain(|stream| stream.ends_at().is_none_or(|ends_at| ends_at > now));
+        if streams.first().is_none_or(|stream| stream.starts_at() > now) {
+            return None;
+        }
+        let stream = streams.pop_first()?;
+        println!("{} live from {}", stream.host(), stream.location());
+        let (source, status) = http_stream::open(stream.location());
+        if self.broadcast_delay.is_zero() {
+            self.push_to_sink(Box::new(source));
+        } else {
+            self.push_to_sink(Box::new(delayed(source, self.broadcast_delay)));
+        }
+        self.current_content = Some(Content::Live(stream));
+        self.live_stream = Some(status);
+        Some(true)
+    }
+
+    /// Adds content ratings for the playlist to skip over, on top of the station's own
+    /// 
+    /// Used to apply a profile's `exclude_ratings` to every station on its dial.
+    pub fn exclude_ratings(&mut self, ratings: &[String]) {
+        self.excluded_ratings.extend(ratings.iter().cloned());
+        self.drop_excluded();
+    }
+
+    /// Re-checks the station's listening restrictions
+    /// 
+    /// # Arguments
+    /// * `now` - Current local time of day
+    /// * `profile` - Dial profile in use, or `None` for the standard layout
+    /// 
+    /// # Returns
+    /// - `Some(true)` - Station just became restricted
+    /// - `Some(false)` - Station just became available again
+    /// - `None` - No change, or the station has no restrictions
+    pub fn update_restrictions(&mut self, now: chrono::NaiveTime, profile: Option<&str>) -> Option<bool> {
+        let restricted = !self.restrictions.as_ref()?.allows(now, profile);
+        if restricted == self.restricted {
+            return None;
+        }
+        self.restricted = restricted;
+        Some(restricted)
+    }
+
+    /// Returns whether the dial is kept from hearing this station right now
+    pub fn is_restricted(&self) -> bool {
+        self.restricted
+    }
+    
+    /// Takes station off-air and pauses playback
+    /// 
+    /// Called when:
+    /// - Chronologic/Reverse playlists are exhausted
+    /// - Station encounters unrecoverable errors
+    /// 
+    /// Effects:
     /// - Pauses the sink
     /// - Sets `on_air = false`
     /// - Station becomes inactive (pure static on the dial)
@@ -318,56 +1679,357 @@ impl Station {
     /// Called by Station Manager based on dial position to create the
     /// smooth fade between station audio and static as the dial is tuned.
     pub fn set_volume(&mut self, volume: f32) {
-        if let Some(sink) = self.sink.as_mut() {
-            sink.set_volume(volume);
-        }
+        self.gain.set(volume);
+    }
+
+    /// Returns the station's volume (0.0 for stations without a sink)
+    pub fn volume(&self) -> f32 {
+        if self.sink.is_some() {self.gain.target()} else {0.0}
+    }
+
+    /// Returns whether the station is paused (or has nothing to play through)
+    pub fn is_paused(&self) -> bool {
+        self.sink.as_ref().is_none_or(|sink| sink.is_paused())
     }
     
-    /// Skips the current track and advances to the next
+    /// Starts or stops copying this station's audio to the monitor output
+    pub fn set_monitored(&mut self, monitored: bool) {
+        self.monitor_tap.set_open(monitored);
+    }
+
+    /// Returns whether this station is playing on the monitor output
+    pub fn is_monitored(&self) -> bool {
+        self.monitor_tap.is_open()
+    }
+
+    /// Sends this station, including what it has queued, to other speaker channels
+    pub fn set_channels(&mut self, channels: Channels) {
+        self.channel_route.set(channels);
+    }
+    
+    /// Drops the queued track and replaces it with a new one
     /// 
     /// Used during turnover events to keep all non-active stations
     /// moving forward in "radio time". Prevents duplicate skips with
     /// the `has_skipped` flag.
     /// 
+    /// The track that is currently playing (even if paused) is left alone;
+    /// only the source queued behind it is dropped, and the playlist advances
+    /// exactly once to pick its replacement.
+    /// 
     /// # Returns
     /// - `Some(PathBuf)` - Path to new track for File Loader to decode
-    /// - `None` - Already skipped this session, or no more tracks available
+    /// - `None` - Already skipped this session, nothing queued to drop,
+    ///   or no more tracks available
     /// 
     /// # Turnover Behavior
     /// The `has_skipped` flag ensures each station only skips once per
     /// turnover event. Flag is reset when station is unpaused (becomes active).
+    /// 
+    /// Continuous mixes with a cue sheet skip to the next cue index within
+    /// the mix instead of dropping the whole file. Announcements are never
+    /// skipped, whether playing or queued.
     pub fn skip(&mut self) -> Option<PathBuf> {
         // Prevent duplicate skips
         if self.has_skipped {
             return None;
         }
+
+        // Signed-off and faulted stations aren't broadcasting, so radio time stands still
+        if self.signed_off || self.faulted_until.is_some() {
+            return None;
+        }
+
+        // Announcements play to the end
+        if self.announcement_playing() || self.announcement_queued() {
+            return None;
+        }
+
+        // Skip within a continuous mix if there's another cue to jump to
+        if self.skip_to_next_cue() {
+            self.has_skipped = true;
+            return None;
+        }
         
-        if let Some(sink) = self.sink.as_mut() {
+        // Only a source that hasn't started playing can be dropped
+        self.prune_finished_sources();
+        if self.queued_sources.len() < 2 {
+            return None;
+        }
+
+        // Enough audio is already buffered to cover the time away
+        if self.queued_duration() >= constants::PLENTY_QUEUED {
+            return None;
+        }
+
+        if let Some(queued) = self.queued_sources.pop_back() {
+            queued.drop_source();
             self.has_skipped = true;
-            sink.skip_one();
-            return self.next();
+            return self.replace_next();
         }
         
         None
     }
     
+    /// Skips the track that is playing, at a listener's request
+    /// 
+    /// The sink moves straight on to the queued next track, and the
+    /// station asks for another on its next `needs_next()`. Nothing is
+    /// skipped while the next track is still loading or an announcement
+    /// is playing.
+    /// 
+    /// # Returns
+    /// `true` if a track was skipped
+    pub fn skip_track(&mut self) -> bool {
+        if self.announcement_playing() {
+            return false;
+        }
+        self.prune_finished_sources();
+        if self.queued_sources.len() < 2 {
+            return false;
+        }
+        let Some(sink) = self.sink.as_ref() else {return false;};
+        sink.skip_one();
+        // A skipped track never finishes, so it goes in the history now
+        if let (Some(Content::Track(track)), None) = (&self.current_content, &self.virtual_playlist) {
+            let file_path = track.get_location();
+            storage().record_play(&self.station_path, file_path, &self.origin_of(file_path), false);
+        }
+        true
+    }
+
+    /// Seeks within the track that is playing
+    /// 
+    /// Relative seeks are measured from the sink's current position, and
+    /// positions before the start of the track clamp to the start.
+    /// 
+    /// # Returns
+    /// `true` if the sink moved to the new position, `false` if it couldn't,
+    /// the position isn't a finite number of seconds, or an announcement is
+    /// playing
+    pub fn seek(&mut self, to: SeekTo) -> bool {
+        if self.announcement_playing() {
+            return false;
+        }
+        let Some(sink) = self.sink.as_ref() else {return false;};
+        let target_seconds = match to {
+            SeekTo::Absolute(seconds) => seconds,
+            SeekTo::Relative(seconds) => sink.get_pos().as_secs_f64() + seconds,
+        };
+        // Not a number, infinite, or past anything a Duration holds
+        let target = Some(target_seconds)
+            .filter(|seconds| seconds.is_finite())
+            .and_then(|seconds| Duration::try_from_secs_f64(seconds.max(0.0)).ok());
+        let Some(target) = target else {
+            eprintln!("Not seeking {} to {} seconds", self.station_path.display(), target_seconds);
+            return false;
+        };
+
+        match sink.try_seek(target) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Failed to seek {} to {:?}: {}", self.station_path.display(), target, e);
+                false
+            }
+        }
+    }
+
+    /// Seeks the sink to the next cue index of the current mix
+    /// 
+    /// # Returns
+    /// `true` if the current content has a cue sheet with a later cue and
+    /// the sink seeked to it, `false` otherwise (caller should skip the file)
+    fn skip_to_next_cue(&mut self) -> bool {
+        let Some(sink) = self.sink.as_ref() else {return false;};
+        let Some(Content::Track(track)) = self.current_content.as_ref() else {return false;};
+        let Some(cue_sheet) = track.get_cue_sheet() else {return false;};
+
+        match cue_sheet.next_after(sink.get_pos()) {
+            Some(next_cue) => sink.try_seek(next_cue.start).is_ok(),
+            None => false
+        }
+    }
+
+    /// Returns the cue sheet entry playing right now, for continuous mixes
+    /// 
+    /// # Returns
+    /// - `Some(&CueTrack)` - Marker covering the sink's current position
+    /// - `None` - Current content has no cue sheet or nothing is playing
+    pub fn current_cue(&self) -> Option<&CueTrack> {
+        let sink = self.sink.as_ref()?;
+        match self.current_content.as_ref()? {
+            Content::Track(track) => track.get_cue_sheet()?.track_at(sink.get_pos()),
+            _ => None
+        }
+    }
+
+    /// Returns where the track this station is playing came from
+    pub fn current_origin(&self) -> Option<&TrackOrigin> {
+        match self.current_content.as_ref()? {
+            Content::Track(track) => Some(track.origin()),
+            _ => None
+        }
+    }
+
+    /// Finds where a queued or playing track came from, reading its
+    /// `.origin` file if the track has already been let go
+    fn origin_of(&self, file_path: &Path) -> TrackOrigin {
+        [&self.current_content, &self.next_content].into_iter()
+            .filter_map(|content| match content {
+                Some(Content::Track(track)) => Some(track),
+                _ => None
+            })
+            .chain(self.queue_edits.iter())
+            .find(|track| track.get_location() == file_path)
+            .map_or_else(|| TrackOrigin::read(file_path), |track| track.origin().clone())
+    }
+
+    /// Returns a display title for what this station is playing
+    /// 
+    /// Uses the cue sheet title when playing a continuous mix, otherwise
+    /// falls back to the file name of the current track.
+    pub fn current_title(&self) -> Option<String> {
+        if let Some(title) = self.current_cue().and_then(|cue| cue.title.clone()) {
+            return Some(title);
+        }
+        match self.current_content.as_ref()? {
+            Content::Track(track) => track.get_location()
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string()),
+            Content::Live(stream) => Some(stream.host().to_string())
+        }
+    }
+    
+    /// Lists the tracks this station will play next, without advancing it
+    /// 
+    /// Starts with the queued content, then reads ahead in the playlist
+    /// in the order each strategy would pick. Random stations can't be
+    /// predicted past what is already queued.
+    /// 
+    /// # Arguments
+    /// * `count` - Maximum number of tracks to list
+    pub fn preview(&self, count: usize) -> Vec<TrackPreview> {
+        let queued = [&self.current_content, &self.next_content].into_iter()
+            .filter_map(|content| match content {
+                Some(Content::Track(track)) => Some(track),
+                _ => None
+            });
+
+        let upcoming: Box<dyn Iterator<Item = &Track> + '_> = match &self.play_list {
+            PlayType::Shuffle(play_list) => Box::new(play_list.iter().rev()),
+            PlayType::Chronologic(play_list) => Box::new(play_list.iter()),
+            PlayType::Reverse(play_list) => Box::new(play_list.iter().rev()),
+            // Once round, as the picker goes, or excluded tracks never run out
+            PlayType::Network(cursor) => Box::new(cursor.upcoming().take(cursor.playlist_length())),
+            PlayType::Rotation(rotation) => Box::new(rotation.upcoming()),
+            PlayType::Random(_) | PlayType::Live(_) | PlayType::Time(_) | PlayType::Generated(..) | PlayType::Dead => {
+                Box::new(std::iter::empty())
+            }
+        };
+
+        let upcoming = upcoming.filter(|track| !rating::is_excluded(track.ratings(), &self.excluded_ratings));
+
+        queued.chain(self.queue_edits.iter())
+            .chain(upcoming)
+            .take(count)
+            .map(TrackPreview::from_track)
+            .collect()
+    }
+    
+    /// Returns how far the station is into the track playing, or zero without a sink
+    pub fn position(&self) -> Duration {
+        self.sink.as_ref().map_or(Duration::ZERO, |sink| sink.get_pos())
+    }
+
+    /// Returns how much audio is buffered in the sink, from the playhead on
+    /// 
+    /// Sums the lengths of the queued sources, less what has already played
+    /// of the first one. Sources of unknown length count as empty.
+    pub fn queued_duration(&mut self) -> Duration {
+        self.prune_finished_sources();
+        let total: Duration = self.queued_sources.iter()
+            .filter(|handle| !handle.is_dropped())
+            .filter_map(|handle| handle.duration())
+            .sum();
+        let played = self.sink.as_ref().map_or(Duration::ZERO, |sink| sink.get_pos());
+
+        total.saturating_sub(played)
+    }
+    
     /// Checks if station's sink needs more audio
     /// 
     /// # Returns
-    /// `true` if sink has fewer than 2 sources queued, indicating it's
-    /// time to request the next track to prevent playback gaps.
+    /// `true` if sink has fewer than 2 sources queued or on their way from
+    /// the File Loader, indicating it's time to request the next track to
+    /// prevent playback gaps.
     /// 
     /// # Usage
     /// Called by Station Manager in main loop to determine when to
     /// request next track from File Loader.
     pub fn needs_next(&self) -> bool {
         if let Some(sink) = self.sink.as_ref() {
-            return sink.len() < 2;
+            return sink.len() + self.loading.len() < 2;
         }
         
         false
     }
     
+    /// Returns how many sources are waiting in the sink, including the one playing
+    pub fn queued_sources(&self) -> usize {
+        self.sink.as_ref().map_or(0, |sink| sink.len())
+    }
+
+    /// Describes everything this station holds, for the debug snapshot
+    pub fn snapshot(&self, station_id: StationID) -> StationSnapshot {
+        let (play_type, playlist_remaining, playlist_cursor) = match &self.play_list {
+            PlayType::Random(tracks) => ("Random", tracks.len(), None),
+            PlayType::Chronologic(tracks) => ("Chronologic", tracks.len(), tracks.first()),
+            PlayType::Reverse(tracks) => ("Reverse", tracks.len(), tracks.last()),
+            PlayType::Shuffle(tracks) => ("Shuffle", tracks.len(), None),
+            PlayType::Network(cursor) => ("Network", cursor.playlist_length(), cursor.upcoming().next()),
+            PlayType::Rotation(rotation) => ("Rotation", rotation.len(), rotation.upcoming().next()),
+            PlayType::Live(streams) => ("Live", streams.len(), None),
+            PlayType::Time(_) => ("Time", 0, None),
+            PlayType::Generated(..) => ("Generated", 0, None),
+            PlayType::Dead => ("Dead", 0, None),
+        };
+        let content_path = |content: &Option<Content>| match content {
+            Some(Content::Track(track)) => Some(track.get_location().to_path_buf()),
+            _ => None
+        };
+        let sink_length = self.queued_sources();
+
+        StationSnapshot {
+            station: station_id.to_string(),
+            name: self.name(),
+            path: self.station_path.clone(),
+            play_type: play_type.to_string(),
+            playlist_remaining,
+            playlist_cursor: playlist_cursor.map(|track| track.get_location().to_path_buf()),
+            current: content_path(&self.current_content),
+            next: content_path(&self.next_content),
+            sink_length,
+            awaiting_load: self.loading.len(),
+            queue_edits: self.queue_edits.iter().map(|track| track.get_location().to_path_buf()).collect(),
+            announcements: self.announcements.clone(),
+            on_air: self.on_air,
+            signed_off: self.signed_off,
+            restricted: self.restricted,
+            paused: self.is_paused(),
+            volume: self.volume(),
+            consecutive_errors: self.consecutive_errors,
+            faulted: self.is_faulted(),
+        }
+    }
+
+    /// Returns whether this station generates its audio instead of loading files
+    /// 
+    /// Generated stations are on air as soon as they're primed, since no
+    /// track comes back from the File Loader to put them there.
+    pub fn is_generated(&self) -> bool {
+        matches!(self.play_list, PlayType::Time(_) | PlayType::Generated(..))
+    }
+    
     /// Returns whether this station is currently on-air
     /// 
     /// # Returns
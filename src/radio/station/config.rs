@@ -4,71 +4,736 @@
 //! Each station directory contains a station.info file that defines:
 //! - Playlist type (Random, Shuffle, Chronologic, etc.)
 //! - Purge flag (whether to delete files after playing)
+//! - Network settings for stations sharing another playlist
+//! - Broadcast hours for stations that sign off overnight
+//! - Restrictions on when and in which profiles listeners can tune in
+//!
+//! Files written for older releases are upgraded by the `migration` module
+//! before they're parsed.
 
-use std::{fs::read_to_string, path::Path};
+pub mod migration;
+
+use std::{fmt, fs, fs::read_to_string, path::Path};
+use chrono::{NaiveDate, NaiveTime, Timelike};
 use serde::Deserialize;
-use serde_json::from_str;
+use serde_json::{Map, Value, from_value};
+
+use crate::config::{drop_invalid_fields, parse_json_object, radio_config};
+use crate::error::MokRadioError;
+
+/// Every play_type a station.info may give
+pub const PLAY_TYPES: [&str; 12] = [
+    "Random", "Shuffle", "Chronologic", "Reverse", "Network", "Live",
+    "Rotation", "Time", "Numbers", "Morse", "Warble", "Dead"
+];
 
 /// Station configuration loaded from station.info JSON file
 /// 
 /// # JSON Format
 /// ```json
 /// {
+///     "version": 2,
 ///     "play_type": "Random",
 ///     "purge": false
 /// }
 /// ```
 /// 
+/// A Chronologic station can add `"window_days": 30` to become a rolling
+/// "recent uploads" station, and `"start_from"` with a date
+/// (`"2021-09-01"`) or an episode's file name to skip the archive before
+/// it. A Shuffle station can add
+/// `"artist_separation": 3` to keep the same artist from playing within
+/// three consecutive tracks. Any station can add `broadcast_hours` to sign
+/// off overnight, and `restrictions` to keep listeners out at some hours
+/// or outside some profiles. A Live station lists its streams in `live`
+/// and can run `broadcast_delay_secs` behind them, and a Rotation station its folders and their shares in `rotation`.
+/// A Time station can tune its announcements with
+/// `time_service`, and the generated stations their sound with `generator`.
+/// Any station can keep rated tracks off the air with `exclude_ratings`,
+/// e.g. `["explicit"]` (see `content::rating`), and bring every track to
+/// the same loudness with `loudness_target` in LUFS, e.g. `-16.0` (see
+/// `station::loudness`).
+/// A `purge` station deletes each track once it has played, or moves it
+/// to the station's `.trash/` folder with `"purge_to_trash": true`.
+/// Small playlists of jingles or loops can set `"ram_cache": true` to play
+/// from tmpfs instead of the SD card, and `"random_start": true` joins the
+/// first track at a random point, like tuning in to a broadcast already
+/// in progress. `balance` (-1.0 left to 1.0 right)
+/// and `stereo_width` (0.0 mono to 1.0 as recorded) place any station in
+/// the stereo field, and `noise_gate` quiets the hiss of old recordings
+/// in their silent passages.
+/// 
 /// # Valid play_type Values
 /// - "Random" - Pick random tracks, keep all in playlist
 /// - "Shuffle" - Play all tracks once in random order
 /// - "Chronologic" - Play tracks oldest to newest by file modification date
 /// - "Reverse" - Play tracks newest to oldest by file modification date
+/// - "Network" - Play a shared playlist offset in time (needs `network`)
+/// - "Live" - Play internet radio streams at scheduled times (needs `live`)
+/// - "Rotation" - Mix several folders at set ratios (needs `rotation`)
+/// - "Time" - Speaking clock, announcing the time with a pip on the mark
+/// - "Numbers" - Numbers station sending DTMF digit groups
+/// - "Morse" - Beacon keying a message in Morse code
+/// - "Warble" - Drifting shortwave heterodyne whistle
 /// - "Dead" - Station is off-air/inactive
 #[derive(Deserialize)]
 pub struct StationConfig {
+    /// station.info format version (always current once migrated)
+    #[serde(default)]
+    pub version: u64,
+
+    /// Display name for the station (defaults to its directory)
+    #[serde(default)]
+    pub name: Option<String>,
+
     /// Type of playlist behavior
     pub play_type: String,
     
     /// Whether to delete audio files after playing (for ephemeral content)
     pub purge: bool,
+
+    /// Purge into the station's `.trash/` folder instead of deleting
+    #[serde(default)]
+    pub purge_to_trash: bool,
+
+    /// Shared playlist settings for "Network" stations
+    #[serde(default)]
+    pub network: Option<NetworkConfig>,
+
+    /// Chronologic only: ignore tracks modified more than this many days ago
+    #[serde(default)]
+    pub window_days: Option<u64>,
+
+    /// Chronologic only: date (YYYY-MM-DD) or episode file name to start from
+    #[serde(default)]
+    pub start_from: Option<String>,
+
+    /// Shuffle only: number of tracks before the same artist may play again
+    #[serde(default)]
+    pub artist_separation: Option<usize>,
+
+    /// Virtual DJ announcing upcoming tracks
+    #[serde(default)]
+    pub dj: Option<DjConfig>,
+
+    /// Daily on-air hours (on air around the clock if not set)
+    #[serde(default)]
+    pub broadcast_hours: Option<BroadcastHours>,
+
+    /// When and in which profiles the station can be tuned in (always if not set)
+    #[serde(default)]
+    pub restrictions: Option<Restrictions>,
+
+    /// Content ratings the playlist skips over
+    #[serde(default)]
+    pub exclude_ratings: Vec<String>,
+
+    /// Loudness in LUFS to play every track at (tracks play as mastered if not set)
+    #[serde(default)]
+    pub loudness_target: Option<f32>,
+
+    /// Play the playlist from a copy in RAM (see `file_loader::ram_cache`)
+    #[serde(default)]
+    pub ram_cache: bool,
+
+    /// Start the first track part-way through, as if the station had been on air all along
+    #[serde(default)]
+    pub random_start: bool,
+
+    /// Time only: announcement settings
+    #[serde(default)]
+    pub time_service: Option<TimeServiceConfig>,
+
+    /// Numbers, Morse and Warble only: sound settings
+    #[serde(default)]
+    pub generator: Option<GeneratorConfig>,
+
+    /// Live only: scheduled streams
+    #[serde(default)]
+    pub live: Vec<LiveStreamConfig>,
+
+    /// Live only: seconds the station plays behind its stream (none if not set, at most a minute)
+    #[serde(default)]
+    pub broadcast_delay_secs: f32,
+
+    /// Rotation only: folders and their shares
+    #[serde(default)]
+    pub rotation: Option<RotationConfig>,
+
+    /// Left/right balance, -1.0 to 1.0 (centered if not set)
+    #[serde(default)]
+    pub balance: f32,
+
+    /// Stereo width, 0.0 (mono) to 1.0 (as recorded)
+    #[serde(default = "default_stereo_width")]
+    pub stereo_width: f32,
+
+    /// Gate turning down hiss between passages (off if not set)
+    #[serde(default)]
+    pub noise_gate: Option<NoiseGateConfig>,
+}
+
+fn default_stereo_width() -> f32 {
+    1.0
+}
+
+/// Settings for the virtual DJ
+/// 
+/// Before every `every`-th track the DJ plays `dj/<track name>.mp3` from
+/// the station folder if it exists, otherwise it speaks `template` with
+/// `{title}` and `{artist}` filled in from the track's tags. Without a
+/// template the DJ uses the "up next" phrase for its language.
+/// 
+/// With `talk_over`, the DJ talks over the start of the track instead:
+/// `beds/<track name>.mp3` (or any file in `beds/` if the track has none
+/// of its own) is mixed over the track's opening, with the track ducked to
+/// `duck` for at most `max_secs`. Tracks with no bed get the usual intro.
+/// 
+/// # JSON Format
+/// ```json
+/// "dj": {
+///     "every": 3,
+///     "template": "Up next, {title} by {artist}",
+///     "language": "fr",
+///     "talk_over": { "duck": 0.3, "max_secs": 15.0 }
+/// }
+/// ```
+#[derive(Deserialize, Clone)]
+pub struct DjConfig {
+    /// Announce before every Nth track
+    #[serde(default = "default_dj_every")]
+    pub every: usize,
+
+    /// Spoken announcement text (the language's "up next" phrase if not set)
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Language this DJ speaks (the radio's language if not set)
+    #[serde(default)]
+    pub language: Option<String>,
+
+    /// Talk over the start of tracks with beds instead of intros
+    #[serde(default)]
+    pub talk_over: Option<TalkOverConfig>,
+}
+
+fn default_dj_every() -> usize {
+    3
+}
+
+/// Mix settings for a DJ talking over the start of tracks
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct TalkOverConfig {
+    /// Track level under the bed, 0.0 to 1.0
+    pub duck: f32,
+
+    /// Longest a bed may run over the track
+    pub max_secs: f32,
+}
+
+impl Default for TalkOverConfig {
+    fn default() -> Self {
+        TalkOverConfig {
+            duck: 0.3,
+            max_secs: 15.0,
+        }
+    }
+}
+
+/// Noise gate for hissy source material (see `audio::noise_gate`)
+/// 
+/// When the audio falls below `threshold_db` (dBFS) the station is turned
+/// down by up to `range_db`, easing off over `release_ms` so the hiss
+/// fades out rather than cutting off. It opens again as soon as the audio
+/// comes back above the threshold.
+/// 
+/// # JSON Format
+/// ```json
+/// "noise_gate": { "threshold_db": -50.0, "release_ms": 250, "range_db": 30.0 }
+/// ```
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct NoiseGateConfig {
+    /// Level below which the gate closes, in dBFS
+    pub threshold_db: f32,
+
+    /// Time taken to close once the audio drops below the threshold
+    pub release_ms: u64,
+
+    /// How far a closed gate turns the station down, in dB
+    pub range_db: f32,
+}
+
+impl Default for NoiseGateConfig {
+    fn default() -> Self {
+        NoiseGateConfig {
+            threshold_db: -50.0,
+            release_ms: 250,
+            range_db: 30.0,
+        }
+    }
+}
+
+/// Settings for a "Time" station
+/// 
+/// The station announces the time every `interval_secs` (which should
+/// divide a minute), with a pip exactly on the mark. Word clips are read
+/// from the station's `clips/` folder, named `at_the_tone`, `oclock`,
+/// `and`, `seconds` and `0` to `59`; missing clips are spoken with
+/// text-to-speech in `language`.
+/// 
+/// # JSON Format
+/// ```json
+/// "time_service": {
+///     "interval_secs": 10,
+///     "twenty_four_hour": false,
+///     "language": "en"
+/// }
+/// ```
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct TimeServiceConfig {
+    /// Seconds between announcements
+    pub interval_secs: u64,
+
+    /// Announce 24-hour time instead of 12-hour
+    pub twenty_four_hour: bool,
+
+    /// Language for spoken clips (the radio's language if not set)
+    pub language: Option<String>,
+}
+
+impl Default for TimeServiceConfig {
+    fn default() -> Self {
+        TimeServiceConfig {
+            interval_secs: 10,
+            twenty_four_hour: false,
+            language: None,
+        }
+    }
+}
+
+/// Settings for the generated "Numbers", "Morse" and "Warble" stations
+/// 
+/// Every field is optional; each generator reads only the ones it uses.
+/// 
+/// # JSON Format
+/// ```json
+/// "generator": {
+///     "message": "VVV VVV DE MOK",
+///     "wpm": 15,
+///     "tone_hz": 700.0,
+///     "groups": 10,
+///     "pause_secs": 5.0,
+///     "seed": 1962
+/// }
+/// ```
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct GeneratorConfig {
+    /// Morse: text to key
+    pub message: String,
+
+    /// Morse: keying speed in words per minute
+    pub wpm: u32,
+
+    /// Morse: tone pitch; Warble: center of the whistle
+    pub tone_hz: f32,
+
+    /// Numbers: digit groups per transmission
+    pub groups: usize,
+
+    /// Numbers and Morse: silence between transmissions
+    pub pause_secs: f32,
+
+    /// Warble: how far the whistle drifts either side of `tone_hz`
+    pub depth_hz: f32,
+
+    /// Warble: drift cycles per second
+    pub rate_hz: f32,
+
+    /// Numbers: fixed random seed, to repeat the same digits every boot
+    pub seed: Option<u64>,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            message: "VVV VVV DE MOK".to_string(),
+            wpm: 15,
+            tone_hz: 700.0,
+            groups: 10,
+            pause_secs: 5.0,
+            depth_hz: 40.0,
+            rate_hz: 0.3,
+            seed: None,
+        }
+    }
+}
+
+/// Daily on-air hours for a station
+/// 
+/// Outside these hours the station signs off and its frequency is static.
+/// Times are local `HH:MM`; `"24:00"` means midnight at the end of the
+/// day, and an `end` before `start` runs past midnight.
+/// 
+/// # JSON Format
+/// ```json
+/// "broadcast_hours": {
+///     "start": "06:00",
+///     "end": "24:00"
+/// }
+/// ```
+#[derive(Deserialize, Clone)]
+pub struct BroadcastHours {
+    /// Sign-on time
+    pub start: String,
+
+    /// Sign-off time
+    pub end: String,
+}
+
+impl BroadcastHours {
+    /// Returns whether the station is scheduled to be on air at a time
+    /// 
+    /// Unreadable times are logged and treated as on air all day.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (minute_of_day(&self.start), minute_of_day(&self.end)) else {
+            eprintln!("Invalid broadcast hours {}-{}, staying on air", self.start, self.end);
+            return true;
+        };
+        let now = time.hour() * 60 + time.minute();
+
+        if start <= end {
+            start <= now && now < end
+        } else {
+            // Overnight schedule, e.g. 18:00-02:00
+            now >= start || now < end
+        }
+    }
+}
+
+/// Limits on who can tune in to a station, and when
+/// 
+/// Unlike broadcast hours, restrictions don't stop the station: it keeps
+/// its place in the playlist, but the dial hears static on its frequency
+/// while it's restricted. `hours` uses the same format as broadcast hours.
+/// A station with `profiles` is only on the dial in those profiles (never
+/// in the standard layout).
+/// 
+/// # JSON Format
+/// ```json
+/// "restrictions": {
+///     "hours": { "start": "21:00", "end": "05:00" },
+///     "profiles": ["adults"]
+/// }
+/// ```
+#[derive(Deserialize, Clone)]
+pub struct Restrictions {
+    /// Hours the station can be tuned in (any time if not set)
+    #[serde(default)]
+    pub hours: Option<BroadcastHours>,
+
+    /// Profiles the station can be tuned in under (any if empty)
+    #[serde(default)]
+    pub profiles: Vec<String>,
+}
+
+impl Restrictions {
+    /// Returns whether listeners may tune in at a time under a profile
+    /// 
+    /// # Arguments
+    /// * `time` - Current local time of day
+    /// * `profile` - Dial profile in use, or `None` for the standard layout
+    pub fn allows(&self, time: NaiveTime, profile: Option<&str>) -> bool {
+        let in_hours = self.hours.as_ref().is_none_or(|hours| hours.contains(time));
+        let in_profile = self.profiles.is_empty()
+            || profile.is_some_and(|profile| self.profiles.iter().any(|allowed| allowed == profile));
+        in_hours && in_profile
+    }
+}
+
+/// Where a Chronologic station starts instead of its oldest track
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartFrom {
+    /// The first track modified on or after this local date
+    Date(NaiveDate),
+
+    /// A track by file name, with or without its extension
+    Episode(String),
+}
+
+impl StartFrom {
+    /// Reads a `start_from` value: a `YYYY-MM-DD` date, or else an episode
+    pub fn parse(start_from: &str) -> StartFrom {
+        let start_from = start_from.trim();
+        match NaiveDate::parse_from_str(start_from, "%Y-%m-%d") {
+            Ok(date) => StartFrom::Date(date),
+            Err(_) => StartFrom::Episode(start_from.to_string())
+        }
+    }
+
+    /// Returns whether a file is the named episode
+    pub fn is_episode(&self, file_path: &Path) -> bool {
+        let StartFrom::Episode(name) = self else {return false;};
+        [file_path.file_name(), file_path.file_stem()].into_iter()
+            .flatten()
+            .any(|file_name| file_name.to_string_lossy() == name.as_str())
+    }
+}
+
+impl fmt::Display for StartFrom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StartFrom::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            StartFrom::Episode(name) => write!(f, "{}", name)
+        }
+    }
+}
+
+/// Parses `HH:MM` (00:00 to 24:00) into minutes since midnight
+fn minute_of_day(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    let minute_of_day = hours * 60 + minutes;
+
+    (minutes < 60 && minute_of_day <= 24 * 60).then_some(minute_of_day)
+}
+
+/// Settings for an affiliate station carrying a shared playlist
+/// 
+/// # JSON Format
+/// ```json
+/// "network": {
+///     "playlist": "/stations/network/news",
+///     "offset_minutes": 60
+/// }
+/// ```
+#[derive(Deserialize)]
+pub struct NetworkConfig {
+    /// Directory holding the shared playlist
+    pub playlist: String,
+
+    /// How far into the shared playlist this station starts
+    #[serde(default)]
+    pub offset_minutes: i64,
+}
+
+/// One scheduled stream for a "Live" station
+/// 
+/// The station comes on air at `start` (plus `delay_secs`) with the
+/// stream at `url`, and cuts back to static when the stream ends or
+/// after `duration_mins`, which keeps ads and premium-only segments off
+/// the dial. Without a `start` the stream plays as soon as the station
+/// loads; without a `duration_mins` it plays until the stream ends.
+/// `host` names the stream in the status and history (the URL's host if
+/// not set).
+/// 
+/// # JSON Format
+/// ```json
+/// "live": [
+///     {
+///         "url": "http://ice.example.org:8000/jazz.mp3",
+///         "start": "2026-11-01T20:00:00Z",
+///         "delay_secs": 30,
+///         "duration_mins": 60,
+///         "host": "Jazz FM"
+///     }
+/// ]
+/// ```
+#[derive(Deserialize, Clone)]
+pub struct LiveStreamConfig {
+    /// Stream URL (`http://` only)
+    pub url: String,
+
+    /// RFC 3339 start time (starts right away if not set)
+    #[serde(default)]
+    pub start: Option<String>,
+
+    /// Seconds to wait after `start`
+    #[serde(default)]
+    pub delay_secs: Option<u64>,
+
+    /// Minutes to play before cutting to static
+    #[serde(default)]
+    pub duration_mins: Option<u64>,
+
+    /// Display name for the stream
+    #[serde(default)]
+    pub host: Option<String>,
+}
+
+/// Folders for a "Rotation" station and how often each plays
+/// 
+/// `percent` is each folder's share of the picks; shares needn't add up
+/// to 100, they're taken relative to each other. The ratios are kept over
+/// every `window` consecutive tracks.
+/// 
+/// # JSON Format
+/// ```json
+/// "rotation": {
+///     "folders": [
+///         { "path": "music", "percent": 70 },
+///         { "path": "ads", "percent": 20 },
+///         { "path": "bumpers", "percent": 10 }
+///     ],
+///     "window": 20
+/// }
+/// ```
+#[derive(Deserialize)]
+pub struct RotationConfig {
+    /// Folders to draw from
+    pub folders: Vec<RotationFolderConfig>,
+
+    /// Tracks the ratios are kept over
+    #[serde(default = "default_rotation_window")]
+    pub window: usize,
+}
+
+fn default_rotation_window() -> usize {
+    20
+}
+
+/// One folder in a station's rotation
+#[derive(Deserialize)]
+pub struct RotationFolderConfig {
+    /// Folder path, relative to the station folder unless absolute
+    pub path: String,
+
+    /// Share of the picks
+    pub percent: f32,
 }
 
 impl StationConfig {
     /// Loads station configuration from station.info JSON file
     /// 
     /// # Arguments
-    /// * `file_path` - Path to station directory (looks for station.info inside)
+    /// * `station_path` - Path to station directory (looks for station.info inside)
     /// 
     /// # Returns
     /// - Successfully parsed StationConfig if file exists and is valid JSON
-    /// - Default "Dead" config if file is missing or malformed
+    /// - Defaults for optional fields that don't parse (`purge` defaults
+    ///   to false, so a bad value never deletes files)
+    /// - Default "Dead" config if the file is missing, isn't a JSON object,
+    ///   or has no usable `play_type`
     /// 
     /// # Error Handling
     /// Rather than propagating errors, this function returns a safe default
     /// (Dead station) and logs the error. This allows the system to continue
-    /// operating even if individual station configs are corrupted.
-    pub fn new(file_path: &Path) -> Self {
+    /// operating even if individual station configs are corrupted. Use
+    /// `load()` to get the error instead.
+    pub fn new(station_path: &Path) -> Self {
+        StationConfig::load(station_path).unwrap_or_else(|e| {
+            // Log error and return default "Dead" station
+            eprintln!("{}", e);
+            StationConfig::dead()
+        })
+    }
+
+    /// Loads station configuration, returning what went wrong if it can't
+    ///
+    /// Bad optional fields still fall back to their defaults, as in `new()`.
+    ///
+    /// # Errors
+    /// - `MokRadioError::Io` if station.info can't be read
+    /// - `MokRadioError::Config` if it isn't a JSON object, or doesn't
+    ///   parse even without its bad fields
+    pub fn load(station_path: &Path) -> Result<Self, MokRadioError> {
         // Attempt to read the configuration file
-        let configuration_file = read_to_string(file_path);
-        
-        match configuration_file {
-            Ok(configuration) => {
-                // Parse JSON into StationConfig struct
-                let station_config: StationConfig = from_str(&configuration).unwrap();
-                station_config
-            },
-            Err(e) => {
-                // Log error and return default "Dead" station
-                eprintln!("Failed to load config from {}: {}", file_path.display(), e);
-                
-                // Return a default "Dead" station config
-                // This allows system to continue even with missing/corrupted configs
-                StationConfig { 
-                    play_type: "Dead".to_string(), 
-                    purge: false
-                }
-            }
+        let file_path = &station_path.join("station.info");
+        let configuration = read_to_string(file_path).map_err(|e| MokRadioError::io(file_path, e))?;
+        let mut fields = parse_json_object(&configuration)
+            .map_err(|message| MokRadioError::Config { path: file_path.clone(), message })?;
+
+        // Bring files from older releases up to date
+        let old_version = migration::declared_version(&fields);
+        if migration::migrate(&mut fields, file_path) && radio_config().rewrite_migrated_configs {
+            migration::rewrite(&fields, file_path, old_version);
+        }
+
+        // Drop bad fields, checking each alongside the required ones
+        let baseline = StationConfig::dead_fields();
+        drop_invalid_fields::<StationConfig>(&mut fields, &baseline, file_path);
+        fields.entry("purge").or_insert(Value::Bool(false));
+
+        from_value(Value::Object(fields))
+            .map_err(|e| MokRadioError::Config { path: file_path.clone(), message: e.to_string() })
+    }
+
+    /// Changes fields of a station's station.info
+    ///
+    /// The edited file must parse whole with a known play_type, so a bad
+    /// edit is turned away rather than left for `load()` to drop. It's
+    /// written to a temporary file and renamed over the old one, so a
+    /// power cut leaves one or the other.
+    ///
+    /// # Arguments
+    /// * `file_path` - Path to station directory (holding station.info)
+    /// * `settings` - Top-level fields to set, replacing any already there
+    ///
+    /// # Errors
+    /// - `MokRadioError::Io` if station.info can't be read or written
+    /// - `MokRadioError::Config` if it, or the edited version, doesn't parse
+    pub fn edit(file_path: &Path, settings: Map<String, Value>) -> Result<(), MokRadioError> {
+        let file_path = &file_path.join("station.info");
+        let configuration = read_to_string(file_path).map_err(|e| MokRadioError::io(file_path, e))?;
+        let mut fields = parse_json_object(&configuration)
+            .map_err(|message| MokRadioError::Config { path: file_path.clone(), message })?;
+        migration::migrate(&mut fields, file_path);
+        fields.extend(settings);
+
+        let config_error = |message: String| MokRadioError::Config { path: file_path.clone(), message };
+        let edited: StationConfig = from_value(Value::Object(fields.clone())).map_err(|e| config_error(e.to_string()))?;
+        if !PLAY_TYPES.contains(&edited.play_type.as_str()) {
+            return Err(config_error(format!("unknown play_type {}", edited.play_type)));
+        }
+
+        let contents = serde_json::to_string_pretty(&fields).map_err(|e| config_error(e.to_string()))?;
+        let temporary_path = file_path.with_extension("info.tmp");
+        fs::write(&temporary_path, contents + "\n")
+            .and_then(|_| fs::rename(&temporary_path, file_path))
+            .map_err(|e| MokRadioError::io(file_path, e))
+    }
+
+    /// Default "Dead" station config
+    /// 
+    /// This allows system to continue even with missing/corrupted configs
+    fn dead() -> Self {
+        StationConfig { 
+            version: migration::CURRENT_VERSION,
+            name: None,
+            play_type: "Dead".to_string(), 
+            purge: false,
+            purge_to_trash: false,
+            network: None,
+            window_days: None,
+            start_from: None,
+            artist_separation: None,
+            dj: None,
+            broadcast_hours: None,
+            restrictions: None,
+            exclude_ratings: Vec::new(),
+            loudness_target: None,
+            ram_cache: false,
+            random_start: false,
+            time_service: None,
+            generator: None,
+            live: Vec::new(),
+            broadcast_delay_secs: 0.0,
+            rotation: None,
+            balance: 0.0,
+            stereo_width: 1.0,
+            noise_gate: None
         }
     }
+
+    /// Smallest station.info that parses: the required fields of a Dead station
+    fn dead_fields() -> Map<String, Value> {
+        let mut fields = Map::new();
+        fields.insert("play_type".to_string(), Value::String("Dead".to_string()));
+        fields.insert("purge".to_string(), Value::Bool(false));
+        fields
+    }
 }
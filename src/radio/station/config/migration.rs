@@ -0,0 +1,95 @@
+//! Station Config Migration
+//!
+//! station.info files carry a `version` number so the format can change
+//! without breaking SD cards written for older releases. Files are
+//! upgraded in memory one version at a time before they're parsed, and
+//! can optionally be written back in the current format (see
+//! `rewrite_migrated_configs` in radio.info).
+//!
+//! # Versions
+//! - 1: Original format, no `version` field, `purge` required
+//! - 2: Adds `version`; `purge` is optional and defaults to false
+//!
+//! To change the format, bump `CURRENT_VERSION` and add a step to
+//! `MIGRATIONS` that upgrades the previous version's fields.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+/// Version of station.info this release writes
+pub const CURRENT_VERSION: u64 = 2;
+
+/// Files without a `version` field predate versioning
+const UNVERSIONED: u64 = 1;
+
+/// Upgrade step from the version it's listed with to the next
+type Migration = fn(&mut Map<String, Value>);
+
+/// Upgrade steps, indexed from version 1
+const MIGRATIONS: [Migration; (CURRENT_VERSION - UNVERSIONED) as usize] = [
+    version_1_to_2,
+];
+
+/// Spells out the `purge` default that version 2 made optional
+fn version_1_to_2(fields: &mut Map<String, Value>) {
+    fields.entry("purge").or_insert(Value::Bool(false));
+}
+
+/// Upgrades station.info fields to the current version
+///
+/// # Arguments
+/// * `fields` - Top-level fields of the file, upgraded in place
+/// * `file_path` - station.info, for the log
+///
+/// # Returns
+/// `true` if any upgrade step ran. Files from a newer release are left
+/// alone and parsed as well as they can be.
+pub fn migrate(fields: &mut Map<String, Value>, file_path: &Path) -> bool {
+    let version = declared_version(fields);
+
+    if version > CURRENT_VERSION {
+        eprintln!(
+            "{} is version {}, newer than this release understands ({})",
+            file_path.display(), version, CURRENT_VERSION
+        );
+        return false;
+    }
+    if version == CURRENT_VERSION {
+        return false;
+    }
+
+    MIGRATIONS[(version - UNVERSIONED) as usize..].iter().for_each(|migration| migration(fields));
+    fields.insert("version".to_string(), Value::from(CURRENT_VERSION));
+    println!("upgraded {} from version {} to {}", file_path.display(), version, CURRENT_VERSION);
+    true
+}
+
+/// Writes upgraded fields back to station.info
+///
+/// The original is kept alongside as `station.info.v<version>.bak`.
+/// Failures are logged; the upgraded config is still used in memory.
+pub fn rewrite(fields: &Map<String, Value>, file_path: &Path, old_version: u64) {
+    let backup_path = file_path.with_extension(format!("info.v{}.bak", old_version));
+    if let Err(e) = fs::copy(file_path, &backup_path) {
+        eprintln!("Not rewriting {}, backup failed: {}", file_path.display(), e);
+        return;
+    }
+
+    let contents = match serde_json::to_string_pretty(fields) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Failed to serialize {}: {}", file_path.display(), e);
+            return;
+        }
+    };
+    if let Err(e) = fs::write(file_path, contents + "\n") {
+        eprintln!("Failed to rewrite {}: {}", file_path.display(), e);
+    }
+}
+
+/// Returns the version a file declares, before migration
+pub fn declared_version(fields: &Map<String, Value>) -> u64 {
+    fields.get("version").and_then(Value::as_u64).unwrap_or(UNVERSIONED).max(UNVERSIONED)
+}
@@ -3,24 +3,73 @@
 //! Defines the types of content a station can play and how playlists behave.
 //! Includes track management, live stream support, and playlist strategies.
 
+pub mod cue;
+pub mod genre;
 pub mod live;
+pub mod metadata_cache;
+pub mod network;
+pub mod origin;
+pub mod rating;
+pub mod rotation;
+pub mod tags;
 pub mod track;
 
-use std::{collections::BTreeSet, path::Path};
+use std::{collections::BTreeSet, fmt, path::Path};
 
 use live::LiveStream;
-use track::{Track, load_tracks_from_path};
+use network::NetworkCursor;
+use rotation::Rotation;
+use metadata_cache::playlist_tracks;
+use track::Track;
 use rand::seq::SliceRandom;
 use rand::rng;
+use chrono::Utc;
 
-/// Radio band identifier (AM or FM)
+use crate::config::radio_config;
+use crate::audio::ambience::GeneratorKind;
+use crate::radio::station::config::{GeneratorConfig, StationConfig, TimeServiceConfig};
+
+/// Radio band identifier
 /// 
-/// Used by Station Manager to organize stations and apply band shift
-/// when mapping encoder values to station indices.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Band {
-    AM,
-    FM 
+/// Index into the bands configured in radio.info. Used by Station Manager
+/// to organize stations and apply band shift when mapping encoder values
+/// to station indices. `Band::AM` and `Band::FM` are the default bands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Band(pub usize);
+
+impl Band {
+    /// First configured band (AM in the default configuration)
+    pub const AM: Band = Band(0);
+
+    /// Second configured band (FM in the default configuration)
+    pub const FM: Band = Band(1);
+
+    /// Returns the display name configured for this band
+    pub fn name(&self) -> &'static str {
+        match radio_config().bands.get(self.0) {
+            Some(band_config) => &band_config.name,
+            None => "Unknown"
+        }
+    }
+
+    /// Returns the directory under the stations root holding this band
+    pub fn directory(&self) -> &'static str {
+        match radio_config().bands.get(self.0) {
+            Some(band_config) => &band_config.directory,
+            None => "Unknown"
+        }
+    }
+
+    /// Returns every configured band in switch order
+    pub fn all() -> impl Iterator<Item = Band> {
+        (0..radio_config().bands.len()).map(Band)
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 /// Unique identifier for a station combining band and index
@@ -30,20 +79,19 @@ pub enum Band {
 /// 
 /// # Example
 /// ```
-/// StationID { band: Band::AM, index: 3 }  // AM station #3 (4th station, 0-indexed)
+/// # use mokradio::radio::station::content::{Band, StationID};
+/// StationID { band: Band::AM, index: 3 };  // AM station #3 (4th station, 0-indexed)
 /// ```
-#[derive(Debug, PartialEq, Eq, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct StationID {
     pub band: Band,
     pub index: usize,  // 0-11 for 12 stations per band
 }
 
-impl Clone for StationID {
-    fn clone(&self) -> Self {
-        StationID { 
-            band: self.band.clone(), 
-            index: self.index.clone() 
-        }
+// Logged as the configured band name and dial slot, e.g. "FM 03"
+impl fmt::Display for StationID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:02}", self.band, self.index)
     }
 }
 
@@ -51,7 +99,6 @@ impl Clone for StationID {
 /// 
 /// Each variant encapsulates both the playlist strategy and the
 /// collection of tracks/streams that implement that strategy.
-
 pub enum PlayType {
     /// Pick any random track from the list
     /// Tracks stay in the list and can be replayed
@@ -69,14 +116,69 @@ pub enum PlayType {
     /// Tracks are removed as played; playlist reloads when exhausted
     Shuffle(Vec<Track>),
     
-    /// Scheduled live streams (not yet implemented)
+    /// Shared playlist carried by several affiliate stations
+    /// Each station keeps its own cursor; the playlist wraps forever
+    Network(NetworkCursor),
+    
+    /// Several folders mixed at set ratios over a rolling window
+    /// Each folder reshuffles on its own when it runs out
+    Rotation(Rotation),
+
+    /// Internet radio streams played at scheduled times
+    /// Streams are removed as they finish; static plays between them
     Live(BTreeSet<LiveStream>),
+
+    /// Speaking clock generated on the fly, with no playlist
+    Time(TimeServiceConfig),
+
+    /// Synthesized numbers station, Morse beacon or warble, with no playlist
+    Generated(GeneratorKind, GeneratorConfig),
     
     /// Station is off-air/inactive (no playlist)
     Dead
 }
 
 impl PlayType {
+    /// Creates a PlayType from a station's full configuration
+    /// 
+    /// Same as `new()`, except "Network" stations are built from their
+    /// `network` section (stations missing that section are Dead), "Live"
+    /// stations from their `live` list, "Rotation" stations from their
+    /// `rotation` section (Dead if missing), and the
+    /// generated stations from their `time_service` or `generator` section
+    /// (defaults if missing).
+    pub fn from_config(station_config: &StationConfig, station_path: &Path) -> Self {
+        if let Some(kind) = GeneratorKind::from_play_type(&station_config.play_type) {
+            return PlayType::Generated(kind, station_config.generator.clone().unwrap_or_default());
+        }
+        match (station_config.play_type.as_str(), &station_config.network) {
+            ("Network", Some(network_config)) => PlayType::Network(NetworkCursor::new(network_config)),
+            ("Time", _) => PlayType::Time(station_config.time_service.clone().unwrap_or_default()),
+            ("Live", _) => {
+                let now = Utc::now();
+                let streams: BTreeSet<LiveStream> = station_config.live.iter()
+                    .filter_map(|stream| LiveStream::from_config(stream, now))
+                    .collect();
+                if streams.is_empty() {
+                    eprintln!("Live station {} has no streams", station_path.display());
+                }
+                PlayType::Live(streams)
+            },
+            ("Network", None) => {
+                eprintln!("Network station {} has no network section", station_path.display());
+                PlayType::Dead
+            },
+            ("Rotation", _) => match &station_config.rotation {
+                Some(rotation_config) => PlayType::Rotation(Rotation::new(rotation_config, station_path)),
+                None => {
+                    eprintln!("Rotation station {} has no rotation section", station_path.display());
+                    PlayType::Dead
+                }
+            },
+            (play_type, _) => PlayType::new(play_type, station_path)
+        }
+    }
+
     /// Creates a PlayType from station.info configuration
     /// 
     /// Loads tracks from the station's playlist directory and initializes
@@ -103,7 +205,7 @@ impl PlayType {
                 // Load and sort tracks by modification date (oldest first)
                 // BTreeSet automatically maintains sorted order
                 let play_list: BTreeSet<Track> = 
-                    load_tracks_from_path(&station_path.join("playlist")).collect();
+                    playlist_tracks(&station_path.join("playlist")).into_iter().collect();
                 PlayType::Chronologic(play_list)
             },
             
@@ -111,21 +213,21 @@ impl PlayType {
                 // Load and sort tracks by modification date (newest first)
                 // BTreeSet maintains sorted order; iteration is reversed in utilities
                 let play_list: BTreeSet<Track> = 
-                    load_tracks_from_path(&station_path.join("playlist")).collect();
+                    playlist_tracks(&station_path.join("playlist")).into_iter().collect();
                 PlayType::Reverse(play_list)
             },
             
             "Random" => {
                 // Load tracks for random selection (tracks stay in list)
                 let play_list: Vec<Track> = 
-                    load_tracks_from_path(&station_path.join("playlist")).collect();
+                    playlist_tracks(&station_path.join("playlist")).into_iter().collect();
                 PlayType::Random(play_list)
             },
             
             "Shuffle" => {
                 // Load and shuffle tracks for one complete playthrough
                 let mut play_list: Vec<Track> = 
-                    load_tracks_from_path(&station_path.join("playlist")).collect();
+                    playlist_tracks(&station_path.join("playlist")).into_iter().collect();
                 
                 // Randomize the initial order
                 play_list.shuffle(&mut rng());
@@ -133,8 +235,13 @@ impl PlayType {
                 PlayType::Shuffle(play_list)
             },
             
-            // Unknown play_type or explicit "Dead" -> inactive station
-            _ => PlayType::Dead,
+            "Dead" => PlayType::Dead,
+
+            // Unknown play_type -> inactive station
+            unknown => {
+                eprintln!("Unknown play_type {} in {}", unknown, station_path.display());
+                PlayType::Dead
+            },
         }
     }
 }
@@ -142,11 +249,10 @@ impl PlayType {
 /// Content types that can be played on a station
 /// 
 /// Currently supports local audio files (Tracks) and live streams.
-/// Live stream support is planned but not yet implemented.
 pub enum Content {
     /// Local audio file (MP3, etc.)
     Track(Track),
     
-    /// Live streaming content (planned feature)
+    /// Internet radio stream (see `audio::http_stream`)
     Live(LiveStream)
 }
@@ -0,0 +1,158 @@
+//! Cue Sheet Module - Track markers for continuous mixes
+//!
+//! Parses `.cue` sheets that sit next to long mix files so a station can
+//! report which track of the mix is playing and skip by cue index instead
+//! of treating a 2-hour mix as one opaque track.
+//!
+//! # Cue Sheet Format
+//! ```text
+//! FILE "mix.mp3" MP3
+//!   TRACK 01 AUDIO
+//!     TITLE "Opening Theme"
+//!     PERFORMER "Some Artist"
+//!     INDEX 01 00:00:00
+//!   TRACK 02 AUDIO
+//!     TITLE "Second Song"
+//!     INDEX 01 04:31:52
+//! ```
+
+use std::{fs::read_to_string, path::Path, time::Duration};
+
+/// Cue sheet timestamps are in minutes:seconds:frames with 75 frames per second
+const FRAMES_PER_SECOND: u64 = 75;
+
+/// A single track marker within a cue sheet
+#[derive(Debug, Clone)]
+pub struct CueTrack {
+    /// Track number as written in the cue sheet (1-based)
+    pub number: usize,
+
+    /// Track title, if the cue sheet provides one
+    pub title: Option<String>,
+
+    /// Track performer, if the cue sheet provides one
+    pub performer: Option<String>,
+
+    /// Offset from the start of the mix file (INDEX 01)
+    pub start: Duration,
+}
+
+/// Ordered list of track markers for one mix file
+#[derive(Debug, Clone)]
+pub struct CueSheet {
+    tracks: Vec<CueTrack>,
+}
+
+impl CueSheet {
+    /// Loads the cue sheet that belongs to an audio file, if there is one
+    ///
+    /// Looks for a file with the same stem and a `.cue` extension next to
+    /// the audio file (e.g., `mix.mp3` → `mix.cue`).
+    ///
+    /// # Returns
+    /// - `Some(CueSheet)` if a cue file exists and contains at least one track
+    /// - `None` if there is no cue file or it can't be read
+    pub fn for_audio_file(audio_path: &Path) -> Option<Self> {
+        let cue_path = audio_path.with_extension("cue");
+        if !cue_path.exists() {
+            return None;
+        }
+
+        match read_to_string(&cue_path) {
+            Ok(contents) => {
+                let cue_sheet = CueSheet::parse(&contents);
+                if cue_sheet.tracks.is_empty() {None} else {Some(cue_sheet)}
+            },
+            Err(e) => {
+                eprintln!("Failed to read cue sheet {}: {}", cue_path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Parses the text of a cue sheet
+    ///
+    /// Only TRACK, TITLE, PERFORMER and INDEX 01 lines are used. Unknown
+    /// commands and malformed lines are ignored so a slightly broken cue
+    /// sheet still yields whatever markers it can.
+    pub fn parse(contents: &str) -> Self {
+        let mut tracks: Vec<CueTrack> = Vec::new();
+
+        for line in contents.lines() {
+            let line = line.trim();
+            let (command, arguments) = match line.split_once(' ') {
+                Some((command, arguments)) => (command, arguments.trim()),
+                None => continue
+            };
+
+            match command {
+                "TRACK" => {
+                    let number = arguments
+                        .split_whitespace()
+                        .next()
+                        .and_then(|number| number.parse().ok())
+                        .unwrap_or(tracks.len() + 1);
+                    tracks.push(CueTrack {
+                        number,
+                        title: None,
+                        performer: None,
+                        start: Duration::ZERO
+                    });
+                },
+                // TITLE/PERFORMER before the first TRACK describe the whole mix
+                "TITLE" => if let Some(track) = tracks.last_mut() {
+                    track.title = Some(unquote(arguments));
+                },
+                "PERFORMER" => if let Some(track) = tracks.last_mut() {
+                    track.performer = Some(unquote(arguments));
+                },
+                "INDEX" => {
+                    let mut parts = arguments.split_whitespace();
+                    if parts.next() != Some("01") {continue;}
+                    if let (Some(track), Some(start)) = (tracks.last_mut(), parts.next().and_then(parse_timestamp)) {
+                        track.start = start;
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        // Keep markers in playback order even if the sheet is out of order
+        tracks.sort_by_key(|track| track.start);
+
+        CueSheet { tracks }
+    }
+
+    /// Returns all track markers in playback order
+    pub fn tracks(&self) -> &[CueTrack] {
+        &self.tracks
+    }
+
+    /// Returns the marker that is playing at the given position in the mix
+    pub fn track_at(&self, position: Duration) -> Option<&CueTrack> {
+        self.tracks.iter().rev().find(|track| track.start <= position)
+    }
+
+    /// Returns the first marker that starts after the given position
+    ///
+    /// Used for skipping by cue index during turnover events.
+    pub fn next_after(&self, position: Duration) -> Option<&CueTrack> {
+        self.tracks.iter().find(|track| track.start > position)
+    }
+}
+
+/// Removes surrounding double quotes from a cue sheet argument
+fn unquote(argument: &str) -> String {
+    argument.trim_matches('"').to_string()
+}
+
+/// Parses an `mm:ss:ff` cue timestamp into a Duration
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let mut parts = timestamp.split(':');
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: u64 = parts.next()?.parse().ok()?;
+    let frames: u64 = parts.next()?.parse().ok()?;
+
+    let millis = (minutes * 60 + seconds) * 1000 + frames * 1000 / FRAMES_PER_SECOND;
+    Some(Duration::from_millis(millis))
+}
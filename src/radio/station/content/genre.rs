@@ -0,0 +1,37 @@
+//! Genre Module - Virtual stations split from one music library
+//!
+//! Lets a single big library folder fill the dial: every track is grouped
+//! by its genre tag and each genre becomes its own virtual station in a
+//! free dial slot.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::radio::station::content::track::{Track, load_tracks_recursive};
+
+/// Groups every track under a library folder by genre tag
+/// 
+/// # Arguments
+/// * `library_path` - Root of the music library (searched recursively)
+/// 
+/// # Returns
+/// Map of genre name to its tracks, in alphabetical genre order.
+/// Untagged tracks are left out rather than lumped into a catch-all station.
+pub fn split_library_by_genre(library_path: &Path) -> BTreeMap<String, Vec<Track>> {
+    let mut genres: BTreeMap<String, Vec<Track>> = BTreeMap::new();
+
+    load_tracks_recursive(library_path).into_iter().for_each(|track| {
+        if let Some(genre) = track.genre() {
+            // Tags vary in case and spacing ("Jazz", "jazz ")
+            let genre = genre.trim();
+            if genre.is_empty() {return;}
+            let key = genres.keys()
+                .find(|existing| existing.eq_ignore_ascii_case(genre))
+                .cloned()
+                .unwrap_or_else(|| genre.to_string());
+            genres.entry(key).or_default().push(track);
+        }
+    });
+
+    genres
+}
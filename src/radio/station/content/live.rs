@@ -1,5 +1,7 @@
 use chrono::{DateTime, Duration, Utc};
 
+use crate::radio::station::config::LiveStreamConfig;
+
 /// Scheduled live stream with timing information
 pub struct LiveStream {
     location: String,             // Stream URL
@@ -9,18 +11,78 @@ pub struct LiveStream {
     host: String                  // Stream host/provider (TODO: replace with enum)
 }
 
+impl LiveStream {
+    /// Creates a scheduled stream from a station's `live` section
+    ///
+    /// # Arguments
+    /// * `config` - One entry of the `live` list
+    /// * `now` - Start time for entries without one
+    ///
+    /// # Returns
+    /// The stream, or None (logged) if its start time doesn't parse
+    pub fn from_config(config: &LiveStreamConfig, now: DateTime<Utc>) -> Option<LiveStream> {
+        let start = match &config.start {
+            Some(start) => match DateTime::parse_from_rfc3339(start) {
+                Ok(start) => start.with_timezone(&Utc),
+                Err(e) => {
+                    eprintln!("Skipping live stream {}: bad start {}: {}", config.url, start, e);
+                    return None;
+                }
+            },
+            None => now,
+        };
+        let host = config.host.clone().unwrap_or_else(|| {
+            let authority = config.url.split("://").nth(1).unwrap_or(&config.url);
+            authority.split(['/', ':']).next().unwrap_or(authority).to_string()
+        });
+        Some(LiveStream {
+            location: config.url.clone(),
+            start,
+            delay: config.delay_secs.map(|secs| Duration::seconds(secs as i64)),
+            duration: config.duration_mins.map(|mins| Duration::minutes(mins as i64)),
+            host,
+        })
+    }
+
+    /// Returns the stream URL
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns when the stream goes on air, after its delay
+    pub fn starts_at(&self) -> DateTime<Utc> {
+        self.delay.map_or(self.start, |delay| self.start + delay)
+    }
+
+    /// Returns when the stream is cut to static, if it has a set duration
+    pub fn ends_at(&self) -> Option<DateTime<Utc>> {
+        self.duration.map(|duration| self.starts_at() + duration)
+    }
+
+    /// Returns the name the stream is shown under
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+}
+
 impl PartialEq for LiveStream {
     fn eq(&self, other: &Self) -> bool {
-        self.start == other.start
+        self.cmp(other) == std::cmp::Ordering::Equal
     }
 }
 
 impl Eq for LiveStream {}
 
-// LiveStreams are ordered by start time for scheduling
+// LiveStreams are ordered by when they go on air for scheduling, then by
+// the rest of their fields so two streams at the same time aren't equal
 impl Ord for LiveStream {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.start.cmp(&other.start)
+        self.starts_at().cmp(&other.starts_at())
+            .then_with(|| self.start.cmp(&other.start))
+            .then_with(|| self.location.cmp(&other.location))
+            .then_with(|| self.delay.cmp(&other.delay))
+            .then_with(|| self.duration.cmp(&other.duration))
+            .then_with(|| self.host.cmp(&other.host))
     }
 }
 
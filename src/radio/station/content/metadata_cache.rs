@@ -0,0 +1,138 @@
+//! Metadata Cache - Playlist scans for network-mounted folders
+//!
+//! Reading durations and tags over NFS/SMB is slow, and a mount that drops
+//! out can hang a scan for a long time. Playlists under the network mounts
+//! listed in radio.info are served from a cache instead:
+//! - Fresh entries (younger than the TTL) are returned as-is
+//! - Stale entries are returned immediately while a background thread
+//!   rescans the folder (stale-while-revalidate)
+//! - The mount is probed with a timeout before any scan, and an
+//!   unavailable mount keeps serving the last good scan
+//!
+//! Local playlists bypass the cache entirely.
+//!
+//! # JSON Format
+//! ```json
+//! "metadata_cache": {
+//!     "network_mounts": ["/mnt/nas"],
+//!     "ttl_secs": 300,
+//!     "mount_timeout_ms": 500
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::radio_config;
+use crate::priority::{ThreadRole, prioritize_current_thread};
+use crate::radio::station::content::track::{Track, load_tracks_from_path};
+
+/// Cached scans keyed by playlist directory
+static METADATA_CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedScan>>> = OnceLock::new();
+
+/// Last good scan of a playlist directory
+struct CachedScan {
+    tracks: Vec<Track>,
+    scanned: Instant,
+    refreshing: bool,
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedScan>> {
+    METADATA_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the tracks in a playlist directory
+///
+/// Playlists on a configured network mount go through the cache; all
+/// others are scanned directly, as before.
+///
+/// # Returns
+/// Tracks found in the folder, or an empty list if the folder (or its
+/// mount) isn't available and there's no earlier scan to fall back on
+pub fn playlist_tracks(playlist_path: &Path) -> Vec<Track> {
+    if !is_on_network_mount(playlist_path) {
+        return load_tracks_from_path(playlist_path)
+            .map(Iterator::collect)
+            .unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                Vec::new()
+            });
+    }
+
+    let ttl = Duration::from_secs(radio_config().metadata_cache.ttl_secs);
+    {
+        let mut cache = cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(cached) = cache.get_mut(playlist_path) {
+            if cached.scanned.elapsed() >= ttl && !cached.refreshing {
+                cached.refreshing = true;
+                let refresh_path = playlist_path.to_path_buf();
+                thread::spawn(move || refresh(refresh_path));
+            }
+            return cached.tracks.clone();
+        }
+    }
+
+    // First scan of this folder has nothing stale to serve, so it blocks
+    let Some(tracks) = scan_if_mounted(playlist_path) else {
+        return Vec::new();
+    };
+    cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(
+        playlist_path.to_path_buf(),
+        CachedScan { tracks: tracks.clone(), scanned: Instant::now(), refreshing: false }
+    );
+    tracks
+}
+
+/// Rescans a stale folder in the background and swaps the result in
+fn refresh(playlist_path: PathBuf) {
+    prioritize_current_thread(ThreadRole::BackgroundScan);
+    let tracks = scan_if_mounted(&playlist_path);
+
+    let mut cache = cache().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(cached) = cache.get_mut(&playlist_path) else {return;};
+    cached.refreshing = false;
+    // An unreachable mount keeps the old scan, and waits a full TTL to retry
+    cached.scanned = Instant::now();
+    if let Some(tracks) = tracks {
+        cached.tracks = tracks;
+    }
+}
+
+/// Scans a folder, but only after checking its mount answers in time
+fn scan_if_mounted(playlist_path: &Path) -> Option<Vec<Track>> {
+    if !is_reachable(playlist_path) {
+        eprintln!("Network playlist {} is unreachable", playlist_path.display());
+        return None;
+    }
+    match load_tracks_from_path(playlist_path) {
+        Ok(tracks) => Some(tracks.collect()),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    }
+}
+
+/// Checks that a folder can be stat'ed within the mount timeout
+///
+/// A hung NFS mount blocks `stat` indefinitely, so the check runs on its
+/// own thread and is abandoned if it doesn't answer in time.
+fn is_reachable(playlist_path: &Path) -> bool {
+    let timeout = Duration::from_millis(radio_config().metadata_cache.mount_timeout_ms);
+    let (reply, answer) = channel();
+    let probe_path = playlist_path.to_path_buf();
+    thread::spawn(move || {
+        let _ = reply.send(probe_path.is_dir());
+    });
+
+    answer.recv_timeout(timeout).unwrap_or(false)
+}
+
+/// Returns whether a path is under one of the configured network mounts
+fn is_on_network_mount(path: &Path) -> bool {
+    radio_config().metadata_cache.network_mounts.iter().any(|mount| path.starts_with(mount))
+}
@@ -0,0 +1,110 @@
+//! Network Module - Shared playlists for affiliate stations
+//!
+//! Several stations can carry the same programming like affiliates of a
+//! network, each running behind the others by a configured offset. The
+//! shared playlist is scanned once and held behind an `Arc`; every station
+//! only keeps its own cursor into it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::radio::station::config::NetworkConfig;
+use crate::radio::station::content::metadata_cache::playlist_tracks;
+use crate::radio::station::content::track::Track;
+
+/// Scan results for each shared playlist directory, keyed by path
+static SHARED_PLAYLISTS: OnceLock<Mutex<HashMap<PathBuf, Arc<Vec<Track>>>>> = OnceLock::new();
+
+/// Returns the shared playlist for a directory, scanning it on first use
+///
+/// Tracks are kept in chronological order so every affiliate sees the
+/// same running order. The scan runs without the lock held, so stations
+/// on other networks aren't held up by it; if two affiliates scan at
+/// once, the first to finish is the one shared.
+pub fn shared_playlist(playlist_path: &Path) -> Arc<Vec<Track>> {
+    let playlists = SHARED_PLAYLISTS.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(tracks) = playlists.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(playlist_path) {
+        return tracks.clone();
+    }
+
+    let mut tracks: Vec<Track> = if playlist_path.is_dir() {
+        playlist_tracks(playlist_path)
+    } else {
+        eprintln!("Network playlist {} does not exist", playlist_path.display());
+        Vec::new()
+    };
+    tracks.sort();
+
+    playlists.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry(playlist_path.to_path_buf())
+        .or_insert_with(|| Arc::new(tracks))
+        .clone()
+}
+
+/// A station's position in a shared network playlist
+pub struct NetworkCursor {
+    /// Playlist shared with the other affiliates
+    tracks: Arc<Vec<Track>>,
+
+    /// Index of the next track to play
+    position: usize,
+}
+
+impl NetworkCursor {
+    /// Creates a cursor into the shared playlist from network settings
+    ///
+    /// The cursor starts at the track that would be playing `offset_minutes`
+    /// into the playlist, so affiliates with different offsets air the same
+    /// programs at different times.
+    pub fn new(network_config: &NetworkConfig) -> Self {
+        let tracks = shared_playlist(Path::new(&network_config.playlist));
+        let position = Self::position_at_offset(&tracks, network_config.offset_minutes);
+
+        NetworkCursor { tracks, position }
+    }
+
+    /// Finds the index of the track airing a number of minutes into the playlist
+    fn position_at_offset(tracks: &[Track], offset_minutes: i64) -> usize {
+        if tracks.is_empty() {
+            return 0;
+        }
+
+        // Offsets longer than one pass around the playlist wrap
+        let total_seconds: i64 = tracks.iter().map(|track| track.get_duration().num_seconds()).sum();
+        if total_seconds <= 0 {
+            return 0;
+        }
+        let mut remaining = (offset_minutes * 60).rem_euclid(total_seconds);
+
+        for (index, track) in tracks.iter().enumerate() {
+            let length = track.get_duration().num_seconds();
+            if remaining < length {
+                return index;
+            }
+            remaining -= length;
+        }
+        0
+    }
+
+    /// Returns how many tracks the shared playlist holds
+    pub fn playlist_length(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// Returns the tracks coming up, starting with the next one, once round
+    pub fn upcoming(&self) -> impl Iterator<Item = &Track> {
+        self.tracks.iter().cycle().skip(self.position).take(self.tracks.len())
+    }
+
+    /// Returns the next track and advances the cursor, wrapping at the end
+    ///
+    /// # Returns
+    /// - `Some(Track)` - Next track in the network's running order
+    /// - `None` - Shared playlist is empty
+    pub fn advance(&mut self) -> Option<Track> {
+        let track = self.tracks.get(self.position)?.clone();
+        self.position = (self.position + 1) % self.tracks.len();
+        Some(track)
+    }
+}
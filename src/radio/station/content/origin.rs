@@ -0,0 +1,81 @@
+//! Track Origin - Where a track came from
+//!
+//! Tracks copied into a playlist by hand are `Local`. Tools that fill
+//! playlists (podcast downloaders, folder syncs) say where a file came
+//! from by leaving a one-line `.origin` file next to it with the same
+//! stem (e.g., `episode.mp3` → `episode.origin`), so a mystery track on
+//! a Random station can be traced back to its source from the history
+//! or the API.
+//!
+//! # Origin File Format
+//! ```text
+//! feed: Tech Talk Weekly
+//! ```
+//! or `synced: nas:/music/incoming`. Any other text is kept as written.
+
+use std::fmt;
+use std::fs::read_to_string;
+use std::path::Path;
+
+/// Where a track came from
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TrackOrigin {
+    /// Put in the playlist by hand
+    #[default]
+    Local,
+
+    /// Downloaded from a podcast feed, by feed name or URL
+    Feed(String),
+
+    /// Copied in by a sync, by where it was synced from
+    Synced(String),
+
+    /// Described some other way by whatever wrote the `.origin` file
+    Other(String),
+}
+
+impl TrackOrigin {
+    /// Reads the origin of an audio file from its `.origin` file
+    ///
+    /// # Returns
+    /// The recorded origin, or `Local` if the file has no `.origin` file
+    pub fn read(audio_path: &Path) -> Self {
+        let origin_path = audio_path.with_extension("origin");
+        if !origin_path.exists() {
+            return TrackOrigin::Local;
+        }
+        match read_to_string(&origin_path) {
+            Ok(contents) => TrackOrigin::parse(contents.lines().next().unwrap_or_default()),
+            Err(e) => {
+                eprintln!("Failed to read origin {}: {}", origin_path.display(), e);
+                TrackOrigin::Local
+            }
+        }
+    }
+
+    /// Parses an origin as written by `Display` (and in `.origin` files)
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let labelled = |label: &str| text.strip_prefix(label).map(|rest| rest.trim().to_string());
+        if let Some(feed) = labelled("feed:") {
+            TrackOrigin::Feed(feed)
+        } else if let Some(source) = labelled("synced:") {
+            TrackOrigin::Synced(source)
+        } else if text.is_empty() || text == "local" {
+            TrackOrigin::Local
+        } else {
+            TrackOrigin::Other(text.to_string())
+        }
+    }
+}
+
+impl fmt::Display for TrackOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrackOrigin::Local => write!(f, "local"),
+            TrackOrigin::Feed(feed) => write!(f, "feed: {}", feed),
+            TrackOrigin::Synced(source) => write!(f, "synced: {}", source),
+            TrackOrigin::Other(description) => write!(f, "{}", description),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Content Rating - Tags that keep tracks off some stations
+//!
+//! A track can be tagged with ratings like `explicit` or `nsfw`, and a
+//! station (or a whole profile) can refuse to play tracks with a rating
+//! through `exclude_ratings`. Ratings come from either of:
+//! - A `.rating` file next to the audio file with the same stem
+//!   (e.g., `song.mp3` → `song.rating`), holding JSON
+//! - A `rating:` line in the file's comment tag, comma separated
+//!
+//! Ratings are compared ignoring case. Tracks with no ratings are never
+//! excluded.
+//!
+//! # Rating File Format
+//! ```json
+//! { "tags": ["explicit", "violence"] }
+//! ```
+//! or, in the comment tag, `rating: explicit, violence`.
+
+use std::fs::read_to_string;
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Contents of a `.rating` file
+#[derive(Deserialize)]
+struct RatingFile {
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Reads an audio file's ratings from its `.rating` file and comment tag
+///
+/// # Arguments
+/// * `audio_path` - The audio file
+/// * `comment` - The file's comment tag, if it has one
+///
+/// # Returns
+/// Every rating found, lowercased, without duplicates
+pub fn read(audio_path: &Path, comment: Option<&str>) -> Vec<String> {
+    let mut ratings: Vec<String> = read_rating_file(audio_path);
+    ratings.extend(comment.map(parse_comment).unwrap_or_default());
+    ratings.sort();
+    ratings.dedup();
+    ratings
+}
+
+/// Returns whether any of a track's ratings is on an exclusion list
+pub fn is_excluded(ratings: &[String], excluded: &[String]) -> bool {
+    ratings.iter().any(|rating| excluded.iter().any(|excluded| excluded.eq_ignore_ascii_case(rating)))
+}
+
+fn read_rating_file(audio_path: &Path) -> Vec<String> {
+    let rating_path = audio_path.with_extension("rating");
+    if !rating_path.exists() {
+        return Vec::new();
+    }
+    let rating_file = read_to_string(&rating_path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| serde_json::from_str::<RatingFile>(&contents).map_err(|e| e.to_string()));
+    match rating_file {
+        Ok(rating_file) => rating_file.tags.iter().map(|tag| normalize(tag)).filter(|tag| !tag.is_empty()).collect(),
+        Err(e) => {
+            eprintln!("Failed to read ratings {}: {}", rating_path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Pulls the ratings out of a comment's `rating:` line, if it has one
+fn parse_comment(comment: &str) -> Vec<String> {
+    comment.lines()
+        .find_map(|line| {
+            let line = line.trim();
+            line.get(..7).filter(|label| label.eq_ignore_ascii_case("rating:")).map(|_| &line[7..])
+        })
+        .map(|tags| tags.split(',').map(normalize).filter(|tag| !tag.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+fn normalize(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
@@ -0,0 +1,185 @@
+//! Rotation Module - Weighted multi-folder stations
+//!
+//! A "Rotation" station draws from several folders at set ratios, the
+//! way a real station log mixes music, ads and bumpers. Each folder is
+//! shuffled on its own and reshuffled from disk when it runs out, whether
+//! by playing or by tracks being taken out of it, leaving out excluded
+//! ratings again. The next folder is whichever is furthest behind its
+//! share over the last `window` picks, so the ratios hold over any
+//! stretch of that length instead of only on average, and no folder comes
+//! up twice in a row while another is owed a turn.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use rand::rng;
+use rand::seq::SliceRandom;
+
+use crate::radio::station::config::RotationConfig;
+use crate::radio::station::content::metadata_cache::playlist_tracks;
+use crate::radio::station::content::rating;
+use crate::radio::station::content::track::Track;
+
+/// One folder in the rotation
+struct RotationFolder {
+    /// Folder the tracks are scanned from
+    path: PathBuf,
+
+    /// Share of picks this folder gets, relative to the others
+    weight: f64,
+
+    /// Tracks left in this folder's shuffle, next last
+    tracks: Vec<Track>,
+}
+
+/// A station's weighted rotation through several folders
+pub struct Rotation {
+    folders: Vec<RotationFolder>,
+
+    /// Folders of the most recent picks, newest last
+    recent: VecDeque<usize>,
+
+    /// Picks the ratios are kept over
+    window: usize,
+
+    /// Ratings left out of every folder, reshuffles included
+    excluded_ratings: Vec<String>,
+}
+
+impl Rotation {
+    /// Scans and shuffles every folder of a station's `rotation` section
+    ///
+    /// Folder paths are relative to the station folder unless absolute.
+    /// Folders with no weight or no tracks are logged and left out.
+    pub fn new(config: &RotationConfig, station_path: &Path) -> Self {
+        let folders = config.folders.iter()
+            .filter_map(|folder| {
+                let path = station_path.join(&folder.path);
+                if folder.percent <= 0.0 {
+                    eprintln!("Rotation folder {} has no share, skipping", path.display());
+                    return None;
+                }
+                let tracks = shuffled(&path, &[]);
+                if tracks.is_empty() {
+                    eprintln!("Rotation folder {} has no playable tracks, skipping", path.display());
+                    return None;
+                }
+                Some(RotationFolder { path, weight: folder.percent as f64, tracks })
+            })
+            .collect();
+
+        Rotation { folders, recent: VecDeque::new(), window: config.window.max(1), excluded_ratings: Vec::new() }
+    }
+
+    /// Picks the next track from the folder furthest behind its share
+    ///
+    /// # Returns
+    /// The track, or None if no folder has anything to play
+    pub fn advance(&mut self) -> Option<Track> {
+        let folder_index = pick_folder(&self.folders, &self.recent, self.window, |folder| !folder.tracks.is_empty())?;
+        let folder = &mut self.folders[folder_index];
+        let track = folder.tracks.pop();
+
+        // Reshuffle a folder that ran out, picking up files added since
+        if folder.tracks.is_empty() {
+            folder.tracks = shuffled(&folder.path, &self.excluded_ratings);
+        }
+
+        self.recent.push_back(folder_index);
+        while self.recent.len() > self.window {
+            self.recent.pop_front();
+        }
+        track
+    }
+
+    /// Iterates over the tracks the rotation will pick, in order, until a folder runs out
+    pub fn upcoming(&self) -> impl Iterator<Item = &Track> {
+        let mut recent = self.recent.clone();
+        let mut remaining: Vec<usize> = self.folders.iter().map(|folder| folder.tracks.len()).collect();
+        let window = self.window;
+
+        std::iter::from_fn(move || {
+            let folder_index = pick_folder(&self.folders, &recent, window, |folder| !folder.tracks.is_empty())?;
+            if remaining[folder_index] == 0 {
+                return None;
+            }
+            remaining[folder_index] -= 1;
+            recent.push_back(folder_index);
+            while recent.len() > window {
+                recent.pop_front();
+            }
+            self.folders[folder_index].tracks.get(remaining[folder_index])
+        })
+    }
+
+    /// Iterates over every track left in every folder
+    pub fn tracks(&self) -> impl Iterator<Item = &Track> {
+        self.folders.iter().flat_map(|folder| folder.tracks.iter())
+    }
+
+    /// Number of tracks left across all folders
+    pub fn len(&self) -> usize {
+        self.folders.iter().map(|folder| folder.tracks.len()).sum()
+    }
+
+    /// Returns whether every folder is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Keeps only the tracks matching a predicate, in every folder
+    ///
+    /// A folder left empty is reshuffled from disk and filtered again, so
+    /// it doesn't drop out of the rotation for good.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Track) -> bool) {
+        let excluded_ratings = &self.excluded_ratings;
+        self.folders.iter_mut().for_each(|folder| {
+            folder.tracks.retain(&mut keep);
+            if folder.tracks.is_empty() {
+                folder.tracks = shuffled(&folder.path, excluded_ratings);
+                folder.tracks.retain(&mut keep);
+            }
+        });
+    }
+
+    /// Takes tracks with an excluded rating out of every folder, now and
+    /// whenever a folder is reshuffled
+    pub fn exclude_ratings(&mut self, excluded_ratings: &[String]) {
+        self.excluded_ratings = excluded_ratings.to_vec();
+        self.retain(|track| !rating::is_excluded(track.ratings(), excluded_ratings));
+    }
+}
+
+/// Finds the folder furthest behind its share of the last `window` picks
+///
+/// Each folder is owed its share of the window plus the pick being made,
+/// less the picks it already had. Ties go to the bigger share.
+fn pick_folder(
+    folders: &[RotationFolder],
+    recent: &VecDeque<usize>,
+    window: usize,
+    available: impl Fn(&RotationFolder) -> bool
+) -> Option<usize> {
+    let total_weight: f64 = folders.iter().map(|folder| folder.weight).sum();
+    let picks = (recent.len() + 1).min(window) as f64;
+    let counted = recent.iter().skip(recent.len().saturating_sub(window - 1));
+
+    let mut counts = vec![0usize; folders.len()];
+    counted.for_each(|&index| counts[index] += 1);
+
+    folders.iter().enumerate()
+        .filter(|(_, folder)| available(folder))
+        .map(|(index, folder)| (index, folder.weight / total_weight * picks - counts[index] as f64, folder.weight))
+        .max_by(|(_, owed, weight), (_, other_owed, other_weight)| {
+            owed.total_cmp(other_owed).then(weight.total_cmp(other_weight))
+        })
+        .map(|(index, _, _)| index)
+}
+
+/// Scans a folder and shuffles its tracks, without excluded ratings
+fn shuffled(path: &Path, excluded_ratings: &[String]) -> Vec<Track> {
+    let mut tracks = if path.is_dir() {playlist_tracks(path)} else {Vec::new()};
+    tracks.retain(|track| !rating::is_excluded(track.ratings(), excluded_ratings));
+    tracks.shuffle(&mut rng());
+    tracks
+}
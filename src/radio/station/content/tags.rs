@@ -0,0 +1,59 @@
+//! Tags Module - Embedded audio tag metadata
+//!
+//! Reads ID3/Vorbis/MP4 tags from audio files at scan time so playlist
+//! strategies can use them (e.g., keeping the same artist apart in Shuffle).
+
+use std::path::Path;
+use lofty::prelude::*;
+use lofty::probe::Probe;
+
+/// Tag values read from an audio file
+/// 
+/// Every field is optional; untagged files simply have no values.
+#[derive(Debug, Clone, Default)]
+pub struct TrackTags {
+    /// Song title
+    pub title: Option<String>,
+
+    /// Performing artist
+    pub artist: Option<String>,
+
+    /// Album the track is from
+    pub album: Option<String>,
+
+    /// Position of the track on its album (1-based)
+    pub track_number: Option<u32>,
+
+    /// Genre, used to split a library into virtual stations
+    pub genre: Option<String>,
+
+    /// Free-text comment, which may carry content ratings (see `rating`)
+    pub comment: Option<String>,
+}
+
+impl TrackTags {
+    /// Reads tags from an audio file
+    /// 
+    /// # Returns
+    /// Tag values found in the file's primary tag (or first tag if the
+    /// format has no primary). Unreadable or untagged files return empty tags.
+    pub fn read(path: &Path) -> Self {
+        let tagged_file = match Probe::open(path).and_then(|probe| probe.read()) {
+            Ok(tagged_file) => tagged_file,
+            Err(_) => return TrackTags::default()
+        };
+
+        let Some(tag) = tagged_file.primary_tag().or_else(|| tagged_file.first_tag()) else {
+            return TrackTags::default();
+        };
+
+        TrackTags {
+            title: tag.title().map(|title| title.to_string()),
+            artist: tag.artist().map(|artist| artist.to_string()),
+            album: tag.album().map(|album| album.to_string()),
+            track_number: tag.track(),
+            genre: tag.genre().map(|genre| genre.to_string()),
+            comment: tag.comment().map(|comment| comment.to_string()),
+        }
+    }
+}
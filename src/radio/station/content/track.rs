@@ -6,12 +6,24 @@
 use std::{fs::DirEntry, path::{Path, PathBuf}, time::SystemTime};
 use chrono::{Duration, TimeDelta};
 
+use crate::error::MokRadioError;
+use crate::file_loader::probe::probe_duration;
+use crate::file_loader::transcode::playable_path;
+use crate::radio::station::content::cue::CueSheet;
+use crate::radio::station::content::origin::TrackOrigin;
+use crate::radio::station::content::rating;
+use crate::radio::station::content::tags::TrackTags;
+
 /// Audio track with metadata for playlist management
 /// 
 /// Represents a single audio file with:
 /// - Duration (for time tracking, UI display)
 /// - Modification time (for Chronologic/Reverse ordering)
 /// - File path (for loading and decoding)
+/// - Cue sheet (for continuous mixes with track markers)
+/// - Embedded tags (artist, for rotation rules)
+/// - Origin (where it was downloaded or synced from)
+/// - Content ratings (for stations that exclude some content)
 pub struct Track {
     /// Length of the audio file
     duration: Duration,
@@ -21,6 +33,18 @@ pub struct Track {
     
     /// Full path to the audio file
     location: PathBuf,
+
+    /// Track markers from a sibling `.cue` file, for continuous mixes
+    cue_sheet: Option<CueSheet>,
+
+    /// Tag metadata read from the file at scan time
+    tags: TrackTags,
+
+    /// Where the file came from, from its `.origin` file
+    origin: TrackOrigin,
+
+    /// Content ratings, from its `.rating` file or comment tag
+    ratings: Vec<String>,
 }
 
 // Tracks are compared by modification time for BTreeSet ordering
@@ -57,33 +81,81 @@ impl Track {
     /// * `dir_entry` - Directory entry from fs::read_dir()
     /// 
     /// # Returns
-    /// - `Some(Track)` if file can be read and duration extracted
-    /// - `None` if file is inaccessible or not a valid audio file
+    /// - `Ok(Track)` if file can be read and duration extracted
+    /// - `Err(MokRadioError::Io)` if the file's metadata can't be read
+    /// - `Err(MokRadioError::NotAudio)` if it isn't a valid audio file
     /// 
-    /// # Current Limitations
-    /// Only supports MP3 files. Other formats will fail to parse duration.
-    /// 
-    /// # Panics
-    /// Currently panics if metadata or duration extraction fails.
-    /// TODO: Return None gracefully for invalid files
-    pub fn new(dir_entry: &DirEntry) -> Option<Self> {
+    /// # Supported Formats
+    /// MP3, FLAC, OGG Vorbis, WAV and AAC/M4A (see `probe_duration`).
+    /// Formats configured for transcoding, such as Opus, are probed from
+    /// their cached copy.
+    pub fn new(dir_entry: &DirEntry) -> Result<Self, MokRadioError> {
         let location = dir_entry.path();
-        
-        // Extract MP3 duration (will fail for non-MP3 files)
-        let duration = Duration::from_std(
-            mp3_duration::from_path(&location).unwrap()
-        ).unwrap();
-        
+
         // Get file modification time from filesystem metadata
-        let modified = dir_entry.metadata().unwrap().modified().unwrap();
+        let modified = dir_entry.metadata()
+            .and_then(|metadata| metadata.modified())
+            .map_err(|e| MokRadioError::io(&location, e))?;
+
+        Track::read(location, modified)
+    }
+
+    /// Reads a track's length, tags and sidecars
+    fn read(location: PathBuf, modified: SystemTime) -> Result<Self, MokRadioError> {
+        // Duration comes from the transcoded copy for formats configured
+        // to be transcoded; files with no readable duration aren't tracks
+        let duration = probe_duration(&playable_path(&location))
+            .and_then(|duration| Duration::from_std(duration).ok())
+            .ok_or_else(|| MokRadioError::NotAudio { path: location.clone() })?;
+
+        // Continuous mixes may carry a cue sheet with per-track markers
+        let cue_sheet = CueSheet::for_audio_file(&location);
+
+        // Embedded tags, if the file has any
+        let tags = TrackTags::read(&location);
+
+        // Downloaders and syncs record where the file came from
+        let origin = TrackOrigin::read(&location);
+
+        // Ratings for stations that keep some content off the air
+        let ratings = rating::read(&location, tags.comment.as_deref());
         
-        Some(Track {
+        Ok(Track {
             duration,
             modified,
-            location
+            location,
+            cue_sheet,
+            tags,
+            origin,
+            ratings
         })
     }
 
+    /// Creates a Track from metadata that is already known
+    /// 
+    /// Used when metadata doesn't come from a directory scan, such as
+    /// benchmarks and generated playlists. No cue sheet is attached.
+    pub fn from_metadata(location: PathBuf, duration: Duration, modified: SystemTime) -> Self {
+        Track {
+            duration,
+            modified,
+            location,
+            cue_sheet: None,
+            tags: TrackTags::default(),
+            origin: TrackOrigin::Local,
+            ratings: Vec::new()
+        }
+    }
+
+    /// Creates a Track for a single file given by path
+    /// 
+    /// Used for one-off files added to a station's queue through the API.
+    /// Files that can't be read return None for the caller to report.
+    pub fn from_path(location: &Path) -> Option<Self> {
+        let modified = location.metadata().ok()?.modified().ok()?;
+        Track::read(location.to_path_buf(), modified).ok()
+    }
+
     /// Returns the file path for this track
     /// 
     /// Used by Station to get the path for FileRequest messages.
@@ -104,19 +176,85 @@ impl Track {
     pub fn was_modified_on(&self) -> &SystemTime {
         &self.modified
     }
+
+    /// Returns the title from the track's tags, if tagged
+    pub fn title(&self) -> Option<&str> {
+        self.tags.title.as_deref()
+    }
+
+    /// Returns the title tag, falling back to the file name
+    pub fn display_title(&self) -> String {
+        match self.title() {
+            Some(title) => title.to_string(),
+            None => self.location.file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }
+    }
+
+    /// Returns the artist from the track's tags, if tagged
+    /// 
+    /// Used by Shuffle stations to keep the same artist apart.
+    pub fn artist(&self) -> Option<&str> {
+        self.tags.artist.as_deref()
+    }
+
+    /// Returns the album from the track's tags, if tagged
+    pub fn album(&self) -> Option<&str> {
+        self.tags.album.as_deref()
+    }
+
+    /// Returns the track's position on its album, if tagged
+    pub fn track_number(&self) -> Option<u32> {
+        self.tags.track_number
+    }
+
+    /// Returns the genre from the track's tags, if tagged
+    pub fn genre(&self) -> Option<&str> {
+        self.tags.genre.as_deref()
+    }
+
+    /// Returns where the track came from
+    pub fn origin(&self) -> &TrackOrigin {
+        &self.origin
+    }
+
+    /// Returns the track's content ratings, lowercased
+    pub fn ratings(&self) -> &[String] {
+        &self.ratings
+    }
+
+    /// Returns the cue sheet for this track, if it is a continuous mix
+    /// 
+    /// Used by Station to report the current title within a mix and to
+    /// skip by cue index during turnover.
+    pub fn get_cue_sheet(&self) -> Option<&CueSheet> {
+        self.cue_sheet.as_ref()
+    }
 }
 
 impl Clone for Track {
     fn clone(&self) -> Self {
         Track { 
-            duration: self.duration.clone(), 
-            modified: self.modified.clone(), 
-            location: self.location.clone() 
+            duration: self.duration, 
+            modified: self.modified, 
+            location: self.location.clone(),
+            cue_sheet: self.cue_sheet.clone(),
+            tags: self.tags.clone(),
+            origin: self.origin.clone(),
+            ratings: self.ratings.clone()
         }
     }
 }
 
-/// Loads MP3 tracks from a playlist directory
+/// Whether a file belongs to an audio file next to it (a `.cue` sheet,
+/// `.origin` or `.rating` file) rather than being a track itself
+fn is_sidecar(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| ["cue", "origin", "rating"].iter().any(|sidecar| extension.eq_ignore_ascii_case(sidecar)))
+}
+
+/// Loads audio tracks from a playlist directory
 /// 
 /// Scans the directory and creates Track objects for all audio files.
 /// Non-file entries (directories, symlinks) are silently skipped.
@@ -125,24 +263,29 @@ impl Clone for Track {
 /// * `playlist_path` - Path to playlist directory (e.g., `/stations/am/00/playlist/`)
 /// 
 /// # Returns
-/// Iterator of Track objects for each valid audio file found
+/// Iterator of Track objects for each valid audio file found, or the
+/// error if the directory itself can't be read
 /// 
 /// # Behavior
 /// - Only processes files (directories are skipped)
-/// - Files that fail to load are filtered out (won't panic entire operation)
-/// - Currently only works with MP3 files
-/// 
-/// # Panics
-/// Panics if the directory cannot be read
+/// - `.cue` sheets, `.origin` and `.rating` files are skipped (they are attached to their audio file instead)
+/// - Files that fail to load are logged and skipped; files that aren't
+///   audio at all (cover art, notes) are skipped quietly
+/// - Works with MP3, FLAC, OGG Vorbis, WAV and AAC/M4A files (and transcoded Opus)
 /// 
 /// # Example
-/// ```
-/// let tracks: Vec<Track> = load_tracks_from_path(Path::new("/stations/am/00/playlist"))
+/// ```no_run
+/// # use std::path::Path;
+/// # use mokradio::radio::station::content::track::{load_tracks_from_path, Track};
+/// # fn main() -> Result<(), mokradio::error::MokRadioError> {
+/// let tracks: Vec<Track> = load_tracks_from_path(Path::new("/stations/am/00/playlist"))?
 ///     .collect();
+/// # Ok(())
+/// # }
 /// ```
-pub fn load_tracks_from_path(playlist_path: &Path) -> impl Iterator<Item = Track> {
-    std::fs::read_dir(playlist_path)
-        .unwrap()
+pub fn load_tracks_from_path(playlist_path: &Path) -> Result<impl Iterator<Item = Track>, MokRadioError> {
+    let entries = std::fs::read_dir(playlist_path).map_err(|e| MokRadioError::io(playlist_path, e))?;
+    Ok(entries
         .filter_map(|dir_entry| {
             // Skip entries that can't be read
             let unwrapped_entry = dir_entry.ok()?;
@@ -150,11 +293,64 @@ pub fn load_tracks_from_path(playlist_path: &Path) -> impl Iterator<Item = Track
             // Get metadata to check if this is a file
             let meta_data = unwrapped_entry.metadata().ok()?;
             
+            // Cue sheets and origin files belong to their audio file and aren't tracks themselves
+            let is_sidecar = is_sidecar(&unwrapped_entry.path());
+            
             // Only process files (skip directories)
-            if meta_data.is_file() {
-                Track::new(&unwrapped_entry)
+            if meta_data.is_file() && !is_sidecar {
+                skip_unloadable(Track::new(&unwrapped_entry))
             } else {
                 None
             }
-        })
+        }))
+}
+
+/// Logs a file that failed to load as a track, unless it just isn't audio
+fn skip_unloadable(track: Result<Track, MokRadioError>) -> Option<Track> {
+    match track {
+        Ok(track) => Some(track),
+        Err(MokRadioError::NotAudio { .. }) => None,
+        Err(e) => {
+            eprintln!("Skipping track: {}", e);
+            None
+        }
+    }
+}
+
+/// Loads tracks from a directory and all of its subdirectories
+/// 
+/// Used for whole music libraries (artist/album/track layouts) rather
+/// than flat station playlists. Unreadable directories are logged and skipped.
+/// 
+/// # Arguments
+/// * `library_path` - Root directory to search
+/// 
+/// # Returns
+/// Every valid track found under the directory
+pub fn load_tracks_recursive(library_path: &Path) -> Vec<Track> {
+    let mut tracks: Vec<Track> = Vec::new();
+    let mut directories: Vec<PathBuf> = vec![library_path.to_path_buf()];
+
+    while let Some(directory) = directories.pop() {
+        let entries = match std::fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("Failed to read {}: {}", directory.display(), e);
+                continue;
+            }
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let Ok(file_type) = entry.file_type() else {continue;};
+            let is_sidecar = is_sidecar(&entry.path());
+
+            if file_type.is_dir() {
+                directories.push(entry.path());
+            } else if file_type.is_file() && !is_sidecar && let Some(track) = skip_unloadable(Track::new(&entry)) {
+                tracks.push(track);
+            }
+        }
+    }
+
+    tracks
 }
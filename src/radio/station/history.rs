@@ -0,0 +1,84 @@
+//! Play History - What each station has played
+//!
+//! Every track that plays to the end is appended to `history.log` in its
+//! station folder, one line per play: seconds since the Unix epoch, the
+//! track's path and where the track came from, separated by tabs. Lines
+//! written before origins were recorded have no third field. The log is plain text so it can be read,
+//! trimmed or deleted by hand; reports use it for play counts and pace.
+//!
+//! A track a listener skips is recorded too, with `skipped` as a fourth
+//! field; lines without it played to the end.
+//!
+//! This is the `JsonFile` storage backend's history; other backends keep
+//! the same records elsewhere (see `storage`).
+
+use std::fs::{OpenOptions, read_to_string};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::radio::station::content::origin::TrackOrigin;
+
+/// File holding a station's play history
+const HISTORY_FILE: &str = "history.log";
+
+/// One finished play of a track
+#[derive(Debug, Clone)]
+pub struct PlayRecord {
+    /// When the track finished
+    pub played_at: SystemTime,
+
+    /// Track that played
+    pub file_path: PathBuf,
+
+    /// Where the track came from (`Local` for plays recorded without one)
+    pub origin: TrackOrigin,
+
+    /// Whether the track played to the end rather than being skipped
+    pub completed: bool,
+}
+
+/// Fourth field of a history line for a skipped track
+const SKIPPED: &str = "skipped";
+
+/// Appends a finished or skipped track to a station's history
+///
+/// Failures are logged; a read-only station folder just has no history.
+pub fn record_play(station_path: &Path, file_path: &Path, origin: &TrackOrigin, completed: bool) {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut line = format!("{}\t{}\t{}", since_epoch.as_secs(), file_path.display(), origin);
+    if !completed {
+        line = format!("{}\t{}", line, SKIPPED);
+    }
+    line.push('\n');
+
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(station_path.join(HISTORY_FILE))
+        .and_then(|mut history| history.write_all(line.as_bytes()));
+    if let Err(e) = written {
+        eprintln!("Failed to record play in {}: {}", station_path.display(), e);
+    }
+}
+
+/// Reads a station's play history, oldest first
+///
+/// # Returns
+/// Every readable record; malformed lines are skipped, and a station that
+/// has never played anything has an empty history
+pub fn load_history(station_path: &Path) -> Vec<PlayRecord> {
+    let Ok(history) = read_to_string(station_path.join(HISTORY_FILE)) else {return Vec::new();};
+    history.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let (Some(seconds), Some(file_path)) = (fields.next(), fields.next()) else {return None;};
+            Some(PlayRecord {
+                played_at: UNIX_EPOCH + Duration::from_secs(seconds.parse().ok()?),
+                file_path: PathBuf::from(file_path),
+                origin: fields.next().map(TrackOrigin::parse).unwrap_or_default(),
+                completed: fields.next() != Some(SKIPPED),
+            })
+        })
+        .collect()
+}
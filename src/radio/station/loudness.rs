@@ -0,0 +1,247 @@
+//! Loudness Matching - Even levels from track to track
+//!
+//! Stations with a `loudness_target` in station.info play every track at
+//! that integrated loudness (in LUFS), so a quietly mastered record
+//! doesn't follow a loud one at half the volume. Each track is measured
+//! once by a background pass (see `audio::loudness`) and the figures kept
+//! in `loudness.log` in the station folder, one line per track: loudness,
+//! sample peak, file length, modification time in seconds since the Unix
+//! epoch and the track's path, separated by tabs. A track whose file has
+//! changed since is measured again, as are tracks added by a hot reload.
+//!
+//! Tracks not yet measured play at their own level. Gain is limited to
+//! `MAX_GAIN_DB` either way, so a near-silent track isn't blown up, and a
+//! track is only turned up as far as its peak stays under
+//! `PEAK_CEILING_DB`.
+//!
+//! Every station's tracks are measured in turn on one background thread,
+//! so a radio full of new stations doesn't decode them all at once. The
+//! log only grows as tracks are measured; stale and repeated lines are
+//! dropped when a station next starts.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions, read_to_string};
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::UNIX_EPOCH;
+
+use crate::audio::loudness::{Measurement, measure};
+use crate::crash_report;
+use crate::file_loader::decoder::load_and_decode;
+use crate::priority::{ThreadRole, prioritize_current_thread};
+
+/// File holding a station's measured loudness figures
+const LOUDNESS_FILE: &str = "loudness.log";
+
+/// Most a track is turned up or down, in dB
+const MAX_GAIN_DB: f32 = 12.0;
+
+/// Highest a turned-up track's peak may reach, in dBFS
+const PEAK_CEILING_DB: f32 = -1.0;
+
+/// Queue of the one thread measuring every station's tracks
+static ANALYSIS_QUEUE: OnceLock<Sender<AnalysisJob>> = OnceLock::new();
+
+type Measured = Arc<Mutex<HashMap<PathBuf, Measurement>>>;
+
+/// Each logged track's measurement and the file stamp it was taken against
+type Logged = HashMap<PathBuf, (Measurement, (u64, u64))>;
+
+/// A station's loudness target and the figures measured so far
+#[derive(Debug, Clone)]
+pub struct LoudnessMatch {
+    /// Loudness every track is brought to, in LUFS
+    target: f32,
+
+    /// The station's `loudness.log`
+    log_path: PathBuf,
+
+    /// Measurements by track path, filled in by the analysis pass
+    measured: Measured,
+}
+
+/// A station's tracks waiting to be measured
+struct AnalysisJob {
+    log_path: PathBuf,
+    tracks: Vec<PathBuf>,
+    measured: Measured,
+
+    /// Rewrite the log with only the measurements still in use first
+    compact: bool,
+}
+
+impl LoudnessMatch {
+    /// Loads a station's measured figures and measures the rest in the background
+    ///
+    /// # Arguments
+    /// * `station_path` - Station folder holding `loudness.log`
+    /// * `target` - Loudness to match tracks to, in LUFS
+    /// * `tracks` - Paths of the station's tracks
+    pub fn start(station_path: &Path, target: f32, tracks: Vec<PathBuf>) -> Self {
+        let log_path = station_path.join(LOUDNESS_FILE);
+        let (cached, log_lines) = read_log(&log_path);
+
+        let mut measured = HashMap::new();
+        let mut unmeasured = Vec::new();
+        for track in tracks {
+            let current = file_stamp(&track);
+            match cached.get(&track) {
+                Some((measurement, stamp)) if Some(*stamp) == current => {
+                    measured.insert(track, *measurement);
+                },
+                _ => unmeasured.push(track)
+            }
+        }
+
+        let compact = log_lines > measured.len();
+        let loudness_match = LoudnessMatch { target, log_path, measured: Arc::new(Mutex::new(measured)) };
+        loudness_match.queue(unmeasured, compact);
+        loudness_match
+    }
+
+    /// Measures tracks that have no figures yet, such as those a reload added
+    pub fn measure_new(&self, tracks: Vec<PathBuf>) {
+        let unmeasured: Vec<PathBuf> = {
+            let measured = self.measured.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            tracks.into_iter().filter(|track| !measured.contains_key(track)).collect()
+        };
+        self.queue(unmeasured, false);
+    }
+
+    /// Hands tracks to the analysis thread, starting it the first time
+    fn queue(&self, tracks: Vec<PathBuf>, compact: bool) {
+        if tracks.is_empty() && !compact {
+            return;
+        }
+        let analysis_queue = ANALYSIS_QUEUE.get_or_init(|| {
+            let (sender, jobs) = channel();
+            thread::spawn(move || {
+                prioritize_current_thread(ThreadRole::BackgroundScan);
+                for job in jobs {
+                    analyze(job);
+                }
+            });
+            sender
+        });
+        let job = AnalysisJob { log_path: self.log_path.clone(), tracks, measured: Arc::clone(&self.measured), compact };
+        if analysis_queue.send(job).is_err() {
+            eprintln!("Loudness analysis has stopped, not measuring tracks for {}", self.log_path.display());
+        }
+    }
+
+    /// Returns the gain that brings a track to the target
+    ///
+    /// # Returns
+    /// Linear gain, or 1.0 for a track that hasn't been measured
+    pub fn gain(&self, file_path: &Path) -> f32 {
+        let measured = self.measured.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(measurement) = measured.get(file_path) else {
+            return 1.0;
+        };
+        let gain_db = (self.target - measurement.lufs).clamp(-MAX_GAIN_DB, MAX_GAIN_DB);
+        // Turned up no further than the peak allows; turning down is always safe
+        let headroom_db = PEAK_CEILING_DB - 20.0 * measurement.peak.max(f32::MIN_POSITIVE).log10();
+        let gain_db = gain_db.min(headroom_db.max(0.0));
+        10f32.powf(gain_db / 20.0)
+    }
+}
+
+/// Measures a station's tracks one at a time, recording each as it's done
+fn analyze(job: AnalysisJob) {
+    if job.compact {
+        compact_log(&job.log_path, &job.measured);
+    }
+    for track in job.tracks {
+        // A track that panics the decoder is skipped, not the whole pass
+        let measurement = panic::catch_unwind(AssertUnwindSafe(|| crash_report::recoverable(|| measure_track(&track))));
+        let measurement = match measurement {
+            Ok(measurement) => measurement,
+            Err(_) => {
+                eprintln!("Measuring loudness of {} panicked, skipping it", track.display());
+                continue;
+            }
+        };
+        let (Some(measurement), Some(stamp)) = (measurement, file_stamp(&track)) else {
+            continue;
+        };
+
+        let written = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&job.log_path)
+            .and_then(|mut file| file.write_all(log_line(&track, &measurement, stamp).as_bytes()));
+        if let Err(e) = written {
+            eprintln!("Failed to record loudness in {}: {}", job.log_path.display(), e);
+        }
+        job.measured.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(track, measurement);
+    }
+}
+
+/// Decodes a track and measures it
+fn measure_track(track: &Path) -> Option<Measurement> {
+    match load_and_decode(track) {
+        Ok(source) => measure(source),
+        Err(e) => {
+            eprintln!("Failed to measure loudness of {}: {}", track.display(), e);
+            None
+        }
+    }
+}
+
+/// Rewrites a loudness log with one line for each measurement still in use
+///
+/// Written to a temporary file and renamed over the log, so a power cut
+/// leaves the old log or the new one.
+fn compact_log(log_path: &Path, measured: &Measured) {
+    let contents: String = {
+        let measured = measured.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        measured.iter()
+            .filter_map(|(track, measurement)| Some(log_line(track, measurement, file_stamp(track)?)))
+            .collect()
+    };
+    let temporary_path = log_path.with_extension("log.tmp");
+    if let Err(e) = fs::write(&temporary_path, contents).and_then(|_| fs::rename(&temporary_path, log_path)) {
+        eprintln!("Failed to compact {}: {}", log_path.display(), e);
+    }
+}
+
+/// Formats a track's line in the loudness log
+fn log_line(track: &Path, measurement: &Measurement, (length, modified): (u64, u64)) -> String {
+    format!("{:.2}\t{:.4}\t{}\t{}\t{}\n", measurement.lufs, measurement.peak, length, modified, track.display())
+}
+
+/// File length and modification time, to tell when a measurement is out of date
+fn file_stamp(file_path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(file_path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), modified.as_secs()))
+}
+
+/// Reads a loudness log; later lines for a track replace earlier ones
+///
+/// # Returns
+/// Each track's measurement and file stamp, and the number of lines in
+/// the log, to tell when it needs compacting. Lines that don't parse
+/// (such as those from before peaks were recorded) are left out, so
+/// their tracks are measured again.
+fn read_log(log_path: &Path) -> (Logged, usize) {
+    let Ok(contents) = read_to_string(log_path) else {
+        return (HashMap::new(), 0);
+    };
+    let entries = contents.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            let lufs = fields.next()?.parse().ok()?;
+            let peak = fields.next()?.parse().ok()?;
+            let length = fields.next()?.parse().ok()?;
+            let modified = fields.next()?.parse().ok()?;
+            let path = PathBuf::from(fields.next()?);
+            Some((path, (Measurement { lufs, peak }, (length, modified))))
+        })
+        .collect();
+    (entries, contents.lines().count())
+}
@@ -0,0 +1,133 @@
+//! Played Episodes - Content fingerprints for purge stations
+//!
+//! Purge stations are usually fed by a podcast downloader, which may fetch
+//! an episode again under a new name after it has been played. So that
+//! the station doesn't replay it, every track a purge station finishes is
+//! fingerprinted by content and the fingerprint kept by the `Storage`
+//! backend (in `played.log` in the station folder, for `JsonFile`); tracks
+//! with a played fingerprint are left out of the playlist whatever they're
+//! called.
+//!
+//! A fingerprint covers the file's length and its first and last 64 KiB,
+//! which is enough to tell episodes apart without reading hours of audio
+//! on every scan. It's an FNV-1a hash, so it stays the same across builds.
+//!
+//! Once fingerprinted, the finished file is purged: deleted along with its
+//! `.cue` and `.origin` sidecars, or moved into the station's `.trash/`
+//! folder if station.info sets `purge_to_trash`, to be emptied by hand.
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions, read_to_string};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::storage::storage;
+
+/// File holding a station's played fingerprints
+const PLAYED_FILE: &str = "played.log";
+
+/// Folder in the station folder that purged files are moved to
+const TRASH_FOLDER: &str = ".trash";
+
+/// Sidecar files that go with an audio file when it's purged
+const SIDECAR_EXTENSIONS: [&str; 3] = ["cue", "origin", "rating"];
+
+/// Bytes read from each end of a file
+const SAMPLE_LENGTH: u64 = 64 * 1024;
+
+/// Fingerprints a file by its length and the content at either end
+///
+/// # Returns
+/// The fingerprint, or None if the file can't be read
+pub fn fingerprint(file_path: &Path) -> Option<u64> {
+    sample(file_path).ok()
+}
+
+fn sample(file_path: &Path) -> io::Result<u64> {
+    let mut file = File::open(file_path)?;
+    let length = file.metadata()?.len();
+
+    let mut hash = fnv1a(FNV_OFFSET, &length.to_le_bytes());
+    let mut buffer = Vec::with_capacity(SAMPLE_LENGTH as usize);
+    (&mut file).take(SAMPLE_LENGTH).read_to_end(&mut buffer)?;
+    hash = fnv1a(hash, &buffer);
+
+    if length > SAMPLE_LENGTH * 2 {
+        buffer.clear();
+        file.seek(SeekFrom::End(-(SAMPLE_LENGTH as i64)))?;
+        file.read_to_end(&mut buffer)?;
+        hash = fnv1a(hash, &buffer);
+    }
+    Ok(hash)
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Continues an FNV-1a hash over more bytes
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| (hash ^ *byte as u64).wrapping_mul(FNV_PRIME))
+}
+
+/// Records a finished track's fingerprint as played by a station
+///
+/// Failures are logged; the episode may then play again if re-downloaded.
+pub fn record_played(station_path: &Path, file_path: &Path) {
+    let Some(fingerprint) = fingerprint(file_path) else {
+        eprintln!("Can't fingerprint {}, it may replay if downloaded again", file_path.display());
+        return;
+    };
+    storage().record_played(station_path, fingerprint);
+}
+
+/// Reads the fingerprints of everything a station has played
+pub fn played_fingerprints(station_path: &Path) -> HashSet<u64> {
+    storage().played_fingerprints(station_path)
+}
+
+/// Appends a fingerprint to a station's `played.log`
+///
+/// Failures are logged, as for the rest of the `JsonFile` backend.
+pub fn append_to_log(station_path: &Path, fingerprint: u64) {
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(station_path.join(PLAYED_FILE))
+        .and_then(|mut played| writeln!(played, "{:016x}", fingerprint));
+    if let Err(e) = written {
+        eprintln!("Failed to record played episode in {}: {}", station_path.display(), e);
+    }
+}
+
+/// Reads the fingerprints in a station's `played.log`
+pub fn read_log(station_path: &Path) -> HashSet<u64> {
+    let Ok(played) = read_to_string(station_path.join(PLAYED_FILE)) else {return HashSet::new();};
+    played.lines()
+        .filter_map(|line| u64::from_str_radix(line.trim(), 16).ok())
+        .collect()
+}
+
+/// Deletes a played file and its sidecars, or moves them to the station's trash
+///
+/// # Arguments
+/// * `station_path` - Station folder, holding the `.trash/` folder
+/// * `file_path` - Audio file that finished playing
+/// * `to_trash` - Move the files into `.trash/` instead of deleting them
+pub fn purge(station_path: &Path, file_path: &Path, to_trash: bool) {
+    let sidecars = SIDECAR_EXTENSIONS.iter()
+        .map(|extension| file_path.with_extension(extension))
+        .filter(|sidecar| sidecar.exists());
+    for path in std::iter::once(file_path.to_path_buf()).chain(sidecars) {
+        let purged = if to_trash {
+            let trash = station_path.join(TRASH_FOLDER);
+            fs::create_dir_all(&trash)
+                .and_then(|_| fs::rename(&path, trash.join(path.file_name().unwrap_or_default())))
+        } else {
+            fs::remove_file(&path)
+        };
+        match purged {
+            Ok(()) => println!("purged {}{}", path.display(), if to_trash {" to trash"} else {""}),
+            Err(e) => eprintln!("Failed to purge {}: {}", path.display(), e),
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Playlist Rescan - Hot reloads worked out off the manager thread
+//!
+//! Scanning a playlist folder reads every file's tags and length, which
+//! can take seconds on a big or network-mounted folder. A reload therefore
+//! takes a `RescanJob` from each station, runs them all on a background
+//! thread, and hands back only what changed for the Station Manager to
+//! swap in (see `Radio::reload_playlists`).
+//!
+//! Dead stations are looked at too: one whose station.info has since
+//! become playable is reported, to be loaded again from scratch.
+
+use std::path::PathBuf;
+
+use crate::config::folder_scanner::directory_hash;
+use crate::radio::station::config::{PLAY_TYPES, StationConfig};
+use crate::radio::station::content::metadata_cache::playlist_tracks;
+use crate::radio::station::content::track::Track;
+
+/// What a station needs checked by a rescan
+pub struct RescanJob {
+    pub station_path: PathBuf,
+
+    /// Content hash of the playlist folder when it was last loaded
+    pub playlist_hash: Option<u64>,
+
+    /// The station is Dead, so its station.info is checked instead
+    pub dead: bool,
+}
+
+/// What a rescan found
+pub enum Rescanned {
+    /// The playlist folder changed; its tracks as they are now
+    Playlist { playlist_hash: Option<u64>, tracks: Vec<Track> },
+
+    /// A Dead station's station.info is playable now
+    Revived,
+}
+
+impl RescanJob {
+    /// Checks the station's folder
+    ///
+    /// # Returns
+    /// What changed, or None if nothing did
+    pub fn run(&self) -> Option<Rescanned> {
+        if self.dead {
+            let revived = StationConfig::load(&self.station_path)
+                .is_ok_and(|config| config.play_type != "Dead" && PLAY_TYPES.contains(&config.play_type.as_str()));
+            return revived.then_some(Rescanned::Revived);
+        }
+        let playlist_path = self.station_path.join("playlist");
+        let playlist_hash = directory_hash(&playlist_path);
+        if playlist_hash == self.playlist_hash {
+            return None;
+        }
+        Some(Rescanned::Playlist { playlist_hash, tracks: playlist_tracks(&playlist_path) })
+    }
+}
@@ -7,7 +7,8 @@
 //! - Chronologic: Pop oldest track (by file modification time)
 //! - Reverse: Pop newest track (by file modification time)
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, VecDeque};
+use std::time::{Duration, SystemTime};
 use rand::seq::IndexedRandom;
 use rand::rng;
 
@@ -19,7 +20,7 @@ use crate::radio::station::content::track::Track;
 /// The same track may be selected again on the next call.
 /// 
 /// # Arguments
-/// * `play_list` - Mutable reference to track vector (not modified)
+/// * `play_list` - Track list to choose from (not modified)
 /// 
 /// # Returns
 /// - `Some(Track)` - Randomly selected track (cloned from list)
@@ -29,14 +30,9 @@ use crate::radio::station::content::track::Track;
 /// - Track remains in the playlist after selection
 /// - Each call is independent - no memory of what was played last
 /// - All tracks have equal probability of selection
-/// 
-/// # Note
-/// Currently unwraps the Option from choose(), which will panic on empty list.
-/// TODO: Handle empty playlist gracefully
-pub fn next_random(play_list: &mut Vec<Track>) -> Option<Track> {
+pub fn next_random(play_list: &[Track]) -> Option<Track> {
     // Choose returns Option<&Track>, so we clone it to return owned Track
-    let next_track = play_list.choose(&mut rng());
-    Some(next_track.unwrap().clone())
+    play_list.choose(&mut rng()).cloned()
 }
 
 /// Removes and returns the last track from a shuffled playlist
@@ -60,6 +56,34 @@ pub fn next_shuffle(play_list: &mut Vec<Track>) -> Option<Track> {
     play_list.pop()
 }
 
+/// Removes and returns the next shuffled track by an artist not heard recently
+/// 
+/// Used by PlayType::Shuffle when the station sets `artist_separation`,
+/// like rotation software keeping the same artist apart on a music station.
+/// 
+/// # Arguments
+/// * `play_list` - Mutable reference to shuffled track vector
+/// * `recent_artists` - Artists of the last few tracks played
+/// 
+/// # Returns
+/// - `Some(Track)` - Next track whose artist isn't in `recent_artists`
+/// - `None` - Playlist is empty (all tracks played)
+/// 
+/// # Behavior
+/// - Searches from the end, so the shuffled order is kept where possible
+/// - Untagged tracks never clash with anything
+/// - If every remaining track clashes, the next shuffled track plays anyway
+pub fn next_shuffle_separated(play_list: &mut Vec<Track>, recent_artists: &VecDeque<String>) -> Option<Track> {
+    let position = play_list.iter().rposition(|track| {
+        track.artist().is_none_or(|artist| !recent_artists.iter().any(|recent| recent == artist))
+    });
+
+    match position {
+        Some(index) => Some(play_list.remove(index)),
+        None => play_list.pop()
+    }
+}
+
 /// Removes and returns the oldest track (earliest file modification time)
 /// 
 /// Used by PlayType::Chronologic - plays tracks in order from oldest to newest.
@@ -101,3 +125,22 @@ pub fn next_chronologic(play_list: &mut BTreeSet<Track>) -> Option<Track> {
 pub fn next_reverse(play_list: &mut BTreeSet<Track>) -> Option<Track> {
     play_list.pop_last()
 }
+
+/// Removes tracks modified before a rolling window from a sorted playlist
+/// 
+/// Used by Chronologic stations with `window_days` set, so content ages
+/// out of a "recent uploads" station while the radio is running.
+/// 
+/// # Arguments
+/// * `play_list` - Mutable reference to BTreeSet of tracks (sorted by time)
+/// * `window` - How far back from now tracks are still allowed
+/// 
+/// # Behavior
+/// - Oldest tracks sit at the front of the set, so only the front is checked
+/// - Removed tracks stay on disk; they are just never selected
+pub fn drop_aged_out(play_list: &mut BTreeSet<Track>, window: Duration) {
+    let Some(cutoff) = SystemTime::now().checked_sub(window) else {return;};
+    while play_list.first().is_some_and(|track| *track.was_modified_on() < cutoff) {
+        play_list.pop_first();
+    }
+}
@@ -1,29 +1,56 @@
+use std::f32::consts::PI;
 use std::sync::mpsc::Sender;
 
+use crate::config::tuning::{FadeCurve, TuningProfile};
 use crate::constants;
 use crate::messages::FileRequest;
 use crate::radio::station::{Station, content::{StationID, Band}};
 
-pub fn generate_station_volume_profile() -> [f32; constants::TICKS_PER_STATION] {
+pub fn generate_station_volume_profile(tuning: &TuningProfile) -> [f32; constants::TICKS_PER_STATION] {
 
     let center = (constants::TICKS_PER_STATION / 2) as f32;
-    let plateau_half_width = center * 0.06;
-    let steepness = 0.05 * constants::TICKS_PER_STATION as f32;
+    let plateau_half_width = center * tuning.station_width;
+    let steepness = tuning.softness * constants::TICKS_PER_STATION as f32;
         
     std::array::from_fn(|tick| {
         // Get position within the station's band (0 to TICKS_PER_STATION)
         let x = (tick % constants::TICKS_PER_STATION) as f32;
-            
-        let left_tanh = ((x - (center - plateau_half_width)) / steepness).tanh();
-        let right_tanh = ((x - (center + plateau_half_width)) / steepness).tanh();
-            
-        let volume = 0.5 * (left_tanh - right_tanh);
+
+        let volume = match tuning.curve {
+            FadeCurve::Tanh => {
+                let left_tanh = ((x - (center - plateau_half_width)) / steepness).tanh();
+                let right_tanh = ((x - (center + plateau_half_width)) / steepness).tanh();
+                0.5 * (left_tanh - right_tanh)
+            },
+            FadeCurve::Linear | FadeCurve::Cosine => {
+                // Distance past the plateau edge, as a fraction of the fade length
+                let past_plateau = ((x - center).abs() - plateau_half_width).max(0.0);
+                let ramp = (1.0 - past_plateau / (2.0 * steepness)).clamp(0.0, 1.0);
+                if tuning.curve == FadeCurve::Linear {ramp} else {0.5 - 0.5 * (PI * ramp).cos()}
+            }
+        };
             
         // Round to 3 decimal places
         (volume * 1000.0).round() / 1000.0
     })
 }
 
+/// Returns how loud a neighboring station bleeds through at a dial tick
+/// 
+/// Bleed grows toward the edges of a station slot, reaching the tuning
+/// profile's `overlap` right at the boundary between two stations.
+/// 
+/// # Returns
+/// The neighbor's index offset (-1 below, +1 above) and its bleed volume
+pub fn neighbor_bleed(tick: usize, tuning: &TuningProfile) -> (isize, f32) {
+    let center = (constants::TICKS_PER_STATION / 2) as f32;
+    let offset = tick as f32 - center;
+    let edge_closeness = (offset.abs() / center).clamp(0.0, 1.0);
+    let direction = if offset < 0.0 {-1} else {1};
+
+    (direction, tuning.overlap * edge_closeness * edge_closeness)
+}
+
 pub fn skip_dormant_stations_in_band(
     current_band: &mut [Station; constants::NUMBER_OF_STATIONS], 
     file_requester: &Sender<FileRequest>,
@@ -31,10 +58,8 @@ pub fn skip_dormant_stations_in_band(
 ) {
     current_band.iter_mut().enumerate().for_each(|(index, station)| {
         if let Some(request_path) = station.skip() {
-            let request = FileRequest::LoadTrack {
-                station_id: StationID { band, index },
-                file_path: request_path
-            };
+            let intro = station.take_intro();
+            let request = station.load_request(StationID { band, index }, request_path, intro);
             file_requester.send(request).ok();
         }
     });
@@ -46,14 +71,10 @@ pub fn skip_dormant_stations_in_band_except_current(
     current_station_index:usize
 ) {
     current_band.iter_mut().enumerate().for_each(|(index, station)| {
-        if current_station_index != index {
-            if let Some(request_path ) = station.skip() {
-                let request = FileRequest::LoadTrack {
-                    station_id: StationID { band, index },
-                    file_path: request_path
-                };
-                file_requester.send(request).ok();
-            }
+        if current_station_index != index && let Some(request_path) = station.skip() {
+            let intro = station.take_intro();
+            let request = station.load_request(StationID { band, index }, request_path, intro);
+            file_requester.send(request).ok();
         }
     });
 }
\ No newline at end of file
@@ -1 +1,65 @@
+//! State Module - Picking up where the radio left off
+//!
+//! Every `SAVE_INTERVAL` the Station Manager saves where the dial is, and
+//! for each station the track it's playing and how far in (see
+//! `state::station`). After a power cut the radio comes back on the same
+//! frequency, and every station carries on with the track it was cut off
+//! in rather than a fresh pick. State goes through the `Storage` backend
+//! chosen in radio.info, like everything else the radio remembers.
+//!
+//! Only dials with no position of their own (keyboard, encoder, touch
+//! pad) start from the saved position; a pot is read at boot and tunes to
+//! wherever the knob really is, and a band switch likewise.
+
 pub mod station;
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{from_value, json};
+
+use crate::constants;
+use crate::radio::station::content::Band;
+use crate::storage::storage;
+
+/// How often playback state is saved while the radio runs
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Storage key for the dial's state
+const RADIO_KEY: &str = "radio";
+
+/// Where the dial was when state was last saved
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadioState {
+    /// Index of the band in radio.info
+    pub band: usize,
+
+    /// Dial position (0..ENCODER_HALF)
+    pub dial_position: usize,
+}
+
+/// Saves where the dial is
+pub fn save_radio_state(band: Band, dial_position: usize) {
+    storage().save_state(RADIO_KEY, &json!(RadioState { band: band.0, dial_position }));
+}
+
+/// Loads where the dial was, if it's still on the dial
+///
+/// A band since removed from radio.info is ignored.
+pub fn load_radio_state() -> Option<RadioState> {
+    let saved = storage().load_state(RADIO_KEY)?;
+    let radio_state: RadioState = from_value(saved)
+        .inspect_err(|e| eprintln!("Ignoring saved radio state: {}", e))
+        .ok()?;
+    (radio_state.band < Band::all().count() && radio_state.dial_position < constants::ENCODER_HALF).then_some(radio_state)
+}
+
+/// Returns the dial position to start from: where it was saved, or mid-dial
+pub fn starting_dial_position() -> usize {
+    load_radio_state().map_or(constants::ENCODER_HALF / 2, |radio_state| radio_state.dial_position)
+}
+
+/// Returns the band to start on: where it was saved, or the first band
+pub fn starting_band() -> Band {
+    load_radio_state().map_or(Band::AM, |radio_state| Band(radio_state.band))
+}
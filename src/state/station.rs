@@ -0,0 +1,31 @@
+//! Station State - Where each station had got to
+//!
+//! Saved under a `playback` key per station by `Station::save_playback`,
+//! and read back when the station primes its content at boot: the saved
+//! track is queued first and joined a little before where it was cut off.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How far before the saved point a resumed track picks up, so the last
+/// few seconds before a power cut are heard again
+pub const RESUME_REWIND: Duration = Duration::from_secs(3);
+
+/// The track a station was playing and how far in
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlaybackState {
+    /// Track playing when the state was saved
+    pub track: PathBuf,
+
+    /// Seconds into the track
+    pub offset_secs: f64,
+}
+
+impl PlaybackState {
+    /// Returns where to join the track again
+    pub fn resume_offset(&self) -> Duration {
+        Duration::from_secs_f64(self.offset_secs.max(0.0)).saturating_sub(RESUME_REWIND)
+    }
+}
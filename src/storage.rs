@@ -0,0 +1,112 @@
+//! Storage Module - Where the radio keeps its state and play history
+//!
+//! Play history, purge stations' played episodes (see `played`) and saved
+//! state go through a `Storage` backend picked by `storage` in radio.info,
+//! trading durability against SD card wear:
+//!
+//! - `JsonFile` (default): history in each station's `history.log` and
+//!   played episodes in its `played.log`, state as one JSON file per key
+//!   under `state/` in the stations root. Plain text that can be read and
+//!   fixed by hand.
+//! - `Sqlite`: everything in `mokradio.db` in the stations root, written
+//!   in transactions so a power cut can't leave half a record. Needs the
+//!   `sqlite` feature; without it the radio falls back to `JsonFile`.
+//! - `Memory`: nothing touches the disk and everything is forgotten at
+//!   exit. For tests, and for read-only SD cards.
+//!
+//! # JSON Format
+//! ```json
+//! "storage": "Sqlite"
+//! ```
+
+pub mod json_file;
+pub mod memory;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::config::{radio_config, stations_root};
+use crate::radio::station::content::origin::TrackOrigin;
+use crate::radio::station::history::PlayRecord;
+
+/// Which backend stores state and history
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// Text files next to the stations
+    JsonFile,
+
+    /// A single SQLite database
+    Sqlite,
+
+    /// RAM only, lost at exit
+    Memory,
+}
+
+/// Persistence for play history and radio state
+/// 
+/// Backends log their own failures: losing a history line or a state
+/// save shouldn't stop the music.
+pub trait Storage: Send + Sync {
+    /// Appends a track that just played to a station's history
+    /// 
+    /// `completed` is false for a track a listener skipped part-way through.
+    fn record_play(&self, station_path: &Path, file_path: &Path, origin: &TrackOrigin, completed: bool);
+
+    /// Reads a station's play history, oldest first
+    fn load_history(&self, station_path: &Path) -> Vec<PlayRecord>;
+
+    /// Adds an episode's content fingerprint to what a station has played
+    fn record_played(&self, station_path: &Path, fingerprint: u64);
+
+    /// Reads the fingerprints of every episode a station has played
+    fn played_fingerprints(&self, station_path: &Path) -> HashSet<u64>;
+
+    /// Saves a piece of state under a key, replacing any earlier value
+    fn save_state(&self, key: &str, value: &Value);
+
+    /// Loads the state saved under a key
+    fn load_state(&self, key: &str) -> Option<Value>;
+
+    /// Files this backend keeps under the stations root, for backups
+    fn files(&self) -> Vec<PathBuf>;
+}
+
+/// Storage backend, opened on first use
+static STORAGE: OnceLock<Box<dyn Storage>> = OnceLock::new();
+
+/// Returns the storage backend chosen in radio.info
+pub fn storage() -> &'static dyn Storage {
+    STORAGE.get_or_init(|| open(radio_config().storage)).as_ref()
+}
+
+/// Opens a backend rooted in the stations root
+fn open(backend: StorageBackend) -> Box<dyn Storage> {
+    match backend {
+        StorageBackend::JsonFile => Box::new(json_file::JsonFileStorage::new(stations_root())),
+        StorageBackend::Memory => Box::new(memory::MemoryStorage::new()),
+        StorageBackend::Sqlite => open_sqlite(),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite() -> Box<dyn Storage> {
+    match sqlite::SqliteStorage::open(stations_root()) {
+        Ok(storage) => Box::new(storage),
+        Err(e) => {
+            eprintln!("Failed to open SQLite storage, using JSON files: {}", e);
+            Box::new(json_file::JsonFileStorage::new(stations_root()))
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite() -> Box<dyn Storage> {
+    eprintln!("Built without the sqlite feature, using JSON files for storage");
+    Box::new(json_file::JsonFileStorage::new(stations_root()))
+}
@@ -0,0 +1,91 @@
+//! JSON File Storage - State and history as plain files
+//!
+//! History stays in each station's `history.log` (see `history`) and
+//! played episodes in its `played.log` (see `played`), and each state key is a pretty-printed `state/<key>.json` in the stations
+//! root. State files are written to a temporary file and renamed over the
+//! old one, so a power cut leaves either the old state or the new.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::radio::station::content::origin::TrackOrigin;
+use crate::radio::station::history::{self, PlayRecord};
+use crate::radio::station::played;
+use crate::storage::Storage;
+
+/// Folder in the stations root holding state files
+const STATE_DIRECTORY: &str = "state";
+
+pub struct JsonFileStorage {
+    state_path: PathBuf,
+}
+
+impl JsonFileStorage {
+    /// Creates a backend keeping state under `root`
+    pub fn new(root: &Path) -> Self {
+        JsonFileStorage { state_path: root.join(STATE_DIRECTORY) }
+    }
+
+    /// File holding a key's state; keys are sanitized to file names
+    fn state_file(&self, key: &str) -> PathBuf {
+        let name: String = key.chars()
+            .map(|character| if character.is_ascii_alphanumeric() || character == '-' || character == '_' {character} else {'_'})
+            .collect();
+        self.state_path.join(format!("{}.json", name))
+    }
+}
+
+impl Storage for JsonFileStorage {
+    fn record_play(&self, station_path: &Path, file_path: &Path, origin: &TrackOrigin, completed: bool) {
+        history::record_play(station_path, file_path, origin, completed);
+    }
+
+    fn load_history(&self, station_path: &Path) -> Vec<PlayRecord> {
+        history::load_history(station_path)
+    }
+
+    fn record_played(&self, station_path: &Path, fingerprint: u64) {
+        played::append_to_log(station_path, fingerprint);
+    }
+
+    fn played_fingerprints(&self, station_path: &Path) -> HashSet<u64> {
+        played::read_log(station_path)
+    }
+
+    fn save_state(&self, key: &str, value: &Value) {
+        let state_file = self.state_file(key);
+        let temporary = state_file.with_extension("json.tmp");
+        let written = fs::create_dir_all(&self.state_path)
+            .and_then(|_| serde_json::to_string_pretty(value).map_err(std::io::Error::other))
+            .and_then(|json| fs::write(&temporary, json))
+            .and_then(|_| fs::rename(&temporary, &state_file));
+        if let Err(e) = written {
+            eprintln!("Failed to save {} state to {}: {}", key, state_file.display(), e);
+        }
+    }
+
+    fn load_state(&self, key: &str) -> Option<Value> {
+        let state_file = self.state_file(key);
+        let json = fs::read_to_string(&state_file).ok()?;
+        match serde_json::from_str(&json) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("Ignoring unreadable state in {}: {}", state_file.display(), e);
+                None
+            }
+        }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.state_path) else {return Vec::new();};
+        let mut files: Vec<PathBuf> = entries.filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+            .collect();
+        files.sort();
+        files
+    }
+}
@@ -0,0 +1,75 @@
+//! In-Memory Storage - State and history that never reach the disk
+//!
+//! Everything is kept in maps and lost when the radio exits. Tests use it
+//! so runs don't leave files behind or see each other's history.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde_json::Value;
+
+use crate::radio::station::content::origin::TrackOrigin;
+use crate::radio::station::history::PlayRecord;
+use crate::storage::Storage;
+
+#[derive(Default)]
+pub struct MemoryStorage {
+    /// Play history by station folder
+    history: Mutex<HashMap<PathBuf, Vec<PlayRecord>>>,
+
+    /// Played episode fingerprints by station folder
+    played: Mutex<HashMap<PathBuf, HashSet<u64>>>,
+
+    state: Mutex<HashMap<String, Value>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        MemoryStorage::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn record_play(&self, station_path: &Path, file_path: &Path, origin: &TrackOrigin, completed: bool) {
+        let record = PlayRecord { played_at: SystemTime::now(), file_path: file_path.to_path_buf(), origin: origin.clone(), completed };
+        self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(station_path.to_path_buf())
+            .or_default()
+            .push(record);
+    }
+
+    fn load_history(&self, station_path: &Path) -> Vec<PlayRecord> {
+        self.history.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(station_path)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn record_played(&self, station_path: &Path, fingerprint: u64) {
+        self.played.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(station_path.to_path_buf())
+            .or_default()
+            .insert(fingerprint);
+    }
+
+    fn played_fingerprints(&self, station_path: &Path) -> HashSet<u64> {
+        self.played.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(station_path)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn save_state(&self, key: &str, value: &Value) {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(key.to_string(), value.clone());
+    }
+
+    fn load_state(&self, key: &str) -> Option<Value> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(key).cloned()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,166 @@
+//! SQLite Storage - State and history in one database
+//!
+//! Everything goes in `mokradio.db` in the stations root. Each write is
+//! its own transaction, so a power cut loses at most the write in flight,
+//! and SQLite's journal keeps the file consistent. Station history is
+//! keyed by the station folder's path, with a row for every track that
+//! played (`completed` 1) or was skipped (`completed` 0), indexed by track
+//! as well for play counts and least-recently-played picks:
+//!
+//! ```text
+//! sqlite3 /stations/mokradio.db \
+//!     "SELECT file_path, COUNT(*) FROM history WHERE completed GROUP BY file_path ORDER BY 2 DESC"
+//! ```
+//!
+//! Purge stations' played episodes are rows of station and fingerprint,
+//! stored as the signed 64-bit integer with the same bits.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, OptionalExtension, params};
+use serde_json::Value;
+
+use crate::radio::station::content::origin::TrackOrigin;
+use crate::radio::station::history::PlayRecord;
+use crate::storage::Storage;
+
+/// Database file in the stations root
+const DATABASE_FILE: &str = "mokradio.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS history (
+        station TEXT NOT NULL,
+        played_at INTEGER NOT NULL,
+        file_path TEXT NOT NULL,
+        origin TEXT,
+        completed INTEGER NOT NULL DEFAULT 1
+    );
+    CREATE INDEX IF NOT EXISTS history_by_station ON history (station, played_at);
+    CREATE TABLE IF NOT EXISTS played (
+        station TEXT NOT NULL,
+        fingerprint INTEGER NOT NULL,
+        PRIMARY KEY (station, fingerprint)
+    );
+    CREATE TABLE IF NOT EXISTS state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+pub struct SqliteStorage {
+    database_path: PathBuf,
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database in `root`
+    pub fn open(root: &Path) -> rusqlite::Result<Self> {
+        let database_path = root.join(DATABASE_FILE);
+        let connection = Connection::open(&database_path)?;
+        connection.execute_batch(SCHEMA)?;
+        add_column(&connection, "origin", "origin TEXT")?;
+        add_column(&connection, "completed", "completed INTEGER NOT NULL DEFAULT 1")?;
+        // After the columns, since it indexes one a database from before them lacks
+        connection.execute_batch("CREATE INDEX IF NOT EXISTS history_by_track ON history (file_path, played_at)")?;
+        Ok(SqliteStorage { database_path, connection: Mutex::new(connection) })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Adds a column to history tables created before it existed
+fn add_column(connection: &Connection, column: &str, definition: &str) -> rusqlite::Result<()> {
+    let has_column = connection.prepare(&format!("SELECT {} FROM history LIMIT 0", column)).is_ok();
+    if !has_column {
+        connection.execute_batch(&format!("ALTER TABLE history ADD COLUMN {}", definition))?;
+    }
+    Ok(())
+}
+
+impl Storage for SqliteStorage {
+    fn record_play(&self, station_path: &Path, file_path: &Path, origin: &TrackOrigin, completed: bool) {
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let inserted = self.connection().execute(
+            "INSERT INTO history (station, played_at, file_path, origin, completed) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![station_path.to_string_lossy(), since_epoch.as_secs() as i64, file_path.to_string_lossy(), origin.to_string(), completed],
+        );
+        if let Err(e) = inserted {
+            eprintln!("Failed to record play in {}: {}", self.database_path.display(), e);
+        }
+    }
+
+    fn load_history(&self, station_path: &Path) -> Vec<PlayRecord> {
+        let connection = self.connection();
+        let records: rusqlite::Result<Vec<PlayRecord>> = connection
+            .prepare("SELECT played_at, file_path, origin, completed FROM history WHERE station = ?1 ORDER BY played_at, rowid")
+            .and_then(|mut statement| {
+                statement.query_map(params![station_path.to_string_lossy()], |row| {
+                    Ok(PlayRecord {
+                        played_at: UNIX_EPOCH + Duration::from_secs(row.get::<_, i64>(0)?.max(0) as u64),
+                        file_path: PathBuf::from(row.get::<_, String>(1)?),
+                        origin: row.get::<_, Option<String>>(2)?.as_deref().map(TrackOrigin::parse).unwrap_or_default(),
+                        completed: row.get(3)?,
+                    })
+                })?.collect()
+            });
+        records.unwrap_or_else(|e| {
+            eprintln!("Failed to read history of {}: {}", station_path.display(), e);
+            Vec::new()
+        })
+    }
+
+    fn record_played(&self, station_path: &Path, fingerprint: u64) {
+        let inserted = self.connection().execute(
+            "INSERT OR IGNORE INTO played (station, fingerprint) VALUES (?1, ?2)",
+            params![station_path.to_string_lossy(), fingerprint as i64],
+        );
+        if let Err(e) = inserted {
+            eprintln!("Failed to record played episode in {}: {}", self.database_path.display(), e);
+        }
+    }
+
+    fn played_fingerprints(&self, station_path: &Path) -> HashSet<u64> {
+        let connection = self.connection();
+        let fingerprints: rusqlite::Result<HashSet<u64>> = connection
+            .prepare("SELECT fingerprint FROM played WHERE station = ?1")
+            .and_then(|mut statement| {
+                statement.query_map(params![station_path.to_string_lossy()], |row| {
+                    Ok(row.get::<_, i64>(0)? as u64)
+                })?.collect()
+            });
+        fingerprints.unwrap_or_else(|e| {
+            eprintln!("Failed to read played episodes of {}: {}", station_path.display(), e);
+            HashSet::new()
+        })
+    }
+
+    fn save_state(&self, key: &str, value: &Value) {
+        let saved = self.connection().execute(
+            "INSERT INTO state (key, value) VALUES (?1, ?2) ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            params![key, value.to_string()],
+        );
+        if let Err(e) = saved {
+            eprintln!("Failed to save {} state in {}: {}", key, self.database_path.display(), e);
+        }
+    }
+
+    fn load_state(&self, key: &str) -> Option<Value> {
+        let json: Option<String> = self.connection()
+            .query_row("SELECT value FROM state WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to load {} state from {}: {}", key, self.database_path.display(), e);
+                None
+            });
+        serde_json::from_str(&json?).ok()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        vec![self.database_path.clone()]
+    }
+}
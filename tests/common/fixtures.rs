@@ -0,0 +1,116 @@
+//! Fixtures - Audio files synthesized at runtime
+//!
+//! Tests and benchmarks need real audio files that the decoder, duration
+//! probe and tag reader all accept. Rather than checking binary fixtures
+//! into the repo, these helpers write tiny valid files of silence with a
+//! chosen length and optional tags:
+//! - WAV: 8kHz mono 16-bit PCM, tagged with a RIFF INFO chunk
+//! - MP3: 32kHz mono 32kbps MPEG-1 Layer III, tagged with ID3v2
+//!
+//! MP3 lengths are rounded up to a whole frame (36ms).
+//!
+//! Kept out of the library so release builds don't carry it. Test crates
+//! get it through `mod common`, the benchmarks by path.
+
+// Each test crate only uses some of the writers
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use lofty::config::WriteOptions;
+use lofty::prelude::*;
+use lofty::tag::{Tag, TagType};
+
+use mokradio::radio::station::content::tags::TrackTags;
+
+/// WAV fixture format
+const WAV_SAMPLE_RATE: u32 = 8_000;
+const WAV_BYTES_PER_SAMPLE: u32 = 2;
+
+/// MPEG-1 Layer III frame header: no CRC, 32kbps, 32kHz, no padding, mono
+const MP3_FRAME_HEADER: [u8; 4] = [0xFF, 0xFB, 0x18, 0xC0];
+
+/// 144 * bitrate / sample rate
+const MP3_FRAME_BYTES: usize = 144;
+
+/// Samples per Layer III frame
+const MP3_FRAME_SAMPLES: u32 = 1152;
+const MP3_SAMPLE_RATE: u32 = 32_000;
+
+/// Writes a silent WAV file
+///
+/// # Arguments
+/// * `path` - File to create (overwritten if it exists)
+/// * `duration` - Length of the audio
+/// * `tags` - Title, artist, album, track number and genre to embed; empty tags write no tag chunk
+pub fn write_wav(path: &Path, duration: Duration, tags: &TrackTags) -> io::Result<()> {
+    let samples = (duration.as_secs_f64() * WAV_SAMPLE_RATE as f64).round() as u32;
+    let data_length = samples * WAV_BYTES_PER_SAMPLE;
+
+    let mut wav = Vec::with_capacity(44 + data_length as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_length).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&WAV_SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(WAV_SAMPLE_RATE * WAV_BYTES_PER_SAMPLE).to_le_bytes());
+    wav.extend_from_slice(&(WAV_BYTES_PER_SAMPLE as u16).to_le_bytes());
+    wav.extend_from_slice(&(WAV_BYTES_PER_SAMPLE as u16 * 8).to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_length.to_le_bytes());
+    wav.resize(44 + data_length as usize, 0);
+
+    File::create(path)?.write_all(&wav)?;
+    write_tags(path, TagType::RiffInfo, tags)
+}
+
+/// Writes a silent MP3 file
+///
+/// Every frame carries zeroed side info and no main data, which decodes
+/// to silence and keeps the file at about 4KB per second.
+///
+/// # Arguments
+/// * `path` - File to create (overwritten if it exists)
+/// * `duration` - Length of the audio, rounded up to a whole frame
+/// * `tags` - Title, artist, album, track number and genre to embed; empty tags write no ID3 tag
+pub fn write_mp3(path: &Path, duration: Duration, tags: &TrackTags) -> io::Result<()> {
+    let frame_seconds = MP3_FRAME_SAMPLES as f64 / MP3_SAMPLE_RATE as f64;
+    let frames = (duration.as_secs_f64() / frame_seconds).ceil() as usize;
+
+    let mut frame = [0u8; MP3_FRAME_BYTES];
+    frame[..MP3_FRAME_HEADER.len()].copy_from_slice(&MP3_FRAME_HEADER);
+    let mp3 = frame.repeat(frames);
+
+    File::create(path)?.write_all(&mp3)?;
+    write_tags(path, TagType::Id3v2, tags)
+}
+
+/// Embeds whichever tag values are set
+fn write_tags(path: &Path, tag_type: TagType, tags: &TrackTags) -> io::Result<()> {
+    if tags.title.is_none() && tags.artist.is_none() && tags.album.is_none() && tags.track_number.is_none() && tags.genre.is_none() {
+        return Ok(());
+    }
+
+    let mut tag = Tag::new(tag_type);
+    if let Some(title) = &tags.title {
+        tag.set_title(title.clone());
+    }
+    if let Some(artist) = &tags.artist {
+        tag.set_artist(artist.clone());
+    }
+    if let Some(album) = &tags.album {
+        tag.set_album(album.clone());
+    }
+    if let Some(track_number) = tags.track_number {
+        tag.set_track(track_number);
+    }
+    if let Some(genre) = &tags.genre {
+        tag.set_genre(genre.clone());
+    }
+    tag.save_to_path(path, WriteOptions::default()).map_err(io::Error::other)
+}
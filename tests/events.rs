@@ -0,0 +1,11 @@
+//! WebSocket handshake for the event stream
+//!
+//! The accept key is built on a hand-rolled SHA-1 and base64, so it's
+//! checked against the worked example in RFC 6455 section 1.3.
+
+use mokradio::api::events::accept_key;
+
+#[test]
+fn accept_key_matches_rfc_6455_example() {
+    assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+}
@@ -0,0 +1,76 @@
+//! Generated fixtures read back as real tracks
+//!
+//! The fixture writers are only useful if the duration probe, tag reader
+//! and decoder all accept their output, so each format is round-tripped
+//! through `Track::from_path` and the loader's decoder.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use mokradio::file_loader::decoder::load_and_decode;
+use mokradio::radio::station::content::tags::TrackTags;
+use mokradio::radio::station::content::track::Track;
+
+mod common;
+use common::fixtures::{write_mp3, write_wav};
+
+fn fixture_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("mokradio-fixture-{}-{}", std::process::id(), name))
+}
+
+fn tags() -> TrackTags {
+    TrackTags {
+        title: Some("Test Title".to_string()),
+        artist: Some("Test Artist".to_string()),
+        album: Some("Test Album".to_string()),
+        track_number: Some(7),
+        genre: Some("Jazz".to_string()),
+        comment: None,
+    }
+}
+
+#[test]
+fn wav_fixture_has_length_and_tags() {
+    let path = fixture_path("tagged.wav");
+    write_wav(&path, Duration::from_secs(3), &tags()).unwrap();
+
+    let track = Track::from_path(&path).unwrap();
+    assert_eq!(track.get_duration().num_seconds(), 3);
+    assert_eq!(track.title(), Some("Test Title"));
+    assert_eq!(track.artist(), Some("Test Artist"));
+    assert_eq!(track.album(), Some("Test Album"));
+    assert_eq!(track.track_number(), Some(7));
+    assert_eq!(track.genre(), Some("Jazz"));
+    assert!(load_and_decode(&path).is_ok());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn mp3_fixture_has_length_and_tags() {
+    let path = fixture_path("tagged.mp3");
+    write_mp3(&path, Duration::from_secs(5), &tags()).unwrap();
+
+    let track = Track::from_path(&path).unwrap();
+    assert_eq!(track.get_duration().num_seconds(), 5);
+    assert_eq!(track.title(), Some("Test Title"));
+    assert_eq!(track.artist(), Some("Test Artist"));
+    assert_eq!(track.album(), Some("Test Album"));
+    assert_eq!(track.track_number(), Some(7));
+    assert_eq!(track.genre(), Some("Jazz"));
+    assert!(load_and_decode(&path).is_ok());
+
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn untagged_fixture_is_named_by_file() {
+    let path = fixture_path("untagged.mp3");
+    write_mp3(&path, Duration::from_secs(1), &TrackTags::default()).unwrap();
+
+    let track = Track::from_path(&path).unwrap();
+    assert_eq!(track.title(), None);
+    assert!(track.display_title().ends_with("untagged"));
+
+    std::fs::remove_file(&path).ok();
+}
@@ -0,0 +1,176 @@
+//! End-to-end run of the radio against a generated stations tree
+//!
+//! Builds a temporary stations root with a few tiny WAV stations, points
+//! the radio at it, and runs the real Station Manager and File Loader with
+//! the null audio backend. Input events are scripted and the radio is
+//! observed through the API, so nothing depends on a sound card or GPIO.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use mokradio::api::{ApiError, RadioApi};
+use mokradio::config::STATIONS_ROOT_VARIABLE;
+use mokradio::constants::TICKS_PER_STATION;
+use mokradio::file_loader;
+use mokradio::messages::{InputEvent, RadioStatus};
+use mokradio::radio::Radio;
+use mokradio::radio::station::content::{Band, StationID};
+use mokradio::radio::station::content::tags::TrackTags;
+
+mod common;
+use common::fixtures::write_wav;
+
+/// Long enough that nothing finishes while the test runs
+const TRACK_LENGTH: Duration = Duration::from_secs(30);
+
+/// Longest the test waits for the radio to reach an expected state
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Creates a station folder whose tracks are dated in the order given
+fn write_station(root: &Path, band: &str, index: usize, play_type: &str, tracks: &[&str]) {
+    let station_path = root.join(band).join(format!("{:02}", index));
+    let playlist_path = station_path.join("playlist");
+    fs::create_dir_all(&playlist_path).unwrap();
+    fs::write(
+        station_path.join("station.info"),
+        format!(r#"{{ "play_type": "{}", "purge": false }}"#, play_type)
+    ).unwrap();
+
+    let oldest = SystemTime::now() - Duration::from_secs(24 * 60 * 60);
+    for (age, name) in tracks.iter().enumerate() {
+        let track_path = playlist_path.join(format!("{}.wav", name));
+        write_wav(&track_path, TRACK_LENGTH, &TrackTags::default()).unwrap();
+        File::options().write(true).open(&track_path).unwrap()
+            .set_modified(oldest + Duration::from_secs(60 * age as u64)).unwrap();
+    }
+}
+
+/// Builds the stations tree used by the test
+///
+/// - AM 00: Chronologic, with file names out of date order
+/// - AM 01: Random with a single (looping) track
+/// - AM 02: Chronologic, left dormant to be turned over
+/// - Everything else is an empty slot
+///
+/// The "kids" profile puts AM 02's folder on AM 00 and nothing else.
+fn build_stations_tree() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("mokradio-run-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(
+        root.join("radio.info"),
+        r#"{
+            "audio_backend": "Null",
+            "turnover_secs": 2,
+            "storage": "Memory",
+            "profiles": { "kids": { "dial": { "AM": { "0": "AM/02" } } } }
+        }"#
+    ).unwrap();
+
+    write_station(&root, "AM", 0, "Chronologic", &["zulu", "alpha", "mike"]);
+    write_station(&root, "AM", 1, "Random", &["only"]);
+    write_station(&root, "AM", 2, "Chronologic", &["first", "second", "third"]);
+    root
+}
+
+/// Dial position in the middle of a station's slot
+fn dial_position(index: usize) -> usize {
+    index * TICKS_PER_STATION + TICKS_PER_STATION / 2
+}
+
+/// Polls the radio's status until it matches, or panics on timeout
+fn wait_for_status(api: &RadioApi, what: &str, matches: impl Fn(&RadioStatus) -> bool) -> RadioStatus {
+    let started = Instant::now();
+    loop {
+        let status = api.status().unwrap();
+        if matches(&status) {
+            return status;
+        }
+        assert!(started.elapsed() < SETTLE_TIMEOUT, "timed out waiting for {}: {:?}", what, status);
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Titles of the next tracks a station will play
+fn upcoming_titles(api: &RadioApi, station_id: StationID, count: usize) -> Vec<String> {
+    api.preview_station(station_id, count).unwrap()
+        .into_iter()
+        .map(|preview| preview.title)
+        .collect()
+}
+
+#[test]
+fn radio_runs_against_fixture_stations() {
+    let root = build_stations_tree();
+    // Must happen before anything reads radio.info
+    unsafe { std::env::set_var(STATIONS_ROOT_VARIABLE, &root) };
+
+    let (input_tx, input_rx) = channel();
+    let (file_request_tx, file_request_rx) = channel();
+    let (file_response_tx, file_response_rx) = channel();
+    let (command_tx, command_rx) = channel();
+    let api = RadioApi::new(command_tx);
+
+    let loader = thread::spawn(|| file_loader::thread::run_file_loader(file_request_rx, file_response_tx));
+    let manager = thread::spawn(move || {
+        let mut radio = Radio::new(dial_position(0), Band::AM);
+        radio.run(input_rx, file_request_tx, file_response_rx, command_rx);
+    });
+
+    let am = |index| StationID { band: Band::AM, index };
+
+    // Chronologic plays oldest first, whatever the file names
+    let status = wait_for_status(&api, "AM 00 on air", |status| status.on_air);
+    assert_eq!(status.index, 0);
+    assert_eq!(status.now_playing.as_deref(), Some("zulu"));
+    assert_eq!(upcoming_titles(&api, am(0), 3), ["zulu", "alpha", "mike"]);
+
+    // Tuning lands on each station, and empty slots stay off the air
+    input_tx.send(InputEvent::DialMoved { new_dial_position: dial_position(1) }).unwrap();
+    let status = wait_for_status(&api, "AM 01", |status| status.index == 1 && status.on_air);
+    assert_eq!(status.now_playing.as_deref(), Some("only"));
+
+    input_tx.send(InputEvent::DialMoved { new_dial_position: dial_position(5) }).unwrap();
+    let status = wait_for_status(&api, "AM 05", |status| status.index == 5);
+    assert!(!status.on_air);
+    assert_eq!(status.now_playing, None);
+
+    input_tx.send(InputEvent::DialMoved { new_dial_position: dial_position(2) }).unwrap();
+    let status = wait_for_status(&api, "AM 02", |status| status.index == 2 && status.on_air);
+    assert_eq!(status.now_playing.as_deref(), Some("first"));
+    assert_eq!(upcoming_titles(&api, am(2), 3), ["first", "second", "third"]);
+
+    // Staying put past the turnover time moves dormant stations along
+    input_tx.send(InputEvent::DialMoved { new_dial_position: dial_position(0) }).unwrap();
+    wait_for_status(&api, "back on AM 00", |status| status.index == 0);
+    let started = Instant::now();
+    while upcoming_titles(&api, am(2), 2) != ["first", "third"] {
+        assert!(started.elapsed() < SETTLE_TIMEOUT, "AM 02 never turned over: {:?}", upcoming_titles(&api, am(2), 3));
+        thread::sleep(Duration::from_millis(50));
+    }
+    // The station being listened to is left alone
+    assert_eq!(upcoming_titles(&api, am(0), 3), ["zulu", "alpha", "mike"]);
+
+    // A profile rebuilds the dial from its own station folders
+    api.select_profile(Some("kids")).unwrap();
+    let status = wait_for_status(&api, "kids profile", |status| status.profile.as_deref() == Some("kids") && status.on_air);
+    assert_eq!(status.now_playing.as_deref(), Some("first"));
+    input_tx.send(InputEvent::DialMoved { new_dial_position: dial_position(1) }).unwrap();
+    let status = wait_for_status(&api, "AM 01 under kids profile", |status| status.index == 1);
+    assert!(!status.on_air);
+
+    api.select_profile(None).unwrap();
+    let status = wait_for_status(&api, "standard layout", |status| status.profile.is_none() && status.on_air);
+    assert_eq!(status.now_playing.as_deref(), Some("only"));
+
+    // Shutdown stops the manager, which in turn lets the loader exit
+    api.shutdown().unwrap();
+    manager.join().unwrap();
+    loader.join().unwrap();
+    assert!(matches!(api.status(), Err(ApiError::RadioStopped)));
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,39 @@
+//! Loading station.info from a station folder
+
+use std::fs;
+use std::path::PathBuf;
+
+use mokradio::radio::station::config::StationConfig;
+
+/// Creates an empty station folder unique to a test
+fn station_folder(name: &str) -> PathBuf {
+    let station_path = std::env::temp_dir().join(format!("mokradio-config-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&station_path);
+    fs::create_dir_all(&station_path).unwrap();
+    station_path
+}
+
+#[test]
+fn config_is_read_from_station_info_in_the_folder() {
+    let station_path = station_folder("read");
+    fs::write(
+        station_path.join("station.info"),
+        r#"{ "version": 2, "name": "Folder FM", "play_type": "Chronologic", "purge": false }"#
+    ).unwrap();
+
+    let station_config = StationConfig::new(&station_path);
+    assert_eq!(station_config.play_type, "Chronologic");
+    assert_eq!(station_config.name.as_deref(), Some("Folder FM"));
+
+    fs::remove_dir_all(&station_path).unwrap();
+}
+
+#[test]
+fn folder_without_station_info_is_dead() {
+    let station_path = station_folder("missing");
+
+    assert!(StationConfig::load(&station_path).is_err());
+    assert_eq!(StationConfig::new(&station_path).play_type, "Dead");
+
+    fs::remove_dir_all(&station_path).unwrap();
+}
@@ -0,0 +1,133 @@
+//! Every storage backend gives back what was stored in it
+//!
+//! The same checks run against each backend, so a station behaves the
+//! same whichever one radio.info picks.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use mokradio::radio::station::content::origin::TrackOrigin;
+use mokradio::storage::Storage;
+use mokradio::storage::json_file::JsonFileStorage;
+use mokradio::storage::memory::MemoryStorage;
+
+/// Creates an empty stations root unique to a test, with one station folder
+fn stations_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("mokradio-storage-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("FM").join("00")).unwrap();
+    root
+}
+
+fn station_path(root: &Path) -> PathBuf {
+    root.join("FM").join("00")
+}
+
+/// Played fingerprints come back per station, without duplicates
+fn check_played(storage: &dyn Storage, root: &Path) {
+    let station_path = station_path(root);
+    assert!(storage.played_fingerprints(&station_path).is_empty());
+
+    storage.record_played(&station_path, 0x0123_4567_89ab_cdef);
+    storage.record_played(&station_path, u64::MAX);
+    storage.record_played(&station_path, u64::MAX);
+
+    assert_eq!(storage.played_fingerprints(&station_path), HashSet::from([0x0123_4567_89ab_cdef, u64::MAX]));
+    assert!(storage.played_fingerprints(&root.join("FM").join("01")).is_empty());
+}
+
+/// Plays come back oldest first, with their origin and whether they were skipped
+fn check_history(storage: &dyn Storage, root: &Path) {
+    let station_path = station_path(root);
+    assert!(storage.load_history(&station_path).is_empty());
+
+    let feed = TrackOrigin::Feed("https://example.com/feed.xml".to_string());
+    storage.record_play(&station_path, &station_path.join("playlist/first.mp3"), &TrackOrigin::Local, true);
+    storage.record_play(&station_path, &station_path.join("playlist/second.mp3"), &feed, false);
+
+    let history = storage.load_history(&station_path);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].file_path, station_path.join("playlist/first.mp3"));
+    assert_eq!(history[0].origin, TrackOrigin::Local);
+    assert!(history[0].completed);
+    assert_eq!(history[1].file_path, station_path.join("playlist/second.mp3"));
+    assert_eq!(history[1].origin, feed);
+    assert!(!history[1].completed);
+    assert!(history[0].played_at <= history[1].played_at);
+
+    assert!(storage.load_history(&root.join("FM").join("01")).is_empty());
+}
+
+/// State comes back as saved, the latest save winning, and can be cleared
+fn check_state(storage: &dyn Storage) {
+    assert_eq!(storage.load_state("radio"), None);
+
+    storage.save_state("radio", &json!({ "band": 0, "dial_position": 12 }));
+    storage.save_state("radio", &json!({ "band": 1, "dial_position": 34 }));
+    storage.save_state("station FM/00", &json!({ "track": "/stations/FM/00/playlist/a.mp3", "offset_secs": 61.5 }));
+
+    assert_eq!(storage.load_state("radio"), Some(json!({ "band": 1, "dial_position": 34 })));
+    assert_eq!(
+        storage.load_state("station FM/00"),
+        Some(json!({ "track": "/stations/FM/00/playlist/a.mp3", "offset_secs": 61.5 }))
+    );
+
+    storage.save_state("radio", &Value::Null);
+    assert!(storage.load_state("radio").is_none_or(|value| value.is_null()));
+}
+
+#[test]
+fn memory_storage_keeps_history_and_state() {
+    let root = stations_root("memory-history");
+    let storage = MemoryStorage::new();
+    check_history(&storage, &root);
+    check_state(&storage);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn json_file_storage_keeps_history_and_state() {
+    let root = stations_root("json-history");
+    let storage = JsonFileStorage::new(&root);
+    check_history(&storage, &root);
+    check_state(&storage);
+    assert!(station_path(&root).join("history.log").exists());
+    assert!(!storage.files().is_empty());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_storage_keeps_history_and_state() {
+    let root = stations_root("sqlite-history");
+    let storage = mokradio::storage::sqlite::SqliteStorage::open(&root).unwrap();
+    check_history(&storage, &root);
+    check_state(&storage);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn memory_storage_keeps_played_episodes() {
+    let root = stations_root("memory-played");
+    check_played(&MemoryStorage::new(), &root);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn json_file_storage_keeps_played_episodes() {
+    let root = stations_root("json-played");
+    check_played(&JsonFileStorage::new(&root), &root);
+    assert!(station_path(&root).join("played.log").exists());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_storage_keeps_played_episodes() {
+    let root = stations_root("sqlite-played");
+    check_played(&mokradio::storage::sqlite::SqliteStorage::open(&root).unwrap(), &root);
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,39 @@
+//! Turning the virtual dial that encoder, touch and keyboard builds tune with
+//!
+//! Whatever it's turned by, the dial has to hand the Station Manager a
+//! position in the same `0..ENCODER_HALF` range an ADC build reads.
+
+use mokradio::config::{DialConfig, DialEdge};
+use mokradio::constants::ENCODER_HALF;
+use mokradio::input::dial::VirtualDial;
+
+/// A dial with no acceleration, so every step moves it exactly one step
+fn dial(edge: DialEdge, initial_position: usize) -> VirtualDial {
+    let dial_config = DialConfig { edge, acceleration: 1.0, ..DialConfig::default() };
+    VirtualDial::new(&dial_config, initial_position)
+}
+
+#[test]
+fn starting_position_survives_scaling() {
+    let dial = dial(DialEdge::EndStop, ENCODER_HALF / 2);
+    assert_eq!(dial.dial_position(), ENCODER_HALF / 2);
+}
+
+#[test]
+fn end_stops_pin_the_dial() {
+    let mut dial = dial(DialEdge::EndStop, 0);
+    assert_eq!(dial.turn(-1), None);
+    assert_eq!(dial.turn(0), None);
+
+    let top = dial.turn(10_000).expect("turning up moves the dial");
+    assert!(top < ENCODER_HALF);
+    assert_eq!(dial.turn(1), None);
+}
+
+#[test]
+fn wrapping_dial_comes_round_the_other_side() {
+    let range = DialConfig::default().range;
+    let mut dial = dial(DialEdge::Wrap, 0);
+    assert_eq!(dial.turn(-1), Some((range - 1) * ENCODER_HALF / range));
+    assert_eq!(dial.turn(1), Some(0));
+}
@@ -0,0 +1,81 @@
+//! Picking the next track from empty, single-track and played-out playlists
+//!
+//! Every `whats_next` pick must hand back `None` rather than panic when
+//! there's nothing left, since an empty playlist folder is a normal state
+//! for a station to be in.
+
+use std::collections::{BTreeSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use chrono::TimeDelta;
+
+use mokradio::radio::station::content::track::Track;
+use mokradio::radio::station::utilities::whats_next::{
+    next_chronologic, next_random, next_reverse, next_shuffle, next_shuffle_separated,
+};
+
+/// A track that doesn't need a file, modified `age` before now
+fn track(name: &str, age: Duration) -> Track {
+    Track::from_metadata(PathBuf::from(name), TimeDelta::seconds(30), SystemTime::now() - age)
+}
+
+#[test]
+fn empty_playlists_have_no_next_track() {
+    assert!(next_random(&[]).is_none());
+    assert!(next_shuffle(&mut Vec::new()).is_none());
+    assert!(next_shuffle_separated(&mut Vec::new(), &VecDeque::new()).is_none());
+    assert!(next_chronologic(&mut BTreeSet::new()).is_none());
+    assert!(next_reverse(&mut BTreeSet::new()).is_none());
+}
+
+#[test]
+fn single_track_random_playlist_repeats() {
+    let play_list = [track("only.wav", Duration::ZERO)];
+    for _ in 0..3 {
+        let next = next_random(&play_list).unwrap();
+        assert_eq!(next.get_location(), play_list[0].get_location());
+    }
+}
+
+#[test]
+fn single_track_playlists_play_out() {
+    let mut shuffled = vec![track("only.wav", Duration::ZERO)];
+    assert!(next_shuffle(&mut shuffled).is_some());
+    assert!(next_shuffle(&mut shuffled).is_none());
+
+    let mut separated = vec![track("only.wav", Duration::ZERO)];
+    assert!(next_shuffle_separated(&mut separated, &VecDeque::new()).is_some());
+    assert!(next_shuffle_separated(&mut separated, &VecDeque::new()).is_none());
+
+    let mut chronologic = BTreeSet::from([track("only.wav", Duration::ZERO)]);
+    assert!(next_chronologic(&mut chronologic).is_some());
+    assert!(next_chronologic(&mut chronologic).is_none());
+
+    let mut reverse = BTreeSet::from([track("only.wav", Duration::ZERO)]);
+    assert!(next_reverse(&mut reverse).is_some());
+    assert!(next_reverse(&mut reverse).is_none());
+}
+
+#[test]
+fn sorted_playlists_play_out_in_order() {
+    let tracks = || BTreeSet::from([
+        track("old.wav", Duration::from_secs(300)),
+        track("middle.wav", Duration::from_secs(200)),
+        track("new.wav", Duration::from_secs(100)),
+    ]);
+
+    let mut chronologic = tracks();
+    let played: Vec<PathBuf> = std::iter::from_fn(|| next_chronologic(&mut chronologic))
+        .map(|track| track.get_location().to_path_buf())
+        .collect();
+    assert_eq!(played, ["old.wav", "middle.wav", "new.wav"].map(PathBuf::from));
+    assert!(next_chronologic(&mut chronologic).is_none());
+
+    let mut reverse = tracks();
+    let played: Vec<PathBuf> = std::iter::from_fn(|| next_reverse(&mut reverse))
+        .map(|track| track.get_location().to_path_buf())
+        .collect();
+    assert_eq!(played, ["new.wav", "middle.wav", "old.wav"].map(PathBuf::from));
+    assert!(next_reverse(&mut reverse).is_none());
+}